@@ -0,0 +1,274 @@
+//! A minimal serenity bot of its own that keeps a status message up to date through the
+//! library, in place of the bundled frontend.
+//!
+//! It reads the bot's own environment variables (`DISCORD_TOKEN`, `SERVER_ADDRESS`,
+//! `TEXT_CHANNEL_ID`, optionally `STATUS_MESSAGE_ID`, ...), and draws a deliberately plainer
+//! embed than the bundled one.
+//!
+//! ```sh
+//! cargo run --example custom_frontend
+//! ```
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use dayz_monitor::{
+    dualstack::DualStack,
+    monitor::{Monitor, Observation},
+    render::EmbedRepresentation,
+    resolve_server_address, retrieve_server_info,
+    title::{render_title, TitleState},
+    transport::{
+        ChannelMessage, MessageAuthor, MessageState, Priority, RenderSequence, Restore,
+        SendRequest, StatusMessage, StatusTransport, StatusUpdate,
+    },
+    DayzMonitorConfig,
+};
+use serenity::{
+    all::{
+        ChannelId, CreateEmbed, CreateMessage, EditChannel, EditMessage, GatewayIntents,
+        GetMessages, MessageFlags, MessageId, Ready,
+    },
+    async_trait,
+    client::{Context, EventHandler},
+    http::Http,
+    Client,
+};
+
+/// Talks to Discord over its REST API on behalf of the library.
+struct Frontend {
+    http: Arc<Http>,
+}
+
+impl StatusTransport for Frontend {
+    type Payload = EditMessage;
+    type Message = CreateMessage;
+    type Error = serenity::Error;
+
+    async fn edit(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        payload: EditMessage,
+    ) -> Result<(), serenity::Error> {
+        ChannelId::new(channel_id)
+            .edit_message(&self.http, MessageId::new(message_id), payload)
+            .await
+            .map(|_| ())
+    }
+
+    async fn author(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> Result<MessageAuthor, serenity::Error> {
+        let msg = ChannelId::new(channel_id)
+            .message(&self.http, MessageId::new(message_id))
+            .await?;
+
+        Ok(MessageAuthor {
+            id: msg.author.id.get(),
+            name: msg.author.tag(),
+        })
+    }
+
+    async fn send(&self, request: SendRequest<CreateMessage>) -> Result<u64, serenity::Error> {
+        ChannelId::new(request.channel_id)
+            .send_message(&self.http, request.message)
+            .await
+            .map(|msg| msg.id.get())
+    }
+
+    async fn recent_messages(
+        &self,
+        channel_id: u64,
+        limit: u8,
+    ) -> Result<Vec<ChannelMessage>, serenity::Error> {
+        let messages = ChannelId::new(channel_id)
+            .messages(&self.http, GetMessages::new().limit(limit))
+            .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|msg| ChannelMessage {
+                id: msg.id.get(),
+                author_id: msg.author.id.get(),
+                created_unix: msg.timestamp.unix_timestamp().max(0) as u64,
+                footer: msg
+                    .embeds
+                    .first()
+                    .and_then(|e| e.footer.as_ref())
+                    .map(|f| f.text.clone()),
+            })
+            .collect())
+    }
+
+    async fn delete(&self, channel_id: u64, message_id: u64) -> Result<(), serenity::Error> {
+        ChannelId::new(channel_id)
+            .delete_message(&self.http, MessageId::new(message_id))
+            .await
+    }
+
+    async fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), serenity::Error> {
+        ChannelId::new(channel_id)
+            .edit(&self.http, EditChannel::new().topic(topic))
+            .await
+            .map(|_| ())
+    }
+
+    async fn inspect(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> Result<MessageState, serenity::Error> {
+        let msg = ChannelId::new(channel_id)
+            .message(&self.http, MessageId::new(message_id))
+            .await?;
+
+        Ok(MessageState {
+            embeds_suppressed: msg
+                .flags
+                .is_some_and(|f| f.contains(MessageFlags::SUPPRESS_EMBEDS)),
+            content: msg.content,
+        })
+    }
+
+    async fn restore(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        restore: Restore,
+    ) -> Result<(), serenity::Error> {
+        let mut edit = EditMessage::new();
+        if restore.unsuppress {
+            edit = edit.suppress_embeds(false);
+        }
+        if restore.clear_content {
+            edit = edit.content("");
+        }
+
+        ChannelId::new(channel_id)
+            .edit_message(&self.http, MessageId::new(message_id), edit)
+            .await
+            .map(|_| ())
+    }
+}
+
+struct Handler {
+    config: DayzMonitorConfig,
+    /// `ready` fires again after reconnects, which must not start a second loop
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        println!("Connected as {}", ready.user.tag());
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(run(self.config.clone(), ctx.http.clone()));
+    }
+}
+
+async fn run(config: DayzMonitorConfig, http: Arc<Http>) {
+    let status = StatusMessage::new(Frontend { http });
+    let seq = RenderSequence::default();
+    let mut monitor = Monitor::new(config.recovery_criterion());
+    let mut dual_stack = DualStack::default();
+    let bounds = config.sanity_bounds();
+    let mut message_id = config.status_message_id;
+
+    loop {
+        let update_seq = seq.next();
+        let result = match resolve_server_address(&config.server_address).await {
+            Ok(addrs) => dual_stack
+                .query(&addrs, |addr| retrieve_server_info(addr, &bounds))
+                .await
+                .map(|(info, _)| info),
+            Err(err) => Err(err),
+        };
+        let embed = to_create_embed(&plain_embed(&config, &monitor.observe(result, false)));
+
+        match message_id {
+            Some(id) => {
+                let update = StatusUpdate {
+                    seq: update_seq,
+                    payload: EditMessage::new().embed(embed),
+                };
+                if let Err(err) = status.apply(config.text_channel_id, id, update).await {
+                    eprintln!("Failed to edit status message: {err}");
+                }
+            }
+            None => {
+                let request = SendRequest {
+                    channel_id: config.text_channel_id,
+                    message: CreateMessage::new().embed(embed),
+                    priority: Priority::Normal,
+                };
+                match status.transport().send(request).await {
+                    Ok(id) => message_id = Some(id),
+                    Err(err) => eprintln!("Failed to post status message: {err}"),
+                }
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(config.update_interval_secs)).await;
+    }
+}
+
+/// The bundled title and colour, with the counts as the only other content.
+fn plain_embed(config: &DayzMonitorConfig, observation: &Observation) -> EmbedRepresentation {
+    let state = TitleState::of(observation);
+    let lang = config.bot_language;
+    let embed = EmbedRepresentation::new()
+        .title(render_title(config, state, observation.info.as_ref(), lang))
+        .colour(state.colour());
+
+    match &observation.info {
+        Some(info) => embed.description(format!(
+            "{}/{} players, {} queueing",
+            info.players,
+            info.max_players,
+            info.players_in_queue.unwrap_or(0)
+        )),
+        None => embed,
+    }
+}
+
+fn to_create_embed(embed: &EmbedRepresentation) -> CreateEmbed {
+    let mut out = CreateEmbed::new();
+
+    if let Some(title) = &embed.title {
+        out = out.title(title);
+    }
+    if let Some(description) = &embed.description {
+        out = out.description(description);
+    }
+    if let Some(colour) = embed.colour {
+        out = out.colour(colour);
+    }
+
+    out
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let config: DayzMonitorConfig = serde_env::from_env()?;
+    config.validate()?;
+
+    let mut client = Client::builder(&config.discord_token, GatewayIntents::GUILDS)
+        .event_handler(Handler {
+            config: config.clone(),
+            started: AtomicBool::new(false),
+        })
+        .await?;
+
+    client.start().await?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Polls a server and prints one status line per poll, with no Discord involved.
+//!
+//! ```sh
+//! cargo run --example headless_poller -- 203.0.113.7:27016
+//! cargo run --example headless_poller -- 203.0.113.7:27016 --interval 10
+//! cargo run --example headless_poller -- 203.0.113.7:27016 --once
+//! ```
+
+use std::time::Duration;
+
+use dayz_monitor::{
+    dualstack::DualStack,
+    i18n::Language,
+    monitor::{Monitor, Observation, RecoveryCriterion},
+    resolve_server_address, retrieve_server_info,
+    title::TitleState,
+    SanityBounds,
+};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut address = None;
+    let mut interval = Duration::from_secs(30);
+    let mut once = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--once" => once = true,
+            "--interval" => {
+                let secs = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--interval takes seconds"))?;
+                interval = Duration::from_secs(secs.parse()?);
+            }
+            _ => address = Some(arg),
+        }
+    }
+
+    let address = address.ok_or_else(|| {
+        eyre::eyre!("usage: headless_poller <host:port> [--interval <secs>] [--once]")
+    })?;
+
+    let mut monitor = Monitor::new(RecoveryCriterion::default());
+    let mut dual_stack = DualStack::default();
+    let bounds = SanityBounds::default();
+
+    loop {
+        let result = match resolve_server_address(&address).await {
+            Ok(addrs) => dual_stack
+                .query(&addrs, |addr| retrieve_server_info(addr, &bounds))
+                .await
+                .map(|(info, _)| info),
+            Err(err) => Err(err),
+        };
+
+        println!("{}", status_line(&monitor.observe(result, false)));
+
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// E.g. `🟢 Online 42/60 • queue 0 • time 14:32 • 38 ms`.
+fn status_line(observation: &Observation) -> String {
+    let state = TitleState::of(observation);
+    let mut line = format!("{} {}", state.emoji(), Language::En.tr(state.text()));
+
+    if let Some(info) = &observation.info {
+        line += &format!(
+            " {}/{} • queue {}",
+            info.players,
+            info.max_players,
+            info.players_in_queue.unwrap_or(0)
+        );
+        if let Some(time) = &info.server_time {
+            line += &format!(" • time {time}");
+        }
+        if let Some(latency) = info.latency {
+            line += &format!(" • {} ms", latency.as_millis());
+        }
+    }
+
+    if let Some(error) = &observation.error {
+        line += &format!(" ({error})");
+    }
+
+    line
+}
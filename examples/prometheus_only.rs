@@ -0,0 +1,139 @@
+//! Polls a server and serves the results as Prometheus metrics, with no Discord at all.
+//!
+//! ```sh
+//! cargo run --example prometheus_only -- 203.0.113.7:27016 --listen 127.0.0.1:9185
+//! curl http://127.0.0.1:9185/metrics
+//! ```
+
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+
+use dayz_monitor::{
+    dualstack::DualStack,
+    monitor::{Monitor, Observation, RecoveryCriterion},
+    resolve_server_address, retrieve_server_info, MonitorState, SanityBounds,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::RwLock,
+};
+
+const STATES: [(MonitorState, &str); 5] = [
+    (MonitorState::Online, "online"),
+    (MonitorState::Degraded, "degraded"),
+    (MonitorState::Offline, "offline"),
+    (MonitorState::Starting, "starting"),
+    (MonitorState::Maintenance, "maintenance"),
+];
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut address = None;
+    let mut listen = "127.0.0.1:9185".to_string();
+    let mut interval = Duration::from_secs(30);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--listen" => {
+                listen = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--listen takes an address"))?;
+            }
+            "--interval" => {
+                let secs = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--interval takes seconds"))?;
+                interval = Duration::from_secs(secs.parse()?);
+            }
+            _ => address = Some(arg),
+        }
+    }
+
+    let address = address.ok_or_else(|| {
+        eyre::eyre!("usage: prometheus_only <host:port> [--listen <addr>] [--interval <secs>]")
+    })?;
+
+    let metrics = Arc::new(RwLock::new(render(&Observation::unknown(), 0)));
+    let listener = TcpListener::bind(&listen).await?;
+    println!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    tokio::spawn(serve(listener, metrics.clone()));
+
+    let mut monitor = Monitor::new(RecoveryCriterion::default());
+    let mut dual_stack = DualStack::default();
+    let bounds = SanityBounds::default();
+    let mut polls = 0u64;
+
+    loop {
+        let result = match resolve_server_address(&address).await {
+            Ok(addrs) => dual_stack
+                .query(&addrs, |addr| retrieve_server_info(addr, &bounds))
+                .await
+                .map(|(info, _)| info),
+            Err(err) => Err(err),
+        };
+        polls += 1;
+
+        *metrics.write().await = render(&monitor.observe(result, false), polls);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// The Prometheus text exposition of the latest observation.
+fn render(observation: &Observation, polls: u64) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# TYPE dayz_polls_total counter");
+    let _ = writeln!(out, "dayz_polls_total {polls}");
+
+    // Before the first poll no state is exported, rather than every state as 0.
+    if observation.state != MonitorState::Unknown {
+        let _ = writeln!(out, "# TYPE dayz_state gauge");
+        for (state, name) in STATES {
+            let value = u8::from(observation.state == state);
+            let _ = writeln!(out, "dayz_state{{state=\"{name}\"}} {value}");
+        }
+    }
+
+    // Degraded and starting observations show an older sample, which is not exported.
+    if let (MonitorState::Online, Some(info)) = (observation.state, &observation.info) {
+        let _ = writeln!(out, "# TYPE dayz_players gauge");
+        let _ = writeln!(out, "dayz_players {}", info.players);
+        let _ = writeln!(out, "# TYPE dayz_max_players gauge");
+        let _ = writeln!(out, "dayz_max_players {}", info.max_players);
+        let _ = writeln!(out, "# TYPE dayz_queue gauge");
+        let _ = writeln!(out, "dayz_queue {}", info.players_in_queue.unwrap_or(0));
+
+        if let Some(latency) = info.latency {
+            let _ = writeln!(out, "# TYPE dayz_query_latency_seconds gauge");
+            let _ = writeln!(out, "dayz_query_latency_seconds {}", latency.as_secs_f64());
+        }
+    }
+
+    out
+}
+
+/// Answers every request with the current metrics; enough for a scraper, not a web server.
+async fn serve(listener: TcpListener, metrics: Arc<RwLock<String>>) {
+    loop {
+        let Ok((mut stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+
+            let body = metrics.read().await.clone();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
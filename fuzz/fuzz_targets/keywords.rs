@@ -0,0 +1,21 @@
+//! Keywords strings as servers send them. Invariants match `tests/parser_robustness.rs`.
+#![no_main]
+
+use dayz_monitor::{
+    parse_keywords, parse_server_time, MAX_IGNORED_TOKENS, MAX_IGNORED_TOKEN_CHARS,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The parser takes a `&str`, so raw bytes are decoded the way a lossy reader would.
+    let keywords = String::from_utf8_lossy(data);
+    let parsed = parse_keywords(&keywords);
+
+    if let Some(time) = &parsed.server_time {
+        assert!(parse_server_time(time).is_some(), "{time:?} is not a time");
+    }
+    assert!(parsed.ignored_tokens.len() <= MAX_IGNORED_TOKENS);
+    for ignored in &parsed.ignored_tokens {
+        assert!(ignored.token.chars().count() <= MAX_IGNORED_TOKEN_CHARS);
+    }
+});
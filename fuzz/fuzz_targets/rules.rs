@@ -0,0 +1,18 @@
+//! A2S_RULES payloads and the mod list DayZ packs into them. Invariants match
+//! `tests/parser_robustness.rs`.
+#![no_main]
+
+use dayz_monitor::{mods::decode_mods, query::parse_rules};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(rules) = parse_rules(data) {
+        assert!(rules.len() <= data.len() / 2);
+        if let Some(mods) = decode_mods(&rules) {
+            assert!(mods.len() <= 255);
+        }
+    }
+
+    // The same bytes as one mod list fragment, to reach the decoder without a valid envelope.
+    let _ = decode_mods(&[(vec![1, 1], data.to_vec())]);
+});
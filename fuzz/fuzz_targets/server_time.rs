@@ -0,0 +1,16 @@
+//! Single time tokens. Invariants match `tests/parser_robustness.rs`.
+#![no_main]
+
+use dayz_monitor::parse_server_time;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(token) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Some((hours, minutes, seconds)) = parse_server_time(token) {
+        assert!(hours < 24 && minutes < 60 && seconds < 60);
+        assert!(token.len() <= 8, "{token:?} is too long for a time");
+    }
+});
@@ -0,0 +1,441 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{
+    clock,
+    probe::RollingPercentile,
+    template::{self, TemplateValues, CONFIG_PLACEHOLDERS, SERVER_PLACEHOLDERS},
+    DayzMonitorConfig, DayzMonitorError, ServerInfo,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertKind {
+    /// The server stopped answering queries
+    Offline,
+    /// The server answers again after being offline
+    Online,
+    /// The queue grew past the configured threshold
+    Queue,
+    /// Query latency stayed above the configured threshold
+    Latency,
+    /// Queries answer, but the game port players connect to is unreachable
+    #[serde(rename = "join_port")]
+    JoinPort,
+}
+
+impl AlertKind {
+    pub const ALL: [AlertKind; 5] = [
+        AlertKind::Offline,
+        AlertKind::Online,
+        AlertKind::Queue,
+        AlertKind::Latency,
+        AlertKind::JoinPort,
+    ];
+
+    pub fn severity(self) -> Severity {
+        match self {
+            AlertKind::Offline | AlertKind::JoinPort => Severity::High,
+            AlertKind::Online | AlertKind::Latency => Severity::Normal,
+            AlertKind::Queue => Severity::Low,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AlertKind::Offline => "offline",
+            AlertKind::Online => "online",
+            AlertKind::Queue => "queue",
+            AlertKind::Latency => "latency",
+            AlertKind::JoinPort => "join_port",
+        }
+    }
+
+    /// Placeholders only this rule provides, on top of the shared set.
+    pub fn rule_placeholders(self) -> &'static [&'static str] {
+        match self {
+            AlertKind::Offline => &["duration"],
+            AlertKind::Online => &["outage_minutes"],
+            AlertKind::Queue => &["threshold"],
+            AlertKind::Latency => &["p95_ms", "threshold_ms"],
+            AlertKind::JoinPort => &["game_port"],
+        }
+    }
+
+    /// Every placeholder a template for this rule may use. The offline rule has no live
+    /// server info, so it only gets the config-derived placeholders.
+    pub fn allowed_placeholders(self) -> Vec<&'static str> {
+        let shared = match self {
+            AlertKind::Offline => CONFIG_PLACEHOLDERS,
+            AlertKind::Online | AlertKind::Queue | AlertKind::Latency | AlertKind::JoinPort => {
+                SERVER_PLACEHOLDERS
+            }
+        };
+
+        shared
+            .iter()
+            .chain(self.rule_placeholders())
+            .copied()
+            .collect()
+    }
+
+    pub fn default_template(self) -> &'static str {
+        match self {
+            AlertKind::Offline => "🔴 **{server_name}** is offline (last seen {duration} ago).",
+            AlertKind::Online => "🟢 **{server_name}** is back online after {outage_minutes} min.",
+            AlertKind::Queue => "⏳ **{server_name}** queue is over {threshold} ({queue} waiting).",
+            AlertKind::Latency => {
+                "🐢 **{server_name}** is answering slowly (p95 {p95_ms} ms, threshold {threshold_ms} ms)."
+            }
+            AlertKind::JoinPort => {
+                "⚠️ **{server_name}** answers queries, but its game port {game_port} is unreachable, so nobody can join."
+            }
+        }
+    }
+
+    fn configured_template(self, config: &DayzMonitorConfig) -> Option<&str> {
+        match self {
+            AlertKind::Offline => config.alert_offline_template.as_deref(),
+            AlertKind::Online => config.alert_online_template.as_deref(),
+            AlertKind::Queue => config.alert_queue_template.as_deref(),
+            AlertKind::Latency => config.alert_latency_template.as_deref(),
+            AlertKind::JoinPort => config.alert_join_port_template.as_deref(),
+        }
+    }
+}
+
+/// How urgently an alert wants attention, for backends that can express it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub kind: AlertKind,
+    pub template: String,
+}
+
+/// The alert rules enabled by `config`. Alerts need a channel or notification backend, the
+/// queue and latency rules a threshold, and the join port rule the game port probe. The queue
+/// rule is off while the player count is hidden.
+pub fn alert_rules(config: &DayzMonitorConfig) -> Vec<AlertRule> {
+    if !config.has_alert_destination() {
+        return Vec::new();
+    }
+
+    AlertKind::ALL
+        .into_iter()
+        .filter(|kind| match kind {
+            AlertKind::Queue => {
+                config.alert_queue_threshold.is_some() && config.player_count_mode.counts_known()
+            }
+            AlertKind::Latency => config.alert_latency_ms.is_some(),
+            AlertKind::JoinPort => config.game_port_probe.is_on(),
+            AlertKind::Offline | AlertKind::Online => true,
+        })
+        .map(|kind| AlertRule {
+            kind,
+            template: kind
+                .configured_template(config)
+                .unwrap_or(kind.default_template())
+                .to_owned(),
+        })
+        .collect()
+}
+
+/// Rejects templates referencing placeholders their rule cannot fill, so mistakes surface at
+/// startup instead of as blanks in a live alert.
+pub fn validate_alert_templates(config: &DayzMonitorConfig) -> Result<(), DayzMonitorError> {
+    for kind in AlertKind::ALL {
+        let Some(template) = kind.configured_template(config) else {
+            continue;
+        };
+
+        if let Some(name) =
+            template::find_unknown_placeholder(template, &kind.allowed_placeholders())
+        {
+            return Err(DayzMonitorError::InvalidAlertTemplate {
+                rule: kind.name(),
+                placeholder: name.to_owned(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub message: String,
+}
+
+/// Turns the stream of poll results into edge-triggered alerts.
+#[derive(Debug)]
+pub struct AlertTracker {
+    rules: Vec<AlertRule>,
+    online: Option<bool>,
+    last_online_unix: Option<u64>,
+    offline_since_unix: Option<u64>,
+
+    /// Offline polls since startup, while the server hasn't been seen online and no offline
+    /// alert was sent for it
+    startup_offline_polls: Option<u32>,
+    queue_alerted: bool,
+    join_port_alerted: bool,
+    latencies: RollingPercentile,
+
+    /// The p95 latency while it is above the alert threshold and not yet back below the clear one
+    high_latency: Option<Duration>,
+}
+
+impl AlertTracker {
+    /// `latency_window` is how many recent samples the latency rule's p95 is taken over.
+    pub fn new(rules: Vec<AlertRule>, latency_window: usize) -> Self {
+        Self {
+            rules,
+            online: None,
+            last_online_unix: None,
+            offline_since_unix: None,
+            startup_offline_polls: Some(0),
+            queue_alerted: false,
+            join_port_alerted: false,
+            latencies: RollingPercentile::new(latency_window),
+            high_latency: None,
+        }
+    }
+
+    /// Re-anchors the outage timestamps after the system clock was stepped by `step` seconds.
+    pub fn shift_clock(&mut self, step: i64) {
+        self.last_online_unix = self.last_online_unix.map(|t| clock::shift(t, step));
+        self.offline_since_unix = self.offline_since_unix.map(|t| clock::shift(t, step));
+    }
+
+    /// The rolling p95 latency while it counts as high.
+    pub fn high_latency(&self) -> Option<Duration> {
+        self.high_latency
+    }
+
+    /// Feeds one poll result (`None` when the query failed) and returns the alerts to send.
+    ///
+    /// The very first observation only establishes the baseline. A server offline from it on
+    /// is alerted once it has stayed offline for `alert_startup_offline_polls` polls.
+    pub fn observe(
+        &mut self,
+        config: &DayzMonitorConfig,
+        info: Option<&ServerInfo>,
+        now: u64,
+    ) -> Vec<Alert> {
+        let mut fired = Vec::new();
+        let was_online = self.online.replace(info.is_some());
+
+        match info {
+            Some(info) => {
+                // An outage since startup that was never alerted isn't announced as over either.
+                if was_online == Some(false) && self.startup_offline_polls.is_none() {
+                    let outage_secs = now.saturating_sub(self.offline_since_unix.unwrap_or(now));
+                    let mut values = TemplateValues::from_server_info(config, info);
+                    values.set_number("outage_minutes", outage_secs / 60);
+                    self.fire(AlertKind::Online, &values, &mut fired);
+                }
+
+                self.last_online_unix = Some(now);
+                self.offline_since_unix = None;
+                self.startup_offline_polls = None;
+
+                if let Some(threshold) = config.alert_queue_threshold {
+                    let queue = info.players_in_queue.unwrap_or(0);
+                    if queue > threshold && !self.queue_alerted {
+                        let mut values = TemplateValues::from_server_info(config, info);
+                        values.set_number("threshold", threshold.into());
+                        self.fire(AlertKind::Queue, &values, &mut fired);
+                        self.queue_alerted = true;
+                    } else if queue <= threshold {
+                        self.queue_alerted = false;
+                    }
+                }
+
+                self.observe_latency(config, info, &mut fired);
+            }
+            None => {
+                self.offline_since_unix.get_or_insert(now);
+
+                // Offline from the first poll on may be an outage that started before the bot,
+                // or a hiccup; only one that persists is alerted, and only once.
+                let persisted = self.startup_offline_polls.as_mut().is_some_and(|polls| {
+                    *polls += 1;
+                    *polls == config.alert_startup_offline_polls
+                });
+                if persisted {
+                    self.startup_offline_polls = None;
+                }
+
+                if was_online == Some(true) || persisted {
+                    let mut values = TemplateValues::from_config(config);
+                    values.set(
+                        "duration",
+                        self.last_online_unix
+                            .map(|t| format_duration(now.saturating_sub(t)))
+                            .unwrap_or_else(|| "unknown".to_owned()),
+                    );
+                    self.fire(AlertKind::Offline, &values, &mut fired);
+                }
+
+                self.queue_alerted = false;
+                self.join_port_alerted = false;
+            }
+        }
+
+        fired
+    }
+
+    /// Feeds the game port the probes flagged as unreachable in an online poll, if any, and
+    /// returns the alert to send. It fires once per episode: again only after the port was
+    /// reachable or the server offline in between.
+    pub fn observe_join_port(
+        &mut self,
+        config: &DayzMonitorConfig,
+        info: &ServerInfo,
+        unreachable_port: Option<u16>,
+    ) -> Vec<Alert> {
+        let mut fired = Vec::new();
+
+        match unreachable_port {
+            Some(port) if !self.join_port_alerted => {
+                let mut values = TemplateValues::from_server_info(config, info);
+                values.set("game_port", port);
+                self.fire(AlertKind::JoinPort, &values, &mut fired);
+                self.join_port_alerted = true;
+            }
+            Some(_) => {}
+            None => self.join_port_alerted = false,
+        }
+
+        fired
+    }
+
+    /// Hysteresis on the rolling p95: it turns high above `alert_latency_ms` once the window is
+    /// full, and only clears below `alert_latency_clear_ms`. Isolated spikes that make up less
+    /// than 5% of the window never move the p95.
+    fn observe_latency(
+        &mut self,
+        config: &DayzMonitorConfig,
+        info: &ServerInfo,
+        fired: &mut Vec<Alert>,
+    ) {
+        let (Some(threshold), Some(latency)) = (config.latency_alert_threshold(), info.latency)
+        else {
+            return;
+        };
+
+        self.latencies.push(latency);
+        if !self.latencies.is_full() {
+            return;
+        }
+        let Some(p95) = self.latencies.percentile(95.0) else {
+            return;
+        };
+
+        match self.high_latency {
+            None if p95 > threshold.alert => {
+                self.high_latency = Some(p95);
+                let mut values = TemplateValues::from_server_info(config, info);
+                values.set_number("p95_ms", p95.as_millis() as u64);
+                values.set_number("threshold_ms", threshold.alert.as_millis() as u64);
+                self.fire(AlertKind::Latency, &values, fired);
+            }
+            Some(_) if p95 < threshold.clear => self.high_latency = None,
+            Some(_) => self.high_latency = Some(p95),
+            None => {}
+        }
+    }
+
+    fn fire(&self, kind: AlertKind, values: &TemplateValues, fired: &mut Vec<Alert>) {
+        if let Some(rule) = self.rules.iter().find(|r| r.kind == kind) {
+            fired.push(Alert {
+                kind,
+                message: template::render(&rule.template, values),
+            });
+        }
+    }
+}
+
+/// Formats a duration as e.g. `45s`, `12m` or `3h 5m`.
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Parses a duration such as `45s`, `30m`, `2h`, `1d` or `1h30m` into seconds.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return None,
+        };
+        let value: u64 = digits.parse().ok()?;
+        total = total.checked_add(value.checked_mul(unit)?)?;
+        digits.clear();
+    }
+
+    // A trailing bare number, or nothing at all, is ambiguous.
+    if !digits.is_empty() || total == 0 {
+        return None;
+    }
+
+    Some(total)
+}
+
+/// Longest snooze `/snooze` accepts, so a typo can't silence alerts indefinitely.
+pub const MAX_SNOOZE_SECS: u64 = 7 * 86_400;
+
+/// Why alerts are being held back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suppression {
+    /// A maintenance window is active; alert tracking itself is paused
+    Maintenance,
+    /// An admin snoozed alerts; tracking continues but nothing is sent
+    Snoozed { until_unix: u64 },
+}
+
+impl std::fmt::Display for Suppression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suppression::Maintenance => write!(f, "maintenance"),
+            Suppression::Snoozed { until_unix } => write!(f, "snoozed until {until_unix}"),
+        }
+    }
+}
+
+/// Decides whether alerts may be sent at `now`. Maintenance wins over a snooze because it is
+/// the broader suppression; an expired snooze is ignored.
+pub fn suppression(
+    now: u64,
+    in_maintenance: bool,
+    snoozed_until: Option<u64>,
+) -> Option<Suppression> {
+    if in_maintenance {
+        return Some(Suppression::Maintenance);
+    }
+
+    snoozed_until
+        .filter(|until| *until > now)
+        .map(|until_unix| Suppression::Snoozed { until_unix })
+}
@@ -0,0 +1,194 @@
+use chrono::DateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{write_atomic, DayzMonitorError, MonitorState};
+
+/// Something the bot observed or did, as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// The monitor state changed; `from` is [`MonitorState::Unknown`] for the first observation
+    /// after start
+    Transition {
+        #[serde(deserialize_with = "unknown_if_null")]
+        from: MonitorState,
+        to: MonitorState,
+        error: Option<String>,
+    },
+    /// An alert was due; `suppressed` names why it was held back, `error` why sending failed
+    Alert {
+        rule: String,
+        channel_id: Option<u64>,
+        message_id: Option<u64>,
+        suppressed: Option<String>,
+        error: Option<String>,
+    },
+    /// An alert was handed to a notification backend other than Discord
+    Notification {
+        backend: String,
+        rule: String,
+        error: Option<String>,
+    },
+    /// The configured address differs from the one the history was recorded at
+    AddressChanged {
+        server_id: String,
+        from: String,
+        to: String,
+    },
+    /// The history was recorded under another server identity, and now continues under this one
+    ServerRenamed { from: String, to: String },
+}
+
+impl AuditEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            AuditEvent::Transition { from, to, error } => {
+                let mut line = format!("state {} → {}", state_name(*from), state_name(*to));
+                if let Some(error) = error {
+                    line.push_str(&format!(" ({error})"));
+                }
+                line
+            }
+            AuditEvent::Alert {
+                rule,
+                channel_id,
+                message_id,
+                suppressed,
+                error,
+            } => match (suppressed, error, channel_id, message_id) {
+                (Some(reason), _, _, _) => format!("alert {rule} suppressed ({reason})"),
+                (None, Some(error), _, _) => format!("alert {rule} failed ({error})"),
+                (None, None, Some(channel), Some(message)) => {
+                    format!("alert {rule} sent to channel {channel} as message {message}")
+                }
+                _ => format!("alert {rule} not sent (no alert channel)"),
+            },
+            AuditEvent::Notification {
+                backend,
+                rule,
+                error,
+            } => match error {
+                Some(error) => format!("alert {rule} failed on {backend} ({error})"),
+                None => format!("alert {rule} sent to {backend}"),
+            },
+            AuditEvent::AddressChanged {
+                server_id,
+                from,
+                to,
+            } => format!("server {server_id} moved from {from} to {to}"),
+            AuditEvent::ServerRenamed { from, to } => {
+                format!("server {from} renamed to {to}, history kept")
+            }
+        }
+    }
+}
+
+/// Earlier versions wrote `null` for the state before the first observation.
+fn unknown_if_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MonitorState, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Lowercase name of a state, as used in log lines.
+pub fn state_name(state: MonitorState) -> &'static str {
+    match state {
+        MonitorState::Unknown => "unknown",
+        MonitorState::Online => "online",
+        MonitorState::Degraded => "degraded",
+        MonitorState::Offline => "offline",
+        MonitorState::Starting => "starting",
+        MonitorState::Maintenance => "maintenance",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at_unix: u64,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+impl AuditEntry {
+    /// One log line with a UTC timestamp, as printed by `dayz-monitor audit`.
+    pub fn to_line(&self) -> String {
+        let at = DateTime::from_timestamp(self.at_unix as i64, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| self.at_unix.to_string());
+
+        format!("{at}  {}", self.event.describe())
+    }
+}
+
+/// Append-only JSON lines file of [`AuditEntry`]s, pruned to the retention period.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    retention_secs: u64,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf, retention_secs: u64) -> Self {
+        Self {
+            path,
+            retention_secs,
+        }
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), DayzMonitorError> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+
+        Ok(())
+    }
+
+    /// Entries at or after `since_unix`, oldest first.
+    pub fn read_since(&self, since_unix: u64) -> Result<Vec<AuditEntry>, DayzMonitorError> {
+        Ok(read_entries(&self.path)?
+            .into_iter()
+            .filter(|e| e.at_unix >= since_unix)
+            .collect())
+    }
+
+    /// Drops entries older than the retention period, returning how many were removed.
+    pub fn prune(&self, now: u64) -> Result<usize, DayzMonitorError> {
+        let entries = read_entries(&self.path)?;
+        let cutoff = now.saturating_sub(self.retention_secs);
+        let kept: Vec<&AuditEntry> = entries.iter().filter(|e| e.at_unix >= cutoff).collect();
+        let removed = entries.len() - kept.len();
+
+        if removed > 0 {
+            let mut bytes = Vec::new();
+            for entry in kept {
+                bytes.extend(serde_json::to_vec(entry)?);
+                bytes.push(b'\n');
+            }
+            write_atomic(&self.path, &bytes)?;
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Reads every entry, skipping lines that do not parse (e.g. one cut short by a crash).
+fn read_entries(path: &Path) -> Result<Vec<AuditEntry>, DayzMonitorError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
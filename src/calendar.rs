@@ -0,0 +1,37 @@
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Longest run of local times a timezone has ever skipped, a whole day (Samoa, 2011).
+const MAX_GAP_MINUTES: i64 = 24 * 60;
+
+/// Resolves a local wall-clock time to the instant it happens at, once, whatever DST does:
+///
+/// - A time repeated when clocks go back resolves to its first (earlier offset) occurrence.
+/// - A time skipped when clocks go forward resolves to the first instant after the gap, e.g.
+///   02:30 on the spring-forward day in Europe/Berlin resolves to 03:00 CEST.
+pub fn localize(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.with_timezone(&Utc),
+        // Gaps start and end on whole minutes, so the first existing minute ends it.
+        LocalResult::None => (1..=MAX_GAP_MINUTES)
+            .find_map(|m| {
+                tz.from_local_datetime(&(local + TimeDelta::minutes(m)))
+                    .earliest()
+            })
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local)),
+    }
+}
+
+/// The date `unix` falls on in `tz`.
+pub fn local_date(unix: u64, tz: Tz) -> NaiveDate {
+    let utc = DateTime::<Utc>::from_timestamp(unix as i64, 0).unwrap_or_default();
+    tz.from_utc_datetime(&utc.naive_utc()).date_naive()
+}
+
+/// When the local day after `date` starts. Midnight itself may be skipped by DST in some
+/// timezones, in which case the day starts when the gap ends.
+pub fn next_midnight(date: NaiveDate, tz: Tz) -> Option<DateTime<Utc>> {
+    let midnight = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+    Some(localize(tz, midnight))
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Distinct names, keeping at most a given number of them. Names past the cap are remembered
+/// as 64-bit hashes only, so the count stays exact while the strings are dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CappedNames {
+    names: BTreeSet<String>,
+
+    /// Hashes of the distinct names that did not fit
+    dropped: BTreeSet<u64>,
+}
+
+impl CappedNames {
+    /// Adds `name`, keeping it only while fewer than `cap` names are kept. Returns whether it
+    /// had not been seen before.
+    pub fn insert(&mut self, name: &str, cap: usize) -> bool {
+        if self.names.contains(name) {
+            return false;
+        }
+        if self.names.len() < cap {
+            return self.names.insert(name.to_owned());
+        }
+
+        self.dropped.insert(fnv1a(name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name) || self.dropped.contains(&fnv1a(name))
+    }
+
+    /// Distinct names seen, kept or not.
+    pub fn len(&self) -> usize {
+        self.names.len() + self.dropped.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The kept names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// How many names were only counted, for an "and N more".
+    pub fn dropped(&self) -> usize {
+        self.dropped.len()
+    }
+}
+
+/// FNV-1a, so hashes written to the state file stay the same across builds.
+fn fnv1a(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A map holding at most `cap` entries, evicting the least recently used one to make room.
+#[derive(Debug, Clone)]
+pub struct LruMap<K, V> {
+    cap: usize,
+    entries: BTreeMap<K, (V, u64)>,
+
+    /// Keys by when they were last used
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    evictions: u64,
+}
+
+impl<K: Ord + Clone, V> LruMap<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            cap,
+            entries: BTreeMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Looks up `key` and marks it as used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.touch(key)?;
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Inserts or replaces `key`, marking it as used. Returns the entry evicted to make room;
+    /// with a cap of 0 that is the new entry itself.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.cap == 0 {
+            self.evictions = self.evictions.saturating_add(1);
+            return Some((key, value));
+        }
+
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.order.remove(&used);
+            self.order.insert(self.tick, key);
+            return None;
+        }
+        self.order.insert(self.tick, key);
+
+        if self.entries.len() <= self.cap {
+            return None;
+        }
+        let (_, oldest) = self.order.pop_first()?;
+        let (value, _) = self.entries.remove(&oldest)?;
+        self.evictions = self.evictions.saturating_add(1);
+        Some((oldest, value))
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, used) = self.entries.remove(key)?;
+        self.order.remove(&used);
+        Some(value)
+    }
+
+    /// Keeps only the entries `keep` accepts; dropping them does not count as eviction.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let order = &mut self.order;
+        self.entries.retain(|key, (value, used)| {
+            let kept = keep(key, value);
+            if !kept {
+                order.remove(used);
+            }
+            kept
+        });
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries evicted to make room so far.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    /// Entries in key order, without marking them as used.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, (value, _))| (key, value))
+    }
+
+    /// Values in key order, without marking them as used.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.values_mut().map(|(value, _)| value)
+    }
+
+    fn touch(&mut self, key: &K) -> Option<()> {
+        let (_, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        self.tick += 1;
+        *used = self.tick;
+        self.order.insert(self.tick, key.clone());
+        Some(())
+    }
+}
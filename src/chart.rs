@@ -0,0 +1,156 @@
+use std::{fmt, io::Write, time::Duration};
+
+use flate2::{write::ZlibEncoder, Compression};
+
+use crate::transport::StatusTransport;
+
+/// How far back an alert chart reaches.
+pub const CHART_SPAN_SECS: u64 = 6 * 3600;
+
+pub const CHART_WIDTH: u32 = 360;
+pub const CHART_HEIGHT: u32 = 120;
+
+/// How long rendering may take before the alert is left without its chart.
+pub const CHART_BUDGET: Duration = Duration::from_secs(5);
+
+pub const CHART_FILE_NAME: &str = "players.png";
+
+const BACKGROUND: [u8; 3] = [0x2b, 0x2d, 0x31];
+const GRID: [u8; 3] = [0x4e, 0x50, 0x58];
+const AREA: [u8; 3] = [0x2d, 0x6a, 0x4a];
+const LINE: [u8; 3] = [0x57, 0xf2, 0x87];
+
+/// Draws the player count over the [`CHART_SPAN_SECS`] up to `end_unix` as a PNG, with grid
+/// lines at full and half occupancy.
+///
+/// `samples` are `(unix time, players)`, oldest first, with the server down counting as 0. Time
+/// before the first sample is left blank.
+pub fn render_png(samples: &[(u64, u32)], max_players: Option<u32>, end_unix: u64) -> Vec<u8> {
+    let (width, height) = (CHART_WIDTH as usize, CHART_HEIGHT as usize);
+    let peak = samples
+        .iter()
+        .map(|&(_, players)| players)
+        .max()
+        .unwrap_or(0);
+    let scale = max_players.unwrap_or(0).max(peak).max(1) as usize;
+
+    let mut pixels = BACKGROUND.repeat(width * height);
+    let mut set = |x: usize, y: usize, colour: [u8; 3]| {
+        let at = (y * width + x) * 3;
+        pixels[at..at + 3].copy_from_slice(&colour);
+    };
+
+    for x in 0..width {
+        set(x, 0, GRID);
+        set(x, (height - 1) / 2, GRID);
+    }
+
+    let start = end_unix.saturating_sub(CHART_SPAN_SECS);
+    let mut next = 0;
+    let mut players = None;
+
+    for x in 0..width {
+        let column_end = start + (x as u64 + 1) * CHART_SPAN_SECS / width as u64;
+        while let Some(&(at, count)) = samples.get(next).filter(|&&(at, _)| at <= column_end) {
+            if at >= start {
+                players = Some(count);
+            }
+            next += 1;
+        }
+
+        let Some(players) = players else {
+            continue;
+        };
+
+        let top = height - 1 - players as usize * (height - 1) / scale;
+        for y in top + 1..height {
+            set(x, y, AREA);
+        }
+        set(x, top, LINE);
+    }
+
+    encode_png(CHART_WIDTH, CHART_HEIGHT, &pixels)
+}
+
+/// Encodes 8-bit RGB pixels, row by row, as a PNG.
+pub fn encode_png(width: u32, height: u32, rgb: &[u8]) -> Vec<u8> {
+    let stride = width as usize * 3;
+    let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in rgb.chunks(stride) {
+        // Filter type 0, none
+        zlib.write_all(&[0]).expect("writing to a Vec can't fail");
+        zlib.write_all(row).expect("writing to a Vec can't fail");
+    }
+    let data = zlib.finish().expect("writing to a Vec can't fail");
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGB, deflate, no filter, not interlaced
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    push_chunk(&mut png, b"IHDR", &header);
+    push_chunk(&mut png, b"IDAT", &data);
+    push_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+/// What became of an alert's chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChartOutcome {
+    Attached,
+    /// Rendering took longer than its budget
+    TimedOut,
+    Failed(String),
+}
+
+/// The second half of an alert with a chart. The text alert is already out as `message_id`;
+/// this renders the chart off the async runtime and edits it into the message, or gives up
+/// after `budget` and leaves the alert as it is.
+pub async fn attach<T>(
+    transport: &T,
+    channel_id: u64,
+    message_id: u64,
+    render: impl FnOnce() -> Vec<u8> + Send + 'static,
+    budget: Duration,
+    encode: fn(Vec<u8>) -> T::Payload,
+) -> ChartOutcome
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let png = match tokio::time::timeout(budget, tokio::task::spawn_blocking(render)).await {
+        Ok(Ok(png)) => png,
+        Ok(Err(err)) => {
+            tracing::warn!("Rendering the chart for alert {message_id} failed: {err}");
+            return ChartOutcome::Failed(err.to_string());
+        }
+        Err(_) => {
+            tracing::warn!(
+                "Rendering the chart for alert {message_id} took over {}s, leaving it out",
+                budget.as_secs_f32()
+            );
+            return ChartOutcome::TimedOut;
+        }
+    };
+
+    match transport.edit(channel_id, message_id, encode(png)).await {
+        Ok(()) => ChartOutcome::Attached,
+        Err(err) => {
+            tracing::warn!("Failed to attach the chart to alert {message_id}: {err}");
+            ChartOutcome::Failed(err.to_string())
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::transport::{ChannelMessage, StatusTransport};
+
+/// Footer of every status embed the bot posts, telling its status messages apart from anything
+/// else it posts, such as alerts or mod list pages.
+pub const STATUS_MARKER: &str = "dayz-monitor";
+
+/// How many of the latest messages per channel are looked through; one request's worth.
+pub const SCAN_LIMIT: u8 = 100;
+
+/// A status message the bot posted but no longer keeps up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub created_unix: u64,
+}
+
+impl Orphan {
+    pub fn age_secs(&self, now_unix: u64) -> u64 {
+        now_unix.saturating_sub(self.created_unix)
+    }
+}
+
+/// Where to look for orphans, and which status messages are still in use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupScope {
+    pub channels: BTreeSet<u64>,
+    pub tracked: BTreeSet<u64>,
+}
+
+pub fn is_status_message(message: &ChannelMessage, own_user_id: u64) -> bool {
+    message.author_id == own_user_id
+        && message
+            .footer
+            .as_deref()
+            .is_some_and(|f| f.starts_with(STATUS_MARKER))
+}
+
+/// The status messages among `messages` that are not tracked.
+pub fn find_orphans(
+    channel_id: u64,
+    messages: &[ChannelMessage],
+    own_user_id: u64,
+    tracked: &BTreeSet<u64>,
+) -> Vec<Orphan> {
+    messages
+        .iter()
+        .filter(|m| is_status_message(m, own_user_id) && !tracked.contains(&m.id))
+        .map(|m| Orphan {
+            channel_id,
+            message_id: m.id,
+            created_unix: m.created_unix,
+        })
+        .collect()
+}
+
+/// Looks through the latest messages of every channel in `scope`. A channel that can't be read
+/// is skipped with a warning, so one missing permission doesn't hide the other channels.
+pub async fn scan<T>(transport: &T, scope: &CleanupScope, own_user_id: u64) -> Vec<Orphan>
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let mut orphans = Vec::new();
+
+    for &channel_id in &scope.channels {
+        match transport.recent_messages(channel_id, SCAN_LIMIT).await {
+            Ok(messages) => orphans.extend(find_orphans(
+                channel_id,
+                &messages,
+                own_user_id,
+                &scope.tracked,
+            )),
+            Err(err) => {
+                tracing::warn!("Could not look for old status messages in {channel_id}: {err}")
+            }
+        }
+    }
+
+    orphans
+}
+
+/// Deletes `orphans`, logging each, and returns how many are gone.
+pub async fn delete<T>(transport: &T, orphans: &[Orphan]) -> usize
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let mut deleted = 0;
+
+    for orphan in orphans {
+        let Orphan {
+            channel_id,
+            message_id,
+            ..
+        } = orphan;
+
+        match transport.delete(*channel_id, *message_id).await {
+            Ok(()) => {
+                tracing::info!("Deleted old status message {message_id} in {channel_id}");
+                deleted += 1;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to delete old status message {message_id}: {err}")
+            }
+        }
+    }
+
+    deleted
+}
+
+/// The automatic cleanup: deletes orphans at least `min_age_secs` old. Younger ones may be from
+/// another instance still starting up, and are left for a later run.
+pub async fn cleanup<T>(
+    transport: &T,
+    scope: &CleanupScope,
+    own_user_id: u64,
+    now_unix: u64,
+    min_age_secs: u64,
+) -> usize
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let expired: Vec<Orphan> = scan(transport, scope, own_user_id)
+        .await
+        .into_iter()
+        .filter(|o| o.age_secs(now_unix) >= min_age_secs)
+        .collect();
+
+    delete(transport, &expired).await
+}
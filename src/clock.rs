@@ -0,0 +1,55 @@
+use std::time::Instant;
+
+use crate::unix_now;
+
+/// Wall-clock steps up to this are ordinary drift and rounding, and are not reported.
+pub const STEP_THRESHOLD_SECS: u64 = 30;
+
+/// Where the monotonic and wall-clock time come from, so both can be faked.
+pub trait Clock {
+    fn instant(&self) -> Instant;
+
+    fn unix(&self) -> u64;
+}
+
+/// The system's clocks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn unix(&self) -> u64 {
+        unix_now()
+    }
+}
+
+/// Notices the system clock being stepped (e.g. by NTP) between two readings, by comparing how
+/// far the wall clock moved with how much monotonic time passed.
+#[derive(Debug, Clone, Default)]
+pub struct StepDetector {
+    last: Option<(Instant, u64)>,
+}
+
+impl StepDetector {
+    /// Takes a reading of both clocks. Returns how many seconds the wall clock was stepped by
+    /// since the previous reading (positive when forward), if more than [`STEP_THRESHOLD_SECS`].
+    pub fn read(&mut self, clock: &impl Clock) -> Option<i64> {
+        let (instant, wall_unix) = (clock.instant(), clock.unix());
+        let step = self.last.map(|(last_instant, last_wall)| {
+            let elapsed = instant.saturating_duration_since(last_instant).as_secs() as i64;
+            wall_unix as i64 - last_wall as i64 - elapsed
+        });
+
+        self.last = Some((instant, wall_unix));
+        step.filter(|s| s.unsigned_abs() > STEP_THRESHOLD_SECS)
+    }
+}
+
+/// Moves a timestamp taken before a clock step by that step, so a duration measured from it
+/// afterwards is the time that really passed.
+pub fn shift(unix: u64, step: i64) -> u64 {
+    unix.saturating_add_signed(step)
+}
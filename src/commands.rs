@@ -0,0 +1,522 @@
+use std::time::{Duration, Instant};
+
+use dayz_monitor::{
+    alerts::{parse_duration, MAX_SNOOZE_SECS},
+    cleanup,
+    clock::Clock,
+    daily::{self, StatsReport},
+    events,
+    i18n::{resolve_language, Language, Text},
+    motd::{effective_motd, sanitize_motd, MOTD_LIMIT},
+    probe::{measure, probe_a2s},
+    query,
+    render::EmbedRepresentation,
+};
+use serenity::all::{
+    ActionRowComponent, ButtonStyle, Command, CommandInteraction, CommandOptionType,
+    ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommand,
+    CreateCommandOption, CreateInputText, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateModal, EditInteractionResponse, InputTextStyle,
+    ModalInteraction, Permissions,
+};
+
+use crate::{embeds, BotState};
+
+pub async fn register(ctx: &Context) {
+    let commands = vec![
+        CreateCommand::new("status").description("Show the current server status"),
+        CreateCommand::new("players").description("List the players currently online"),
+        CreateCommand::new("ping").description("Measure latency to Discord and the game server"),
+        CreateCommand::new("stats").description("Show today's and this week's numbers"),
+        CreateCommand::new("snooze")
+            .description("Suppress alerts for a while")
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "duration",
+                    "How long, e.g. 30m, 2h or 1d, or `off` to resume alerts",
+                )
+                .required(true),
+            ),
+        CreateCommand::new("config")
+            .description("Show the effective configuration and which features are active")
+            .default_member_permissions(Permissions::MANAGE_GUILD),
+        CreateCommand::new("audit")
+            .description("Show what the bot observed and sent recently")
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "hours",
+                    "How far back to look (default 24)",
+                )
+                .min_int_value(1)
+                .max_int_value(24 * 30),
+            ),
+        CreateCommand::new("motd")
+            .description("Change the message of the day shown in the status embed")
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "edit",
+                "Edit the message of the day",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "reset",
+                "Go back to the configured message of the day",
+            )),
+        CreateCommand::new("cleanup")
+            .description("List status messages of earlier runs, to delete them")
+            .default_member_permissions(Permissions::MANAGE_GUILD),
+        CreateCommand::new("refresh")
+            .description("Update the status now")
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "verbose",
+                "Wait for the update and show where its time went",
+            )),
+    ];
+
+    if let Err(err) = Command::set_global_commands(&ctx.http, commands).await {
+        tracing::warn!("Failed to register slash commands: {err}");
+    }
+}
+
+pub async fn handle(state: &BotState, ctx: &Context, cmd: &CommandInteraction) {
+    let lang = resolve_language(
+        Some(&cmd.locale),
+        state.config.bot_language,
+        state.config.force_bot_language,
+    );
+
+    let embed = match cmd.data.name.as_str() {
+        "status" => status(state, lang, is_admin(cmd)).await,
+        "players" => players(state, lang).await,
+        "ping" => ping(state, ctx, lang).await,
+        "stats" => stats(state, cmd, lang).await,
+        "snooze" => snooze(state, cmd, lang).await,
+        "audit" => audit(state, cmd, lang),
+        "cleanup" => return cleanup(state, ctx, cmd, lang).await,
+        "refresh" if verbose(cmd) => return refresh_verbose(state, ctx, cmd).await,
+        "refresh" => {
+            state.refresh.notify_one();
+            tracing::info!("Refresh requested by {}", cmd.user.name);
+            embeds::refresh_embed(lang)
+        }
+        "config" => embeds::config_embed(&state.config, &state.shared.subsystems.snapshot()),
+        "motd" => match motd(state, cmd, lang).await {
+            MotdReply::Embed(embed) => embed,
+            MotdReply::Editor(modal) => {
+                let response = CreateInteractionResponse::Modal(modal);
+                if let Err(err) = cmd.create_response(&ctx.http, response).await {
+                    tracing::warn!("Failed to open the MOTD editor: {err}");
+                }
+                return;
+            }
+        },
+        _ => return,
+    };
+
+    let reply = CreateInteractionResponseMessage::new()
+        .embed(embeds::to_create_embed(&embed))
+        .ephemeral(true);
+
+    if let Err(err) = cmd
+        .create_response(&ctx.http, CreateInteractionResponse::Message(reply))
+        .await
+    {
+        tracing::warn!("Failed to reply to /{}: {err}", cmd.data.name);
+    }
+}
+
+const MOTD_MODAL: &str = "motd";
+const MOTD_INPUT: &str = "text";
+
+/// Handles a submitted modal; only the `/motd edit` one exists.
+pub async fn handle_modal(state: &BotState, ctx: &Context, modal: &ModalInteraction) {
+    if modal.data.custom_id != MOTD_MODAL {
+        return;
+    }
+
+    let lang = resolve_language(
+        Some(&modal.locale),
+        state.config.bot_language,
+        state.config.force_bot_language,
+    );
+
+    let submitted = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == MOTD_INPUT => {
+                input.value.clone()
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let embed = match sanitize_motd(&submitted) {
+        Ok(text) => {
+            state.shared.persistent.write().await.motd_override = Some(text);
+            state.shared.save_persistent(&state.clock).await;
+            state.refresh.notify_one();
+            tracing::info!("MOTD edited by {}", modal.user.name);
+            embeds::motd_embed(Text::MotdUpdated, lang)
+        }
+        Err(rejection) => embeds::invalid_motd_embed(rejection, lang),
+    };
+
+    let reply = CreateInteractionResponseMessage::new()
+        .embed(embeds::to_create_embed(&embed))
+        .ephemeral(true);
+
+    if let Err(err) = modal
+        .create_response(&ctx.http, CreateInteractionResponse::Message(reply))
+        .await
+    {
+        tracing::warn!("Failed to reply to the MOTD editor: {err}");
+    }
+}
+
+const CLEANUP_CONFIRM: &str = "cleanup:confirm";
+const CLEANUP_CANCEL: &str = "cleanup:cancel";
+
+/// Handles a clicked button; only the `/cleanup` ones exist.
+pub async fn handle_component(state: &BotState, ctx: &Context, component: &ComponentInteraction) {
+    let confirmed = match component.data.custom_id.as_str() {
+        CLEANUP_CONFIRM => true,
+        CLEANUP_CANCEL => false,
+        _ => return,
+    };
+
+    let lang = resolve_language(
+        Some(&component.locale),
+        state.config.bot_language,
+        state.config.force_bot_language,
+    );
+
+    // Deleting many messages can outlast the time Discord gives for a reply.
+    let acknowledged = component
+        .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+        .await;
+    if let Err(err) = acknowledged {
+        tracing::warn!("Failed to acknowledge /cleanup: {err}");
+        return;
+    }
+
+    let pending = std::mem::take(&mut *state.cleanup_pending.lock().await);
+    let deleted = match (confirmed, state.status.get()) {
+        (true, Some(status)) => {
+            tracing::info!(
+                "Deleting {} old status message(s), confirmed by {}",
+                pending.len(),
+                component.user.name
+            );
+            Some(cleanup::delete(status.transport(), &pending).await)
+        }
+        _ => None,
+    };
+
+    let response = EditInteractionResponse::new()
+        .embed(embeds::to_create_embed(&embeds::cleanup_result_embed(
+            deleted, lang,
+        )))
+        .components(Vec::new());
+
+    if let Err(err) = component.edit_response(&ctx.http, response).await {
+        tracing::warn!("Failed to reply to /cleanup: {err}");
+    }
+}
+
+/// `/cleanup` lists the status messages no longer kept up to date, with buttons to delete or
+/// keep them. Looking through the channels can take a while, so the reply is deferred.
+async fn cleanup(state: &BotState, ctx: &Context, cmd: &CommandInteraction, lang: Language) {
+    if let Err(err) = cmd.defer_ephemeral(&ctx.http).await {
+        tracing::warn!("Failed to reply to /cleanup: {err}");
+        return;
+    }
+
+    let orphans = match (state.status.get(), ctx.http.get_current_user().await) {
+        (Some(status), Ok(me)) => {
+            let scope = state.shared.cleanup_scope(&state.config).await;
+            cleanup::scan(status.transport(), &scope, me.id.get()).await
+        }
+        (_, Err(err)) => {
+            tracing::warn!("Could not look up the bot's own user for /cleanup: {err}");
+            Vec::new()
+        }
+        (None, _) => Vec::new(),
+    };
+
+    let embed = embeds::cleanup_embed(&orphans, cmd.guild_id.map(|g| g.get()), lang);
+    let mut response = EditInteractionResponse::new().embed(embeds::to_create_embed(&embed));
+
+    if !orphans.is_empty() {
+        let delete = CreateButton::new(CLEANUP_CONFIRM)
+            .label(format!("{} ({})", lang.tr(Text::Delete), orphans.len()))
+            .style(ButtonStyle::Danger);
+        let cancel = CreateButton::new(CLEANUP_CANCEL)
+            .label(lang.tr(Text::Cancel))
+            .style(ButtonStyle::Secondary);
+        response = response.components(vec![CreateActionRow::Buttons(vec![delete, cancel])]);
+    }
+
+    *state.cleanup_pending.lock().await = orphans;
+
+    if let Err(err) = cmd.edit_response(&ctx.http, response).await {
+        tracing::warn!("Failed to reply to /cleanup: {err}");
+    }
+}
+
+/// Whether the invoking member may manage the server, and so sees admin-only details.
+fn is_admin(cmd: &CommandInteraction) -> bool {
+    cmd.member
+        .as_ref()
+        .and_then(|m| m.permissions)
+        .is_some_and(|p| p.manage_guild())
+}
+
+async fn status(state: &BotState, lang: Language, admin: bool) -> EmbedRepresentation {
+    let embed = embeds::status_embed(
+        &state.config,
+        &*state.shared.last_observation.read().await,
+        lang,
+    );
+
+    let snoozed_until = state
+        .shared
+        .persistent
+        .read()
+        .await
+        .snoozed_until_unix
+        .filter(|until| *until > state.clock.unix());
+
+    match snoozed_until {
+        Some(until) if admin => embeds::with_snooze_field(embed, until, lang),
+        _ => embed,
+    }
+}
+
+async fn players(state: &BotState, lang: Language) -> EmbedRepresentation {
+    let players = match state.server_addr().await {
+        Ok(addr) => query::players(addr, query::QUERY_TIMEOUT).await,
+        Err(err) => Err(err),
+    };
+
+    match players {
+        Ok(players) => embeds::players_embed(&state.config, &players, lang),
+        Err(err) => embeds::offline_embed(&state.config, &err.to_string(), lang),
+    }
+}
+
+/// How long a `/stats` result is reused for the same guild.
+const STATS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+async fn stats(state: &BotState, cmd: &CommandInteraction, lang: Language) -> EmbedRepresentation {
+    let cached = state
+        .stats_cache
+        .lock()
+        .await
+        .get(&cmd.guild_id)
+        .filter(|(at, _)| at.elapsed() < STATS_CACHE_TTL)
+        .map(|(_, report)| report.clone());
+
+    let report = match cached {
+        Some(report) => report,
+        None => {
+            let report = stats_report(state).await;
+            state
+                .stats_cache
+                .lock()
+                .await
+                .insert(cmd.guild_id, (Instant::now(), report.clone()));
+            report
+        }
+    };
+
+    embeds::stats_embed(&state.config, &report, lang)
+}
+
+async fn stats_report(state: &BotState) -> StatsReport {
+    // Validated at startup.
+    let tz = state.config.stats_timezone().unwrap_or(chrono_tz::UTC);
+    let persistent = state.shared.persistent.read().await;
+    let now = state.clock.unix();
+
+    let mut report = daily::report(
+        &persistent.daily,
+        &persistent.player_hours,
+        &persistent.monitoring_gaps,
+        now,
+        tz,
+    );
+    if state.config.event_impact {
+        report.events = events::impacts(
+            &persistent.hourly_players,
+            &persistent.events.events,
+            now,
+            tz,
+        );
+    }
+    report
+}
+
+async fn snooze(state: &BotState, cmd: &CommandInteraction, lang: Language) -> EmbedRepresentation {
+    let arg = cmd
+        .data
+        .options
+        .first()
+        .and_then(|o| o.value.as_str())
+        .unwrap_or_default();
+
+    if arg.trim().eq_ignore_ascii_case("off") {
+        state.shared.persistent.write().await.snoozed_until_unix = None;
+        state.shared.save_persistent(&state.clock).await;
+        tracing::info!("Alerts un-snoozed by {}", cmd.user.name);
+        return embeds::snooze_embed(None, lang);
+    }
+
+    match parse_duration(arg).filter(|secs| *secs <= MAX_SNOOZE_SECS) {
+        Some(secs) => {
+            let until = state.clock.unix() + secs;
+            state.shared.persistent.write().await.snoozed_until_unix = Some(until);
+            state.shared.save_persistent(&state.clock).await;
+            tracing::info!("Alerts snoozed by {} until {until}", cmd.user.name);
+            embeds::snooze_embed(Some(until), lang)
+        }
+        None => embeds::invalid_snooze_embed(lang),
+    }
+}
+
+fn audit(state: &BotState, cmd: &CommandInteraction, lang: Language) -> EmbedRepresentation {
+    let hours = cmd
+        .data
+        .options
+        .first()
+        .and_then(|o| o.value.as_i64())
+        .map_or(24, |h| h.max(1) as u64);
+
+    let Some(audit) = &state.shared.audit else {
+        return embeds::audit_embed(None, hours, lang);
+    };
+
+    match audit.read_since(state.clock.unix().saturating_sub(hours * 3600)) {
+        Ok(entries) => embeds::audit_embed(Some(&entries), hours, lang),
+        Err(err) => embeds::offline_embed(&state.config, &err.to_string(), lang),
+    }
+}
+
+/// How long `/refresh verbose` waits for the cycle it started.
+const REFRESH_WAIT: Duration = Duration::from_secs(60);
+
+fn verbose(cmd: &CommandInteraction) -> bool {
+    cmd.data
+        .options
+        .iter()
+        .find(|o| o.name == "verbose")
+        .and_then(|o| o.value.as_bool())
+        .unwrap_or(false)
+}
+
+/// `/refresh verbose:true` starts a cycle, waits for it to finish and replies with where its
+/// time went. A cycle already running when the command arrives doesn't count, since it may
+/// have started before whatever the admin wants to see.
+async fn refresh_verbose(state: &BotState, ctx: &Context, cmd: &CommandInteraction) {
+    if let Err(err) = cmd.defer_ephemeral(&ctx.http).await {
+        tracing::warn!("Failed to reply to /refresh: {err}");
+        return;
+    }
+
+    let requested = Instant::now();
+    let mut reports = state.cycle_report.subscribe();
+    state.refresh.notify_one();
+    tracing::info!("Verbose refresh requested by {}", cmd.user.name);
+
+    let wait = reports.wait_for(|r| r.as_ref().is_some_and(|r| r.started >= requested));
+    let report = match tokio::time::timeout(REFRESH_WAIT, wait).await {
+        Ok(Ok(report)) => report.clone(),
+        _ => None,
+    };
+
+    let embed = embeds::cycle_report_embed(&state.config, report.as_ref());
+    let response = EditInteractionResponse::new().embed(embeds::to_create_embed(&embed));
+
+    if let Err(err) = cmd.edit_response(&ctx.http, response).await {
+        tracing::warn!("Failed to reply to /refresh: {err}");
+    }
+}
+
+enum MotdReply {
+    Embed(EmbedRepresentation),
+    /// A modal to edit the MOTD in
+    Editor(CreateModal),
+}
+
+/// `/motd reset` is answered right away; `/motd edit` opens an editor pre-filled with the MOTD
+/// in effect.
+async fn motd(state: &BotState, cmd: &CommandInteraction, lang: Language) -> MotdReply {
+    let subcommand = cmd.data.options.first().map(|o| o.name.as_str());
+
+    if subcommand == Some("reset") {
+        state.shared.persistent.write().await.motd_override = None;
+        state.shared.save_persistent(&state.clock).await;
+        state.refresh.notify_one();
+        tracing::info!("MOTD reset by {}", cmd.user.name);
+        return MotdReply::Embed(embeds::motd_embed(Text::MotdReset, lang));
+    }
+
+    let current = effective_motd(
+        state.config.motd.as_deref(),
+        state
+            .shared
+            .persistent
+            .read()
+            .await
+            .motd_override
+            .as_deref(),
+    );
+
+    let input = CreateInputText::new(InputTextStyle::Paragraph, lang.tr(Text::Motd), MOTD_INPUT)
+        .value(current.unwrap_or_default())
+        .max_length(MOTD_LIMIT as u16)
+        .required(false);
+
+    MotdReply::Editor(
+        CreateModal::new(MOTD_MODAL, lang.tr(Text::Motd))
+            .components(vec![CreateActionRow::InputText(input)]),
+    )
+}
+
+async fn ping(state: &BotState, ctx: &Context, lang: Language) -> EmbedRepresentation {
+    let timeout = Duration::from_millis(state.config.ping_timeout_ms);
+
+    let gateway = match state.shard_manager.get() {
+        Some(manager) => manager
+            .runners
+            .lock()
+            .await
+            .get(&ctx.shard_id)
+            .and_then(|runner| runner.latency),
+        None => None,
+    };
+
+    let (rest, a2s) = tokio::join!(measure(ctx.http.get_current_user(), timeout), async {
+        match state.server_addr().await {
+            Ok(addr) => probe_a2s(addr, timeout).await,
+            Err(_) => None,
+        }
+    });
+
+    let hops = [
+        (Text::Gateway, gateway),
+        (Text::DiscordApi, rest),
+        (Text::GameServer, a2s),
+    ];
+
+    embeds::ping_embed(&hops, &state.config.latency_thresholds(), lang)
+}
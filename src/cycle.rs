@@ -0,0 +1,1515 @@
+use std::{
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use tokio::sync::RwLock;
+
+#[cfg(feature = "charts")]
+use crate::chart::CHART_SPAN_SECS;
+use crate::{
+    alerts::{alert_rules, suppression, Alert, AlertTracker},
+    audit::{state_name, AuditEntry, AuditEvent, AuditLog},
+    cleanup::{self, CleanupScope, STATUS_MARKER},
+    clock::{self, Clock, StepDetector},
+    dualstack::{DualStack, Family},
+    events::ScheduledEvent,
+    forecast::{self, SampleWindow, RAMP_WINDOW_SECS},
+    gameport::{self, JoinPortCheck, PROBE_TIMEOUT},
+    headcount::{CountCheck, CountMismatch, Headcount},
+    hook::{PostRender, HOOK_BUDGET},
+    maps,
+    mods::{query_mods, sort_mods, Mod},
+    monitor::{Counts, Monitor, Observation},
+    motd::effective_motd,
+    oplog::{OpEvent, OpLogBatch},
+    output::{render_output, Encoders, Outputs, Rendered},
+    query, reconcile,
+    regions::{self, DirStore, PingStore, RegionPing},
+    render::{self, EmbedRepresentation},
+    resolve_server_address, retrieve_server_info,
+    retry::RetryPolicy,
+    roster::{self, Sessions},
+    schedule::{self, Window},
+    state::{
+        FileStore, GuardedStore, PersistentState, SaveOutcome, SUSPEND_AFTER_FAILURES,
+        WRITE_PROBE_INTERVAL_SECS,
+    },
+    status_file::{write_status_file, StatusFile},
+    subsystem::{self, Subsystems},
+    topic::{render_topic, TopicUpdater},
+    transport::{
+        render_or_placeholder, verify_own_message, EditGuard, EditOutcome, Priority,
+        RenderSequence, SendRequest, StatusMessage, StatusTransport, StatusUpdate,
+        PLACEHOLDER_BUDGET,
+    },
+    wipe::{self, WipeChannel, WipeEvent, WipePlan},
+    DayzMonitorConfig, DayzMonitorError, MonitorState, SanityBounds, ServerInfo,
+};
+
+/// Edits slower than this are logged as a warning.
+pub const SLOW_EDIT: Duration = Duration::from_secs(5);
+
+/// How often the guild's scheduled events are fetched for the event impact report.
+pub const EVENT_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// What a cycle's log line reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleSnapshot {
+    pub state: MonitorState,
+    pub players: Option<u32>,
+    pub max_players: Option<u32>,
+    pub queue: Option<u32>,
+    pub map: Option<String>,
+}
+
+impl CycleSnapshot {
+    pub fn of(observation: &Observation) -> Self {
+        let info = observation.info.as_ref();
+
+        Self {
+            state: observation.state,
+            players: info.map(|i| i.players),
+            max_players: info.map(|i| i.max_players),
+            queue: info.map(|i| i.players_in_queue.unwrap_or(0)),
+            map: info.map(|i| i.map.clone()),
+        }
+    }
+}
+
+/// One value that differs from the previous cycle; `None` is a value the cycle had no sample
+/// for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CycleChange {
+    Status {
+        from: MonitorState,
+        to: MonitorState,
+    },
+    Players {
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    MaxPlayers {
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    Queue {
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    Map {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+/// Compares two cycles value by value, in the order they are logged.
+pub fn diff(prev: &CycleSnapshot, next: &CycleSnapshot) -> Vec<CycleChange> {
+    let mut changes = Vec::new();
+
+    if prev.state != next.state {
+        changes.push(CycleChange::Status {
+            from: prev.state,
+            to: next.state,
+        });
+    }
+    if prev.players != next.players {
+        changes.push(CycleChange::Players {
+            from: prev.players,
+            to: next.players,
+        });
+    }
+    if prev.max_players != next.max_players {
+        changes.push(CycleChange::MaxPlayers {
+            from: prev.max_players,
+            to: next.max_players,
+        });
+    }
+    if prev.queue != next.queue {
+        changes.push(CycleChange::Queue {
+            from: prev.queue,
+            to: next.queue,
+        });
+    }
+    if prev.map != next.map {
+        changes.push(CycleChange::Map {
+            from: prev.map.clone(),
+            to: next.map.clone(),
+        });
+    }
+
+    changes
+}
+
+fn value<T: fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "–".to_string(), ToString::to_string)
+}
+
+impl fmt::Display for CycleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CycleChange::Status { from, to } => {
+                write!(f, "status {}→{}", state_name(*from), state_name(*to))
+            }
+            CycleChange::Players { from, to } => {
+                write!(f, "players {}→{}", value(from), value(to))
+            }
+            CycleChange::MaxPlayers { from, to } => {
+                write!(f, "slots {}→{}", value(from), value(to))
+            }
+            CycleChange::Queue { from, to } => write!(f, "queue {}→{}", value(from), value(to)),
+            CycleChange::Map { from, to } => write!(f, "map {}→{}", value(from), value(to)),
+        }
+    }
+}
+
+/// A cycle's log line, e.g. `online 51/60, queue 2 (players 44→51, queue 0→2, status
+/// unchanged)`. The change list is left out when nothing changed, and on the first cycle.
+#[derive(Debug, Clone)]
+pub struct CycleSummary {
+    pub snapshot: CycleSnapshot,
+    pub changes: Vec<CycleChange>,
+}
+
+impl CycleSummary {
+    pub fn new(prev: Option<&CycleSnapshot>, snapshot: CycleSnapshot) -> Self {
+        let changes = prev.map(|prev| diff(prev, &snapshot)).unwrap_or_default();
+
+        Self { snapshot, changes }
+    }
+
+    /// Whether the monitor state changed this cycle.
+    pub fn is_transition(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, CycleChange::Status { .. }))
+    }
+}
+
+impl fmt::Display for CycleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let snapshot = &self.snapshot;
+        write!(f, "{}", state_name(snapshot.state))?;
+
+        if let (Some(players), Some(max_players)) = (snapshot.players, snapshot.max_players) {
+            write!(
+                f,
+                " {players}/{max_players}, queue {}",
+                value(&snapshot.queue)
+            )?;
+        }
+
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+
+        let list: Vec<String> = self.changes.iter().map(ToString::to_string).collect();
+        write!(f, " ({}", list.join(", "))?;
+        if !self.is_transition() {
+            write!(f, ", status unchanged")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Where a cycle's time went, for `/refresh verbose` and the slow edit warning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleReport {
+    pub started: Instant,
+    /// Resolving `SERVER_ADDRESS`; `None` when the cycle didn't query, e.g. in maintenance
+    pub resolve: Option<Duration>,
+    /// A2S queries in the order they started
+    pub attempts: Vec<QueryAttempt>,
+    pub render: Option<Duration>,
+    /// Editing the status message; `None` when it was unchanged
+    pub edit: Option<Duration>,
+    pub total: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryAttempt {
+    /// `info`, `rules` or `players`
+    pub query: &'static str,
+    pub addr: SocketAddr,
+    pub took: Duration,
+    pub outcome: AttemptOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Answered,
+    Failed(String),
+    /// Dropped unfinished, like the slower family of a dual-stack race
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Resolve,
+    Render,
+    Edit,
+}
+
+/// Collects a [`CycleReport`] while a cycle runs. Shared by reference, so query closures can
+/// record their attempts.
+#[derive(Debug)]
+pub struct CycleTimer {
+    started: Instant,
+    report: Mutex<CycleReport>,
+}
+
+impl CycleTimer {
+    pub fn start() -> Self {
+        let started = Instant::now();
+
+        Self {
+            started,
+            report: Mutex::new(CycleReport {
+                started,
+                resolve: None,
+                attempts: Vec::new(),
+                render: None,
+                edit: None,
+                total: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Adds `took` to `phase`, which may run more than once per cycle.
+    pub fn record(&self, phase: Phase, took: Duration) {
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        let slot = match phase {
+            Phase::Resolve => &mut report.resolve,
+            Phase::Render => &mut report.render,
+            Phase::Edit => &mut report.edit,
+        };
+        *slot = Some(slot.unwrap_or_default() + took);
+    }
+
+    /// Runs `fut`, counting its time towards `phase`.
+    pub async fn time<T>(&self, phase: Phase, fut: impl Future<Output = T>) -> T {
+        let started = Instant::now();
+        let out = fut.await;
+        self.record(phase, started.elapsed());
+        out
+    }
+
+    /// Runs one `query` to `addr`, recording how long it took and how it ended, including
+    /// when it is dropped before finishing.
+    pub async fn attempt<T, E: fmt::Display>(
+        &self,
+        query: &'static str,
+        addr: SocketAddr,
+        fut: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let mut guard = AttemptGuard {
+            report: &self.report,
+            query,
+            addr,
+            started: Instant::now(),
+            outcome: Some(AttemptOutcome::Cancelled),
+        };
+
+        let result = fut.await;
+        guard.outcome = Some(match &result {
+            Ok(_) => AttemptOutcome::Answered,
+            Err(err) => AttemptOutcome::Failed(err.to_string()),
+        });
+
+        result
+    }
+
+    pub fn finish(self) -> CycleReport {
+        let mut report = self
+            .report
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        report.total = self.started.elapsed();
+        report
+    }
+}
+
+struct AttemptGuard<'a> {
+    report: &'a Mutex<CycleReport>,
+    query: &'static str,
+    addr: SocketAddr,
+    started: Instant,
+    outcome: Option<AttemptOutcome>,
+}
+
+impl Drop for AttemptGuard<'_> {
+    fn drop(&mut self) {
+        let attempt = QueryAttempt {
+            query: self.query,
+            addr: self.addr,
+            took: self.started.elapsed(),
+            outcome: self.outcome.take().unwrap_or(AttemptOutcome::Cancelled),
+        };
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        report.attempts.push(attempt);
+    }
+}
+
+fn millis(d: Duration) -> String {
+    format!("{}ms", d.as_millis())
+}
+
+impl fmt::Display for QueryAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.query, self.addr, millis(self.took))?;
+        match &self.outcome {
+            AttemptOutcome::Answered => Ok(()),
+            AttemptOutcome::Failed(err) => write!(f, " failed: {err}"),
+            AttemptOutcome::Cancelled => write!(f, " cancelled"),
+        }
+    }
+}
+
+impl fmt::Display for CycleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = |d: Option<Duration>| d.map_or_else(|| "–".to_string(), millis);
+        let attempts: Vec<String> = self.attempts.iter().map(ToString::to_string).collect();
+
+        write!(
+            f,
+            "resolve {}, queries [{}], render {}, edit {}, total {}",
+            phase(self.resolve),
+            attempts.join("; "),
+            phase(self.render),
+            phase(self.edit),
+            millis(self.total)
+        )
+    }
+}
+
+/// What the status loop shares with slash commands.
+pub struct SharedState {
+    /// The status message, once adopted or posted
+    pub status_message_id: RwLock<Option<u64>>,
+
+    /// Address the last successful query went to, so commands use the same family
+    pub query_addr: RwLock<Option<SocketAddr>>,
+
+    /// Outcome of the most recent poll, for slash commands
+    pub last_observation: RwLock<Observation>,
+    pub render_seq: RenderSequence,
+
+    /// Mirrors the state file; kept in memory only when `STATE_FILE` is unset
+    pub persistent: RwLock<PersistentState>,
+
+    /// Where `persistent` is saved; unset without a usable `STATE_FILE`
+    pub state_store: Option<tokio::sync::Mutex<GuardedStore<FileStore>>>,
+
+    pub audit: Option<AuditLog>,
+
+    /// Health of the optional subsystems, which never stop the status loop
+    pub subsystems: Subsystems,
+
+    /// Changes applied to the status embed before it is sent, such as `EMBED_PATCH_FILE`
+    pub post_render: PostRender,
+}
+
+impl SharedState {
+    /// Starts from `persistent`, without a state file, audit log or render hooks.
+    pub fn new(persistent: PersistentState) -> Self {
+        Self {
+            status_message_id: RwLock::new(None),
+            query_addr: RwLock::new(None),
+            last_observation: RwLock::new(Observation::unknown()),
+            render_seq: RenderSequence::default(),
+            persistent: RwLock::new(persistent),
+            state_store: None,
+            audit: None,
+            subsystems: Subsystems::default(),
+            post_render: PostRender::new(),
+        }
+    }
+
+    /// Every transition and alert goes through here, so the audit log can't miss one.
+    pub fn record_audit(&self, event: AuditEvent, clock: &impl Clock) {
+        let Some(audit) = self
+            .audit
+            .as_ref()
+            .filter(|_| self.subsystems.is_enabled(subsystem::AUDIT_LOG))
+        else {
+            return;
+        };
+
+        let entry = AuditEntry {
+            at_unix: clock.unix(),
+            event,
+        };
+
+        self.subsystems
+            .record(subsystem::AUDIT_LOG, audit.append(&entry));
+    }
+
+    pub async fn save_persistent(&self, clock: &impl Clock) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+        let mut store = store.lock().await;
+
+        match store.save(&*self.persistent.read().await, clock.unix()) {
+            SaveOutcome::Saved | SaveOutcome::Resumed => {
+                self.subsystems
+                    .record(subsystem::STATE_FILE, Ok::<(), String>(()));
+            }
+            SaveOutcome::Failed(err) => self.subsystems.record(subsystem::STATE_FILE, Err(err)),
+            SaveOutcome::Suspended(err) => {
+                tracing::error!(
+                    "Saving the state failed {SUSPEND_AFTER_FAILURES} times in a row ({err}). \
+                     Keeping it in memory only and checking every {} minutes whether writes \
+                     work again; anything that changes meanwhile is lost on restart.",
+                    WRITE_PROBE_INTERVAL_SECS / 60
+                );
+                self.subsystems
+                    .record(subsystem::STATE_FILE, Err(format!("in memory only, {err}")));
+            }
+            SaveOutcome::Skipped => {}
+        }
+    }
+
+    /// The channels status messages are posted in, and the messages still kept up to date
+    /// there.
+    pub async fn cleanup_scope(&self, config: &DayzMonitorConfig) -> CleanupScope {
+        let mut scope = CleanupScope::default();
+
+        scope.channels.insert(config.text_channel_id);
+        // Validated at startup.
+        let outputs = config.outputs().unwrap_or_default();
+        scope.channels.extend(outputs.iter().map(|o| o.channel_id));
+
+        if let Some(id) = *self.status_message_id.read().await {
+            scope.tracked.insert(id);
+        }
+        let persistent = self.persistent.read().await;
+        scope
+            .tracked
+            .extend(persistent.output_message_ids.values().copied());
+        scope
+            .tracked
+            .extend(persistent.mod_list_message_ids.iter().copied());
+
+        scope
+    }
+}
+
+/// One poll of the server.
+#[derive(Debug)]
+pub struct Polled {
+    /// The sample, and the address that answered it
+    pub result: Result<(ServerInfo, SocketAddr), DayzMonitorError>,
+    /// Whether a rules query succeeded, which completes a recovery under
+    /// `RECOVERY_CHECK=joinable`
+    pub rules_answered: bool,
+}
+
+/// Where a cycle's samples come from.
+pub trait Poller: Send {
+    /// Queries the server once, recording every query on `timer`. `needs_rules` asks for a
+    /// rules query even when the mod list isn't queried.
+    fn poll(
+        &mut self,
+        needs_rules: bool,
+        timer: &CycleTimer,
+    ) -> impl Future<Output = Polled> + Send;
+}
+
+/// Polls `SERVER_ADDRESS` over A2S, with the extra queries the config turns on.
+#[derive(Debug)]
+pub struct A2sPoller {
+    config: DayzMonitorConfig,
+    bounds: SanityBounds,
+    retry: RetryPolicy,
+    dual_stack: DualStack,
+}
+
+impl A2sPoller {
+    pub fn new(config: &DayzMonitorConfig) -> Self {
+        Self {
+            config: config.clone(),
+            bounds: config.sanity_bounds(),
+            retry: config.a2s_retry(),
+            dual_stack: DualStack::default(),
+        }
+    }
+}
+
+impl Poller for A2sPoller {
+    async fn poll(&mut self, needs_rules: bool, timer: &CycleTimer) -> Polled {
+        let transient = DayzMonitorError::is_transient;
+        let retry = &self.retry;
+        let resolved = timer
+            .time(
+                Phase::Resolve,
+                resolve_server_address(&self.config.server_address),
+            )
+            .await;
+        let mut result = match resolved {
+            Ok(addrs) => {
+                self.dual_stack
+                    .query(&addrs, |addr| {
+                        let bounds = &self.bounds;
+                        let info =
+                            retry.run_if(move || retrieve_server_info(addr, bounds), transient);
+                        timer.attempt("info", addr, info)
+                    })
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Ok((_, addr)) = &result {
+            tracing::debug!(
+                "Queried '{}' over {} ({addr})",
+                self.config.server_address,
+                Family::of(addr).name()
+            );
+        }
+
+        let mut rules_answered = false;
+
+        if let (true, Ok((info, addr))) = (self.config.mod_list, &mut result) {
+            let mods = retry.run_if(|| query_mods(*addr, Duration::from_secs(5)), transient);
+            match timer.attempt("rules", *addr, mods).await {
+                Ok(mut mods) => {
+                    sort_mods(&mut mods);
+                    info.mods = Some(mods);
+                    rules_answered = true;
+                }
+                Err(err) => tracing::debug!("Failed to query mod list: {err}"),
+            }
+        }
+
+        if let (true, Ok((info, addr))) = (self.config.verify_player_count, &mut result) {
+            let players = retry.run_if(|| query::players(*addr, Duration::from_secs(5)), transient);
+            match timer.attempt("players", *addr, players).await {
+                Ok(players) => {
+                    info.headcount = Some(Headcount::of(&players));
+                    info.player_names = Some(roster::listed_names(&players));
+                }
+                Err(err) => tracing::debug!("Failed to query player list: {err}"),
+            }
+        }
+
+        if let (true, Ok((info, addr))) = (self.config.game_port_probe.is_on(), &mut result) {
+            match gameport::target(*addr, self.config.game_port, info.game_port) {
+                Some(target) => info.join_port = Some(gameport::probe(target, PROBE_TIMEOUT).await),
+                None => tracing::debug!("No game port to probe; set GAME_PORT"),
+            }
+        }
+
+        if let (false, true, Ok((_, addr))) = (rules_answered, needs_rules, &result) {
+            let rules = retry.run_if(
+                || query::raw_rules(*addr, Duration::from_secs(5)),
+                transient,
+            );
+            rules_answered = timer.attempt("rules", *addr, rules).await.is_ok();
+        }
+
+        Polled {
+            result,
+            rules_answered,
+        }
+    }
+}
+
+/// What a cycle leaves to the bot it runs in: drawing messages for its transport, and the chat
+/// features this library has no interface for. The binary implements it over serenity.
+pub trait CycleHost: Send + Sync {
+    type Transport: StatusTransport;
+
+    /// Builds the posts and edits of the status message, outputs, alerts and wipe
+    /// announcements.
+    fn encoders(&self) -> &Encoders<Self::Transport>;
+
+    /// The status embed under `config`, before the post-render hooks.
+    fn status_embed(
+        &self,
+        config: &DayzMonitorConfig,
+        observation: &Observation,
+    ) -> EmbedRepresentation;
+
+    /// An edit of the status message to show `embed`, leaving its text alone.
+    fn status_edit(
+        &self,
+        embed: &EmbedRepresentation,
+    ) -> <Self::Transport as StatusTransport>::Payload;
+
+    /// One log channel message, with an entry per event.
+    fn log_message(
+        &self,
+        config: &DayzMonitorConfig,
+        events: &[OpEvent],
+        at_unix: u64,
+    ) -> <Self::Transport as StatusTransport>::Message;
+
+    /// Whether a message lookup failed because the message isn't there.
+    fn is_not_found(&self, err: &<Self::Transport as StatusTransport>::Error) -> bool;
+
+    /// The scheduled events of the status channel's guild, for the event impact report.
+    fn scheduled_events(
+        &self,
+    ) -> impl Future<Output = Result<Vec<ScheduledEvent>, <Self::Transport as StatusTransport>::Error>>
+           + Send;
+
+    /// Keeps the companion messages of a mod list too long for the status embed in step.
+    fn sync_mod_list(
+        &self,
+        transport: &Self::Transport,
+        mods: &[Mod],
+    ) -> impl Future<Output = ()> + Send;
+
+    /// Attaches a chart of `samples` to the alert posted as `message_id`, off the cycle.
+    #[cfg(feature = "charts")]
+    fn attach_chart(
+        &self,
+        _channel_id: u64,
+        _message_id: u64,
+        _samples: Vec<(u64, u32)>,
+        _max_players: Option<u32>,
+        _now_unix: u64,
+    ) {
+    }
+
+    /// Sends an alert to the notification backends, off the cycle.
+    fn notify(&self, _alert: &Alert) {}
+}
+
+/// A status loop between two cycles: what the server did, what was last shown where, and when
+/// the periodic chores last ran.
+pub struct Cycle<P> {
+    config: DayzMonitorConfig,
+    poller: P,
+    bot_user_id: u64,
+    monitor: Monitor,
+    alerts: AlertTracker,
+    maintenance: Vec<Window>,
+    wipe_plan: Option<WipePlan>,
+    stats_tz: Tz,
+    edit_guard: EditGuard,
+    outputs: Outputs,
+    topic_updater: Option<TopicUpdater>,
+    step_detector: StepDetector,
+    last_state: MonitorState,
+
+    /// What the previous cycle's log line reported
+    last_cycle: Option<CycleSnapshot>,
+
+    /// What the status message was last successfully edited to show
+    last_rendered: Option<(u64, EmbedRepresentation)>,
+
+    /// The status message the log channel last heard about
+    announced: Option<u64>,
+
+    /// Counts of the last online poll, reset by anything else but a degraded one
+    last_counts: Option<Counts>,
+    last_mismatch: Option<CountMismatch>,
+    count_check: CountCheck,
+    last_join_port: Option<u16>,
+    join_port_check: JoinPortCheck,
+    ramp: SampleWindow,
+
+    /// Player counts for alert charts, with the server down counting as 0
+    #[cfg(feature = "charts")]
+    history: SampleWindow,
+    #[cfg(feature = "charts")]
+    last_max_players: Option<u32>,
+
+    sessions: Sessions,
+    last_audit_prune: u64,
+    last_cleanup: u64,
+    events_fetched: Option<Instant>,
+}
+
+impl<P: Poller> Cycle<P> {
+    /// Gets a status loop going: adopts `STATUS_MESSAGE_ID` if the bot posted it, checks the
+    /// saved message ids and those in `OUTPUTS`. Fails only when `RECONCILE_POLICY=strict`
+    /// finds a message it can't confirm.
+    pub async fn start<H>(
+        config: &DayzMonitorConfig,
+        poller: P,
+        status: &StatusMessage<H::Transport>,
+        host: &H,
+        shared: &SharedState,
+        bot_user_id: u64,
+        clock: &impl Clock,
+    ) -> Result<Self, DayzMonitorError>
+    where
+        H: CycleHost,
+        <H::Transport as StatusTransport>::Error: fmt::Display,
+    {
+        let channel_id = config.text_channel_id;
+        // Replaces the previous run's document, which may still look fresh.
+        write_status(
+            config,
+            &shared.subsystems,
+            &Observation::unknown(),
+            clock.unix(),
+        );
+
+        if let Some(mid) = config.status_message_id {
+            let verified =
+                verify_own_message(status.transport(), channel_id, mid, bot_user_id).await;
+            let adopt = match verified {
+                Ok(Ok(())) => true,
+                Ok(Err(err)) => {
+                    tracing::error!(
+                        "Not using STATUS_MESSAGE_ID: {err} Sending a new one instead."
+                    );
+                    false
+                }
+                // Possibly transient; the edits will report it if it persists.
+                Err(err) => {
+                    tracing::warn!("Could not check who authored status message {mid}: {err}");
+                    true
+                }
+            };
+
+            if adopt {
+                *shared.status_message_id.write().await = Some(mid);
+            }
+        }
+
+        // Before the first cycle edits them, and saved right away so a restart doesn't bring
+        // dropped ids back.
+        let reconciled = reconcile::reconcile(
+            status.transport(),
+            config,
+            &mut *shared.persistent.write().await,
+            bot_user_id,
+            |err| host.is_not_found(err),
+        )
+        .await;
+        match reconciled? {
+            summary if summary.changed() => {
+                tracing::info!("Saved messages: {summary}");
+                shared.save_persistent(clock).await;
+            }
+            summary => tracing::debug!("Saved messages: {summary}"),
+        }
+
+        let mut outputs = Outputs::new(
+            config,
+            config.outputs().unwrap_or_default(),
+            &shared.persistent.read().await.output_message_ids,
+        );
+        outputs.adopt(status.transport(), bot_user_id).await;
+
+        Ok(Self {
+            config: config.clone(),
+            poller,
+            bot_user_id,
+            monitor: Monitor::new(config.recovery_criterion()),
+            alerts: AlertTracker::new(alert_rules(config), config.alert_latency_window),
+            // Validated at startup.
+            maintenance: config.maintenance_schedule().unwrap_or_default(),
+            wipe_plan: config.wipe_plan().unwrap_or_default(),
+            stats_tz: config.stats_timezone().unwrap_or(chrono_tz::UTC),
+            edit_guard: config.status_edit_guard(),
+            outputs,
+            topic_updater: config
+                .topic_channel_id
+                .map(|id| TopicUpdater::new(id, config.topic_min_interval_secs)),
+            step_detector: StepDetector::default(),
+            last_state: MonitorState::Unknown,
+            last_cycle: None,
+            last_rendered: None,
+            announced: *shared.status_message_id.read().await,
+            last_counts: None,
+            last_mismatch: None,
+            count_check: CountCheck::new(
+                config.player_count_tolerance,
+                config.player_count_mismatch_polls,
+            ),
+            last_join_port: None,
+            join_port_check: JoinPortCheck::new(
+                config.game_port_probe,
+                config.game_port_unreachable_polls,
+            ),
+            ramp: SampleWindow::new(RAMP_WINDOW_SECS),
+            #[cfg(feature = "charts")]
+            history: SampleWindow::new(CHART_SPAN_SECS),
+            #[cfg(feature = "charts")]
+            last_max_players: None,
+            sessions: Sessions::new(config.tracked_sessions),
+            last_audit_prune: 0,
+            last_cleanup: 0,
+            events_fetched: None,
+        })
+    }
+
+    /// Runs one cycle: polls the server, or not during maintenance, updates the statistics,
+    /// sends alerts and announcements, brings the status message and outputs up to date and
+    /// saves the state. Returns where the time went.
+    pub async fn run<H>(
+        &mut self,
+        status: &StatusMessage<H::Transport>,
+        host: &H,
+        shared: &SharedState,
+        clock: &(impl Clock + Sync),
+    ) -> CycleReport
+    where
+        H: CycleHost,
+        <H::Transport as StatusTransport>::Error: fmt::Display,
+    {
+        let seq = shared.render_seq.next();
+        let timer = CycleTimer::start();
+        let config = &self.config;
+        let transport = status.transport();
+        let channel_id = config.text_channel_id;
+        let now = clock.unix();
+
+        let poll = poll(
+            &mut self.monitor,
+            &mut self.poller,
+            &self.maintenance,
+            shared,
+            &timer,
+            now,
+        );
+
+        // The first status is posted directly when the poll is quick, and only replaces a
+        // placeholder when it is not.
+        let mut observation = if shared.status_message_id.read().await.is_some() {
+            poll.await
+        } else {
+            let placeholder = async {
+                let embed = host
+                    .status_embed(config, &Observation::unknown())
+                    .footer(STATUS_MARKER);
+                post_status_message(config, transport, host, shared, embed).await;
+            };
+            render_or_placeholder(poll, PLACEHOLDER_BUDGET, placeholder).await
+        };
+
+        let summary = CycleSummary::new(self.last_cycle.as_ref(), CycleSnapshot::of(&observation));
+        if summary.is_transition() {
+            tracing::warn!("Cycle: {summary}");
+        } else {
+            tracing::info!("Cycle: {summary}");
+        }
+        self.last_cycle = Some(summary.snapshot);
+
+        write_status(config, &shared.subsystems, &observation, now);
+
+        observation.other_regions =
+            share_region_ping(config, &shared.subsystems, &observation, now);
+        let clock_step = self.step_detector.read(clock);
+        if let Some(step) = clock_step {
+            tracing::warn!("System clock stepped by {step:+}s, re-anchoring timers");
+            self.alerts.shift_clock(step);
+            self.sessions.shift_clock(step);
+            self.ramp.clear();
+            self.last_audit_prune = clock::shift(self.last_audit_prune, step);
+            self.last_cleanup = clock::shift(self.last_cleanup, step);
+        }
+        let mut oplog = OpLogBatch::new(now);
+        let stats_tz = self.stats_tz;
+        // A few missed polls still count as continuous play; a longer gap means the bot
+        // itself was down.
+        let max_sample_gap = config.update_interval_secs * 3;
+        let wipe_event;
+
+        {
+            let mut persistent = shared.persistent.write().await;
+            let persistent = &mut *persistent;
+            if let Some(step) = clock_step {
+                persistent.player_hours.shift_clock(step);
+            }
+
+            // Missing a moment by a few polls is still on time; jumping over it is not.
+            wipe_event = self.wipe_plan.as_ref().and_then(|plan| {
+                clock_step
+                    .filter(|step| *step > 0)
+                    .and_then(|step| wipe::skip_stepped(plan, &mut persistent.wipe, now, step))
+                    .or_else(|| {
+                        wipe::due(
+                            plan,
+                            config.wipe_missed_announcements,
+                            &mut persistent.wipe,
+                            now,
+                            max_sample_gap,
+                        )
+                    })
+            });
+            if matches!(wipe_event, Some(WipeEvent::Wiped { .. })) && config.wipe_reset_stats {
+                tracing::info!("Server wiped, starting the statistics over");
+                wipe::reset_stats(persistent);
+            }
+            observation.wipe = wipe::field(self.wipe_plan.as_ref(), &persistent.wipe, now);
+
+            maps::track(
+                &mut persistent.map_rotation,
+                &mut persistent.map_stats,
+                &mut observation,
+                now,
+            );
+
+            let time_reported = observation
+                .info
+                .as_ref()
+                .is_some_and(|i| i.server_time.is_some());
+            persistent.server_time_seen |= time_reported;
+            observation.server_time_seen = persistent.server_time_seen;
+
+            // A degraded sample neither counts players nor ends the streak.
+            if observation.state != MonitorState::Degraded {
+                let players = observation.info.as_ref().map(|i| i.players);
+                persistent
+                    .player_hours
+                    .record(now, players, max_sample_gap, stats_tz);
+            }
+            observation.player_secs_today = persistent.player_hours.today(now, stats_tz);
+            observation.motd =
+                effective_motd(config.motd.as_deref(), persistent.motd_override.as_deref());
+
+            persistent
+                .daily
+                .record(&observation, self.last_state, now, stats_tz);
+            if config.tracks_event_impact() {
+                if let (MonitorState::Online, Some(info)) = (observation.state, &observation.info) {
+                    persistent.hourly_players.record(now, info.players);
+                }
+            }
+
+            let listed = observation
+                .info
+                .as_ref()
+                .and_then(|i| i.player_names.as_ref());
+            match (observation.state, listed) {
+                (MonitorState::Online, Some(names)) => {
+                    let evicted = self.sessions.evictions();
+                    let dropped =
+                        persistent
+                            .roster
+                            .record(names, now, stats_tz, config.unique_names_per_day);
+                    let longest = self.sessions.observe(names, now);
+                    persistent.daily.record_roster(
+                        persistent.roster.seen.len(),
+                        longest,
+                        now,
+                        stats_tz,
+                    );
+                    persistent
+                        .usage
+                        .record_caps(dropped, self.sessions.evictions() - evicted);
+                }
+                (MonitorState::Offline, _) => self.sessions.clear(),
+                _ => {}
+            }
+        }
+
+        let announce = match wipe_event {
+            Some(WipeEvent::Countdown { .. }) | Some(WipeEvent::Wiped { announce: true }) => {
+                wipe_event
+            }
+            _ => None,
+        };
+        if let (Some(event), Some(plan)) = (announce, &self.wipe_plan) {
+            let channel = match config.wipe_announce_channel {
+                WipeChannel::Alert => config.alert_channel_id.unwrap_or(channel_id),
+                WipeChannel::Status => channel_id,
+            };
+            let text = wipe::announcement(
+                event,
+                &config.server_name,
+                plan.at_unix,
+                config.bot_language,
+            );
+            let request = SendRequest {
+                channel_id: channel,
+                message: (host.encoders().post)(&Rendered::Text(text)),
+                priority: Priority::silent_if(config.silent_wipe_announcement),
+            };
+
+            if let Err(err) = transport.send(request).await {
+                tracing::warn!("Failed to send the wipe announcement: {err}");
+            }
+        }
+
+        #[cfg(feature = "charts")]
+        if observation.state != MonitorState::Degraded {
+            self.history
+                .push(now, observation.info.as_ref().map_or(0, |i| i.players));
+        }
+        #[cfg(feature = "charts")]
+        if let Some(info) = &observation.info {
+            self.last_max_players = Some(info.max_players);
+        }
+
+        match observation.info.as_ref() {
+            Some(info) if observation.state == MonitorState::Online => {
+                self.ramp.push(now, info.players);
+                observation.full_in_minutes = config
+                    .full_forecast_occupancy
+                    .filter(|_| config.player_count_mode.counts_known())
+                    .and_then(|occupancy| {
+                        forecast::full_in_minutes(
+                            &self.ramp,
+                            info.players,
+                            info.max_players,
+                            occupancy,
+                        )
+                    });
+            }
+            // A degraded sample repeats the last good one, so it says nothing about the ramp.
+            _ if observation.state == MonitorState::Degraded => {}
+            _ => self.ramp.clear(),
+        }
+
+        if self.last_state != observation.state {
+            oplog.push(OpEvent::transition(self.last_state, &observation));
+            shared.record_audit(
+                AuditEvent::Transition {
+                    from: self.last_state,
+                    to: observation.state,
+                    error: observation.error.clone(),
+                },
+                clock,
+            );
+            self.last_state = observation.state;
+        }
+
+        if let Some(audit) = &shared.audit {
+            if now.saturating_sub(self.last_audit_prune) >= 3600
+                && shared.subsystems.is_enabled(subsystem::AUDIT_LOG)
+            {
+                shared
+                    .subsystems
+                    .record(subsystem::AUDIT_LOG, audit.prune(now).map(|_| ()));
+                self.last_audit_prune = now;
+            }
+        }
+
+        match (observation.state, &observation.info) {
+            (MonitorState::Online, Some(info)) => {
+                observation.previous_counts = self.last_counts.replace(Counts::of(info));
+
+                let mismatch = self.count_check.observe(info.players, info.headcount);
+                if let (Some(m), None) = (mismatch, self.last_mismatch) {
+                    tracing::warn!(
+                        "Server reports {} players, but its player list has {}",
+                        m.reported,
+                        m.verified
+                    );
+                }
+                self.last_mismatch = mismatch;
+                observation.count_mismatch = mismatch;
+
+                let join_port = self
+                    .join_port_check
+                    .observe(info.join_port.as_ref())
+                    .then(|| config.game_port.or(info.game_port))
+                    .flatten();
+                match (join_port, self.last_join_port) {
+                    (Some(port), None) => tracing::warn!(
+                        "Server answers queries, but its game port {port} is unreachable"
+                    ),
+                    (None, Some(port)) => tracing::info!("Game port {port} is reachable again"),
+                    _ => {}
+                }
+                self.last_join_port = join_port;
+                observation.join_port_unreachable = join_port;
+            }
+            (MonitorState::Degraded, _) => {}
+            _ => {
+                self.last_counts = None;
+                self.last_mismatch = None;
+                self.count_check.reset();
+                self.last_join_port = None;
+                self.join_port_check.reset();
+            }
+        }
+
+        let in_maintenance = observation.state == MonitorState::Maintenance;
+
+        // A degraded sample says nothing reliable about availability either way, and
+        // downtime during maintenance is expected.
+        let fired = if observation.state == MonitorState::Degraded || in_maintenance {
+            Vec::new()
+        } else {
+            // A server still starting up is down as far as alerts are concerned.
+            let info = observation
+                .info
+                .as_ref()
+                .filter(|_| observation.state != MonitorState::Starting);
+            let mut fired = self.alerts.observe(config, info, now);
+            if let (MonitorState::Online, Some(info)) = (observation.state, info) {
+                fired.extend(self.alerts.observe_join_port(
+                    config,
+                    info,
+                    observation.join_port_unreachable,
+                ));
+            }
+            fired
+        };
+        observation.high_latency = self.alerts.high_latency();
+
+        let snoozed_until = shared.persistent.read().await.snoozed_until_unix;
+        let suppressed = suppression(now, in_maintenance, snoozed_until);
+
+        for alert in fired {
+            let rule = alert.kind.name();
+            shared.persistent.write().await.usage.record_alert(rule);
+
+            let mut message_id = None;
+            let mut error = None;
+
+            match (suppressed, config.alert_channel_id) {
+                (Some(reason), _) => tracing::info!("Not sending {rule} alert ({reason})"),
+                (None, Some(channel)) => {
+                    let request = SendRequest {
+                        channel_id: channel,
+                        message: (host.encoders().post)(&Rendered::Text(alert.message.clone())),
+                        priority: Priority::silent_if(config.silent_alerts),
+                    };
+
+                    match transport.send(request).await {
+                        Ok(id) => {
+                            message_id = Some(id);
+
+                            #[cfg(feature = "charts")]
+                            if config.alert_chart_rules.contains(&alert.kind)
+                                && config.player_count_mode.shows_exact()
+                            {
+                                // Drawn after the text is out, so it never delays the alert.
+                                host.attach_chart(
+                                    channel,
+                                    id,
+                                    self.history.samples().collect(),
+                                    self.last_max_players,
+                                    now,
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            tracing::warn!("Failed to send {rule} alert: {err}");
+                            error = Some(err.to_string());
+                        }
+                    }
+                }
+                (None, None) => {}
+            }
+
+            shared.record_audit(
+                AuditEvent::Alert {
+                    rule: rule.to_string(),
+                    channel_id: config.alert_channel_id,
+                    message_id,
+                    suppressed: suppressed.map(|s| s.to_string()),
+                    error,
+                },
+                clock,
+            );
+
+            if suppressed.is_none() {
+                host.notify(&alert);
+            }
+        }
+
+        if snoozed_until.is_some_and(|until| until <= now) {
+            shared.persistent.write().await.snoozed_until_unix = None;
+        }
+
+        shared
+            .persistent
+            .write()
+            .await
+            .usage
+            .record_cycle(observation.state);
+
+        let mods = match observation.state {
+            MonitorState::Online => observation.info.as_ref().and_then(|i| i.mods.clone()),
+            _ => None,
+        };
+        let render_started = Instant::now();
+        let embed = host.status_embed(config, &observation);
+        // The marker goes on after the hooks, so a patch can't hide the message from `/cleanup`.
+        let embed = shared
+            .post_render
+            .apply(&observation, embed, HOOK_BUDGET)
+            .await
+            .normalized(&config.server_name)
+            .footer(STATUS_MARKER);
+        timer.record(Phase::Render, render_started.elapsed());
+        let topic = self
+            .topic_updater
+            .is_some()
+            .then(|| render_topic(config, &observation, now));
+
+        if !self.outputs.is_empty() {
+            let posted = self
+                .outputs
+                .publish(
+                    status,
+                    seq,
+                    Priority::silent_if(config.silent_status_message),
+                    host.encoders(),
+                    |output, config| {
+                        render_output(config, output.layout, &observation, now, |config| {
+                            host.status_embed(config, &observation)
+                        })
+                    },
+                )
+                .await;
+
+            for message_id in posted {
+                oplog.push(OpEvent::StatusMessagePosted {
+                    message_id,
+                    replaced: None,
+                });
+            }
+            shared.persistent.write().await.output_message_ids = self.outputs.message_ids();
+        }
+        *shared.last_observation.write().await = observation;
+
+        // Copied out first: posting takes the write lock.
+        let current = *shared.status_message_id.read().await;
+        let msg_id = match current {
+            Some(id) => Some(id),
+            None => {
+                let id = post_status_message(config, transport, host, shared, embed.clone()).await;
+                if let Some(id) = id {
+                    self.last_rendered = Some((id, embed.clone()));
+                }
+                id
+            }
+        };
+
+        if let Some(id) = msg_id.filter(|id| Some(*id) != self.announced) {
+            oplog.push(OpEvent::StatusMessagePosted {
+                message_id: id,
+                // A configured message is only left unused when it was rejected.
+                replaced: self.announced.or(config.status_message_id),
+            });
+            self.announced = Some(id);
+        }
+
+        let changes = match (&self.last_rendered, msg_id) {
+            (Some((id, prev)), Some(msg_id)) if *id == msg_id => Some(render::diff(prev, &embed)),
+            _ => None,
+        };
+
+        match (&changes, msg_id) {
+            (_, None) => {}
+            (Some(changes), Some(msg_id)) if changes.is_empty() => {
+                tracing::debug!("Status unchanged, not editing message {msg_id}");
+            }
+            (_, Some(msg_id)) => {
+                if let Some(changes) = &changes {
+                    tracing::debug!("Status changed: {changes:?}");
+                }
+
+                let update = StatusUpdate {
+                    seq,
+                    payload: host.status_edit(&embed),
+                };
+                let edit = status.apply_guarded(channel_id, msg_id, update, &self.edit_guard);
+                match timer.time(Phase::Edit, edit).await {
+                    Ok(EditOutcome::Applied) => self.last_rendered = Some((msg_id, embed)),
+                    Ok(EditOutcome::Stale) => {}
+                    Err(err) => {
+                        tracing::warn!("Failed to edit status message {msg_id}: {err}");
+                        self.last_rendered = None;
+                    }
+                }
+            }
+        }
+
+        if let (Some(updater), Some(topic)) = (&mut self.topic_updater, topic) {
+            if let Err(err) = updater.update(transport, topic, now).await {
+                tracing::warn!("Failed to set the channel topic: {err}");
+            }
+        }
+
+        let events_due = self.events_fetched.is_none_or(|at| {
+            clock.instant().saturating_duration_since(at) >= EVENT_REFRESH_INTERVAL
+        });
+        if config.tracks_event_impact() && events_due {
+            self.events_fetched = Some(clock.instant());
+            match host.scheduled_events().await {
+                Ok(listed) => shared.persistent.write().await.events.remember(listed, now),
+                Err(err) => tracing::warn!("Failed to fetch scheduled events: {err}"),
+            }
+        }
+
+        if let Some(mods) = mods {
+            host.sync_mod_list(transport, &mods).await;
+        }
+
+        if let Some(channel) = config.log_channel_id {
+            for events in oplog.messages() {
+                let request = SendRequest {
+                    channel_id: channel,
+                    message: host.log_message(config, events, oplog.at_unix),
+                    priority: Priority::silent_if(config.silent_log_channel),
+                };
+
+                if let Err(err) = transport.send(request).await {
+                    tracing::warn!("Failed to post to the log channel: {err}");
+                }
+            }
+        }
+
+        // Only once the status message is known, so the current one is never mistaken for an
+        // old one.
+        if config.cleanup_orphans
+            && msg_id.is_some()
+            && now.saturating_sub(self.last_cleanup) >= 24 * 3600
+        {
+            self.last_cleanup = now;
+            let scope = shared.cleanup_scope(config).await;
+            let min_age = config.cleanup_min_age_hours * 3600;
+            let deleted = cleanup::cleanup(transport, &scope, self.bot_user_id, now, min_age).await;
+            if deleted > 0 {
+                tracing::info!("Cleaned up {deleted} old status message(s)");
+            }
+        }
+
+        {
+            let mut persistent = shared.persistent.write().await;
+            persistent.heartbeat_unix = Some(now);
+            persistent.heartbeat_interval_secs = Some(config.update_interval_secs);
+        }
+        shared.save_persistent(clock).await;
+
+        let report = timer.finish();
+        if let Some(edit) = report.edit.filter(|edit| *edit > SLOW_EDIT) {
+            tracing::warn!(
+                "Editing the status message took {}ms: {report}",
+                edit.as_millis()
+            );
+        } else {
+            tracing::debug!("Cycle timing: {report}");
+        }
+
+        report
+    }
+}
+
+/// The cycle's observation: the server queried through `poller`, or left alone inside a
+/// maintenance window.
+async fn poll(
+    monitor: &mut Monitor,
+    poller: &mut impl Poller,
+    maintenance: &[Window],
+    shared: &SharedState,
+    timer: &CycleTimer,
+    now: u64,
+) -> Observation {
+    let now = DateTime::from_timestamp(now as i64, 0).unwrap_or_default();
+    if let Some(until) = schedule::active_until(maintenance, now) {
+        return monitor.maintenance(until.timestamp() as u64);
+    }
+
+    let polled = poller.poll(monitor.needs_rules_probe(), timer).await;
+    if let Ok((_, addr)) = &polled.result {
+        *shared.query_addr.write().await = Some(*addr);
+    }
+
+    monitor.observe(polled.result.map(|(info, _)| info), polled.rules_answered)
+}
+
+/// Posts a new status message and adopts it for all further edits.
+async fn post_status_message<H>(
+    config: &DayzMonitorConfig,
+    transport: &H::Transport,
+    host: &H,
+    shared: &SharedState,
+    embed: EmbedRepresentation,
+) -> Option<u64>
+where
+    H: CycleHost,
+    <H::Transport as StatusTransport>::Error: fmt::Display,
+{
+    let request = SendRequest {
+        channel_id: config.text_channel_id,
+        message: (host.encoders().post)(&Rendered::Embed(embed)),
+        priority: Priority::silent_if(config.silent_status_message),
+    };
+
+    match transport.send(request).await {
+        Ok(id) => {
+            *shared.status_message_id.write().await = Some(id);
+            Some(id)
+        }
+        Err(err) => {
+            tracing::warn!("Failed to post status message: {err}");
+            None
+        }
+    }
+}
+
+/// Writes `STATUS_FILE` for `observation`, unless it is unset or its subsystem is disabled.
+fn write_status(
+    config: &DayzMonitorConfig,
+    subsystems: &Subsystems,
+    observation: &Observation,
+    now: u64,
+) {
+    let Some(path) = config
+        .status_file
+        .as_ref()
+        .filter(|_| subsystems.is_enabled(subsystem::STATUS_FILE))
+    else {
+        return;
+    };
+
+    let status = StatusFile {
+        written_unix: now,
+        ..StatusFile::new(
+            observation.state,
+            observation.info.as_ref(),
+            observation.diagnostics.clone(),
+        )
+    };
+    subsystems.record(subsystem::STATUS_FILE, write_status_file(path, &status));
+}
+
+/// Publishes this bot's ping to `REGION_PINGS_DIR` and returns the other regions' fresh ones;
+/// none unless both it and `BOT_REGION` are set.
+fn share_region_ping(
+    config: &DayzMonitorConfig,
+    subsystems: &Subsystems,
+    observation: &Observation,
+    now: u64,
+) -> Vec<RegionPing> {
+    let (Some(dir), Some(region)) = (&config.region_pings_dir, &config.bot_region) else {
+        return Vec::new();
+    };
+    let store = DirStore { dir: dir.clone() };
+
+    // Only a fresh sample measured the latency; a degraded one repeats the last good one.
+    let latency = observation
+        .info
+        .as_ref()
+        .filter(|_| observation.state == MonitorState::Online)
+        .and_then(|info| info.latency);
+    let published = latency.map_or(Ok(()), |latency| {
+        store.publish(&RegionPing {
+            region: region.clone(),
+            latency_ms: latency.as_millis() as u32,
+            measured_unix: now,
+        })
+    });
+
+    let read = published.and_then(|()| store.read_all());
+    let pings = match &read {
+        Ok(pings) => {
+            regions::other_regions(pings.clone(), region, now, config.update_interval_secs)
+        }
+        Err(_) => Vec::new(),
+    };
+    subsystems.record(subsystem::REGION_PINGS, read.map(|_| ()));
+    pings
+}
@@ -0,0 +1,178 @@
+use chrono::Days;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    calendar::{local_date, next_midnight},
+    events::EventImpact,
+    gaps::{self, MonitoringGap},
+    monitor::Observation,
+    playtime::{self, PlayerHours, DAYS_KEPT},
+    MonitorState,
+};
+
+/// Totals for one local day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayStats {
+    /// Polls outside maintenance windows
+    pub polls: u64,
+    pub online_polls: u64,
+    pub peak_players: u32,
+    pub peak_at_unix: Option<u64>,
+    pub longest_queue: u32,
+
+    /// Times the server came back after being offline
+    pub restarts: u32,
+
+    /// Distinct names on the day's player lists, with `VERIFY_PLAYER_COUNT`
+    pub unique_players: u32,
+    pub longest_session_secs: u64,
+}
+
+impl DayStats {
+    pub fn uptime_percent(&self) -> Option<f64> {
+        match self.polls {
+            0 => None,
+            n => Some(self.online_polls as f64 * 100.0 / n as f64),
+        }
+    }
+}
+
+/// Per-day totals, keyed by local date (`YYYY-MM-DD`) like [`PlayerHours`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyStats {
+    pub days: BTreeMap<String, DayStats>,
+}
+
+impl DailyStats {
+    /// Folds in one poll. `previous` is the state the poll before it concluded.
+    pub fn record(&mut self, observation: &Observation, previous: MonitorState, now: u64, tz: Tz) {
+        if matches!(
+            observation.state,
+            MonitorState::Unknown | MonitorState::Maintenance
+        ) {
+            return;
+        }
+
+        let day = self
+            .days
+            .entry(local_date(now, tz).to_string())
+            .or_default();
+        day.polls += 1;
+
+        let answered = matches!(
+            observation.state,
+            MonitorState::Online | MonitorState::Degraded
+        );
+        if answered {
+            day.online_polls += 1;
+        }
+
+        let came_back = matches!(
+            observation.state,
+            MonitorState::Online | MonitorState::Starting
+        );
+        if came_back && previous == MonitorState::Offline {
+            day.restarts += 1;
+        }
+
+        // Degraded observations carry the last good sample, which was already counted.
+        if let (MonitorState::Online, Some(info)) = (observation.state, &observation.info) {
+            if info.players > day.peak_players || day.peak_at_unix.is_none() {
+                day.peak_players = info.players;
+                day.peak_at_unix = Some(now);
+            }
+            day.longest_queue = day.longest_queue.max(info.players_in_queue.unwrap_or(0));
+        }
+
+        while self.days.len() > DAYS_KEPT {
+            self.days.pop_first();
+        }
+    }
+
+    /// Folds in what today's player lists showed: `unique` distinct players so far, and the
+    /// longest session still going.
+    pub fn record_roster(&mut self, unique: usize, longest_session: Option<u64>, now: u64, tz: Tz) {
+        let day = self
+            .days
+            .entry(local_date(now, tz).to_string())
+            .or_default();
+        day.unique_players = u32::try_from(unique).unwrap_or(u32::MAX);
+        day.longest_session_secs = day.longest_session_secs.max(longest_session.unwrap_or(0));
+    }
+
+    /// The totals of the `days` local days up to and including the one containing `now`.
+    pub fn last_days(&self, days: u64, now: u64, tz: Tz) -> impl Iterator<Item = &DayStats> {
+        let today = local_date(now, tz);
+        let first = today
+            .checked_sub_days(Days::new(days.saturating_sub(1)))
+            .unwrap_or(today);
+
+        self.days
+            .range(first.to_string()..=today.to_string())
+            .map(|(_, day)| day)
+    }
+}
+
+/// What `/stats` reports; `None` fields have no data and are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsReport {
+    /// Today's highest player count and when it was first reached
+    pub peak: Option<(u32, u64)>,
+    pub uptime_today: Option<f64>,
+    pub uptime_week: Option<f64>,
+    pub player_hours_today: Option<f64>,
+    pub longest_queue: Option<u32>,
+    pub restarts_today: Option<u32>,
+    pub unique_players_today: Option<u32>,
+    pub longest_session_today_secs: Option<u64>,
+    /// How long the bot itself was down over the last 7 days. Polls stop while it is, so the
+    /// figures above only cover the time it was running
+    pub unmonitored_week_secs: Option<u64>,
+    /// Players drawn by each Discord scheduled event of the last 7 days, with `EVENT_IMPACT`
+    pub events: Vec<EventImpact>,
+}
+
+impl StatsReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub fn report(
+    daily: &DailyStats,
+    player_hours: &PlayerHours,
+    monitoring_gaps: &[MonitoringGap],
+    now: u64,
+    tz: Tz,
+) -> StatsReport {
+    let today = daily.last_days(1, now, tz).next();
+
+    let (polls, online_polls) = daily
+        .last_days(7, now, tz)
+        .fold((0, 0), |(p, o), day| (p + day.polls, o + day.online_polls));
+
+    let player_secs = player_hours.today(now, tz);
+
+    let week_start = local_date(now, tz)
+        .checked_sub_days(Days::new(7))
+        .and_then(|day| next_midnight(day, tz))
+        .map_or(0, |t| t.timestamp().max(0) as u64);
+    let unmonitored = gaps::unmonitored_secs(monitoring_gaps, week_start, now);
+
+    StatsReport {
+        peak: today.and_then(|d| d.peak_at_unix.map(|at| (d.peak_players, at))),
+        uptime_today: today.and_then(DayStats::uptime_percent),
+        uptime_week: (polls > 0).then(|| online_polls as f64 * 100.0 / polls as f64),
+        player_hours_today: (player_secs > 0).then(|| playtime::hours(player_secs)),
+        longest_queue: today.map(|d| d.longest_queue).filter(|q| *q > 0),
+        restarts_today: today.map(|d| d.restarts),
+        unique_players_today: today.map(|d| d.unique_players).filter(|n| *n > 0),
+        longest_session_today_secs: today.map(|d| d.longest_session_secs).filter(|s| *s > 0),
+        unmonitored_week_secs: (unmonitored > 0).then_some(unmonitored),
+        events: Vec::new(),
+    }
+}
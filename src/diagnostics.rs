@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Why the keywords parser passed over a token that looked like something it reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoreReason {
+    /// `lqs` not followed by a number
+    MalformedQueue,
+    /// Contains `:` but is longer than `HH:MM:SS`
+    TooLongForTime,
+    /// Contains `:` but is not a time, e.g. `99:99`
+    MalformedTime,
+    /// Another time came first
+    DuplicateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IgnoredToken {
+    pub token: String,
+    pub reason: IgnoreReason,
+}
+
+/// What one poll could not make sense of, so that reports like "the bot shows the wrong time"
+/// carry their own explanation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct Diagnostics {
+    /// Keywords that looked like a value the bot reads, but were not used
+    pub ignored_tokens: Vec<IgnoredToken>,
+
+    /// Why the response was discarded as implausible
+    pub rejected: Option<String>,
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.ignored_tokens.is_empty() && self.rejected.is_none()
+    }
+}
@@ -0,0 +1,451 @@
+use std::sync::Arc;
+
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+use dayz_monitor::{alerts::Alert, audit::AuditEvent};
+use dayz_monitor::{
+    cycle::CycleHost,
+    events::ScheduledEvent,
+    mods::{chunk_lines, mod_lines, mod_list_hash, plan_companion_sync, Mod},
+    monitor::Observation,
+    oplog::OpEvent,
+    output::{Encoders, Rendered},
+    render::EmbedRepresentation,
+    retry::RetryPolicy,
+    transport::{
+        ChannelMessage, MessageAuthor, MessageState, Priority, Restore, SendRequest,
+        StatusTransport,
+    },
+    DayzMonitorConfig,
+};
+use serenity::{
+    all::{
+        ChannelId, CreateEmbed, CreateMessage, EditChannel, EditMessage, GetMessages, MessageFlags,
+        MessageId, ScheduledEventStatus,
+    },
+    http::{Http, HttpError},
+};
+
+#[cfg(feature = "charts")]
+use dayz_monitor::chart::{self, CHART_BUDGET, CHART_FILE_NAME};
+#[cfg(feature = "charts")]
+use serenity::all::CreateAttachment;
+
+use crate::{embeds, BotState};
+
+/// Edits and posts messages through the Discord REST API. `channel_id` is the status channel,
+/// where companion messages live.
+pub struct DiscordTransport {
+    pub http: Arc<Http>,
+    pub channel_id: ChannelId,
+    /// For edits Discord failed to answer
+    pub edit_retry: RetryPolicy,
+}
+
+/// Output renders as Discord messages. A text render clears any embed the message had.
+pub const ENCODERS: Encoders<DiscordTransport> = Encoders {
+    post: |rendered| match rendered {
+        Rendered::Embed(embed) => CreateMessage::new().embed(embeds::to_create_embed(embed)),
+        Rendered::Text(text) => CreateMessage::new().content(text),
+    },
+    edit: |rendered| match rendered {
+        Rendered::Embed(embed) => EditMessage::new()
+            .content("")
+            .embed(embeds::to_create_embed(embed)),
+        Rendered::Text(text) => EditMessage::new().content(text).embeds(Vec::new()),
+    },
+};
+
+/// Whether Discord may accept the same request on another try: it failed to connect or had
+/// an error of its own. Rate limits are waited out by serenity already.
+fn is_transient(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+            response.status_code.is_server_error()
+        }
+        serenity::Error::Http(HttpError::Request(_)) | serenity::Error::Io(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether a message lookup failed because the message isn't in that channel, as opposed to
+/// Discord being unreachable or denying access.
+pub fn is_not_found(err: &serenity::Error) -> bool {
+    matches!(
+        err,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.status_code.as_u16() == 404
+    )
+}
+
+/// Adds a rendered chart to an alert message.
+#[cfg(feature = "charts")]
+pub fn chart_attachment(png: Vec<u8>) -> EditMessage {
+    EditMessage::new().new_attachment(CreateAttachment::bytes(png, CHART_FILE_NAME))
+}
+
+impl StatusTransport for DiscordTransport {
+    type Payload = EditMessage;
+    type Message = CreateMessage;
+    type Error = serenity::Error;
+
+    async fn edit(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        payload: EditMessage,
+    ) -> Result<(), serenity::Error> {
+        let edit = || {
+            ChannelId::new(channel_id).edit_message(
+                &self.http,
+                MessageId::new(message_id),
+                payload.clone(),
+            )
+        };
+
+        self.edit_retry.run_if(edit, is_transient).await.map(|_| ())
+    }
+
+    async fn author(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> Result<MessageAuthor, serenity::Error> {
+        let msg = ChannelId::new(channel_id)
+            .message(&self.http, MessageId::new(message_id))
+            .await?;
+
+        Ok(MessageAuthor {
+            id: msg.author.id.get(),
+            name: msg.author.tag(),
+        })
+    }
+
+    async fn send(&self, request: SendRequest<CreateMessage>) -> Result<u64, serenity::Error> {
+        ChannelId::new(request.channel_id)
+            .send_message(
+                &self.http,
+                request.message.flags(message_flags(request.priority)),
+            )
+            .await
+            .map(|msg| msg.id.get())
+    }
+
+    async fn recent_messages(
+        &self,
+        channel_id: u64,
+        limit: u8,
+    ) -> Result<Vec<ChannelMessage>, serenity::Error> {
+        let messages = ChannelId::new(channel_id)
+            .messages(&self.http, GetMessages::new().limit(limit))
+            .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|msg| ChannelMessage {
+                id: msg.id.get(),
+                author_id: msg.author.id.get(),
+                created_unix: msg.timestamp.unix_timestamp().max(0) as u64,
+                footer: msg
+                    .embeds
+                    .first()
+                    .and_then(|e| e.footer.as_ref())
+                    .map(|f| f.text.clone()),
+            })
+            .collect())
+    }
+
+    async fn delete(&self, channel_id: u64, message_id: u64) -> Result<(), serenity::Error> {
+        ChannelId::new(channel_id)
+            .delete_message(&self.http, MessageId::new(message_id))
+            .await
+    }
+
+    async fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), serenity::Error> {
+        ChannelId::new(channel_id)
+            .edit(&self.http, EditChannel::new().topic(topic))
+            .await
+            .map(|_| ())
+    }
+
+    async fn inspect(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> Result<MessageState, serenity::Error> {
+        let msg = ChannelId::new(channel_id)
+            .message(&self.http, MessageId::new(message_id))
+            .await?;
+
+        Ok(MessageState {
+            embeds_suppressed: msg
+                .flags
+                .is_some_and(|f| f.contains(MessageFlags::SUPPRESS_EMBEDS)),
+            content: msg.content,
+        })
+    }
+
+    async fn restore(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        restore: Restore,
+    ) -> Result<(), serenity::Error> {
+        let mut edit = EditMessage::new();
+        if restore.unsuppress {
+            edit = edit.suppress_embeds(false);
+        }
+        if restore.clear_content {
+            edit = edit.content("");
+        }
+
+        ChannelId::new(channel_id)
+            .edit_message(&self.http, MessageId::new(message_id), edit)
+            .await
+            .map(|_| ())
+    }
+}
+
+/// The status loop's Discord side: embeds, scheduled events, the mod list, and the alert charts
+/// and notifications sent off the cycle.
+pub struct DiscordHost {
+    pub state: Arc<BotState>,
+    pub http: Arc<Http>,
+}
+
+impl CycleHost for DiscordHost {
+    type Transport = DiscordTransport;
+
+    fn encoders(&self) -> &Encoders<DiscordTransport> {
+        &ENCODERS
+    }
+
+    fn status_embed(
+        &self,
+        config: &DayzMonitorConfig,
+        observation: &Observation,
+    ) -> EmbedRepresentation {
+        embeds::status_embed(config, observation, config.bot_language)
+    }
+
+    fn status_edit(&self, embed: &EmbedRepresentation) -> EditMessage {
+        EditMessage::new().embed(embeds::to_create_embed(embed))
+    }
+
+    fn log_message(
+        &self,
+        config: &DayzMonitorConfig,
+        events: &[OpEvent],
+        at_unix: u64,
+    ) -> CreateMessage {
+        let embeds = events
+            .iter()
+            .map(|e| embeds::to_create_embed(&embeds::log_embed(config, e, at_unix)))
+            .collect();
+
+        CreateMessage::new().embeds(embeds)
+    }
+
+    fn is_not_found(&self, err: &serenity::Error) -> bool {
+        is_not_found(err)
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<ScheduledEvent>, serenity::Error> {
+        let channel_id = ChannelId::new(self.state.config.text_channel_id);
+        scheduled_events(&self.http, channel_id).await
+    }
+
+    async fn sync_mod_list(&self, transport: &DiscordTransport, mods: &[Mod]) {
+        sync_mod_list(&self.state, transport, mods).await
+    }
+
+    #[cfg(feature = "charts")]
+    fn attach_chart(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        samples: Vec<(u64, u32)>,
+        max_players: Option<u32>,
+        now_unix: u64,
+    ) {
+        let transport = DiscordTransport {
+            http: self.http.clone(),
+            channel_id: ChannelId::new(self.state.config.text_channel_id),
+            edit_retry: self.state.config.status_edit_retry(),
+        };
+
+        self.state.alert_tasks.spawn(async move {
+            chart::attach(
+                &transport,
+                channel_id,
+                message_id,
+                move || chart::render_png(&samples, max_players, now_unix),
+                CHART_BUDGET,
+                chart_attachment,
+            )
+            .await;
+        });
+    }
+
+    #[cfg(any(feature = "telegram", feature = "ntfy"))]
+    fn notify(&self, alert: &Alert) {
+        for backend in self.state.notifiers.iter().filter(|b| b.routes(alert.kind)) {
+            // Retries back off for seconds, which must not hold up the status.
+            let state = self.state.clone();
+            let backend = backend.clone();
+            let alert = alert.clone();
+
+            self.state.alert_tasks.spawn(async move {
+                let result = backend.send(&state.notify_client, &alert).await;
+                state
+                    .shared
+                    .subsystems
+                    .record(backend.name(), result.as_ref().map(|_| ()));
+
+                state.shared.record_audit(
+                    AuditEvent::Notification {
+                        backend: backend.name().to_string(),
+                        rule: alert.kind.name().to_string(),
+                        error: result.err().map(|e| e.to_string()),
+                    },
+                    &state.clock,
+                );
+            });
+        }
+    }
+}
+
+/// Low-priority messages are posted without a push notification.
+fn message_flags(priority: Priority) -> MessageFlags {
+    match priority {
+        Priority::Normal => MessageFlags::empty(),
+        Priority::Low => MessageFlags::SUPPRESS_NOTIFICATIONS,
+    }
+}
+
+/// The scheduled events listed in the guild of `channel_id`, leaving out cancelled ones.
+pub async fn scheduled_events(
+    http: &Http,
+    channel_id: ChannelId,
+) -> Result<Vec<ScheduledEvent>, serenity::Error> {
+    let Some(channel) = channel_id.to_channel(http).await?.guild() else {
+        return Ok(Vec::new());
+    };
+
+    Ok(channel
+        .guild_id
+        .scheduled_events(http, false)
+        .await?
+        .into_iter()
+        .filter(|e| e.status != ScheduledEventStatus::Canceled)
+        .map(|e| ScheduledEvent {
+            id: e.id.get(),
+            name: e.name,
+            start_unix: e.start_time.unix_timestamp().max(0) as u64,
+            end_unix: e.end_time.map(|t| t.unix_timestamp().max(0) as u64),
+        })
+        .collect())
+}
+
+/// Keeps the companion messages in step with a mod list too long for the status embed.
+///
+/// Nothing is sent while the list is unchanged. Existing messages are edited in place, extra
+/// pages are appended and surplus pages deleted, so a shrinking list leaves nothing stale.
+pub async fn sync_mod_list(state: &BotState, transport: &DiscordTransport, mods: &[Mod]) {
+    let hash = mod_list_hash(mods);
+    let existing = {
+        let persistent = state.shared.persistent.read().await;
+        if persistent.mod_list_hash == Some(hash) {
+            return;
+        }
+        persistent.mod_list_message_ids.clone()
+    };
+
+    let lines = mod_lines(mods);
+    let chunks = if lines.join("\n").len() <= embeds::FIELD_LIMIT {
+        Vec::new()
+    } else {
+        chunk_lines(&lines, embeds::DESCRIPTION_LIMIT)
+    };
+
+    let pages = chunks.len();
+    let plan = plan_companion_sync(&existing, chunks);
+    let lang = state.config.bot_language;
+    let page_embed = |page: usize, chunk: &str| {
+        embeds::to_create_embed(&embeds::mod_page_embed(
+            &state.config,
+            chunk,
+            page,
+            pages,
+            lang,
+        ))
+    };
+
+    let mut ids = Vec::with_capacity(pages);
+    let mut complete = true;
+
+    for (id, chunk) in plan.edit {
+        let edit = EditMessage::new().embed(page_embed(ids.len() + 1, &chunk));
+        match transport.edit(transport.channel_id.get(), id, edit).await {
+            Ok(_) => ids.push(id),
+            Err(err) => {
+                tracing::warn!("Failed to edit mod list message {id}, sending a new one: {err}");
+                match send_page(state, transport, page_embed(ids.len() + 1, &chunk)).await {
+                    Some(new_id) => ids.push(new_id),
+                    None => complete = false,
+                }
+            }
+        }
+    }
+
+    for chunk in plan.send {
+        match send_page(state, transport, page_embed(ids.len() + 1, &chunk)).await {
+            Some(id) => ids.push(id),
+            None => complete = false,
+        }
+    }
+
+    for id in plan.delete {
+        if let Err(err) = transport.delete(transport.channel_id.get(), id).await {
+            tracing::warn!("Failed to delete surplus mod list message {id}: {err}");
+        }
+    }
+
+    let mut persistent = state.shared.persistent.write().await;
+    persistent.mod_list_message_ids = ids;
+    // Leaving the hash unset makes the next cycle retry whatever failed.
+    persistent.mod_list_hash = complete.then_some(hash);
+}
+
+async fn send_page(
+    state: &BotState,
+    transport: &DiscordTransport,
+    embed: CreateEmbed,
+) -> Option<u64> {
+    let request = SendRequest {
+        channel_id: transport.channel_id.get(),
+        message: CreateMessage::new().embed(embed),
+        priority: Priority::silent_if(state.config.silent_mod_list),
+    };
+
+    match transport.send(request).await {
+        Ok(id) => Some(id),
+        Err(err) => {
+            tracing::warn!("Failed to send mod list message: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_priority_suppresses_notifications() {
+        assert_eq!(message_flags(Priority::Normal), MessageFlags::empty());
+        assert_eq!(
+            message_flags(Priority::Low),
+            MessageFlags::SUPPRESS_NOTIFICATIONS
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::{future::Future, net::SocketAddr};
+
+use crate::DayzMonitorError;
+
+/// Successful cycles on the remembered family before both families are raced again.
+const RERACE_EVERY: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    pub fn of(addr: &SocketAddr) -> Family {
+        if addr.is_ipv4() {
+            Family::V4
+        } else {
+            Family::V6
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Family::V4 => "IPv4",
+            Family::V6 => "IPv6",
+        }
+    }
+}
+
+/// Picks the address family to query a dual-stack host over.
+///
+/// When a hostname resolves to both families, one address of each is raced and the winner is
+/// remembered. The remembered family is used alone until it fails or [`RERACE_EVERY`] cycles
+/// pass, at which point both are raced again.
+#[derive(Debug, Default)]
+pub struct DualStack {
+    preferred: Option<Family>,
+    cycles_since_race: u32,
+}
+
+impl DualStack {
+    /// Runs `query` against the best address in `addrs`, returning the result and the address
+    /// that produced it.
+    pub async fn query<F, Fut, T>(
+        &mut self,
+        addrs: &[SocketAddr],
+        query: F,
+    ) -> Result<(T, SocketAddr), DayzMonitorError>
+    where
+        F: Fn(SocketAddr) -> Fut,
+        Fut: Future<Output = Result<T, DayzMonitorError>>,
+    {
+        let v4 = addrs.iter().find(|a| a.is_ipv4()).copied();
+        let v6 = addrs.iter().find(|a| a.is_ipv6()).copied();
+
+        let (v4, v6) = match (v4, v6) {
+            (Some(v4), Some(v6)) => (v4, v6),
+            (Some(only), None) | (None, Some(only)) => {
+                return query(only).await.map(|value| (value, only));
+            }
+            (None, None) => {
+                return Err(DayzMonitorError::TokioIOError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "server address did not resolve to any address",
+                )))
+            }
+        };
+
+        if let Some(family) = self
+            .preferred
+            .filter(|_| self.cycles_since_race < RERACE_EVERY)
+        {
+            let addr = if family == Family::V4 { v4 } else { v6 };
+
+            match query(addr).await {
+                Ok(value) => {
+                    self.cycles_since_race += 1;
+                    return Ok((value, addr));
+                }
+                Err(err) => {
+                    tracing::debug!(
+                        "{} query to '{addr}' failed ({err}), racing both families",
+                        family.name()
+                    );
+                }
+            }
+        }
+
+        let result = race(&query, v4, v6).await;
+
+        self.cycles_since_race = 0;
+        self.preferred = result.as_ref().ok().map(|(_, addr)| Family::of(addr));
+
+        if let Some(family) = self.preferred {
+            tracing::debug!("{} won the dual-stack race", family.name());
+        }
+
+        result
+    }
+}
+
+/// Queries both addresses concurrently and returns the first success. The slower query is
+/// cancelled by dropping it; if the first to finish failed, the other is awaited instead.
+async fn race<F, Fut, T>(
+    query: &F,
+    a: SocketAddr,
+    b: SocketAddr,
+) -> Result<(T, SocketAddr), DayzMonitorError>
+where
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T, DayzMonitorError>>,
+{
+    let fut_a = query(a);
+    let fut_b = query(b);
+    tokio::pin!(fut_a, fut_b);
+
+    tokio::select! {
+        res = &mut fut_a => match res {
+            Ok(value) => Ok((value, a)),
+            Err(_) => fut_b.await.map(|value| (value, b)),
+        },
+        res = &mut fut_b => match res {
+            Ok(value) => Ok((value, b)),
+            Err(_) => fut_a.await.map(|value| (value, a)),
+        },
+    }
+}
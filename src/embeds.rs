@@ -0,0 +1,1420 @@
+use std::time::Duration;
+
+use a2s::players::Player;
+use dayz_monitor::{
+    alerts::format_duration,
+    audit::{state_name, AuditEntry},
+    cleanup::Orphan,
+    cycle::{AttemptOutcome, CycleReport},
+    daily::StatsReport,
+    features::{self, Activation, Area},
+    i18n::{Language, Noun, Text},
+    maps::display_name,
+    mods::{chunk_lines, mod_lines},
+    monitor::Observation,
+    motd::MotdRejection,
+    numbers::{format_decimal, format_number},
+    oplog::OpEvent,
+    playtime,
+    probe::{LatencyGrade, LatencyThresholds},
+    regions::{ping_line, RegionPing},
+    render::EmbedRepresentation,
+    subsystem::Health,
+    template::sanitize,
+    title::{render_title, TitleState},
+    wipe::WipeField,
+    DayzMonitorConfig, MonitorState, ServerInfo,
+};
+use serenity::all::{CreateEmbed, CreateEmbedFooter};
+
+/// Discord's limit on embed descriptions.
+pub const DESCRIPTION_LIMIT: usize = 4096;
+
+/// Discord's limit on embed field values.
+pub const FIELD_LIMIT: usize = 1024;
+
+/// Converts a render into serenity's builder right before it is sent.
+pub fn to_create_embed(embed: &EmbedRepresentation) -> CreateEmbed {
+    let mut out = CreateEmbed::new();
+
+    if let Some(title) = &embed.title {
+        out = out.title(title);
+    }
+    if let Some(description) = &embed.description {
+        out = out.description(description);
+    }
+    if let Some(colour) = embed.colour {
+        out = out.colour(colour);
+    }
+    if let Some(footer) = &embed.footer {
+        out = out.footer(CreateEmbedFooter::new(footer));
+    }
+
+    out.fields(
+        embed
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), f.value.clone(), f.inline)),
+    )
+}
+
+fn map_value(info: &ServerInfo, since_unix: Option<u64>, lang: Language) -> String {
+    if info.map.is_empty() {
+        return lang.tr(Text::Unknown).to_string();
+    }
+
+    let name = sanitize(display_name(&info.map));
+    match since_unix {
+        Some(since) => format!("{} • {} <t:{}:D>", name, lang.tr(Text::Since), since),
+        None => name,
+    }
+}
+
+fn rel_ts(secs: u64) -> String {
+    format!("<t:{}:R>", secs)
+}
+
+/// The status embed for an observation, shared by the status message and `/status`.
+pub fn status_embed(
+    config: &DayzMonitorConfig,
+    observation: &Observation,
+    lang: Language,
+) -> EmbedRepresentation {
+    let error = observation.error.as_deref().unwrap_or_default();
+
+    let embed = match (observation.state, &observation.info) {
+        (MonitorState::Unknown, _) => unknown_embed(config, lang),
+        (MonitorState::Online, Some(info)) => online_embed(config, info, observation, lang),
+        (MonitorState::Degraded, Some(info)) => degraded_embed(config, info, observation, lang),
+        (MonitorState::Starting, Some(info)) => starting_embed(config, info, observation, lang),
+        (MonitorState::Maintenance, _) => {
+            maintenance_embed(config, observation.maintenance_until, lang)
+        }
+        _ => offline_embed(config, error, lang),
+    };
+
+    embed.normalized(&config.server_name)
+}
+
+/// `info` is the sample to show; `observation` supplies what the bot knows beyond it.
+pub fn online_embed(
+    config: &DayzMonitorConfig,
+    info: &ServerInfo,
+    observation: &Observation,
+    lang: Language,
+) -> EmbedRepresentation {
+    let num = |n: u32| format_number(n.into(), config.number_locale());
+
+    let mode = config.player_count_mode;
+    let previous = observation
+        .previous_counts
+        .filter(|_| config.change_annotations && mode.shows_exact());
+    let delta = |now: u32, before: Option<u32>| match before {
+        Some(before) if now > before => format!(" (+{})", num(now - before)),
+        Some(before) if now < before => format!(" (-{})", num(before - now)),
+        _ => String::new(),
+    };
+
+    let players_delta = delta(info.players, previous.map(|p| p.players));
+    let players = match mode.display(info.players, info.max_players, config.number_locale()) {
+        Some(shown) if players_delta.is_empty() => format!(
+            "{}: **{} / {}**",
+            lang.tr(Text::Players),
+            shown,
+            num(info.max_players)
+        ),
+        Some(shown) => format!(
+            "{}: **{}**{} / {}",
+            lang.tr(Text::Players),
+            shown,
+            players_delta,
+            num(info.max_players)
+        ),
+        None => lang.tr(Text::PlayerCountHidden).to_string(),
+    };
+
+    let players_line = match info.players_in_queue {
+        Some(q) if q > 0 => format!(
+            "{} • ⏳ {}: **{}**{}",
+            players,
+            lang.tr(Text::Queue),
+            num(q),
+            delta(q, previous.map(|p| p.queue))
+        ),
+        _ => players,
+    };
+
+    let mut description = format!("👥 {}", players_line);
+
+    let time_seen = observation.server_time_seen || info.server_time.is_some();
+    if config.time_field.shows_line(time_seen) {
+        let time_line = info
+            .server_time
+            .clone()
+            .unwrap_or_else(|| lang.tr(Text::Unknown).to_string());
+
+        description.push_str(&format!(
+            "\n🕒 {}: **{}**",
+            lang.tr(Text::ServerTime),
+            time_line
+        ));
+    }
+
+    if config.player_hours_field && mode.counts_known() {
+        description.push_str(&format!(
+            "\n⏱️ {}: **{}**",
+            lang.tr(Text::PlayerHoursToday),
+            format_decimal(
+                playtime::hours(observation.player_secs_today),
+                0,
+                config.number_locale()
+            )
+        ));
+    }
+
+    if let Some(minutes) = observation.full_in_minutes {
+        description.push_str(&format!(
+            "\n📈 {} ~{} min ({})",
+            lang.tr(Text::FullIn),
+            num(minutes),
+            lang.tr(Text::Estimate)
+        ));
+    }
+
+    if let Some(mismatch) = observation
+        .count_mismatch
+        .filter(|_| config.player_count_annotation && mode.shows_exact())
+    {
+        description.push_str(&format!(
+            "\n⚠️ {} **{}**, {} **{}**",
+            lang.tr(Text::ReportedPlayers),
+            num(mismatch.reported),
+            lang.tr(Text::VerifiedPlayers),
+            num(mismatch.verified)
+        ));
+    }
+
+    if let Some(port) = observation.join_port_unreachable {
+        description.push_str(&format!(
+            "\n⚠️ {}: UDP **{port}**",
+            lang.tr(Text::JoinPortUnreachable)
+        ));
+    }
+
+    if let (Some(region), Some(latency)) = (&config.bot_region, info.latency) {
+        let own = RegionPing {
+            region: region.clone(),
+            latency_ms: latency.as_millis() as u32,
+            measured_unix: info.last_updated_unix,
+        };
+        description.push_str(&format!(
+            "\n📶 {}",
+            ping_line(
+                &own,
+                &observation.other_regions,
+                lang,
+                config.number_locale()
+            )
+        ));
+    }
+
+    if let Some(p95) = observation.high_latency {
+        description.push_str(&format!(
+            "\n⚠️ {}: **{} ms** (p95)",
+            lang.tr(Text::HighLatency),
+            num(p95.as_millis() as u32)
+        ));
+    }
+
+    let state = TitleState::of_online(info);
+
+    let embed = EmbedRepresentation::new()
+        .title(render_title(config, state, Some(info), lang))
+        .description(description)
+        .colour(state.colour())
+        .field(
+            format!("📍 {}", lang.tr(Text::Address)),
+            format!("`{}`", config.server_address),
+            true,
+        );
+
+    let embed = match &config.server_region {
+        Some(region) => embed.field(
+            format!("🌍 {}", lang.tr(Text::Region)),
+            sanitize(region),
+            true,
+        ),
+        None => embed,
+    };
+
+    let embed = embed
+        .field(
+            format!("🔄 {}", lang.tr(Text::Update)),
+            format!("`{}s`", config.update_interval_secs),
+            true,
+        )
+        .field(
+            format!("🗺️ {}", lang.tr(Text::Map)),
+            map_value(info, observation.map_since_unix, lang),
+            true,
+        )
+        .field(
+            format!("🕐 {}", lang.tr(Text::LastUpdated)),
+            rel_ts(info.last_updated_unix),
+            false,
+        );
+
+    let embed = match &observation.motd {
+        Some(motd) => embed.field(format!("📜 {}", lang.tr(Text::Motd)), motd.clone(), false),
+        None => embed,
+    };
+
+    let embed = match observation.wipe {
+        Some(WipeField::Countdown { at_unix }) => embed.field(
+            format!("⏳ {}", lang.tr(Text::Wipe)),
+            rel_ts(at_unix),
+            false,
+        ),
+        Some(WipeField::DaysSince(days)) => embed.field(
+            format!("🧹 {}", lang.tr(Text::DaysSinceWipe)),
+            format_number(days, config.number_locale()),
+            true,
+        ),
+        None => embed,
+    };
+
+    match info.mods.as_deref() {
+        Some(mods) if !mods.is_empty() => {
+            let list = mod_lines(mods).join("\n");
+            let value = if list.len() <= FIELD_LIMIT {
+                list
+            } else {
+                lang.tr(Text::ModListBelow).to_string()
+            };
+
+            embed.field(
+                format!("🧩 {} ({})", lang.tr(Text::Mods), mods.len()),
+                value,
+                false,
+            )
+        }
+        _ => embed,
+    }
+}
+
+/// One page of a mod list too long for the status embed.
+pub fn mod_page_embed(
+    config: &DayzMonitorConfig,
+    chunk: &str,
+    page: usize,
+    pages: usize,
+    lang: Language,
+) -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .title(format!(
+            "🧩 {} — {} ({}/{})",
+            config.server_name,
+            lang.tr(Text::Mods),
+            page,
+            pages
+        ))
+        .description(chunk)
+        .colour(0x5865F2)
+}
+
+/// The server answers again after an outage but has not yet met the recovery criterion.
+pub fn starting_embed(
+    config: &DayzMonitorConfig,
+    info: &ServerInfo,
+    observation: &Observation,
+    lang: Language,
+) -> EmbedRepresentation {
+    let state = TitleState::Starting;
+
+    online_embed(config, info, observation, lang)
+        .title(render_title(config, state, Some(info), lang))
+        .colour(state.colour())
+}
+
+/// The last good data, flagged as stale because the latest response was implausible.
+pub fn degraded_embed(
+    config: &DayzMonitorConfig,
+    last_good: &ServerInfo,
+    observation: &Observation,
+    lang: Language,
+) -> EmbedRepresentation {
+    let state = TitleState::Degraded;
+    let reason = observation.error.as_deref().unwrap_or_default();
+
+    online_embed(config, last_good, observation, lang)
+        .title(render_title(config, state, Some(last_good), lang))
+        .colour(state.colour())
+        .field(
+            format!("⚠️ {}", lang.tr(Text::Error)),
+            format!("`{}`", reason),
+            false,
+        )
+}
+
+/// Before the first poll, neither online nor offline.
+pub fn unknown_embed(config: &DayzMonitorConfig, lang: Language) -> EmbedRepresentation {
+    let state = TitleState::Unknown;
+
+    EmbedRepresentation::new()
+        .title(render_title(config, state, None, lang))
+        .description(lang.tr(Text::NoStatusYet))
+        .colour(state.colour())
+        .field(
+            format!("📍 {}", lang.tr(Text::Address)),
+            format!("`{}`", config.server_address),
+            true,
+        )
+}
+
+pub fn offline_embed(config: &DayzMonitorConfig, err: &str, lang: Language) -> EmbedRepresentation {
+    let state = TitleState::Offline;
+
+    EmbedRepresentation::new()
+        .title(render_title(config, state, None, lang))
+        .description(format!("⚠️ {}", lang.tr(Text::QueryFailed)))
+        .colour(state.colour())
+        .field(
+            format!("📍 {}", lang.tr(Text::Address)),
+            format!("`{}`", config.server_address),
+            true,
+        )
+        .field(
+            format!("🧾 {}", lang.tr(Text::Error)),
+            format!("`{}`", err),
+            false,
+        )
+}
+
+pub fn maintenance_embed(
+    config: &DayzMonitorConfig,
+    until_unix: Option<u64>,
+    lang: Language,
+) -> EmbedRepresentation {
+    let state = TitleState::Maintenance;
+
+    let embed = EmbedRepresentation::new()
+        .title(render_title(config, state, None, lang))
+        .colour(state.colour());
+
+    match until_unix {
+        Some(until) => embed.description(format!(
+            "{} <t:{}:t> ({})",
+            lang.tr(Text::MaintenanceUntil),
+            until,
+            rel_ts(until)
+        )),
+        None => embed,
+    }
+}
+
+/// Adds the remaining snooze time to an embed shown to an admin.
+pub fn with_snooze_field(
+    embed: EmbedRepresentation,
+    until_unix: u64,
+    lang: Language,
+) -> EmbedRepresentation {
+    embed.field(
+        format!("🔕 {}", lang.tr(Text::SnoozedUntil)),
+        format!("<t:{}:f> ({})", until_unix, rel_ts(until_unix)),
+        false,
+    )
+}
+
+/// Confirms a `/snooze`; `None` means the snooze was cancelled.
+pub fn snooze_embed(until_unix: Option<u64>, lang: Language) -> EmbedRepresentation {
+    match until_unix {
+        Some(until) => EmbedRepresentation::new()
+            .description(format!(
+                "🔕 {} <t:{}:f> ({})",
+                lang.tr(Text::SnoozedUntil),
+                until,
+                rel_ts(until)
+            ))
+            .colour(0x5865F2),
+        None => EmbedRepresentation::new()
+            .description(format!("🔔 {}", lang.tr(Text::SnoozeCancelled)))
+            .colour(0x57F287),
+    }
+}
+
+pub fn refresh_embed(lang: Language) -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .description(format!("🔄 {}", lang.tr(Text::RefreshRequested)))
+        .colour(0x5865F2)
+}
+
+pub fn invalid_snooze_embed(lang: Language) -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .description(format!("⚠️ {}", lang.tr(Text::InvalidSnooze)))
+        .colour(0xED4245)
+}
+
+/// Confirms a `/motd edit` or `/motd reset`.
+pub fn motd_embed(confirmation: Text, lang: Language) -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .description(format!("📜 {}", lang.tr(confirmation)))
+        .colour(0x57F287)
+}
+
+/// Explains why a submitted MOTD was not saved.
+pub fn invalid_motd_embed(rejection: MotdRejection, lang: Language) -> EmbedRepresentation {
+    let (count, limit) = rejection.counts();
+
+    EmbedRepresentation::new()
+        .description(format!(
+            "⚠️ {}: **{}** / {}",
+            lang.tr(rejection.text()),
+            count,
+            limit
+        ))
+        .colour(0xED4245)
+}
+
+/// Untracked status messages found by `/cleanup`, as links, waiting for confirmation.
+pub fn cleanup_embed(
+    orphans: &[Orphan],
+    guild_id: Option<u64>,
+    lang: Language,
+) -> EmbedRepresentation {
+    let embed = EmbedRepresentation::new()
+        .title(format!(
+            "🧹 {} ({})",
+            lang.tr(Text::OldStatusMessages),
+            orphans.len()
+        ))
+        .colour(0x5865F2);
+
+    if orphans.is_empty() {
+        return embed.description(lang.tr(Text::NoOldStatusMessages));
+    }
+
+    let line = |o: &Orphan| match guild_id {
+        Some(guild) => format!(
+            "https://discord.com/channels/{guild}/{}/{} • <t:{}:R>",
+            o.channel_id, o.message_id, o.created_unix
+        ),
+        None => format!(
+            "<#{}> {} • <t:{}:R>",
+            o.channel_id, o.message_id, o.created_unix
+        ),
+    };
+
+    let mut description = String::new();
+    for (shown, orphan) in orphans.iter().enumerate() {
+        let next = line(orphan);
+        let more = format!("… +{}", orphans.len() - shown);
+        if description.len() + next.len() + more.len() + 2 > DESCRIPTION_LIMIT {
+            description.push_str(&more);
+            break;
+        }
+        description.push_str(&next);
+        description.push('\n');
+    }
+
+    embed.description(description.trim_end().to_string())
+}
+
+/// What became of a `/cleanup` listing: how many were deleted, or `None` if it was cancelled.
+pub fn cleanup_result_embed(deleted: Option<usize>, lang: Language) -> EmbedRepresentation {
+    let description = match deleted {
+        Some(count) => format!("🧹 {}: **{count}**", lang.tr(Text::Deleted)),
+        None => lang.tr(Text::NothingDeleted).to_string(),
+    };
+
+    EmbedRepresentation::new()
+        .description(description)
+        .colour(0x57F287)
+}
+
+/// The newest audit entries that fit, oldest first; `None` when the audit log is disabled.
+pub fn audit_embed(
+    entries: Option<&[AuditEntry]>,
+    hours: u64,
+    lang: Language,
+) -> EmbedRepresentation {
+    let embed = EmbedRepresentation::new()
+        .title(format!("📜 {} ({}h)", lang.tr(Text::AuditLog), hours))
+        .colour(0x5865F2);
+
+    let Some(entries) = entries else {
+        return embed.description(lang.tr(Text::AuditDisabled));
+    };
+
+    let mut lines = Vec::new();
+    let mut len = 0;
+    for entry in entries.iter().rev() {
+        let line = format!(
+            "<t:{}:f> {}",
+            entry.at_unix,
+            sanitize(&entry.event.describe())
+        );
+        if len + line.len() + 1 > DESCRIPTION_LIMIT {
+            break;
+        }
+        len += line.len() + 1;
+        lines.push(line);
+    }
+    lines.reverse();
+
+    if lines.is_empty() {
+        return embed.description(lang.tr(Text::NoAuditEntries));
+    }
+
+    embed.description(lines.join("\n"))
+}
+
+/// Only the stats with data are shown.
+pub fn stats_embed(
+    config: &DayzMonitorConfig,
+    report: &StatsReport,
+    lang: Language,
+) -> EmbedRepresentation {
+    let locale = config.number_locale();
+    let percent = |p: f64| format!("**{}%**", format_decimal(p, 1, locale));
+
+    let mut embed = EmbedRepresentation::new()
+        .title(format!(
+            "📊 {} — {}",
+            config.server_name,
+            lang.tr(Text::Stats)
+        ))
+        .colour(0x5865F2);
+
+    if report.is_empty() {
+        return embed.description(lang.tr(Text::NoStatsYet));
+    }
+
+    if let Some((players, at)) = report.peak {
+        embed = embed.field(
+            format!("📈 {}", lang.tr(Text::PeakToday)),
+            format!(
+                "**{}** (<t:{}:t>)",
+                format_number(players.into(), locale),
+                at
+            ),
+            true,
+        );
+    }
+    if let Some(hours) = report.player_hours_today {
+        embed = embed.field(
+            format!("⏱️ {}", lang.tr(Text::PlayerHoursToday)),
+            format!("**{}**", format_decimal(hours, 0, locale)),
+            true,
+        );
+    }
+    if let Some(queue) = report.longest_queue {
+        embed = embed.field(
+            format!("⏳ {}", lang.tr(Text::LongestQueueToday)),
+            format!("**{}**", format_number(queue.into(), locale)),
+            true,
+        );
+    }
+    if let Some(uptime) = report.uptime_today {
+        embed = embed.field(
+            format!("✅ {}", lang.tr(Text::UptimeToday)),
+            percent(uptime),
+            true,
+        );
+    }
+    if let Some(uptime) = report.uptime_week {
+        embed = embed.field(
+            format!("📅 {}", lang.tr(Text::UptimeWeek)),
+            percent(uptime),
+            true,
+        );
+    }
+    if let Some(restarts) = report.restarts_today {
+        embed = embed.field(
+            format!("🔁 {}", lang.tr(Text::RestartsToday)),
+            format!("**{}**", format_number(restarts.into(), locale)),
+            true,
+        );
+    }
+    if let Some(players) = report.unique_players_today {
+        embed = embed.field(
+            format!("👤 {}", lang.tr(Text::UniquePlayersToday)),
+            format!("**{}**", format_number(players.into(), locale)),
+            true,
+        );
+    }
+    if let Some(secs) = report.longest_session_today_secs {
+        embed = embed.field(
+            format!("🕒 {}", lang.tr(Text::LongestSessionToday)),
+            format!("**{}**", format_duration(secs)),
+            true,
+        );
+    }
+    if !report.events.is_empty() {
+        let mut value = String::new();
+        for event in &report.events {
+            let delta = event.delta();
+            let line = format!(
+                "**{}**: **{}{}** {} {}",
+                sanitize(&event.name),
+                if delta < 0 { "-" } else { "+" },
+                format_number(delta.unsigned_abs(), locale),
+                lang.noun(Noun::Player, delta.unsigned_abs()),
+                lang.vs_typical(event.weekday, &event.time)
+            );
+            if value.len() + line.len() + 1 > FIELD_LIMIT {
+                break;
+            }
+            value.push_str(&line);
+            value.push('\n');
+        }
+        embed = embed.field(format!("🎉 {}", lang.tr(Text::EventImpact)), value, false);
+    }
+    if let Some(secs) = report.unmonitored_week_secs {
+        embed = embed.footer(format!(
+            "⬛ {}: {} — {}",
+            lang.tr(Text::NotMonitoredWeek),
+            format_duration(secs),
+            lang.tr(Text::LeftOutOfStats)
+        ));
+    }
+
+    embed
+}
+
+/// Settings and features grouped by area, for admins. Areas too long for one field continue in
+/// the next.
+pub fn config_embed(
+    config: &DayzMonitorConfig,
+    subsystems: &[(&'static str, Health)],
+) -> EmbedRepresentation {
+    let settings = features::settings(config);
+    let features = features::registry(config);
+
+    let mut embed = EmbedRepresentation::new()
+        .title(format!("⚙️ {} — configuration", config.server_name))
+        .colour(0x5865F2);
+
+    for area in Area::ALL {
+        let mut lines: Vec<String> = settings
+            .iter()
+            .filter(|s| s.area == area)
+            .map(|s| format!("`{}` {}", s.name, sanitize(&s.value)))
+            .collect();
+
+        lines.extend(features.iter().filter(|f| f.area == area).map(|f| {
+            let mark = match f.activation {
+                Activation::On => "✅",
+                Activation::Off => "➖",
+                Activation::Inactive(_) => "⚠️",
+                Activation::NotBuilt(_) => "📦",
+            };
+            format!("{mark} {}: {}", f.name, f.activation)
+        }));
+
+        for (i, chunk) in chunk_lines(&lines, FIELD_LIMIT).into_iter().enumerate() {
+            let name = match i {
+                0 => area.name().to_string(),
+                n => format!("{} ({})", area.name(), n + 1),
+            };
+            embed = embed.field(name, chunk, false);
+        }
+    }
+
+    let lines: Vec<String> = subsystems
+        .iter()
+        .map(|(name, health)| {
+            let mark = match health {
+                Health::Up => "🟢",
+                Health::Degraded(_) => "🟡",
+                Health::Disabled(_) => "🔴",
+            };
+            format!("{mark} {name}: {}", sanitize(&health.to_string()))
+        })
+        .collect();
+
+    for (i, chunk) in chunk_lines(&lines, FIELD_LIMIT).into_iter().enumerate() {
+        let name = match i {
+            0 => "Subsystems".to_string(),
+            n => format!("Subsystems ({})", n + 1),
+        };
+        embed = embed.field(name, chunk, false);
+    }
+
+    embed
+}
+
+/// Where the time of a `/refresh verbose` cycle went, for admins. `None` when no cycle finished
+/// in time.
+pub fn cycle_report_embed(
+    config: &DayzMonitorConfig,
+    report: Option<&CycleReport>,
+) -> EmbedRepresentation {
+    let embed = EmbedRepresentation::new()
+        .title(format!("⏱️ {} — cycle timing", config.server_name))
+        .colour(0x5865F2);
+
+    let Some(report) = report else {
+        return embed
+            .description("No cycle finished in time; the server may not be answering.")
+            .colour(0xED4245);
+    };
+
+    let ms = |d: Duration| format!("`{}ms`", d.as_millis());
+    let phase = |d: Option<Duration>| d.map_or_else(|| "–".to_string(), ms);
+
+    let attempts: Vec<String> = report
+        .attempts
+        .iter()
+        .map(|a| {
+            let outcome = match &a.outcome {
+                AttemptOutcome::Answered => "✅".to_string(),
+                AttemptOutcome::Failed(err) => format!("❌ {}", sanitize(err)),
+                AttemptOutcome::Cancelled => "➖ cancelled".to_string(),
+            };
+            format!("{} `{}` {} {outcome}", a.query, a.addr, ms(a.took))
+        })
+        .collect();
+    let attempts = chunk_lines(&attempts, FIELD_LIMIT)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "–".to_string());
+
+    embed
+        .field("DNS", phase(report.resolve), true)
+        .field("Render", phase(report.render), true)
+        .field("Discord edit", phase(report.edit), true)
+        .field("A2S queries", attempts, false)
+        .field("Total", ms(report.total), true)
+}
+
+pub fn players_embed(
+    config: &DayzMonitorConfig,
+    players: &[Player],
+    lang: Language,
+) -> EmbedRepresentation {
+    // Players still connecting are reported with an empty name.
+    let names: Vec<String> = players
+        .iter()
+        .filter(|p| !p.name.is_empty())
+        .map(|p| format!("• {}", sanitize(&p.name)))
+        .collect();
+
+    let mut description = String::new();
+    for name in &names {
+        if description.len() + name.len() + 1 > DESCRIPTION_LIMIT {
+            break;
+        }
+        description.push_str(name);
+        description.push('\n');
+    }
+
+    if description.is_empty() {
+        description = lang.tr(Text::NoPlayers).to_string();
+    }
+
+    EmbedRepresentation::new()
+        .title(format!(
+            "👥 {} — {} ({})",
+            config.server_name,
+            lang.tr(Text::OnlinePlayers),
+            names.len()
+        ))
+        .description(description)
+        .colour(0x57F287)
+}
+
+/// One line per hop, coloured by the worst hop.
+pub fn ping_embed(
+    hops: &[(Text, Option<Duration>)],
+    thresholds: &LatencyThresholds,
+    lang: Language,
+) -> EmbedRepresentation {
+    let mut worst = LatencyGrade::Good;
+    let mut description = String::new();
+
+    for (label, latency) in hops {
+        let grade = thresholds.grade(*latency);
+        worst = worst.max(grade);
+
+        let value = match latency {
+            Some(l) => format!("**{} ms**", l.as_millis()),
+            None => lang.tr(Text::Unavailable).to_string(),
+        };
+
+        description.push_str(&format!(
+            "{} {}: {}\n",
+            grade.emoji(),
+            lang.tr(*label),
+            value
+        ));
+    }
+
+    EmbedRepresentation::new()
+        .title(format!("📶 {}", lang.tr(Text::Latency)))
+        .description(description)
+        .colour(worst.colour())
+}
+
+/// One compact entry of the log channel. Like `/config`, it is for operators and not translated.
+pub fn log_embed(config: &DayzMonitorConfig, event: &OpEvent, at_unix: u64) -> EmbedRepresentation {
+    let locale = config.number_locale();
+
+    match event {
+        OpEvent::Transition {
+            from,
+            to,
+            cause,
+            players,
+            maintenance_until,
+        } => {
+            let mut description = format!("<t:{at_unix}:f>");
+            if let Some(cause) = cause {
+                description.push_str(&format!("\n{}", sanitize(cause)));
+            }
+
+            let mut embed = EmbedRepresentation::new()
+                .title(format!(
+                    "{} {} → {}",
+                    TitleState::of_state(*to).emoji(),
+                    state_name(*from),
+                    state_name(*to)
+                ))
+                .description(description)
+                .colour(TitleState::of_state(*to).colour());
+
+            if let Some((players, max_players)) = players {
+                embed = embed.field(
+                    "Players",
+                    format!(
+                        "{}/{}",
+                        format_number((*players).into(), locale),
+                        format_number((*max_players).into(), locale)
+                    ),
+                    true,
+                );
+            }
+            if let Some(until) = maintenance_until {
+                embed = embed.field("Until", format!("<t:{until}:f>"), true);
+            }
+
+            embed
+        }
+        OpEvent::StatusMessagePosted {
+            message_id,
+            replaced,
+        } => {
+            let mut description = format!("<t:{at_unix}:f>\nMessage {message_id}");
+            if let Some(replaced) = replaced {
+                description.push_str(&format!(", replacing {replaced}"));
+            }
+
+            EmbedRepresentation::new()
+                .title("📌 Status message posted")
+                .description(description)
+                .colour(0x5865F2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dayz_monitor::{
+        headcount::CountMismatch,
+        monitor::Counts,
+        render::{diff, EmbedChange},
+        DayzMonitorConfig, ServerInfo,
+    };
+    use serde_json::json;
+
+    fn config(extra: serde_json::Value) -> DayzMonitorConfig {
+        let mut config = json!({
+            "discord_token": "token",
+            "server_address": "127.0.0.1:2303",
+            "server_name": "Chernarus 1",
+            "text_channel_id": 1,
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    fn online(other_regions: Vec<RegionPing>) -> Observation {
+        let mut observation = Observation::unknown();
+        observation.state = MonitorState::Online;
+        observation.info = Some(ServerInfo {
+            server_time: None,
+            players_in_queue: Some(0),
+            players: 12,
+            max_players: 60,
+            map: "chernarusplus".to_string(),
+            latency: Some(Duration::from_millis(18)),
+            last_updated_unix: 1_760_000_000,
+            mods: None,
+            headcount: None,
+            player_names: None,
+            game_port: None,
+            join_port: None,
+            ignored_tokens: Vec::new(),
+        });
+        observation.other_regions = other_regions;
+        observation
+    }
+
+    fn ping_line(embed: &EmbedRepresentation) -> Option<&str> {
+        embed
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .find(|line| line.starts_with("📶"))
+    }
+
+    fn player(name: &str) -> Player {
+        Player {
+            index: 0,
+            name: name.to_string(),
+            score: 0,
+            duration: 60.0,
+            the_ship: None,
+        }
+    }
+
+    #[test]
+    fn player_list_is_localized_and_skips_connecting_players() {
+        let players = [player("Survivor"), player(""), player("*Bandit*")];
+
+        let embed = players_embed(&config(json!({})), &players, Language::De);
+
+        assert_eq!(
+            embed.title.as_deref(),
+            Some("👥 Chernarus 1 — Spieler online (2)")
+        );
+        assert_eq!(
+            embed.description.as_deref(),
+            Some("• Survivor\n• \\*Bandit\\*\n")
+        );
+    }
+
+    #[test]
+    fn empty_player_list_says_so_in_the_reply_language() {
+        let embed = players_embed(&config(json!({})), &[player("")], Language::Fr);
+
+        assert_eq!(
+            embed.description.as_deref(),
+            Some("Personne n'est en ligne pour le moment.")
+        );
+    }
+
+    #[test]
+    fn long_player_list_stops_at_the_description_limit() {
+        let players: Vec<Player> = (0..400)
+            .map(|i| player(&format!("Player {i:04}")))
+            .collect();
+
+        let embed = players_embed(&config(json!({})), &players, Language::En);
+
+        let description = embed.description.unwrap();
+        assert!(description.len() <= DESCRIPTION_LIMIT);
+        assert!(description.ends_with('\n'));
+        assert_eq!(
+            embed.title.as_deref(),
+            Some("👥 Chernarus 1 — Online players (400)")
+        );
+    }
+
+    #[test]
+    fn ping_lists_every_hop_coloured_by_the_worst() {
+        let thresholds = config(json!({})).latency_thresholds();
+        let hops = [
+            (Text::Gateway, Some(Duration::from_millis(40))),
+            (Text::DiscordApi, Some(Duration::from_millis(180))),
+            (Text::GameServer, None),
+        ];
+
+        let embed = ping_embed(&hops, &thresholds, Language::En);
+
+        assert_eq!(
+            embed.description.as_deref(),
+            Some(
+                "🟢 Discord gateway: **40 ms**\n\
+                 🟡 Discord API: **180 ms**\n\
+                 🔴 Game server: unavailable\n"
+            )
+        );
+        assert_eq!(embed.colour, Some(LatencyGrade::Unavailable.colour()));
+
+        let embed = ping_embed(&hops[..2], &thresholds, Language::En);
+        assert_eq!(embed.colour, Some(LatencyGrade::Warn.colour()));
+    }
+
+    #[test]
+    fn server_region_is_a_field_next_to_the_address() {
+        let embed = status_embed(
+            &config(json!({ "server_region": "EU-Frankfurt" })),
+            &online(Vec::new()),
+            Language::De,
+        );
+
+        let names: Vec<&str> = embed.fields.iter().map(|f| f.name.as_str()).collect();
+        let region = names.iter().position(|n| *n == "🌍 Region").unwrap();
+        assert_eq!(names[region - 1], "📍 Adresse");
+        assert_eq!(embed.fields[region].value, "EU-Frankfurt");
+        assert!(embed.fields[region].inline);
+
+        let embed = status_embed(&config(json!({})), &online(Vec::new()), Language::En);
+        assert!(embed.fields.iter().all(|f| !f.name.contains("Region")));
+    }
+
+    #[test]
+    fn ping_is_labelled_with_the_bot_region() {
+        let observation = online(Vec::new());
+
+        let embed = status_embed(&config(json!({})), &observation, Language::En);
+        assert_eq!(ping_line(&embed), None);
+
+        let config = config(json!({ "bot_region": "EU" }));
+        let cases = [
+            (Language::En, "📶 Ping from EU: **18 ms**"),
+            (Language::De, "📶 Ping aus EU: **18 ms**"),
+            (Language::Fr, "📶 Ping depuis EU: **18 ms**"),
+            (Language::Ru, "📶 Пинг из EU: **18 ms**"),
+        ];
+        for (lang, expected) in cases {
+            let embed = status_embed(&config, &observation, lang);
+            assert_eq!(ping_line(&embed), Some(expected), "{lang:?}");
+        }
+    }
+
+    #[test]
+    fn other_regions_follow_the_own_ping() {
+        let na = RegionPing {
+            region: "NA".to_string(),
+            latency_ms: 1_095,
+            measured_unix: 1_760_000_000,
+        };
+
+        let embed = status_embed(
+            &config(json!({ "bot_region": "EU", "number_locale": "de" })),
+            &online(vec![na]),
+            Language::En,
+        );
+
+        assert_eq!(
+            ping_line(&embed),
+            Some("📶 Ping from EU: **18 ms** • NA: **1.095 ms**")
+        );
+    }
+
+    #[test]
+    fn unchanged_observation_renders_the_same_embed() {
+        let config = config(json!({}));
+        let observation = online(Vec::new());
+
+        let first = status_embed(&config, &observation, Language::En);
+        let again = status_embed(&config, &observation, Language::En);
+        assert_eq!(diff(&first, &again), []);
+
+        let mut busier = observation.clone();
+        busier.info.as_mut().unwrap().players = 13;
+        let changed = status_embed(&config, &busier, Language::En);
+        assert!(diff(&first, &changed).contains(&EmbedChange::Description));
+    }
+
+    fn time_line(embed: &EmbedRepresentation) -> Option<&str> {
+        embed
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .find(|line| line.starts_with("🕒"))
+    }
+
+    #[test]
+    fn time_line_follows_the_time_field_setting() {
+        let cases = [
+            ("auto", false, None, None),
+            ("auto", true, None, Some("🕒 Server Time: **Unknown**")),
+            (
+                "auto",
+                false,
+                Some("14:32"),
+                Some("🕒 Server Time: **14:32**"),
+            ),
+            ("always", false, None, Some("🕒 Server Time: **Unknown**")),
+            ("never", true, Some("14:32"), None),
+        ];
+
+        for (time_field, seen_before, server_time, expected) in cases {
+            let config = config(json!({ "time_field": time_field }));
+            let mut observation = online(Vec::new());
+            observation.server_time_seen = seen_before;
+            observation.info.as_mut().unwrap().server_time = server_time.map(str::to_string);
+
+            let embed = status_embed(&config, &observation, Language::En);
+
+            assert_eq!(
+                time_line(&embed),
+                expected,
+                "{time_field}, seen before: {seen_before}, reported: {server_time:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn high_latency_is_flagged_in_the_description() {
+        let config = config(json!({ "number_locale": "de" }));
+        let mut observation = online(Vec::new());
+        let flagged = |embed: &EmbedRepresentation| {
+            embed
+                .description
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .find(|line| line.starts_with("⚠️"))
+                .map(str::to_string)
+        };
+
+        let embed = status_embed(&config, &observation, Language::En);
+        assert_eq!(flagged(&embed), None);
+
+        observation.high_latency = Some(Duration::from_millis(1_250));
+        let embed = status_embed(&config, &observation, Language::En);
+        assert_eq!(
+            flagged(&embed).as_deref(),
+            Some("⚠️ High latency: **1.250 ms** (p95)")
+        );
+    }
+
+    #[test]
+    fn stats_show_only_what_has_data() {
+        let config = config(json!({ "number_locale": "de" }));
+
+        let embed = stats_embed(&config, &StatsReport::default(), Language::En);
+        assert_eq!(
+            embed.description.as_deref(),
+            Some("No stats have been collected yet.")
+        );
+        assert!(embed.fields.is_empty());
+
+        let report = StatsReport {
+            peak: Some((1_024, 1_760_000_000)),
+            uptime_week: Some(99.26),
+            ..StatsReport::default()
+        };
+        let embed = stats_embed(&config, &report, Language::En);
+        let fields: Vec<(&str, &str)> = embed
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("📈 Peak today", "**1.024** (<t:1760000000:t>)"),
+                ("📅 Uptime 7 days", "**99,3%**")
+            ]
+        );
+    }
+
+    fn players_line(embed: &EmbedRepresentation) -> &str {
+        embed
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn changes_are_annotated_against_the_previous_poll() {
+        let cases = [
+            // No previous online poll: nothing to compare with.
+            (None, 12, 0, "👥 Players: **12 / 60**"),
+            (Some((12, 0)), 12, 0, "👥 Players: **12 / 60**"),
+            (Some((9, 0)), 12, 0, "👥 Players: **12** (+3) / 60"),
+            (Some((1_500, 0)), 12, 0, "👥 Players: **12** (-1,488) / 60"),
+            (
+                Some((60, 2)),
+                60,
+                5,
+                "👥 Players: **60 / 60** • ⏳ Queue: **5** (+3)",
+            ),
+            (
+                Some((58, 4)),
+                60,
+                1,
+                "👥 Players: **60** (+2) / 60 • ⏳ Queue: **1** (-3)",
+            ),
+            // An emptied queue isn't shown, so neither is its change.
+            (Some((60, 4)), 60, 0, "👥 Players: **60 / 60**"),
+        ];
+
+        let config = config(json!({ "change_annotations": true }));
+        for (previous, players, queue, expected) in cases {
+            let mut observation = online(Vec::new());
+            let info = observation.info.as_mut().unwrap();
+            info.players = players;
+            info.players_in_queue = Some(queue);
+            observation.previous_counts =
+                previous.map(|(players, queue)| Counts { players, queue });
+
+            let embed = status_embed(&config, &observation, Language::En);
+            assert_eq!(players_line(&embed), expected, "{previous:?}");
+        }
+    }
+
+    #[test]
+    fn annotations_need_the_setting_and_an_exact_count() {
+        let mut observation = online(Vec::new());
+        observation.previous_counts = Some(Counts {
+            players: 9,
+            queue: 0,
+        });
+
+        let cases = [
+            (json!({}), "👥 Players: **12 / 60**"),
+            (
+                json!({ "change_annotations": true }),
+                "👥 Players: **12** (+3) / 60",
+            ),
+            (
+                json!({ "change_annotations": true, "player_count_mode": "hidden" }),
+                "👥 Player count hidden by server",
+            ),
+        ];
+        for (extra, expected) in cases {
+            let embed = status_embed(&config(extra.clone()), &observation, Language::En);
+            assert_eq!(players_line(&embed), expected, "{extra}");
+        }
+
+        let approximate = config(json!({
+            "change_annotations": true,
+            "player_count_mode": "approximate",
+        }));
+        let embed = status_embed(&approximate, &observation, Language::En);
+        assert!(!players_line(&embed).contains("(+"));
+    }
+
+    #[test]
+    fn config_lists_areas_in_order_and_subsystems_last() {
+        // Field names without the continuation numbers, each area once.
+        let area_names = |embed: &EmbedRepresentation| {
+            let mut names: Vec<&str> = embed
+                .fields
+                .iter()
+                .map(|f| f.name.split(" (").next().unwrap())
+                .collect();
+            names.dedup();
+            names.into_iter().map(str::to_string).collect::<Vec<_>>()
+        };
+        let subsystems = [("audit_log", Health::Disabled("disk full".to_string()))];
+
+        let short = config_embed(&config(json!({})), &subsystems);
+        // Long values push an area into a continuation field rather than past the limit.
+        let long = config_embed(
+            &config(json!({ "outputs": ["x".repeat(600), "y".repeat(600)] })),
+            &subsystems,
+        );
+
+        for embed in [&short, &long] {
+            assert_eq!(
+                area_names(embed),
+                ["Polling", "Embed", "Alerts", "Integrations", "Subsystems"]
+            );
+            assert!(embed.fields.iter().all(|f| f.value.len() <= FIELD_LIMIT));
+            assert_eq!(
+                embed.fields.last().unwrap().value,
+                "🔴 audit_log: disabled (disk full)"
+            );
+        }
+        assert!(long.fields.len() > short.fields.len());
+        assert!(long.fields.iter().any(|f| f.name == "Embed (2)"));
+    }
+
+    #[test]
+    fn log_entries_show_the_transition_and_its_details() {
+        let config = config(json!({ "number_locale": "de" }));
+        let transition = OpEvent::Transition {
+            from: MonitorState::Online,
+            to: MonitorState::Offline,
+            cause: Some("query *timed* out".to_string()),
+            players: Some((1_200, 2_000)),
+            maintenance_until: Some(1_760_001_200),
+        };
+
+        let embed = log_embed(&config, &transition, 1_760_000_000);
+
+        assert_eq!(embed.title.as_deref(), Some("🔴 online → offline"));
+        assert_eq!(
+            embed.description.as_deref(),
+            Some("<t:1760000000:f>\nquery \\*timed\\* out")
+        );
+        let fields: Vec<(&str, &str)> = embed
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [("Players", "1.200/2.000"), ("Until", "<t:1760001200:f>")]
+        );
+    }
+
+    #[test]
+    fn log_entry_for_a_new_status_message_names_the_one_it_replaces() {
+        let config = config(json!({}));
+        let cases = [
+            (None, "<t:1760000000:f>\nMessage 1001"),
+            (Some(900), "<t:1760000000:f>\nMessage 1001, replacing 900"),
+        ];
+
+        for (replaced, expected) in cases {
+            let event = OpEvent::StatusMessagePosted {
+                message_id: 1001,
+                replaced,
+            };
+            let embed = log_embed(&config, &event, 1_760_000_000);
+            assert_eq!(embed.title.as_deref(), Some("📌 Status message posted"));
+            assert_eq!(embed.description.as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn flagged_player_count_is_shown_next_to_the_verified_one() {
+        let mut observation = online(Vec::new());
+        observation.count_mismatch = Some(CountMismatch {
+            reported: 40,
+            verified: 12,
+        });
+        let warning = |embed: &EmbedRepresentation| {
+            embed
+                .description
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .find(|line| line.starts_with("⚠️"))
+                .map(str::to_string)
+        };
+
+        let cases = [
+            (json!({}), None),
+            (
+                json!({ "player_count_annotation": true }),
+                Some("⚠️ Reported **40**, verified **12**"),
+            ),
+            (
+                json!({ "player_count_annotation": true, "player_count_mode": "approximate" }),
+                None,
+            ),
+        ];
+        for (extra, expected) in cases {
+            let embed = status_embed(&config(extra.clone()), &observation, Language::En);
+            assert_eq!(warning(&embed).as_deref(), expected, "{extra}");
+        }
+    }
+}
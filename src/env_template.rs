@@ -0,0 +1,653 @@
+use serde::{
+    de::{self, value, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use std::fmt::{Debug, Write};
+
+use crate::{
+    default_a2s_retry_delay_ms, default_alert_latency_window, default_alert_startup_offline_polls,
+    default_audit_retention_hours, default_cleanup_min_age_hours,
+    default_game_port_unreachable_polls, default_notify_retries, default_ping_bad_ms,
+    default_ping_timeout_ms, default_ping_warn_ms, default_player_count_mismatch_polls,
+    default_player_count_tolerance, default_reconcile_concurrency, default_recovery_polls,
+    default_retry_delay_ms, default_sanity_max_players, default_sanity_max_queue,
+    default_server_name, default_status_check_every, default_status_edit_retries,
+    default_topic_min_interval_secs, default_tracked_sessions, default_unique_names_per_day,
+    default_update_interval_secs, default_wipe_announce_offsets,
+    gameport::GamePortProbe,
+    i18n::Language,
+    monitor::RecoveryCheck,
+    player_count::PlayerCountMode,
+    reconcile::ReconcilePolicy,
+    topic::DEFAULT_TOPIC_TEMPLATE,
+    wipe::{MissedAnnouncements, WipeChannel},
+    DayzMonitorConfig, TimeField,
+};
+
+/// One environment variable of [`DayzMonitorConfig`], as printed by `--print-env-template`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: &'static str,
+    pub kind: &'static str,
+
+    /// `None` when the variable has no default and is simply unset
+    pub default: Option<String>,
+    pub required: bool,
+
+    /// Grants access elsewhere and must not be shared
+    pub secret: bool,
+    pub description: &'static str,
+}
+
+impl EnvVar {
+    fn new(name: &'static str, kind: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            default: None,
+            required: false,
+            secret: false,
+            description,
+        }
+    }
+
+    fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    fn with_default(mut self, default: impl ToString) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+}
+
+/// Every variable the config reads, in declaration order. A test checks this against the fields
+/// of [`DayzMonitorConfig`], so a new field cannot be left out.
+pub fn env_vars() -> Vec<EnvVar> {
+    const ID: &str = "Discord id";
+    const TEXT: &str = "string";
+    const INT: &str = "integer";
+    const BOOL: &str = "boolean";
+    const PATH: &str = "path";
+    const RULES: &str = "comma-separated list of offline, online, queue, latency";
+
+    vec![
+        EnvVar::new("DISCORD_TOKEN", TEXT, "Discord bot token")
+            .required()
+            .secret(),
+        EnvVar::new(
+            "SERVER_ADDRESS",
+            "host:port",
+            "Query port of the server, not the game port",
+        )
+        .required(),
+        EnvVar::new("SERVER_NAME", TEXT, "Name shown in the status embed")
+            .with_default(default_server_name()),
+        EnvVar::new(
+            "SERVER_ID",
+            TEXT,
+            "Stable name the stats history belongs to (default SERVER_NAME)",
+        ),
+        EnvVar::new(
+            "SERVER_REGION",
+            TEXT,
+            "Where the server is hosted, shown in the status embed",
+        ),
+        EnvVar::new(
+            "TEXT_CHANNEL_ID",
+            ID,
+            "Channel the status message is posted in",
+        )
+        .required(),
+        EnvVar::new(
+            "STATUS_MESSAGE_ID",
+            ID,
+            "Existing message of this bot to keep editing",
+        ),
+        EnvVar::new(
+            "GATEWAY",
+            BOOL,
+            "Connect to the gateway; false leaves out slash commands",
+        )
+        .with_default(true),
+        EnvVar::new("UPDATE_INTERVAL_SECS", INT, "Seconds between updates")
+            .with_default(default_update_interval_secs()),
+        EnvVar::new(
+            "STATUS_FILE",
+            PATH,
+            "JSON status file rewritten every update",
+        ),
+        EnvVar::new("MOD_LIST", BOOL, "Show the server's mods").with_default(false),
+        EnvVar::new(
+            "STATE_FILE",
+            PATH,
+            "Where the bot remembers things across restarts",
+        ),
+        EnvVar::new("ALERT_CHANNEL_ID", ID, "Channel alerts are posted in"),
+        EnvVar::new(
+            "ALERT_OFFLINE_TEMPLATE",
+            TEXT,
+            "Message sent when the server goes offline",
+        ),
+        EnvVar::new(
+            "ALERT_STARTUP_OFFLINE_POLLS",
+            INT,
+            "Offline polls after startup before alerting, 0 never alerts",
+        )
+        .with_default(default_alert_startup_offline_polls()),
+        EnvVar::new(
+            "ALERT_ONLINE_TEMPLATE",
+            TEXT,
+            "Message sent when the server comes back",
+        ),
+        EnvVar::new(
+            "ALERT_QUEUE_THRESHOLD",
+            INT,
+            "Alert when more players than this are queued",
+        ),
+        EnvVar::new(
+            "ALERT_QUEUE_TEMPLATE",
+            TEXT,
+            "Message sent when the queue passes the threshold",
+        ),
+        EnvVar::new(
+            "SILENT_ALERTS",
+            BOOL,
+            "Post alerts without a push notification",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "ALERT_CHART_RULES",
+            RULES,
+            "Alerts posted with a chart of the last 6 hours",
+        ),
+        EnvVar::new(
+            "TELEGRAM_BOT_TOKEN",
+            TEXT,
+            "Telegram bot to also send alerts with",
+        )
+        .secret(),
+        EnvVar::new(
+            "TELEGRAM_CHAT_ID",
+            TEXT,
+            "Telegram chat id or @channelname to send alerts to",
+        ),
+        EnvVar::new(
+            "TELEGRAM_RULES",
+            RULES,
+            "Alerts sent to Telegram (default all)",
+        ),
+        EnvVar::new("TELEGRAM_RETRIES", INT, "Retries per Telegram alert")
+            .with_default(default_notify_retries()),
+        EnvVar::new(
+            "TELEGRAM_RETRY_DELAY_MS",
+            INT,
+            "Delay before the first Telegram retry, doubling after each",
+        )
+        .with_default(default_retry_delay_ms()),
+        EnvVar::new("NTFY_URL", "URL", "ntfy topic to also publish alerts to").secret(),
+        EnvVar::new(
+            "NTFY_TOKEN",
+            TEXT,
+            "Access token for a protected ntfy topic",
+        )
+        .secret(),
+        EnvVar::new(
+            "NTFY_RULES",
+            RULES,
+            "Alerts published to ntfy (default all)",
+        ),
+        EnvVar::new("NTFY_RETRIES", INT, "Retries per ntfy alert")
+            .with_default(default_notify_retries()),
+        EnvVar::new(
+            "NTFY_RETRY_DELAY_MS",
+            INT,
+            "Delay before the first ntfy retry, doubling after each",
+        )
+        .with_default(default_retry_delay_ms()),
+        EnvVar::new(
+            "SILENT_STATUS_MESSAGE",
+            BOOL,
+            "Post the first status message without a push notification",
+        )
+        .with_default(true),
+        EnvVar::new(
+            "SILENT_MOD_LIST",
+            BOOL,
+            "Post the mod list messages without a push notification",
+        )
+        .with_default(true),
+        EnvVar::new(
+            "SILENT_LOG_CHANNEL",
+            BOOL,
+            "Post the log channel entries without a push notification",
+        )
+        .with_default(true),
+        EnvVar::new(
+            "SILENT_WIPE_ANNOUNCEMENT",
+            BOOL,
+            "Post wipe announcements without a push notification",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "ALERT_LATENCY_MS",
+            INT,
+            "Alert when the p95 query latency exceeds this many milliseconds",
+        ),
+        EnvVar::new(
+            "ALERT_LATENCY_CLEAR_MS",
+            INT,
+            "Latency below which the latency alert clears (default 80% of ALERT_LATENCY_MS)",
+        ),
+        EnvVar::new(
+            "ALERT_LATENCY_WINDOW",
+            INT,
+            "Number of recent queries the p95 is taken over",
+        )
+        .with_default(default_alert_latency_window()),
+        EnvVar::new(
+            "ALERT_LATENCY_TEMPLATE",
+            TEXT,
+            "Message sent when latency turns high",
+        ),
+        EnvVar::new(
+            "ALERT_JOIN_PORT_TEMPLATE",
+            TEXT,
+            "Message sent when the game port turns unreachable",
+        ),
+        EnvVar::new(
+            "BOT_LANGUAGE",
+            "one of en, de, fr, ru",
+            "Language of the status embed",
+        )
+        .with_default(Language::default().code()),
+        EnvVar::new(
+            "NUMBER_LOCALE",
+            "one of en, de, fr, ru",
+            "Digit grouping for numbers (default BOT_LANGUAGE)",
+        ),
+        EnvVar::new(
+            "FORCE_BOT_LANGUAGE",
+            BOOL,
+            "Answer commands in BOT_LANGUAGE instead of each user's language",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "SANITY_MAX_PLAYERS",
+            INT,
+            "Responses claiming more players are ignored as corrupt",
+        )
+        .with_default(default_sanity_max_players()),
+        EnvVar::new(
+            "SANITY_MAX_SLOTS",
+            INT,
+            "Responses claiming more slots are ignored as corrupt",
+        )
+        .with_default(default_sanity_max_players()),
+        EnvVar::new(
+            "SANITY_MAX_QUEUE",
+            INT,
+            "Responses claiming a longer queue are ignored as corrupt",
+        )
+        .with_default(default_sanity_max_queue()),
+        EnvVar::new(
+            "PING_WARN_MS",
+            INT,
+            "/ping shows latencies from this as slow",
+        )
+        .with_default(default_ping_warn_ms()),
+        EnvVar::new(
+            "PING_BAD_MS",
+            INT,
+            "/ping shows latencies from this as very slow",
+        )
+        .with_default(default_ping_bad_ms()),
+        EnvVar::new("PING_TIMEOUT_MS", INT, "How long /ping waits for each hop")
+            .with_default(default_ping_timeout_ms()),
+        EnvVar::new(
+            "BOT_REGION",
+            TEXT,
+            "Where the bot runs, to show its latency as the ping from there",
+        ),
+        EnvVar::new(
+            "REGION_PINGS_DIR",
+            PATH,
+            "Directory shared with bots in other regions, to show their pings as well",
+        ),
+        EnvVar::new(
+            "A2S_RETRIES",
+            INT,
+            "Retries of a failed server query within one update",
+        )
+        .with_default(0),
+        EnvVar::new(
+            "A2S_RETRY_DELAY_MS",
+            INT,
+            "Delay before the first query retry, doubling after each",
+        )
+        .with_default(default_a2s_retry_delay_ms()),
+        EnvVar::new(
+            "STATUS_EDIT_RETRIES",
+            INT,
+            "Retries of a status message edit Discord failed to answer",
+        )
+        .with_default(default_status_edit_retries()),
+        EnvVar::new(
+            "STATUS_EDIT_RETRY_DELAY_MS",
+            INT,
+            "Delay before the first edit retry, doubling after each",
+        )
+        .with_default(default_retry_delay_ms()),
+        EnvVar::new(
+            "STATUS_CHECK_EVERY",
+            INT,
+            "Look at the status message for changes by others every this many edits; 0 only after a failed edit",
+        )
+        .with_default(default_status_check_every()),
+        EnvVar::new(
+            "STATUS_UNSUPPRESS_EMBEDS",
+            BOOL,
+            "Show the status message's embeds again when someone hid them",
+        )
+        .with_default(true),
+        EnvVar::new(
+            "OWN_CONTENT_ONLY",
+            BOOL,
+            "Remove text others added to the status message instead of keeping it",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "RECOVERY_POLLS",
+            INT,
+            "Successful polls before an offline server counts as back",
+        )
+        .with_default(default_recovery_polls()),
+        EnvVar::new(
+            "RECOVERY_CHECK",
+            "one of answer, joinable",
+            "What a recovering server must show besides answering",
+        )
+        .with_default(lower(RecoveryCheck::default())),
+        EnvVar::new(
+            "TIME_FIELD",
+            "one of auto, always, never",
+            "When to show the in-game time",
+        )
+        .with_default(lower(TimeField::default())),
+        EnvVar::new(
+            "PLAYER_COUNT_MODE",
+            "one of normal, hidden, approximate",
+            "How the player count is shown",
+        )
+        .with_default(lower(PlayerCountMode::default())),
+        EnvVar::new("AUDIT_FILE", PATH, "Log of every state change and alert"),
+        EnvVar::new(
+            "AUDIT_RETENTION_HOURS",
+            INT,
+            "How long audit log entries are kept",
+        )
+        .with_default(default_audit_retention_hours()),
+        EnvVar::new(
+            "LOG_CHANNEL_ID",
+            ID,
+            "Channel every state change is logged in",
+        ),
+        EnvVar::new(
+            "TOPIC_CHANNEL_ID",
+            ID,
+            "Channel whose topic shows the status",
+        ),
+        EnvVar::new("TOPIC_TEMPLATE", TEXT, "The topic line").with_default(DEFAULT_TOPIC_TEMPLATE),
+        EnvVar::new(
+            "TOPIC_MIN_INTERVAL_SECS",
+            INT,
+            "Least seconds between two topic edits",
+        )
+        .with_default(default_topic_min_interval_secs()),
+        EnvVar::new(
+            "TITLE_FULL_TEMPLATE",
+            TEXT,
+            "Status title while the server is full",
+        ),
+        EnvVar::new(
+            "MAINTENANCE_WINDOWS",
+            "comma-separated list",
+            "Recurring maintenance windows, e.g. daily 04:00-04:20",
+        ),
+        EnvVar::new(
+            "MAINTENANCE_TIMEZONE",
+            "timezone",
+            "Timezone of maintenance windows that don't name one",
+        )
+        .with_default("UTC"),
+        EnvVar::new(
+            "STATS_TIMEZONE",
+            "timezone",
+            "Timezone whose midnight starts a new day for daily stats",
+        )
+        .with_default("UTC"),
+        EnvVar::new(
+            "EVENT_IMPACT",
+            BOOL,
+            "Show in /stats how many players Discord scheduled events drew",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "PLAYER_HOURS_FIELD",
+            BOOL,
+            "Show the player-hours served today",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "CHANGE_ANNOTATIONS",
+            BOOL,
+            "Mark how player and queue counts changed since the previous update",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "VERIFY_PLAYER_COUNT",
+            BOOL,
+            "Check the reported player count against the player list",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "PLAYER_COUNT_TOLERANCE",
+            INT,
+            "Players the reported count may be off by",
+        )
+        .with_default(default_player_count_tolerance()),
+        EnvVar::new(
+            "PLAYER_COUNT_MISMATCH_POLLS",
+            INT,
+            "Mismatching polls in a row before the count is flagged",
+        )
+        .with_default(default_player_count_mismatch_polls()),
+        EnvVar::new(
+            "PLAYER_COUNT_ANNOTATION",
+            BOOL,
+            "Show a flagged count next to the verified one",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "UNIQUE_NAMES_PER_DAY",
+            INT,
+            "Names of today's players kept; past that they are only counted",
+        )
+        .with_default(default_unique_names_per_day()),
+        EnvVar::new(
+            "TRACKED_SESSIONS",
+            INT,
+            "Players whose session length is tracked at once",
+        )
+        .with_default(default_tracked_sessions()),
+        EnvVar::new(
+            "GAME_PORT_PROBE",
+            "one of off, icmp, strict",
+            "Probe the game port every update",
+        )
+        .with_default(GamePortProbe::default().name()),
+        EnvVar::new(
+            "GAME_PORT",
+            INT,
+            "Game port to probe (default: the one the server reports)",
+        ),
+        EnvVar::new(
+            "GAME_PORT_UNREACHABLE_POLLS",
+            INT,
+            "Unreachable probes in a row before the game port is flagged",
+        )
+        .with_default(default_game_port_unreachable_polls()),
+        EnvVar::new(
+            "FULL_FORECAST_OCCUPANCY",
+            INT,
+            "Occupancy in percent from which to estimate when the server is full",
+        ),
+        EnvVar::new(
+            "MOTD",
+            TEXT,
+            "Message of the day for the status embed, \\n for line breaks",
+        ),
+        EnvVar::new(
+            "OUTPUTS",
+            "comma-separated list",
+            "Extra status messages, e.g. 123456789 compact de",
+        ),
+        EnvVar::new(
+            "CLEANUP_ORPHANS",
+            BOOL,
+            "Delete status messages of earlier runs once a day",
+        )
+        .with_default(false),
+        EnvVar::new(
+            "CLEANUP_MIN_AGE_HOURS",
+            INT,
+            "Least age of a status message the automatic cleanup deletes",
+        )
+        .with_default(default_cleanup_min_age_hours()),
+        EnvVar::new(
+            "RECONCILE_POLICY",
+            "one of adopt, recreate, strict",
+            "What startup does with saved messages that are gone or not the bot's",
+        )
+        .with_default(ReconcilePolicy::default().name()),
+        EnvVar::new(
+            "RECONCILE_CONCURRENCY",
+            INT,
+            "How many saved messages startup looks up at once",
+        )
+        .with_default(default_reconcile_concurrency()),
+        EnvVar::new(
+            "EMBED_PATCH_FILE",
+            PATH,
+            "TOML file of changes to the status embed",
+        ),
+        EnvVar::new(
+            "NEXT_WIPE_DATE",
+            "YYYY-MM-DD HH:MM [timezone]",
+            "When the server is next wiped, for a countdown and announcements",
+        ),
+        EnvVar::new(
+            "WIPE_ANNOUNCE_OFFSETS",
+            "comma-separated list",
+            "How long before the wipe to announce it",
+        )
+        .with_default(default_wipe_announce_offsets().join(",")),
+        EnvVar::new(
+            "WIPE_ANNOUNCE_CHANNEL",
+            "one of alert, status",
+            "Where wipe announcements are posted",
+        )
+        .with_default(lower(WipeChannel::default())),
+        EnvVar::new(
+            "WIPE_MISSED_ANNOUNCEMENTS",
+            "one of skip, catch_up",
+            "Announcements missed while the bot was down",
+        )
+        .with_default(MissedAnnouncements::default().name()),
+        EnvVar::new(
+            "WIPE_RESET_STATS",
+            BOOL,
+            "Start the statistics over at the wipe",
+        )
+        .with_default(false),
+    ]
+}
+
+/// A ready-to-edit `.env` file. Required variables are left empty to fill in, optional ones are
+/// commented out at their default.
+pub fn render() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Generated by `dayz-monitor --print-env-template`.");
+
+    for var in env_vars() {
+        let mut traits = vec![var.kind.to_string()];
+        if var.required {
+            traits.push("required".into());
+        }
+        if let Some(default) = &var.default {
+            traits.push(format!("default {default}"));
+        }
+        if var.secret {
+            traits.push("SECRET, keep out of version control".into());
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "# {}", var.description);
+        let _ = writeln!(out, "# ({})", traits.join(", "));
+        let comment = if var.required { "" } else { "# " };
+        let _ = writeln!(
+            out,
+            "{comment}{}={}",
+            var.name,
+            var.default.as_deref().unwrap_or("")
+        );
+    }
+
+    out
+}
+
+/// The variable names [`DayzMonitorConfig`] deserializes, as declared on its fields.
+pub fn config_fields() -> Vec<String> {
+    let mut fields: &'static [&'static str] = &[];
+    // Always fails once the field list is captured; there is nothing to deserialize from.
+    let _ = DayzMonitorConfig::deserialize(FieldNames(&mut fields));
+
+    fields.iter().map(|f| f.to_uppercase()).collect()
+}
+
+/// A deserializer that only records the fields a struct asks for.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+fn lower(value: impl Debug) -> String {
+    format!("{value:?}").to_lowercase()
+}
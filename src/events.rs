@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::localize;
+
+/// Hours of player counts kept: the report's week plus the weeks its baselines reach back.
+pub const HOURS_KEPT: usize = (BASELINE_WEEKS as usize + 1) * 7 * 24;
+
+/// How many earlier weeks an event's baseline averages over.
+pub const BASELINE_WEEKS: u32 = 4;
+
+/// How long an event without a scheduled end counts as running.
+pub const DEFAULT_EVENT_SECS: u64 = 2 * 3600;
+
+/// How far back the report looks for events.
+pub const REPORT_SECS: u64 = 7 * 86_400;
+
+/// Scheduled events are remembered this long after they start, for the report to find them
+/// after Discord has dropped them.
+const EVENTS_KEPT_SECS: u64 = REPORT_SECS + 86_400;
+
+const HOUR: u64 = 3600;
+
+/// Online polls within one hour.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourSample {
+    pub polls: u32,
+    pub players: u64,
+}
+
+/// The average player count of each hour the server was online, keyed by the hour's start.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HourlyPlayers {
+    pub hours: BTreeMap<u64, HourSample>,
+}
+
+impl HourlyPlayers {
+    /// Folds in a poll that found `players` online.
+    pub fn record(&mut self, now: u64, players: u32) {
+        let hour = self.hours.entry(now - now % HOUR).or_default();
+        hour.polls += 1;
+        hour.players += u64::from(players);
+
+        while self.hours.len() > HOURS_KEPT {
+            self.hours.pop_first();
+        }
+    }
+
+    /// The average player count of the hour containing `unix`, if the server was polled online
+    /// in it.
+    pub fn average(&self, unix: u64) -> Option<f64> {
+        self.hours
+            .get(&(unix - unix % HOUR))
+            .filter(|h| h.polls > 0)
+            .map(|h| h.players as f64 / f64::from(h.polls))
+    }
+}
+
+/// One occurrence of a Discord scheduled event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub id: u64,
+    pub name: String,
+    pub start_unix: u64,
+    pub end_unix: Option<u64>,
+}
+
+impl ScheduledEvent {
+    /// When the event counts as over: its scheduled end, or [`DEFAULT_EVENT_SECS`] after it
+    /// starts.
+    pub fn end(&self) -> u64 {
+        self.end_unix
+            .filter(|end| *end > self.start_unix)
+            .unwrap_or(self.start_unix + DEFAULT_EVENT_SECS)
+    }
+}
+
+/// Scheduled events seen on Discord. Discord only lists events still to come or running, so
+/// past ones are remembered here.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventLog {
+    pub events: Vec<ScheduledEvent>,
+}
+
+impl EventLog {
+    /// Takes in the events Discord lists at `now`. Discord is the authority on events still to
+    /// come, so remembered ones that haven't started are replaced; those that have are kept.
+    pub fn remember(&mut self, listed: Vec<ScheduledEvent>, now: u64) {
+        self.events.retain(|e| {
+            e.start_unix <= now
+                && e.start_unix + EVENTS_KEPT_SECS > now
+                && !listed
+                    .iter()
+                    .any(|l| l.id == e.id && l.start_unix == e.start_unix)
+        });
+        self.events.extend(listed);
+        self.events.sort_by_key(|e| (e.start_unix, e.id));
+    }
+}
+
+/// The starts of the hours overlapping `start..end`.
+pub fn window_hours(start: u64, end: u64) -> impl Iterator<Item = u64> {
+    let first = start - start % HOUR;
+    (first..end).step_by(HOUR as usize)
+}
+
+/// The same local weekday and time as `unix`, `weeks` weeks earlier.
+pub fn weeks_before(unix: u64, weeks: u32, tz: Tz) -> u64 {
+    let utc = DateTime::<Utc>::from_timestamp(unix as i64, 0).unwrap_or_default();
+    let local = tz.from_utc_datetime(&utc.naive_utc()).naive_local();
+    let earlier = local - TimeDelta::weeks(i64::from(weeks));
+
+    localize(tz, earlier).timestamp().max(0) as u64
+}
+
+/// The typical player count in `hours`: the average of the same local hours over the
+/// [`BASELINE_WEEKS`] weeks before, leaving out hours the server wasn't polled online.
+pub fn baseline(history: &HourlyPlayers, hours: &[u64], tz: Tz) -> Option<f64> {
+    mean(hours.iter().flat_map(|&hour| {
+        (1..=BASELINE_WEEKS).filter_map(move |weeks| history.average(weeks_before(hour, weeks, tz)))
+    }))
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0u32), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / f64::from(n))
+}
+
+/// How many players an event drew compared with a typical week.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventImpact {
+    pub name: String,
+    pub start_unix: u64,
+    /// Average player count while it ran
+    pub players: f64,
+    /// Average player count at the same local time in earlier weeks
+    pub baseline: f64,
+    /// Weekday and `HH:MM` of the start, local to the stats timezone
+    pub weekday: Weekday,
+    pub time: String,
+}
+
+impl EventImpact {
+    /// Extra players over the baseline, rounded.
+    pub fn delta(&self) -> i64 {
+        (self.players - self.baseline).round() as i64
+    }
+}
+
+/// The impact of every event that started in the [`REPORT_SECS`] up to `now`, oldest first.
+/// Events without polls while they ran, or without any from earlier weeks to compare with, are
+/// left out.
+pub fn impacts(
+    history: &HourlyPlayers,
+    events: &[ScheduledEvent],
+    now: u64,
+    tz: Tz,
+) -> Vec<EventImpact> {
+    let mut impacts: Vec<EventImpact> = events
+        .iter()
+        .filter(|e| e.start_unix <= now && e.start_unix + REPORT_SECS > now)
+        .filter_map(|event| {
+            let hours: Vec<u64> = window_hours(event.start_unix, event.end().min(now)).collect();
+            let players = mean(hours.iter().filter_map(|&h| history.average(h)))?;
+            let baseline = baseline(history, &hours, tz)?;
+
+            let utc = DateTime::<Utc>::from_timestamp(event.start_unix as i64, 0)?;
+            let local = utc.with_timezone(&tz);
+
+            Some(EventImpact {
+                name: event.name.clone(),
+                start_unix: event.start_unix,
+                players,
+                baseline,
+                weekday: local.weekday(),
+                time: local.format("%H:%M").to_string(),
+            })
+        })
+        .collect();
+
+    impacts.sort_by_key(|i| i.start_unix);
+    impacts
+}
@@ -0,0 +1,737 @@
+use std::fmt::{self, Debug};
+
+use crate::{monitor::RecoveryCheck, topic::default_topic_template, DayzMonitorConfig};
+
+/// Where a setting or feature belongs, for grouping in `/config`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Area {
+    Polling,
+    Embed,
+    Alerts,
+    Integrations,
+}
+
+impl Area {
+    pub const ALL: [Area; 4] = [Area::Polling, Area::Embed, Area::Alerts, Area::Integrations];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Area::Polling => "Polling",
+            Area::Embed => "Embed",
+            Area::Alerts => "Alerts",
+            Area::Integrations => "Integrations",
+        }
+    }
+}
+
+/// Whether a feature is in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    On,
+    /// Not configured
+    Off,
+    /// Configured, but something it depends on is missing
+    Inactive(&'static str),
+    /// Configured, but left out of this build; needs the cargo feature named here
+    NotBuilt(&'static str),
+}
+
+impl fmt::Display for Activation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Activation::On => write!(f, "on"),
+            Activation::Off => write!(f, "off"),
+            Activation::Inactive(reason) => write!(f, "inactive, {reason}"),
+            Activation::NotBuilt(cargo_feature) => {
+                write!(f, "not built, needs the `{cargo_feature}` cargo feature")
+            }
+        }
+    }
+}
+
+/// An optional feature and whether this config turns it on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Feature {
+    /// Stable name, also used as the usage counter key
+    pub name: &'static str,
+    pub area: Area,
+    pub activation: Activation,
+}
+
+/// One effective setting, with secrets already redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub area: Area,
+    /// Environment variable the setting comes from
+    pub name: &'static str,
+    pub value: String,
+}
+
+/// Every optional feature, each reporting its own activation.
+pub fn registry(config: &DayzMonitorConfig) -> Vec<Feature> {
+    let has_alert_destination = config.has_alert_destination();
+    let needs_destination = |configured: bool| match (configured, has_alert_destination) {
+        (false, _) => Activation::Off,
+        (true, true) => Activation::On,
+        (true, false) => Activation::Inactive("no alert channel or notification backend"),
+    };
+    let when = |on: bool| {
+        if on {
+            Activation::On
+        } else {
+            Activation::Off
+        }
+    };
+
+    // Features built on the player count can't run while the server hides it.
+    let needs_counts = |activation: Activation| match activation {
+        Activation::On if !config.player_count_mode.counts_known() => {
+            Activation::Inactive("PLAYER_COUNT_MODE is hidden")
+        }
+        activation => activation,
+    };
+
+    // Compiled out, a configured feature says so rather than that something else is missing.
+    let built = |built: bool, cargo_feature, activation| match activation {
+        Activation::Off => Activation::Off,
+        _ if !built => Activation::NotBuilt(cargo_feature),
+        activation => activation,
+    };
+
+    let feature = |name, area, activation| Feature {
+        name,
+        area,
+        activation,
+    };
+
+    vec![
+        feature(
+            "strict_recovery",
+            Area::Polling,
+            when(config.recovery_polls > 1 || config.recovery_check != RecoveryCheck::Answer),
+        ),
+        feature(
+            "maintenance_windows",
+            Area::Polling,
+            when(!config.maintenance_windows.is_empty()),
+        ),
+        feature(
+            "player_count_check",
+            Area::Polling,
+            when(config.verify_player_count),
+        ),
+        feature(
+            "game_port_probe",
+            Area::Polling,
+            when(config.game_port_probe.is_on()),
+        ),
+        feature(
+            "fixed_status_message",
+            Area::Embed,
+            when(config.status_message_id.is_some()),
+        ),
+        feature("mod_list", Area::Embed, when(config.mod_list)),
+        feature(
+            "channel_topic",
+            Area::Embed,
+            when(config.topic_channel_id.is_some()),
+        ),
+        feature(
+            "player_hours_field",
+            Area::Embed,
+            when(config.player_hours_field),
+        ),
+        feature(
+            "region_ping",
+            Area::Embed,
+            when(config.bot_region.is_some()),
+        ),
+        feature(
+            "region_ping_sharing",
+            Area::Embed,
+            match (&config.region_pings_dir, &config.bot_region) {
+                (None, _) => Activation::Off,
+                (Some(_), Some(_)) => Activation::On,
+                (Some(_), None) => Activation::Inactive("BOT_REGION is unset"),
+            },
+        ),
+        feature(
+            "change_annotations",
+            Area::Embed,
+            when(config.change_annotations),
+        ),
+        feature(
+            "player_count_annotation",
+            Area::Embed,
+            match (config.player_count_annotation, config.verify_player_count) {
+                (false, _) => Activation::Off,
+                (true, true) => Activation::On,
+                (true, false) => Activation::Inactive("VERIFY_PLAYER_COUNT is off"),
+            },
+        ),
+        feature(
+            "full_forecast",
+            Area::Embed,
+            needs_counts(when(config.full_forecast_occupancy.is_some())),
+        ),
+        feature("outputs", Area::Embed, when(!config.outputs.is_empty())),
+        feature("cleanup_orphans", Area::Embed, when(config.cleanup_orphans)),
+        feature(
+            "embed_patch",
+            Area::Embed,
+            when(config.embed_patch_file.is_some()),
+        ),
+        feature(
+            "wipe_countdown",
+            Area::Embed,
+            when(config.next_wipe_date.is_some()),
+        ),
+        feature(
+            "force_bot_language",
+            Area::Embed,
+            when(config.force_bot_language),
+        ),
+        feature(
+            "alerts",
+            Area::Alerts,
+            when(config.alert_channel_id.is_some()),
+        ),
+        feature(
+            "alert_chart",
+            Area::Alerts,
+            built(
+                cfg!(feature = "charts"),
+                "charts",
+                match (
+                    config.alert_chart_rules.is_empty(),
+                    config.alert_channel_id.is_some(),
+                ) {
+                    (true, _) => Activation::Off,
+                    (false, false) => Activation::Inactive("ALERT_CHANNEL_ID is unset"),
+                    (false, true) if !config.player_count_mode.shows_exact() => {
+                        Activation::Inactive("PLAYER_COUNT_MODE is not normal")
+                    }
+                    (false, true) => Activation::On,
+                },
+            ),
+        ),
+        feature(
+            "queue_alert",
+            Area::Alerts,
+            needs_counts(needs_destination(config.alert_queue_threshold.is_some())),
+        ),
+        feature(
+            "latency_alert",
+            Area::Alerts,
+            needs_destination(config.alert_latency_ms.is_some()),
+        ),
+        feature(
+            "join_port_alert",
+            Area::Alerts,
+            needs_destination(config.game_port_probe.is_on()),
+        ),
+        feature(
+            "slash_commands",
+            Area::Integrations,
+            if config.gateway {
+                Activation::On
+            } else {
+                Activation::Inactive("GATEWAY is false")
+            },
+        ),
+        feature(
+            "event_impact",
+            Area::Integrations,
+            match (config.event_impact, config.gateway) {
+                (false, _) => Activation::Off,
+                (true, false) => Activation::Inactive("GATEWAY is false, so there is no /stats"),
+                (true, true) => Activation::On,
+            },
+        ),
+        feature(
+            "telegram",
+            Area::Integrations,
+            built(
+                cfg!(feature = "telegram"),
+                "telegram",
+                when(config.telegram_bot_token.is_some()),
+            ),
+        ),
+        feature(
+            "ntfy",
+            Area::Integrations,
+            built(
+                cfg!(feature = "ntfy"),
+                "ntfy",
+                when(config.ntfy_url.is_some()),
+            ),
+        ),
+        feature(
+            "status_file",
+            Area::Integrations,
+            when(config.status_file.is_some()),
+        ),
+        feature(
+            "audit_log",
+            Area::Integrations,
+            when(config.audit_file.is_some()),
+        ),
+        feature(
+            "log_channel",
+            Area::Integrations,
+            when(config.log_channel_id.is_some()),
+        ),
+    ]
+}
+
+/// Features that only work over the gateway connection.
+const NEEDS_GATEWAY: [&str; 2] = ["slash_commands", "event_impact"];
+
+/// Names of the features that `GATEWAY=false` keeps from running, in registry order.
+pub fn skipped_without_gateway(config: &DayzMonitorConfig) -> Vec<&'static str> {
+    if config.gateway {
+        return Vec::new();
+    }
+
+    registry(config)
+        .into_iter()
+        .filter(|f| {
+            NEEDS_GATEWAY.contains(&f.name) && matches!(f.activation, Activation::Inactive(_))
+        })
+        .map(|f| f.name)
+        .collect()
+}
+
+/// The effective configuration as shown by `/config`. Tokens are never included, and values
+/// that grant access elsewhere are only reported as set.
+pub fn settings(config: &DayzMonitorConfig) -> Vec<Setting> {
+    let mut settings = Vec::new();
+    let mut add = |area, name, value: String| settings.push(Setting { area, name, value });
+
+    add(
+        Area::Polling,
+        "SERVER_ADDRESS",
+        config.server_address.clone(),
+    );
+    add(
+        Area::Polling,
+        "SERVER_ID",
+        config.server_identity().to_string(),
+    );
+    add(
+        Area::Polling,
+        "UPDATE_INTERVAL_SECS",
+        config.update_interval_secs.to_string(),
+    );
+    add(
+        Area::Polling,
+        "RECOVERY_POLLS",
+        config.recovery_polls.to_string(),
+    );
+    add(
+        Area::Polling,
+        "RECOVERY_CHECK",
+        lower(config.recovery_check),
+    );
+    add(Area::Polling, "A2S_RETRIES", config.a2s_retries.to_string());
+    if config.a2s_retries > 0 {
+        add(
+            Area::Polling,
+            "A2S_RETRY_DELAY_MS",
+            config.a2s_retry_delay_ms.to_string(),
+        );
+    }
+    add(
+        Area::Polling,
+        "MAINTENANCE_WINDOWS",
+        list(&config.maintenance_windows),
+    );
+    add(
+        Area::Polling,
+        "MAINTENANCE_TIMEZONE",
+        or_default(&config.maintenance_timezone, "UTC"),
+    );
+    add(
+        Area::Polling,
+        "SANITY_MAX_PLAYERS",
+        config.sanity_max_players.to_string(),
+    );
+    add(
+        Area::Polling,
+        "SANITY_MAX_SLOTS",
+        config.sanity_max_slots.to_string(),
+    );
+    add(
+        Area::Polling,
+        "SANITY_MAX_QUEUE",
+        config.sanity_max_queue.to_string(),
+    );
+    add(
+        Area::Polling,
+        "VERIFY_PLAYER_COUNT",
+        config.verify_player_count.to_string(),
+    );
+    if config.verify_player_count {
+        add(
+            Area::Polling,
+            "PLAYER_COUNT_TOLERANCE",
+            config.player_count_tolerance.to_string(),
+        );
+        add(
+            Area::Polling,
+            "PLAYER_COUNT_MISMATCH_POLLS",
+            config.player_count_mismatch_polls.to_string(),
+        );
+        add(
+            Area::Polling,
+            "UNIQUE_NAMES_PER_DAY",
+            config.unique_names_per_day.to_string(),
+        );
+        add(
+            Area::Polling,
+            "TRACKED_SESSIONS",
+            config.tracked_sessions.to_string(),
+        );
+    }
+    add(
+        Area::Polling,
+        "GAME_PORT_PROBE",
+        config.game_port_probe.name().to_string(),
+    );
+    if config.game_port_probe.is_on() {
+        add(
+            Area::Polling,
+            "GAME_PORT",
+            config
+                .game_port
+                .map(|port| port.to_string())
+                .unwrap_or_else(|| "reported by the server".to_string()),
+        );
+        add(
+            Area::Polling,
+            "GAME_PORT_UNREACHABLE_POLLS",
+            config.game_port_unreachable_polls.to_string(),
+        );
+    }
+
+    add(Area::Embed, "SERVER_NAME", config.server_name.clone());
+    add(
+        Area::Embed,
+        "SERVER_REGION",
+        or_unset(config.server_region.as_ref()),
+    );
+    add(
+        Area::Embed,
+        "BOT_REGION",
+        or_unset(config.bot_region.as_ref()),
+    );
+    add(
+        Area::Embed,
+        "REGION_PINGS_DIR",
+        path(&config.region_pings_dir),
+    );
+    add(
+        Area::Embed,
+        "TEXT_CHANNEL_ID",
+        config.text_channel_id.to_string(),
+    );
+    add(
+        Area::Embed,
+        "STATUS_MESSAGE_ID",
+        or_unset(config.status_message_id),
+    );
+    add(
+        Area::Embed,
+        "STATUS_EDIT_RETRIES",
+        config.status_edit_retries.to_string(),
+    );
+    if config.status_edit_retries > 0 {
+        add(
+            Area::Embed,
+            "STATUS_EDIT_RETRY_DELAY_MS",
+            config.status_edit_retry_delay_ms.to_string(),
+        );
+    }
+    add(
+        Area::Embed,
+        "STATUS_CHECK_EVERY",
+        config.status_check_every.to_string(),
+    );
+    add(
+        Area::Embed,
+        "STATUS_UNSUPPRESS_EMBEDS",
+        config.status_unsuppress_embeds.to_string(),
+    );
+    add(
+        Area::Embed,
+        "OWN_CONTENT_ONLY",
+        config.own_content_only.to_string(),
+    );
+    add(
+        Area::Embed,
+        "BOT_LANGUAGE",
+        config.bot_language.code().into(),
+    );
+    add(
+        Area::Embed,
+        "NUMBER_LOCALE",
+        config.number_locale().code().into(),
+    );
+    add(Area::Embed, "TIME_FIELD", lower(config.time_field));
+    add(
+        Area::Embed,
+        "PLAYER_COUNT_MODE",
+        lower(config.player_count_mode),
+    );
+    add(
+        Area::Embed,
+        "TITLE_FULL_TEMPLATE",
+        or_default(&config.title_full_template, "default"),
+    );
+    add(
+        Area::Embed,
+        "TOPIC_CHANNEL_ID",
+        or_unset(config.topic_channel_id),
+    );
+    if config.topic_channel_id.is_some() {
+        add(
+            Area::Embed,
+            "TOPIC_TEMPLATE",
+            or_default(
+                &config.topic_template,
+                default_topic_template(config.bot_language),
+            ),
+        );
+        add(
+            Area::Embed,
+            "TOPIC_MIN_INTERVAL_SECS",
+            config.topic_min_interval_secs.to_string(),
+        );
+    }
+    add(
+        Area::Embed,
+        "STATS_TIMEZONE",
+        or_default(&config.stats_timezone, "UTC"),
+    );
+    add(
+        Area::Integrations,
+        "EVENT_IMPACT",
+        config.event_impact.to_string(),
+    );
+    // Shown as set only: it can be long, and `/motd edit` may override it.
+    add(
+        Area::Embed,
+        "MOTD",
+        or_unset(config.motd.as_ref().map(|_| "set")),
+    );
+    add(Area::Embed, "OUTPUTS", list(&config.outputs));
+    add(
+        Area::Embed,
+        "CLEANUP_ORPHANS",
+        config.cleanup_orphans.to_string(),
+    );
+    if config.cleanup_orphans {
+        add(
+            Area::Embed,
+            "CLEANUP_MIN_AGE_HOURS",
+            config.cleanup_min_age_hours.to_string(),
+        );
+    }
+    add(
+        Area::Embed,
+        "RECONCILE_POLICY",
+        config.reconcile_policy.name().to_string(),
+    );
+    add(
+        Area::Embed,
+        "RECONCILE_CONCURRENCY",
+        config.reconcile_concurrency.to_string(),
+    );
+    add(
+        Area::Embed,
+        "EMBED_PATCH_FILE",
+        path(&config.embed_patch_file),
+    );
+    add(
+        Area::Embed,
+        "NEXT_WIPE_DATE",
+        or_unset(config.next_wipe_date.as_ref()),
+    );
+    if config.next_wipe_date.is_some() {
+        add(
+            Area::Embed,
+            "WIPE_ANNOUNCE_OFFSETS",
+            list(&config.wipe_announce_offsets),
+        );
+        add(
+            Area::Embed,
+            "WIPE_ANNOUNCE_CHANNEL",
+            lower(config.wipe_announce_channel),
+        );
+        add(
+            Area::Embed,
+            "WIPE_MISSED_ANNOUNCEMENTS",
+            config.wipe_missed_announcements.name().to_string(),
+        );
+        add(
+            Area::Embed,
+            "WIPE_RESET_STATS",
+            config.wipe_reset_stats.to_string(),
+        );
+        add(
+            Area::Embed,
+            "SILENT_WIPE_ANNOUNCEMENT",
+            config.silent_wipe_announcement.to_string(),
+        );
+    }
+
+    add(
+        Area::Alerts,
+        "ALERT_CHANNEL_ID",
+        or_unset(config.alert_channel_id),
+    );
+    add(
+        Area::Alerts,
+        "ALERT_STARTUP_OFFLINE_POLLS",
+        config.alert_startup_offline_polls.to_string(),
+    );
+    add(
+        Area::Alerts,
+        "ALERT_QUEUE_THRESHOLD",
+        or_unset(config.alert_queue_threshold),
+    );
+    add(
+        Area::Alerts,
+        "ALERT_LATENCY_MS",
+        or_unset(config.alert_latency_ms),
+    );
+    if let Some(threshold) = config.latency_alert_threshold() {
+        add(
+            Area::Alerts,
+            "ALERT_LATENCY_CLEAR_MS",
+            threshold.clear.as_millis().to_string(),
+        );
+        add(
+            Area::Alerts,
+            "ALERT_LATENCY_WINDOW",
+            config.alert_latency_window.to_string(),
+        );
+    }
+    add(
+        Area::Alerts,
+        "SILENT_ALERTS",
+        config.silent_alerts.to_string(),
+    );
+    add(
+        Area::Alerts,
+        "ALERT_CHART_RULES",
+        if config.alert_chart_rules.is_empty() {
+            "none".to_string()
+        } else {
+            rules(&Some(config.alert_chart_rules.clone()))
+        },
+    );
+
+    add(Area::Integrations, "GATEWAY", config.gateway.to_string());
+    add(Area::Integrations, "STATE_FILE", path(&config.state_file));
+    add(Area::Integrations, "STATUS_FILE", path(&config.status_file));
+    add(Area::Integrations, "AUDIT_FILE", path(&config.audit_file));
+    add(
+        Area::Integrations,
+        "LOG_CHANNEL_ID",
+        or_unset(config.log_channel_id),
+    );
+    if config.log_channel_id.is_some() {
+        add(
+            Area::Integrations,
+            "SILENT_LOG_CHANNEL",
+            config.silent_log_channel.to_string(),
+        );
+    }
+    add(
+        Area::Integrations,
+        "TELEGRAM_BOT_TOKEN",
+        redacted(config.telegram_bot_token.is_some()),
+    );
+    add(
+        Area::Integrations,
+        "TELEGRAM_RULES",
+        rules(&config.telegram_rules),
+    );
+    if config.telegram_bot_token.is_some() {
+        add(
+            Area::Integrations,
+            "TELEGRAM_RETRIES",
+            config.telegram_retries.to_string(),
+        );
+        add(
+            Area::Integrations,
+            "TELEGRAM_RETRY_DELAY_MS",
+            config.telegram_retry_delay_ms.to_string(),
+        );
+    }
+    // The topic name is all it takes to publish to a public ntfy topic.
+    add(
+        Area::Integrations,
+        "NTFY_URL",
+        redacted(config.ntfy_url.is_some()),
+    );
+    add(Area::Integrations, "NTFY_RULES", rules(&config.ntfy_rules));
+    if config.ntfy_url.is_some() {
+        add(
+            Area::Integrations,
+            "NTFY_RETRIES",
+            config.ntfy_retries.to_string(),
+        );
+        add(
+            Area::Integrations,
+            "NTFY_RETRY_DELAY_MS",
+            config.ntfy_retry_delay_ms.to_string(),
+        );
+    }
+
+    settings
+}
+
+fn lower(value: impl Debug) -> String {
+    format!("{value:?}").to_lowercase()
+}
+
+fn or_unset(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "unset".to_string(), |v| v.to_string())
+}
+
+fn or_default(value: &Option<String>, default: &str) -> String {
+    value
+        .clone()
+        .unwrap_or_else(|| format!("{default} (default)"))
+}
+
+fn path(value: &Option<std::path::PathBuf>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "unset".to_string(), |p| p.display().to_string())
+}
+
+fn list(values: &[String]) -> String {
+    if values.is_empty() {
+        "none".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+fn redacted(set: bool) -> String {
+    if set { "set (hidden)" } else { "unset" }.to_string()
+}
+
+fn rules(rules: &Option<Vec<crate::alerts::AlertKind>>) -> String {
+    match rules {
+        Some(rules) => rules
+            .iter()
+            .map(|r| r.name())
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "all".to_string(),
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+
+/// How far back the ramp rate looks.
+pub const RAMP_WINDOW_SECS: u64 = 25 * 60;
+
+/// The samples must span at least this long before a rate is trusted.
+pub const MIN_RAMP_SPAN_SECS: u64 = 10 * 60;
+
+/// Slower than this, in players per minute, is a plateau rather than a ramp.
+pub const MIN_RAMP_RATE: f64 = 0.1;
+
+/// Forecasts are rounded up to this many minutes, so they never look more precise than they are.
+pub const FORECAST_STEP_MINUTES: u32 = 5;
+
+/// Further out than this, too much can change for a forecast to mean anything.
+pub const MAX_FORECAST_MINUTES: u32 = 120;
+
+/// Timestamped values of the recent past, oldest first, dropping those older than the span.
+#[derive(Debug, Clone)]
+pub struct SampleWindow {
+    span_secs: u64,
+    samples: VecDeque<(u64, u32)>,
+}
+
+impl SampleWindow {
+    pub fn new(span_secs: u64) -> Self {
+        Self {
+            span_secs,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Adds a sample taken at `now_unix`. A sample older than the newest one means the clock
+    /// went back, and starts the window over.
+    pub fn push(&mut self, now_unix: u64, value: u32) {
+        if self.samples.back().is_some_and(|&(at, _)| at > now_unix) {
+            self.samples.clear();
+        }
+
+        self.samples.push_back((now_unix, value));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(at, _)| now_unix - at > self.span_secs)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Forgets everything, e.g. once the server went down.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Seconds between the oldest and the newest sample.
+    pub fn covered_secs(&self) -> u64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some((first, _)), Some((last, _))) => last - first,
+            _ => 0,
+        }
+    }
+
+    /// Least-squares slope in values per minute, over samples taken at or after `since_unix`.
+    pub fn rate_per_minute(&self, since_unix: u64) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .filter(|&&(at, _)| at >= since_unix)
+            .map(|&(at, value)| ((at - since_unix) as f64 / 60.0, value as f64))
+            .collect();
+
+        if points.len() < 2 {
+            return None;
+        }
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+
+        (variance > 0.0).then(|| covariance / variance)
+    }
+
+    /// The samples in the window, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    fn newest(&self) -> Option<u64> {
+        self.samples.back().map(|&(at, _)| at)
+    }
+}
+
+/// A cautious estimate of the minutes until every slot is taken, if players are steadily
+/// arriving and the server is at least `min_occupancy_pct` full.
+///
+/// Nothing is predicted while the window is too short, the count is flat or falling, the second
+/// half of the window stopped rising (a plateau or a sudden drop), or the result would be more
+/// than [`MAX_FORECAST_MINUTES`] away. Results are rounded up to [`FORECAST_STEP_MINUTES`].
+pub fn full_in_minutes(
+    window: &SampleWindow,
+    players: u32,
+    max_players: u32,
+    min_occupancy_pct: u32,
+) -> Option<u32> {
+    if max_players == 0 || players >= max_players {
+        return None;
+    }
+    if u64::from(players) * 100 < u64::from(min_occupancy_pct) * u64::from(max_players) {
+        return None;
+    }
+    if window.covered_secs() < MIN_RAMP_SPAN_SECS {
+        return None;
+    }
+
+    let newest = window.newest()?;
+    let rate = window.rate_per_minute(newest - window.covered_secs())?;
+    let recent_rate = window.rate_per_minute(newest - window.covered_secs() / 2)?;
+
+    if rate < MIN_RAMP_RATE || recent_rate < MIN_RAMP_RATE {
+        return None;
+    }
+
+    let minutes = (f64::from(max_players - players) / rate).ceil();
+    if !minutes.is_finite() || minutes > f64::from(MAX_FORECAST_MINUTES) {
+        return None;
+    }
+
+    let minutes = (minutes as u32).max(1);
+    Some(minutes.div_ceil(FORECAST_STEP_MINUTES) * FORECAST_STEP_MINUTES)
+}
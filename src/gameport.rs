@@ -0,0 +1,158 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tokio::net::UdpSocket;
+
+/// What the probe sends. DayZ's connection handshake is undocumented, and the server ignores a
+/// datagram it doesn't understand, so the probe listens for what the network says instead: an
+/// ICMP port unreachable comes back as a refused connection on a connected UDP socket.
+pub const PROBE_PAYLOAD: &[u8] = &[0x00];
+
+/// How long the probe waits for an answer or an ICMP error.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether and how the game port is probed, from `GAME_PORT_PROBE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GamePortProbe {
+    #[default]
+    Off,
+    /// Only an ICMP unreachable counts; silence is what a healthy port sounds like too
+    Icmp,
+    /// Silence counts as well, for hosts whose firewall drops instead of rejects
+    Strict,
+}
+
+impl GamePortProbe {
+    pub fn is_on(self) -> bool {
+        self != GamePortProbe::Off
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GamePortProbe::Off => "off",
+            GamePortProbe::Icmp => "icmp",
+            GamePortProbe::Strict => "strict",
+        }
+    }
+}
+
+/// What one probe heard back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortProbe {
+    /// Something answered
+    Answered,
+    /// The host or network reported the port or host unreachable
+    Unreachable,
+    /// Nothing came back within the timeout
+    Silent,
+    /// The probe itself failed, which says nothing about the port
+    Failed(String),
+}
+
+impl PortProbe {
+    /// Classifies the probe's receive: `None` when it timed out, otherwise what the socket
+    /// returned.
+    pub fn classify(received: Option<io::Result<usize>>) -> Self {
+        match received {
+            None => PortProbe::Silent,
+            Some(Ok(_)) => PortProbe::Answered,
+            Some(Err(err)) => match err.kind() {
+                io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::HostUnreachable
+                | io::ErrorKind::NetworkUnreachable => PortProbe::Unreachable,
+                _ => PortProbe::Failed(err.to_string()),
+            },
+        }
+    }
+
+    /// Whether this outcome means players can't reach the port, in `mode`.
+    pub fn is_unreachable(&self, mode: GamePortProbe) -> bool {
+        match self {
+            PortProbe::Unreachable => mode.is_on(),
+            PortProbe::Silent => mode == GamePortProbe::Strict,
+            PortProbe::Answered | PortProbe::Failed(_) => false,
+        }
+    }
+}
+
+/// Where the game port is: `configured` if set, else the port the server reports, on the
+/// address its queries were answered from.
+pub fn target(
+    query_addr: SocketAddr,
+    configured: Option<u16>,
+    reported: Option<u16>,
+) -> Option<SocketAddr> {
+    configured
+        .or(reported)
+        .map(|port| SocketAddr::new(query_addr.ip(), port))
+}
+
+/// Sends one datagram to `addr` and classifies what comes back within `timeout`.
+pub async fn probe(addr: SocketAddr, timeout: Duration) -> PortProbe {
+    let local = match addr.ip() {
+        IpAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+        IpAddr::V6(_) => SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+    };
+    let socket = match UdpSocket::bind(local).await {
+        Ok(socket) => socket,
+        Err(err) => return PortProbe::Failed(err.to_string()),
+    };
+    if let Err(err) = socket.connect(addr).await {
+        return PortProbe::Failed(err.to_string());
+    }
+    // An ICMP error from an earlier datagram can already surface here.
+    if let Err(err) = socket.send(PROBE_PAYLOAD).await {
+        return PortProbe::classify(Some(Err(err)));
+    }
+
+    let mut buf = [0u8; 1400];
+    PortProbe::classify(
+        tokio::time::timeout(timeout, socket.recv(&mut buf))
+            .await
+            .ok(),
+    )
+}
+
+/// Flags the game port once probes have found it unreachable for several polls in a row while
+/// the query port answered.
+#[derive(Debug, Clone)]
+pub struct JoinPortCheck {
+    mode: GamePortProbe,
+    polls: u32,
+    streak: u32,
+}
+
+impl JoinPortCheck {
+    pub fn new(mode: GamePortProbe, polls: u32) -> Self {
+        Self {
+            mode,
+            polls: polls.max(1),
+            streak: 0,
+        }
+    }
+
+    /// Folds in one online sample's probe, returning whether the port counts as unreachable.
+    /// A sample without a probe, or with one that failed, neither extends nor ends a streak.
+    pub fn observe(&mut self, probe: Option<&PortProbe>) -> bool {
+        match probe {
+            None | Some(PortProbe::Failed(_)) => {}
+            Some(probe) if probe.is_unreachable(self.mode) => {
+                self.streak = self.streak.saturating_add(1)
+            }
+            Some(_) => self.streak = 0,
+        }
+
+        self.streak >= self.polls
+    }
+
+    /// Forgets the streak, e.g. when the server went down in between.
+    pub fn reset(&mut self) {
+        self.streak = 0;
+    }
+}
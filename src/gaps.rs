@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::{
+    alerts::format_duration,
+    i18n::{Language, Text},
+    playtime::DAYS_KEPT,
+};
+
+/// Gaps are kept as long as the daily stats they annotate.
+pub const GAPS_KEPT_SECS: u64 = DAYS_KEPT as u64 * 86_400;
+
+/// A stretch of time the bot itself was not running, so nothing about the server is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitoringGap {
+    /// The last heartbeat before the bot stopped
+    pub from_unix: u64,
+    /// When the bot started again
+    pub to_unix: u64,
+}
+
+impl MonitoringGap {
+    pub fn secs(&self) -> u64 {
+        self.to_unix.saturating_sub(self.from_unix)
+    }
+}
+
+/// The gap since the last heartbeat of an earlier run, if it is longer than `threshold_secs`.
+/// Shorter ones are just the usual time between polls, or a quick restart.
+pub fn detect(
+    last_heartbeat: Option<u64>,
+    now_unix: u64,
+    threshold_secs: u64,
+) -> Option<MonitoringGap> {
+    let from_unix = last_heartbeat?;
+
+    (now_unix.saturating_sub(from_unix) > threshold_secs).then_some(MonitoringGap {
+        from_unix,
+        to_unix: now_unix,
+    })
+}
+
+/// Adds `gap`, merging gaps that overlap or touch, and drops those that ended more than
+/// [`GAPS_KEPT_SECS`] ago.
+pub fn record(gaps: &mut Vec<MonitoringGap>, gap: MonitoringGap, now_unix: u64) {
+    gaps.push(gap);
+    *gaps = merged(gaps);
+
+    let oldest = now_unix.saturating_sub(GAPS_KEPT_SECS);
+    gaps.retain(|g| g.to_unix > oldest);
+}
+
+/// `gaps` sorted, with overlapping and touching ones combined.
+pub fn merged(gaps: &[MonitoringGap]) -> Vec<MonitoringGap> {
+    let mut sorted: Vec<MonitoringGap> = gaps
+        .iter()
+        .copied()
+        .filter(|g| g.to_unix > g.from_unix)
+        .collect();
+    sorted.sort_by_key(|g| g.from_unix);
+
+    let mut out: Vec<MonitoringGap> = Vec::with_capacity(sorted.len());
+    for gap in sorted {
+        match out.last_mut() {
+            Some(last) if gap.from_unix <= last.to_unix => {
+                last.to_unix = last.to_unix.max(gap.to_unix);
+            }
+            _ => out.push(gap),
+        }
+    }
+
+    out
+}
+
+/// Seconds of `[from_unix, to_unix)` the bot was not running. Overlapping gaps count once.
+pub fn unmonitored_secs(gaps: &[MonitoringGap], from_unix: u64, to_unix: u64) -> u64 {
+    let clipped: Vec<MonitoringGap> = gaps
+        .iter()
+        .map(|g| MonitoringGap {
+            from_unix: g.from_unix.max(from_unix),
+            to_unix: g.to_unix.min(to_unix),
+        })
+        .collect();
+
+    merged(&clipped).iter().map(MonitoringGap::secs).sum()
+}
+
+/// The share of `[from_unix, to_unix)` that was monitored, in percent; `None` for an empty span.
+pub fn monitored_percent(gaps: &[MonitoringGap], from_unix: u64, to_unix: u64) -> Option<f64> {
+    let span = to_unix.checked_sub(from_unix).filter(|s| *s > 0)?;
+    let monitored = span - unmonitored_secs(gaps, from_unix, to_unix);
+
+    Some(monitored as f64 * 100.0 / span as f64)
+}
+
+/// The recorded gaps for the `stats` command, in UTC.
+pub fn summary(gaps: &[MonitoringGap], lang: Language) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}:", lang.tr(Text::MonitoringGaps));
+
+    if gaps.is_empty() {
+        let _ = writeln!(out, "  {}", lang.tr(Text::NoneYet));
+    }
+
+    for gap in gaps {
+        let _ = writeln!(
+            out,
+            "  {} – {}  {}",
+            utc(gap.from_unix),
+            utc(gap.to_unix),
+            format_duration(gap.secs())
+        );
+    }
+
+    out
+}
+
+fn utc(unix: u64) -> String {
+    DateTime::<Utc>::from_timestamp(unix as i64, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
@@ -0,0 +1,85 @@
+use a2s::players::Player;
+
+/// What the A2S_PLAYER list says about the player count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Headcount {
+    /// Entries with a name
+    pub named: u32,
+
+    /// Entries without a name yet, usually players still connecting
+    pub connecting: u32,
+}
+
+impl Headcount {
+    pub fn of(players: &[Player]) -> Self {
+        let connecting = players.iter().filter(|p| p.name.is_empty()).count() as u32;
+
+        Self {
+            named: players.len() as u32 - connecting,
+            connecting,
+        }
+    }
+
+    pub fn listed(self) -> u32 {
+        self.named + self.connecting
+    }
+
+    /// Whether the list backs up `reported`. Servers differ on whether connecting players are
+    /// counted, so anything from the named entries to the whole list agrees, give or take
+    /// `tolerance` players joining or leaving between the two queries.
+    pub fn agrees_with(self, reported: u32, tolerance: u32) -> bool {
+        reported.saturating_add(tolerance) >= self.named
+            && reported <= self.listed().saturating_add(tolerance)
+    }
+}
+
+/// A reported player count the player list has contradicted for several polls in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountMismatch {
+    pub reported: u32,
+    pub verified: u32,
+}
+
+/// Compares the A2S_INFO player count with the player list, poll after poll.
+#[derive(Debug, Clone)]
+pub struct CountCheck {
+    tolerance: u32,
+    polls: u32,
+    streak: u32,
+}
+
+impl CountCheck {
+    pub fn new(tolerance: u32, polls: u32) -> Self {
+        Self {
+            tolerance,
+            polls: polls.max(1),
+            streak: 0,
+        }
+    }
+
+    /// Folds in one online sample, returning the mismatch once it has lasted the configured
+    /// number of polls. A sample without a player list neither extends nor ends a streak.
+    pub fn observe(
+        &mut self,
+        reported: u32,
+        headcount: Option<Headcount>,
+    ) -> Option<CountMismatch> {
+        let headcount = headcount?;
+
+        if headcount.agrees_with(reported, self.tolerance) {
+            self.streak = 0;
+            return None;
+        }
+
+        self.streak = self.streak.saturating_add(1);
+        (self.streak >= self.polls).then_some(CountMismatch {
+            reported,
+            verified: headcount.listed(),
+        })
+    }
+
+    /// Forgets the streak, e.g. when the server went down in between.
+    pub fn reset(&mut self) {
+        self.streak = 0;
+    }
+}
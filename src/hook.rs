@@ -0,0 +1,210 @@
+use std::{
+    fmt,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::{
+    monitor::Observation,
+    render::{EmbedField, EmbedRepresentation},
+    DayzMonitorError,
+};
+
+/// How long the post-render hooks together may take per render.
+pub const HOOK_BUDGET: Duration = Duration::from_secs(1);
+
+/// Tweaks the finished status embed, given the observation it shows.
+pub type RenderHook =
+    dyn Fn(&Observation, EmbedRepresentation) -> EmbedRepresentation + Send + Sync;
+
+/// Hooks run on every status embed after it is rendered and before it is converted for
+/// sending, in the order they were registered.
+#[derive(Clone, Default)]
+pub struct PostRender {
+    hooks: Vec<Arc<RenderHook>>,
+    /// The last run, if it overran its budget
+    overrun: Arc<Mutex<Option<JoinHandle<EmbedRepresentation>>>>,
+}
+
+impl fmt::Debug for PostRender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostRender")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl PostRender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        hook: impl Fn(&Observation, EmbedRepresentation) -> EmbedRepresentation + Send + Sync + 'static,
+    ) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Runs the hooks on `embed` off the async runtime. If one panics, or all of them together
+    /// take longer than `budget`, the unpatched embed is used instead, with a warning.
+    ///
+    /// A run past its budget can't be stopped and keeps its thread, so until it returns the
+    /// hooks are skipped rather than started on yet another thread.
+    pub async fn apply(
+        &self,
+        observation: &Observation,
+        embed: EmbedRepresentation,
+        budget: Duration,
+    ) -> EmbedRepresentation {
+        if self.hooks.is_empty() {
+            return embed;
+        }
+
+        {
+            let mut overrun = self.overrun.lock().unwrap_or_else(|e| e.into_inner());
+            if overrun.as_ref().is_some_and(|run| !run.is_finished()) {
+                tracing::warn!(
+                    "Render hooks of an earlier render are still running, sending the unpatched embed"
+                );
+                return embed;
+            }
+            *overrun = None;
+        }
+
+        let hooks = self.hooks.clone();
+        let observation = observation.clone();
+        let unpatched = embed.clone();
+        let run = move || {
+            hooks
+                .iter()
+                .fold(embed, |embed, hook| hook(&observation, embed))
+        };
+
+        let mut run = tokio::task::spawn_blocking(run);
+        match tokio::time::timeout(budget, &mut run).await {
+            Ok(Ok(patched)) => patched,
+            Ok(Err(err)) => {
+                tracing::warn!("Render hook failed, sending the unpatched embed: {err}");
+                unpatched
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Render hooks took over {}s, sending the unpatched embed",
+                    budget.as_secs_f32()
+                );
+                *self.overrun.lock().unwrap_or_else(|e| e.into_inner()) = Some(run);
+                unpatched
+            }
+        }
+    }
+}
+
+/// Changes to the status embed, read from a TOML file so they need no code, e.g.
+///
+/// ```toml
+/// title = "Chernarus 1 — Event weekend"
+/// remove_fields = ["Update"]
+///
+/// [[field]]
+/// name = "📣 Discord"
+/// value = "discord.gg/example"
+/// inline = true
+/// ```
+///
+/// Fields are matched by name, with or without their leading emoji and ignoring case. A
+/// `[[field]]` replaces the value of the field it matches, or is added at the end.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbedPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// e.g. `0x5865F2`
+    pub colour: Option<u32>,
+    #[serde(default)]
+    pub remove_fields: Vec<String>,
+    #[serde(default, rename = "field")]
+    pub fields: Vec<FieldPatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldPatch {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub inline: bool,
+}
+
+impl EmbedPatch {
+    pub fn parse(toml: &str) -> Result<EmbedPatch, String> {
+        toml::from_str(toml).map_err(|err| err.message().to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<EmbedPatch, DayzMonitorError> {
+        let invalid = |reason: String| DayzMonitorError::InvalidEmbedPatch {
+            path: path.display().to_string(),
+            reason,
+        };
+
+        let toml = std::fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+        EmbedPatch::parse(&toml).map_err(invalid)
+    }
+
+    pub fn apply(&self, mut embed: EmbedRepresentation) -> EmbedRepresentation {
+        if let Some(title) = &self.title {
+            embed.title = Some(title.clone());
+        }
+        if let Some(description) = &self.description {
+            embed.description = Some(description.clone());
+        }
+        if let Some(colour) = self.colour {
+            embed.colour = Some(colour);
+        }
+
+        embed.fields.retain(|field| {
+            !self
+                .remove_fields
+                .iter()
+                .any(|name| same_field(&field.name, name))
+        });
+
+        for patch in &self.fields {
+            match embed
+                .fields
+                .iter_mut()
+                .find(|field| same_field(&field.name, &patch.name))
+            {
+                Some(field) => {
+                    field.value = patch.value.clone();
+                    field.inline = patch.inline;
+                }
+                None => embed.fields.push(EmbedField {
+                    name: patch.name.clone(),
+                    value: patch.value.clone(),
+                    inline: patch.inline,
+                }),
+            }
+        }
+
+        embed
+    }
+}
+
+/// Whether a field named `name` is the one a patch calls `wanted`; `🗺️ Map` is `Map`.
+fn same_field(name: &str, wanted: &str) -> bool {
+    name == wanted || bare_name(name).eq_ignore_ascii_case(bare_name(wanted))
+}
+
+fn bare_name(name: &str) -> &str {
+    name.trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim()
+}
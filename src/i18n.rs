@@ -0,0 +1,735 @@
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Fr,
+    Ru,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [Language::En, Language::De, Language::Fr, Language::Ru];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::De => "de",
+            Language::Fr => "fr",
+            Language::Ru => "ru",
+        }
+    }
+
+    /// Maps a locale string such as Discord's `en-US` or `pt-BR`, or a POSIX-style `ru_RU.UTF-8`,
+    /// to a supported language. Returns `None` for unsupported or malformed input.
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let primary = locale
+            .trim()
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        Language::ALL.into_iter().find(|l| l.code() == primary)
+    }
+
+    pub fn tr(self, key: Text) -> &'static str {
+        use Language::*;
+        use Text::*;
+
+        match (self, key) {
+            (En, Online) => "Online",
+            (De, Online) => "Online",
+            (Fr, Online) => "En ligne",
+            (Ru, Online) => "В сети",
+
+            (En, Offline) => "Offline",
+            (De, Offline) => "Offline",
+            (Fr, Offline) => "Hors ligne",
+            (Ru, Offline) => "Не в сети",
+
+            (En, Degraded) => "Degraded",
+            (De, Degraded) => "Eingeschränkt",
+            (Fr, Degraded) => "Dégradé",
+            (Ru, Degraded) => "Нестабильно",
+
+            (En, Players) => "Players",
+            (De, Players) => "Spieler",
+            (Fr, Players) => "Joueurs",
+            (Ru, Players) => "Игроки",
+
+            (En, Queue) => "Queue",
+            (De, Queue) => "Warteschlange",
+            (Fr, Queue) => "File d'attente",
+            (Ru, Queue) => "Очередь",
+
+            (En, ServerTime) => "Server Time",
+            (De, ServerTime) => "Serverzeit",
+            (Fr, ServerTime) => "Heure du serveur",
+            (Ru, ServerTime) => "Время на сервере",
+
+            (En, Unknown) => "Unknown",
+            (De, Unknown) => "Unbekannt",
+            (Fr, Unknown) => "Inconnue",
+            (Ru, Unknown) => "Неизвестно",
+
+            (En, Address) => "Address",
+            (De, Address) => "Adresse",
+            (Fr, Address) => "Adresse",
+            (Ru, Address) => "Адрес",
+
+            (En, Update) => "Update",
+            (De, Update) => "Aktualisierung",
+            (Fr, Update) => "Actualisation",
+            (Ru, Update) => "Обновление",
+
+            (En, LastUpdated) => "Last Updated",
+            (De, LastUpdated) => "Zuletzt aktualisiert",
+            (Fr, LastUpdated) => "Dernière mise à jour",
+            (Ru, LastUpdated) => "Обновлено",
+
+            (En, QueryFailed) => "Could not query the server.",
+            (De, QueryFailed) => "Der Server konnte nicht abgefragt werden.",
+            (Fr, QueryFailed) => "Impossible d'interroger le serveur.",
+            (Ru, QueryFailed) => "Не удалось опросить сервер.",
+
+            (En, Error) => "Error",
+            (De, Error) => "Fehler",
+            (Fr, Error) => "Erreur",
+            (Ru, Error) => "Ошибка",
+
+            (En, OnlinePlayers) => "Online players",
+            (De, OnlinePlayers) => "Spieler online",
+            (Fr, OnlinePlayers) => "Joueurs en ligne",
+            (Ru, OnlinePlayers) => "Игроки в сети",
+
+            (En, NoPlayers) => "Nobody is online right now.",
+            (De, NoPlayers) => "Gerade ist niemand online.",
+            (Fr, NoPlayers) => "Personne n'est en ligne pour le moment.",
+            (Ru, NoPlayers) => "Сейчас никого нет в сети.",
+
+            (En, NoStatusYet) => "No status has been retrieved yet.",
+            (De, NoStatusYet) => "Es wurde noch kein Status abgerufen.",
+            (Fr, NoStatusYet) => "Aucun statut n'a encore été récupéré.",
+            (Ru, NoStatusYet) => "Статус ещё не получен.",
+
+            (En, Latency) => "Latency",
+            (De, Latency) => "Latenz",
+            (Fr, Latency) => "Latence",
+            (Ru, Latency) => "Задержка",
+
+            (En, Gateway) => "Discord gateway",
+            (De, Gateway) => "Discord-Gateway",
+            (Fr, Gateway) => "Passerelle Discord",
+            (Ru, Gateway) => "Шлюз Discord",
+
+            (En, DiscordApi) => "Discord API",
+            (De, DiscordApi) => "Discord-API",
+            (Fr, DiscordApi) => "API Discord",
+            (Ru, DiscordApi) => "API Discord",
+
+            (En, GameServer) => "Game server",
+            (De, GameServer) => "Spielserver",
+            (Fr, GameServer) => "Serveur de jeu",
+            (Ru, GameServer) => "Игровой сервер",
+
+            (En, Unavailable) => "unavailable",
+            (De, Unavailable) => "nicht erreichbar",
+            (Fr, Unavailable) => "indisponible",
+            (Ru, Unavailable) => "недоступно",
+
+            (En, Mods) => "Mods",
+            (De, Mods) => "Mods",
+            (Fr, Mods) => "Mods",
+            (Ru, Mods) => "Моды",
+
+            (En, ModListBelow) => "Too many to show here; the full list is posted below.",
+            (De, ModListBelow) => "Zu viele für hier; die vollständige Liste steht darunter.",
+            (Fr, ModListBelow) => {
+                "Trop nombreux pour être affichés ici ; la liste complète est publiée ci-dessous."
+            }
+            (Ru, ModListBelow) => "Слишком много для показа здесь; полный список опубликован ниже.",
+
+            (En, Full) => "Full",
+            (De, Full) => "Voll",
+            (Fr, Full) => "Complet",
+            (Ru, Full) => "Заполнен",
+
+            (En, SnoozedUntil) => "Alerts snoozed until",
+            (De, SnoozedUntil) => "Warnungen pausiert bis",
+            (Fr, SnoozedUntil) => "Alertes suspendues jusqu'à",
+            (Ru, SnoozedUntil) => "Оповещения приостановлены до",
+
+            (En, SnoozeCancelled) => "Alerts are no longer snoozed.",
+            (De, SnoozeCancelled) => "Warnungen sind nicht mehr pausiert.",
+            (Fr, SnoozeCancelled) => "Les alertes ne sont plus suspendues.",
+            (Ru, SnoozeCancelled) => "Оповещения больше не приостановлены.",
+
+            (En, InvalidSnooze) => {
+                "Use a duration of up to 7 days such as `30m`, `2h` or `1d`, or `off`."
+            }
+            (De, InvalidSnooze) => {
+                "Gib eine Dauer bis zu 7 Tagen wie `30m`, `2h` oder `1d` an, oder `off`."
+            }
+            (Fr, InvalidSnooze) => {
+                "Indiquez une durée d'au plus 7 jours comme `30m`, `2h` ou `1d`, ou `off`."
+            }
+            (Ru, InvalidSnooze) => {
+                "Укажите длительность до 7 дней, например `30m`, `2h` или `1d`, или `off`."
+            }
+
+            (En, AuditLog) => "Audit log",
+            (De, AuditLog) => "Protokoll",
+            (Fr, AuditLog) => "Journal d'audit",
+            (Ru, AuditLog) => "Журнал событий",
+
+            (En, NoAuditEntries) => "Nothing was recorded in this period.",
+            (De, NoAuditEntries) => "In diesem Zeitraum wurde nichts aufgezeichnet.",
+            (Fr, NoAuditEntries) => "Rien n'a été enregistré sur cette période.",
+            (Ru, NoAuditEntries) => "За этот период ничего не записано.",
+
+            (En, AuditDisabled) => "The audit log is disabled (`AUDIT_FILE` is not set).",
+            (De, AuditDisabled) => {
+                "Das Protokoll ist deaktiviert (`AUDIT_FILE` ist nicht gesetzt)."
+            }
+            (Fr, AuditDisabled) => {
+                "Le journal d'audit est désactivé (`AUDIT_FILE` n'est pas défini)."
+            }
+            (Ru, AuditDisabled) => "Журнал событий отключён (`AUDIT_FILE` не задан).",
+
+            (En, StartingUp) => "Starting up…",
+            (De, StartingUp) => "Startet…",
+            (Fr, StartingUp) => "Démarrage…",
+            (Ru, StartingUp) => "Запускается…",
+
+            (En, Map) => "Map",
+            (De, Map) => "Karte",
+            (Fr, Map) => "Carte",
+            (Ru, Map) => "Карта",
+
+            (En, Since) => "since",
+            (De, Since) => "seit",
+            (Fr, Since) => "depuis le",
+            (Ru, Since) => "с",
+            (En, HighLatency) => "High latency",
+            (De, HighLatency) => "Hohe Latenz",
+            (Fr, HighLatency) => "Latence élevée",
+            (Ru, HighLatency) => "Высокая задержка",
+            (En, PlayerHoursToday) => "Player-hours today",
+            (De, PlayerHoursToday) => "Spielerstunden heute",
+            (Fr, PlayerHoursToday) => "Heures-joueurs aujourd'hui",
+            (Ru, PlayerHoursToday) => "Игроко-часов сегодня",
+            (En, Stats) => "Stats",
+            (De, Stats) => "Statistik",
+            (Fr, Stats) => "Statistiques",
+            (Ru, Stats) => "Статистика",
+            (En, PeakToday) => "Peak today",
+            (De, PeakToday) => "Höchststand heute",
+            (Fr, PeakToday) => "Pic aujourd'hui",
+            (Ru, PeakToday) => "Пик сегодня",
+            (En, UptimeToday) => "Uptime today",
+            (De, UptimeToday) => "Verfügbarkeit heute",
+            (Fr, UptimeToday) => "Disponibilité aujourd'hui",
+            (Ru, UptimeToday) => "Аптайм сегодня",
+            (En, UptimeWeek) => "Uptime 7 days",
+            (De, UptimeWeek) => "Verfügbarkeit 7 Tage",
+            (Fr, UptimeWeek) => "Disponibilité 7 jours",
+            (Ru, UptimeWeek) => "Аптайм за 7 дней",
+            (En, LongestQueueToday) => "Longest queue today",
+            (De, LongestQueueToday) => "Längste Warteschlange heute",
+            (Fr, LongestQueueToday) => "File la plus longue aujourd'hui",
+            (Ru, LongestQueueToday) => "Самая длинная очередь сегодня",
+            (En, RestartsToday) => "Restarts today",
+            (De, RestartsToday) => "Neustarts heute",
+            (Fr, RestartsToday) => "Redémarrages aujourd'hui",
+            (Ru, RestartsToday) => "Перезапусков сегодня",
+            (En, UniquePlayersToday) => "Players today",
+            (De, UniquePlayersToday) => "Spieler heute",
+            (Fr, UniquePlayersToday) => "Joueurs aujourd'hui",
+            (Ru, UniquePlayersToday) => "Игроков сегодня",
+            (En, LongestSessionToday) => "Longest session today",
+            (De, LongestSessionToday) => "Längste Sitzung heute",
+            (Fr, LongestSessionToday) => "Plus longue session aujourd'hui",
+            (Ru, LongestSessionToday) => "Самая долгая сессия сегодня",
+            (En, NoStatsYet) => "No stats have been collected yet.",
+            (De, NoStatsYet) => "Es wurden noch keine Statistiken gesammelt.",
+            (Fr, NoStatsYet) => "Aucune statistique n'a encore été collectée.",
+            (Ru, NoStatsYet) => "Статистика ещё не собрана.",
+
+            (En, ReportedPlayers) => "Reported",
+            (De, ReportedPlayers) => "Gemeldet",
+            (Fr, ReportedPlayers) => "Annoncés",
+            (Ru, ReportedPlayers) => "Заявлено",
+
+            (En, VerifiedPlayers) => "verified",
+            (De, VerifiedPlayers) => "geprüft",
+            (Fr, VerifiedPlayers) => "vérifiés",
+            (Ru, VerifiedPlayers) => "проверено",
+
+            (En, Maintenance) => "Maintenance",
+            (De, Maintenance) => "Wartung",
+            (Fr, Maintenance) => "Maintenance",
+            (Ru, Maintenance) => "Техобслуживание",
+
+            (En, MaintenanceUntil) => "Scheduled maintenance until",
+            (De, MaintenanceUntil) => "Geplante Wartung bis",
+            (Fr, MaintenanceUntil) => "Maintenance planifiée jusqu'à",
+            (Ru, MaintenanceUntil) => "Плановое техобслуживание до",
+
+            (En, Motd) => "Message of the day",
+            (De, Motd) => "Nachricht des Tages",
+            (Fr, Motd) => "Message du jour",
+            (Ru, Motd) => "Сообщение дня",
+
+            (En, MotdUpdated) => "Message of the day updated.",
+            (De, MotdUpdated) => "Nachricht des Tages aktualisiert.",
+            (Fr, MotdUpdated) => "Message du jour mis à jour.",
+            (Ru, MotdUpdated) => "Сообщение дня обновлено.",
+
+            (En, MotdReset) => "Message of the day reset to the configured one.",
+            (De, MotdReset) => "Nachricht des Tages auf die konfigurierte zurückgesetzt.",
+            (Fr, MotdReset) => "Message du jour rétabli sur celui de la configuration.",
+            (Ru, MotdReset) => "Сообщение дня сброшено к настроенному.",
+
+            (En, MotdTooLong) => "Not saved, too many characters",
+            (De, MotdTooLong) => "Nicht gespeichert, zu viele Zeichen",
+            (Fr, MotdTooLong) => "Non enregistré, trop de caractères",
+            (Ru, MotdTooLong) => "Не сохранено, слишком много символов",
+
+            (En, MotdTooManyLines) => "Not saved, too many lines",
+            (De, MotdTooManyLines) => "Nicht gespeichert, zu viele Zeilen",
+            (Fr, MotdTooManyLines) => "Non enregistré, trop de lignes",
+            (Ru, MotdTooManyLines) => "Не сохранено, слишком много строк",
+
+            (En, OldStatusMessages) => "Old status messages",
+            (De, OldStatusMessages) => "Alte Statusnachrichten",
+            (Fr, OldStatusMessages) => "Anciens messages de statut",
+            (Ru, OldStatusMessages) => "Старые сообщения статуса",
+
+            (En, NoOldStatusMessages) => "No old status messages found.",
+            (De, NoOldStatusMessages) => "Keine alten Statusnachrichten gefunden.",
+            (Fr, NoOldStatusMessages) => "Aucun ancien message de statut trouvé.",
+            (Ru, NoOldStatusMessages) => "Старых сообщений статуса не найдено.",
+
+            (En, Delete) => "Delete",
+            (De, Delete) => "Löschen",
+            (Fr, Delete) => "Supprimer",
+            (Ru, Delete) => "Удалить",
+
+            (En, Cancel) => "Cancel",
+            (De, Cancel) => "Abbrechen",
+            (Fr, Cancel) => "Annuler",
+            (Ru, Cancel) => "Отмена",
+
+            (En, Deleted) => "Deleted",
+            (De, Deleted) => "Gelöscht",
+            (Fr, Deleted) => "Supprimés",
+            (Ru, Deleted) => "Удалено",
+
+            (En, NothingDeleted) => "Nothing was deleted.",
+            (De, NothingDeleted) => "Nichts wurde gelöscht.",
+            (Fr, NothingDeleted) => "Rien n'a été supprimé.",
+            (Ru, NothingDeleted) => "Ничего не удалено.",
+            (En, FullIn) => "At this rate, full in",
+            (De, FullIn) => "Bei diesem Tempo voll in",
+            (Fr, FullIn) => "À ce rythme, plein dans",
+            (Ru, FullIn) => "При таком темпе заполнится через",
+            (En, Estimate) => "estimate",
+            (De, Estimate) => "Schätzung",
+            (Fr, Estimate) => "estimation",
+            (Ru, Estimate) => "оценка",
+            (En, PlayerCountHidden) => "Player count hidden by server",
+            (De, PlayerCountHidden) => "Spielerzahl vom Server verborgen",
+            (Fr, PlayerCountHidden) => "Nombre de joueurs masqué par le serveur",
+            (Ru, PlayerCountHidden) => "Сервер скрывает число игроков",
+            (En, NotMonitoredWeek) => "Bot not running in the last 7 days",
+            (De, NotMonitoredWeek) => "Bot in den letzten 7 Tagen nicht aktiv",
+            (Fr, NotMonitoredWeek) => "Bot arrêté ces 7 derniers jours",
+            (Ru, NotMonitoredWeek) => "Бот не работал за 7 дней",
+            (En, LeftOutOfStats) => "not counted in these figures",
+            (De, LeftOutOfStats) => "in diesen Zahlen nicht enthalten",
+            (Fr, LeftOutOfStats) => "non pris en compte dans ces chiffres",
+            (Ru, LeftOutOfStats) => "не учтено в этих цифрах",
+
+            (En, RunningFor) => "Running for",
+            (De, RunningFor) => "Läuft seit",
+            (Fr, RunningFor) => "En service depuis",
+            (Ru, RunningFor) => "Работает",
+            (En, NeverStarted) => "never started",
+            (De, NeverStarted) => "nie gestartet",
+            (Fr, NeverStarted) => "jamais démarré",
+            (Ru, NeverStarted) => "ни разу не запускался",
+            (En, UpdateCycles) => "Update cycles",
+            (De, UpdateCycles) => "Aktualisierungen",
+            (Fr, UpdateCycles) => "Cycles de mise à jour",
+            (Ru, UpdateCycles) => "Циклов обновления",
+            (En, OfCycles) => "of cycles",
+            (De, OfCycles) => "der Zyklen",
+            (Fr, OfCycles) => "des cycles",
+            (Ru, OfCycles) => "циклов",
+            (En, NamesDropped) => "Names not kept",
+            (De, NamesDropped) => "Namen verworfen",
+            (Fr, NamesDropped) => "Noms non gardés",
+            (Ru, NamesDropped) => "Имён не сохранено",
+            (En, SessionsEvicted) => "Sessions dropped",
+            (De, SessionsEvicted) => "Sitzungen verworfen",
+            (Fr, SessionsEvicted) => "Sessions oubliées",
+            (Ru, SessionsEvicted) => "Сессий забыто",
+            (En, AlertsFired) => "Alerts fired",
+            (De, AlertsFired) => "Ausgelöste Warnungen",
+            (Fr, AlertsFired) => "Alertes envoyées",
+            (Ru, AlertsFired) => "Отправлено оповещений",
+            (En, FeaturesEnabled) => "Features enabled (starts)",
+            (De, FeaturesEnabled) => "Aktivierte Funktionen (Starts)",
+            (Fr, FeaturesEnabled) => "Fonctions activées (démarrages)",
+            (Ru, FeaturesEnabled) => "Включённые функции (запуски)",
+            (En, NoneYet) => "(none)",
+            (De, NoneYet) => "(keine)",
+            (Fr, NoneYet) => "(aucune)",
+            (Ru, NoneYet) => "(нет)",
+            (En, Maps) => "Maps",
+            (De, Maps) => "Karten",
+            (Fr, Maps) => "Cartes",
+            (Ru, Maps) => "Карты",
+            (En, Current) => "current",
+            (De, Current) => "aktuell",
+            (Fr, Current) => "actuelle",
+            (Ru, Current) => "текущая",
+            (En, AveragePlayers) => "avg players",
+            (De, AveragePlayers) => "Ø Spieler",
+            (Fr, AveragePlayers) => "joueurs en moyenne",
+            (Ru, AveragePlayers) => "в среднем игроков",
+            (En, Peak) => "peak",
+            (De, Peak) => "Spitze",
+            (Fr, Peak) => "pic",
+            (Ru, Peak) => "пик",
+            (En, Uptime) => "uptime",
+            (De, Uptime) => "Verfügbarkeit",
+            (Fr, Uptime) => "disponibilité",
+            (Ru, Uptime) => "аптайм",
+            (En, PlayerHours) => "Player-hours",
+            (De, PlayerHours) => "Spielerstunden",
+            (Fr, PlayerHours) => "Heures-joueurs",
+            (Ru, PlayerHours) => "Игроко-часы",
+            (En, MonitoringGaps) => "Monitoring gaps",
+            (De, MonitoringGaps) => "Überwachungslücken",
+            (Fr, MonitoringGaps) => "Interruptions de surveillance",
+            (Ru, MonitoringGaps) => "Перерывы мониторинга",
+            (En, RefreshRequested) => "Updating the status now.",
+            (De, RefreshRequested) => "Der Status wird jetzt aktualisiert.",
+            (Fr, RefreshRequested) => "Mise à jour du statut en cours.",
+            (Ru, RefreshRequested) => "Статус обновляется.",
+            (En, Wipe) => "Wipe",
+            (De, Wipe) => "Wipe",
+            (Fr, Wipe) => "Wipe",
+            (Ru, Wipe) => "Вайп",
+            (En, Wiped) => "the server has been wiped.",
+            (De, Wiped) => "der Server wurde gewipt.",
+            (Fr, Wiped) => "le serveur a été wipé.",
+            (Ru, Wiped) => "сервер вайпнут.",
+            (En, DaysSinceWipe) => "Days since wipe",
+            (De, DaysSinceWipe) => "Tage seit dem Wipe",
+            (Fr, DaysSinceWipe) => "Jours depuis le wipe",
+            (Ru, DaysSinceWipe) => "Дней с вайпа",
+
+            (En, JoinPortUnreachable) => "Join port unreachable",
+            (De, JoinPortUnreachable) => "Spielport nicht erreichbar",
+            (Fr, JoinPortUnreachable) => "Port de jeu injoignable",
+            (Ru, JoinPortUnreachable) => "Игровой порт недоступен",
+
+            (En, Region) => "Region",
+            (De, Region) => "Region",
+            (Fr, Region) => "Région",
+            (Ru, Region) => "Регион",
+
+            (En, PingFrom) => "Ping from",
+            (De, PingFrom) => "Ping aus",
+            (Fr, PingFrom) => "Ping depuis",
+            (Ru, PingFrom) => "Пинг из",
+
+            (En, EventImpact) => "Event impact",
+            (De, EventImpact) => "Wirkung von Events",
+            (Fr, EventImpact) => "Effet des événements",
+            (Ru, EventImpact) => "Эффект событий",
+
+            (En, Checking) => "Checking…",
+            (De, Checking) => "Wird geprüft…",
+            (Fr, Checking) => "Vérification…",
+            (Ru, Checking) => "Проверка…",
+        }
+    }
+
+    /// `key` as a line label in the `stats` reports, padded so the values line up.
+    pub fn report_label(self, key: Text) -> String {
+        format!("{:<17} ", format!("{}:", self.tr(key)))
+    }
+
+    /// The plural category of `n` in this language, which picks the form of a counted noun.
+    pub fn plural(self, n: u64) -> Plural {
+        match self {
+            Language::En | Language::De => match n {
+                1 => Plural::One,
+                _ => Plural::Other,
+            },
+            // French counts 0 as singular: "0 jour", "1 jour", "2 jours".
+            Language::Fr => match n {
+                0 | 1 => Plural::One,
+                _ => Plural::Other,
+            },
+            // 1, 21, 101 день; 2–4, 22–24 дня; 5–20, 25–30, 11–14 дней.
+            Language::Ru => match (n % 10, n % 100) {
+                (1, rem) if rem != 11 => Plural::One,
+                (2..=4, rem) if !(12..=14).contains(&rem) => Plural::Few,
+                _ => Plural::Other,
+            },
+        }
+    }
+
+    /// `noun` in the form that goes with `n`, e.g. "days" for 3 or "дня" for 3 in Russian.
+    pub fn noun(self, noun: Noun, n: u64) -> &'static str {
+        use Language::*;
+        use Noun::*;
+        use Plural::*;
+
+        match (self, noun, self.plural(n)) {
+            (En, Day, One) => "day",
+            (En, Day, _) => "days",
+            (De, Day, One) => "Tag",
+            (De, Day, _) => "Tage",
+            (Fr, Day, One) => "jour",
+            (Fr, Day, _) => "jours",
+            (Ru, Day, One) => "день",
+            (Ru, Day, Few) => "дня",
+            (Ru, Day, Other) => "дней",
+
+            (En, Start, One) => "start",
+            (En, Start, _) => "starts",
+            (De, Start, _) => "Starts",
+            (Fr, Start, One) => "démarrage",
+            (Fr, Start, _) => "démarrages",
+            (Ru, Start, One) => "запуск",
+            (Ru, Start, Few) => "запуска",
+            (Ru, Start, Other) => "запусков",
+
+            (En, Player, One) => "player",
+            (En, Player, _) => "players",
+            (De, Player, _) => "Spieler",
+            (Fr, Player, One) => "joueur",
+            (Fr, Player, _) => "joueurs",
+            (Ru, Player, One) => "игрок",
+            (Ru, Player, Few) => "игрока",
+            (Ru, Player, Other) => "игроков",
+        }
+    }
+
+    /// What an event's player count is compared with, e.g. "vs typical Friday 20:00".
+    pub fn vs_typical(self, weekday: Weekday, time: &str) -> String {
+        use Weekday::*;
+
+        match self {
+            Language::En => {
+                let day = match weekday {
+                    Mon => "Monday",
+                    Tue => "Tuesday",
+                    Wed => "Wednesday",
+                    Thu => "Thursday",
+                    Fri => "Friday",
+                    Sat => "Saturday",
+                    Sun => "Sunday",
+                };
+                format!("vs typical {day} {time}")
+            }
+            Language::De => {
+                let day = match weekday {
+                    Mon => "Montag",
+                    Tue => "Dienstag",
+                    Wed => "Mittwoch",
+                    Thu => "Donnerstag",
+                    Fri => "Freitag",
+                    Sat => "Samstag",
+                    Sun => "Sonntag",
+                };
+                format!("ggü. einem üblichen {day} {time}")
+            }
+            Language::Fr => {
+                let day = match weekday {
+                    Mon => "lundi",
+                    Tue => "mardi",
+                    Wed => "mercredi",
+                    Thu => "jeudi",
+                    Fri => "vendredi",
+                    Sat => "samedi",
+                    Sun => "dimanche",
+                };
+                format!("par rapport à un {day} {time} habituel")
+            }
+            // Instrumental case, after "с"
+            Language::Ru => {
+                let day = match weekday {
+                    Mon => "обычным понедельником",
+                    Tue => "обычным вторником",
+                    Wed => "обычной средой",
+                    Thu => "обычным четвергом",
+                    Fri => "обычной пятницей",
+                    Sat => "обычной субботой",
+                    Sun => "обычным воскресеньем",
+                };
+                format!("по сравнению с {day} {time}")
+            }
+        }
+    }
+}
+
+/// Plural categories, after CLDR. Only the ones the supported languages need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plural {
+    One,
+    /// Russian 2–4, 22–24, ...
+    Few,
+    Other,
+}
+
+/// Counted nouns in reports, see [`Language::noun`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Noun {
+    Day,
+    Start,
+    Player,
+}
+
+/// Every translatable string shown to users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    Online,
+    Offline,
+    Degraded,
+    Players,
+    Queue,
+    ServerTime,
+    Unknown,
+    Address,
+    Update,
+    LastUpdated,
+    QueryFailed,
+    Error,
+    OnlinePlayers,
+    NoPlayers,
+    NoStatusYet,
+    Latency,
+    Gateway,
+    DiscordApi,
+    GameServer,
+    Unavailable,
+    Mods,
+    ModListBelow,
+    Maintenance,
+    MaintenanceUntil,
+    Full,
+    SnoozedUntil,
+    SnoozeCancelled,
+    InvalidSnooze,
+    AuditLog,
+    NoAuditEntries,
+    AuditDisabled,
+    StartingUp,
+    Map,
+    Since,
+    HighLatency,
+    PlayerHoursToday,
+    Stats,
+    PeakToday,
+    UptimeToday,
+    UptimeWeek,
+    LongestQueueToday,
+    RestartsToday,
+    UniquePlayersToday,
+    LongestSessionToday,
+    NoStatsYet,
+    ReportedPlayers,
+    VerifiedPlayers,
+    Motd,
+    MotdUpdated,
+    MotdReset,
+    MotdTooLong,
+    MotdTooManyLines,
+    OldStatusMessages,
+    NoOldStatusMessages,
+    Delete,
+    Cancel,
+    Deleted,
+    NothingDeleted,
+    FullIn,
+    Estimate,
+    PlayerCountHidden,
+    NotMonitoredWeek,
+    LeftOutOfStats,
+    RunningFor,
+    NeverStarted,
+    UpdateCycles,
+    OfCycles,
+    NamesDropped,
+    SessionsEvicted,
+    AlertsFired,
+    FeaturesEnabled,
+    NoneYet,
+    Maps,
+    Current,
+    AveragePlayers,
+    Peak,
+    Uptime,
+    PlayerHours,
+    MonitoringGaps,
+    RefreshRequested,
+    Wipe,
+    Wiped,
+    DaysSinceWipe,
+    JoinPortUnreachable,
+    Region,
+    PingFrom,
+    EventImpact,
+    Checking,
+}
+
+/// Strings of the `stats` reports, which must read well in every language the live embed
+/// supports.
+pub const REPORT_TEXTS: &[Text] = &[
+    Text::RunningFor,
+    Text::NeverStarted,
+    Text::UpdateCycles,
+    Text::Offline,
+    Text::OfCycles,
+    Text::NamesDropped,
+    Text::SessionsEvicted,
+    Text::AlertsFired,
+    Text::FeaturesEnabled,
+    Text::NoneYet,
+    Text::Maps,
+    Text::Current,
+    Text::AveragePlayers,
+    Text::Peak,
+    Text::Uptime,
+    Text::PlayerHours,
+    Text::MonitoringGaps,
+];
+
+/// Nouns counted in the `stats` reports.
+pub const REPORT_NOUNS: &[Noun] = &[Noun::Day, Noun::Start];
+
+/// Picks the language for a reply: the invoking user's locale when supported, unless the bot
+/// is configured to force its own language.
+pub fn resolve_language(
+    interaction_locale: Option<&str>,
+    configured: Language,
+    force: bool,
+) -> Language {
+    if force {
+        return configured;
+    }
+
+    interaction_locale
+        .and_then(Language::from_locale)
+        .unwrap_or(configured)
+}
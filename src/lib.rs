@@ -1,11 +1,67 @@
-use a2s::{info::ExtendedServerInfo, A2SClient};
-use serde::Deserialize;
+use a2s::info::ExtendedServerInfo;
+use serde::{Deserialize, Serialize};
 use std::{
+    fs,
+    io::Write,
     net::SocketAddr,
-    time::{SystemTime, UNIX_EPOCH},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 
+use crate::diagnostics::{IgnoreReason, IgnoredToken};
+
+pub mod alerts;
+pub mod audit;
+pub mod calendar;
+pub mod capped;
+#[cfg(feature = "charts")]
+pub mod chart;
+pub mod cleanup;
+pub mod clock;
+pub mod cycle;
+pub mod daily;
+pub mod diagnostics;
+pub mod dualstack;
+pub mod env_template;
+pub mod events;
+pub mod features;
+pub mod forecast;
+pub mod gameport;
+pub mod gaps;
+pub mod headcount;
+pub mod hook;
+pub mod i18n;
+pub mod maps;
+pub mod mods;
+pub mod monitor;
+pub mod motd;
+pub mod notify;
+pub mod numbers;
+pub mod oplog;
+pub mod output;
+pub mod player_count;
+pub mod playtime;
+pub mod probe;
+pub mod query;
+pub mod reconcile;
+pub mod regions;
+pub mod render;
+pub mod retry;
+pub mod roster;
+pub mod schedule;
+pub mod shutdown;
+pub mod snapshot;
+pub mod state;
+pub mod status_file;
+pub mod subsystem;
+pub mod template;
+pub mod title;
+pub mod topic;
+pub mod transport;
+pub mod usage;
+pub mod wipe;
+
 #[derive(Error, Debug)]
 pub enum DayzMonitorError {
     #[error("Tokio IO error: {0}")]
@@ -16,23 +72,198 @@ pub enum DayzMonitorError {
 
     #[error("Failed to extract server keywords from A2S response (keywords missing).")]
     ExtractServerInfoKeywordsMissing,
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Status file has no field named '{0}'.")]
+    UnknownStatusField(String),
+
+    #[error("Timed out after {0}s waiting for a fresh status file.")]
+    StatusFileWaitTimeout(u64),
+
+    #[error("Implausible A2S response ({0}).")]
+    ImplausibleResponse(String),
+
+    #[error(
+        "Alert rule '{rule}' template uses '{{{placeholder}}}', which that rule does not provide."
+    )]
+    InvalidAlertTemplate {
+        rule: &'static str,
+        placeholder: String,
+    },
+
+    #[error("Full title template uses '{{{0}}}', which is not available.")]
+    InvalidTitleTemplate(String),
+
+    #[error("Topic template uses '{{{0}}}', which is not available.")]
+    InvalidTopicTemplate(String),
+
+    #[error("Invalid schedule '{spec}': {reason}.")]
+    InvalidSchedule { spec: String, reason: String },
+
+    #[error("Invalid output '{spec}': {reason}.")]
+    InvalidOutput { spec: String, reason: String },
+
+    #[error("Invalid embed patch {path}: {reason}")]
+    InvalidEmbedPatch { path: String, reason: String },
+
+    #[error("Invalid wipe setting '{spec}': {reason}.")]
+    InvalidWipe { spec: String, reason: String },
+
+    #[error("Invalid snapshot: {0}.")]
+    InvalidSnapshot(String),
+
+    #[error(
+        "State version {0} is newer than this bot's {current}; it was written by a newer release.",
+        current = state::STATE_VERSION
+    )]
+    NewerState(u32),
+
+    #[error("The state was saved {0}s ago, so the bot looks to be running; stop it first or pass --force.")]
+    BotRunning(u64),
+
+    #[error("Persisted messages don't match Discord under RECONCILE_POLICY=strict: {0}.")]
+    MessageMismatch(String),
+
+    #[error("Message {message_id} was authored by {author}, not me, so it can't be edited.")]
+    ForeignStatusMessage { message_id: u64, author: String },
+
+    #[error("Incomplete notification backend config: {0}.")]
+    IncompleteBackend(&'static str),
+
+    #[error("Sending to {backend} failed: {reason}")]
+    NotificationFailed {
+        backend: &'static str,
+        reason: String,
+    },
 }
 
+impl DayzMonitorError {
+    /// Soft failures mean the server answered but the answer can't be trusted. They degrade the
+    /// status instead of counting towards it being offline.
+    pub fn is_soft(&self) -> bool {
+        matches!(self, DayzMonitorError::ImplausibleResponse(_))
+    }
+
+    /// Whether trying again soon may work: timeouts and network errors, not answers that could
+    /// not be used.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            DayzMonitorError::TokioIOError(_)
+                | DayzMonitorError::A2SError(a2s::errors::Error::Io(_))
+                | DayzMonitorError::A2SError(a2s::errors::Error::ErrTimeout)
+        )
+    }
+
+    /// Why a response was discarded as implausible, if that is what happened.
+    pub fn rejection(&self) -> Option<&str> {
+        match self {
+            DayzMonitorError::ImplausibleResponse(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
 fn default_server_name() -> String {
     "DayZ Server".to_string()
 }
 fn default_update_interval_secs() -> u64 {
     60
 }
+fn default_sanity_max_players() -> u32 {
+    200
+}
+fn default_sanity_max_queue() -> u32 {
+    500
+}
+fn default_ping_warn_ms() -> u64 {
+    150
+}
+fn default_ping_bad_ms() -> u64 {
+    400
+}
+fn default_ping_timeout_ms() -> u64 {
+    3000
+}
+fn default_alert_latency_window() -> usize {
+    20
+}
+fn default_notify_retries() -> u32 {
+    3
+}
+fn default_retry_delay_ms() -> u64 {
+    1000
+}
+fn default_a2s_retry_delay_ms() -> u64 {
+    500
+}
+fn default_status_edit_retries() -> u32 {
+    2
+}
+fn default_status_check_every() -> u32 {
+    10
+}
+fn default_player_count_tolerance() -> u32 {
+    3
+}
+fn default_player_count_mismatch_polls() -> u32 {
+    3
+}
+fn default_unique_names_per_day() -> usize {
+    1000
+}
+fn default_tracked_sessions() -> usize {
+    256
+}
+fn default_game_port_unreachable_polls() -> u32 {
+    3
+}
+fn default_topic_min_interval_secs() -> u64 {
+    300
+}
+fn default_recovery_polls() -> u32 {
+    1
+}
+fn default_alert_startup_offline_polls() -> u32 {
+    3
+}
+fn default_audit_retention_hours() -> u64 {
+    24 * 7
+}
+fn default_cleanup_min_age_hours() -> u64 {
+    24
+}
+fn default_reconcile_concurrency() -> usize {
+    reconcile::DEFAULT_CONCURRENCY
+}
+fn default_wipe_announce_offsets() -> Vec<String> {
+    ["7d", "1d", "1h"].map(String::from).to_vec()
+}
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct DayzMonitorConfig {
     pub discord_token: String,
-    pub server_address: SocketAddr,
+
+    /// Query address as `host:port`; the host may be a name resolving to IPv4 and/or IPv6
+    pub server_address: String,
 
     #[serde(default = "default_server_name")]
     pub server_name: String,
 
+    /// Optional: stable name the persisted history belongs to, kept when the address or display
+    /// name changes (default `server_name`)
+    #[serde(default)]
+    pub server_id: Option<String>,
+
+    /// Optional: where the server is hosted, e.g. `EU-Frankfurt`, shown in the status embed
+    #[serde(default)]
+    pub server_region: Option<String>,
+
     /// Text channel to post/edit the status embed in
     pub text_channel_id: u64,
 
@@ -40,8 +271,554 @@ pub struct DayzMonitorConfig {
     #[serde(default)]
     pub status_message_id: Option<u64>,
 
+    /// Connect to the gateway; without it only the status message is kept updated
+    #[serde(default = "default_true")]
+    pub gateway: bool,
+
     #[serde(default = "default_update_interval_secs")]
     pub update_interval_secs: u64,
+
+    /// Optional: path of a JSON status document rewritten every cycle
+    #[serde(default)]
+    pub status_file: Option<PathBuf>,
+
+    /// Query and show the server's mod list
+    #[serde(default)]
+    pub mod_list: bool,
+
+    /// Optional: where the bot persists what it needs to remember across restarts
+    #[serde(default)]
+    pub state_file: Option<PathBuf>,
+
+    /// Optional: channel to post alerts in; alerts are disabled without it
+    #[serde(default)]
+    pub alert_channel_id: Option<u64>,
+
+    #[serde(default)]
+    pub alert_offline_template: Option<String>,
+
+    /// Consecutive offline polls after startup before the offline alert fires for a server the
+    /// bot hasn't seen online yet; 0 never alerts for it
+    #[serde(default = "default_alert_startup_offline_polls")]
+    pub alert_startup_offline_polls: u32,
+
+    #[serde(default)]
+    pub alert_online_template: Option<String>,
+
+    /// Optional: alert when the queue grows past this many players
+    #[serde(default)]
+    pub alert_queue_threshold: Option<u32>,
+
+    #[serde(default)]
+    pub alert_queue_template: Option<String>,
+
+    /// Post alerts without a push notification
+    #[serde(default)]
+    pub silent_alerts: bool,
+
+    /// Alert rules whose Discord alert gets a chart of the player count over the last 6 hours
+    #[serde(default)]
+    pub alert_chart_rules: Vec<alerts::AlertKind>,
+
+    /// Optional: also send alerts to Telegram, with `telegram_chat_id`
+    #[serde(default)]
+    pub telegram_bot_token: Option<String>,
+
+    #[serde(default)]
+    pub telegram_chat_id: Option<String>,
+
+    /// Alert rules sent to Telegram (default all)
+    #[serde(default)]
+    pub telegram_rules: Option<Vec<alerts::AlertKind>>,
+
+    #[serde(default = "default_notify_retries")]
+    pub telegram_retries: u32,
+
+    #[serde(default = "default_retry_delay_ms")]
+    pub telegram_retry_delay_ms: u64,
+
+    /// Optional: also publish alerts to this ntfy topic URL
+    #[serde(default)]
+    pub ntfy_url: Option<String>,
+
+    /// Access token for protected ntfy topics
+    #[serde(default)]
+    pub ntfy_token: Option<String>,
+
+    /// Alert rules published to ntfy (default all)
+    #[serde(default)]
+    pub ntfy_rules: Option<Vec<alerts::AlertKind>>,
+
+    #[serde(default = "default_notify_retries")]
+    pub ntfy_retries: u32,
+
+    #[serde(default = "default_retry_delay_ms")]
+    pub ntfy_retry_delay_ms: u64,
+
+    /// Post the initial status message without a push notification
+    #[serde(default = "default_true")]
+    pub silent_status_message: bool,
+
+    /// Post the mod list companion messages without a push notification
+    #[serde(default = "default_true")]
+    pub silent_mod_list: bool,
+
+    /// Post the log channel entries without a push notification
+    #[serde(default = "default_true")]
+    pub silent_log_channel: bool,
+
+    /// Post wipe announcements without a push notification
+    #[serde(default)]
+    pub silent_wipe_announcement: bool,
+
+    /// Optional: alert when the rolling p95 query latency exceeds this many milliseconds
+    #[serde(default)]
+    pub alert_latency_ms: Option<u64>,
+
+    /// The latency alert clears once the p95 drops below this (default 80% of the threshold)
+    #[serde(default)]
+    pub alert_latency_clear_ms: Option<u64>,
+
+    /// Number of recent queries the latency p95 is taken over
+    #[serde(default = "default_alert_latency_window")]
+    pub alert_latency_window: usize,
+
+    #[serde(default)]
+    pub alert_latency_template: Option<String>,
+
+    #[serde(default)]
+    pub alert_join_port_template: Option<String>,
+
+    /// Language of the status embed, and of command replies when the user's locale is unsupported
+    #[serde(default)]
+    pub bot_language: i18n::Language,
+
+    /// Digit grouping for numbers in the embed and templates; follows `bot_language` when unset
+    #[serde(default)]
+    pub number_locale: Option<numbers::NumberLocale>,
+
+    /// Reply to commands in `bot_language` even when the user's Discord locale is supported
+    #[serde(default)]
+    pub force_bot_language: bool,
+
+    /// Responses reporting more players than this are treated as corrupt
+    #[serde(default = "default_sanity_max_players")]
+    pub sanity_max_players: u32,
+
+    /// Responses reporting more slots than this are treated as corrupt
+    #[serde(default = "default_sanity_max_players")]
+    pub sanity_max_slots: u32,
+
+    /// Responses reporting a longer queue than this are treated as corrupt
+    #[serde(default = "default_sanity_max_queue")]
+    pub sanity_max_queue: u32,
+
+    /// Latencies at or above this are shown as slow by `/ping`
+    #[serde(default = "default_ping_warn_ms")]
+    pub ping_warn_ms: u64,
+
+    /// Latencies at or above this are shown as very slow by `/ping`
+    #[serde(default = "default_ping_bad_ms")]
+    pub ping_bad_ms: u64,
+
+    /// How long `/ping` waits for each hop before reporting it unavailable
+    #[serde(default = "default_ping_timeout_ms")]
+    pub ping_timeout_ms: u64,
+
+    /// Optional: where the bot runs, e.g. `EU`; shows its query latency as the ping from there
+    #[serde(default)]
+    pub bot_region: Option<String>,
+
+    /// Optional: a directory shared with bots in other regions, to show their pings as well
+    #[serde(default)]
+    pub region_pings_dir: Option<PathBuf>,
+
+    /// Retries of a failed server query within one update
+    #[serde(default)]
+    pub a2s_retries: u32,
+
+    /// Delay before the first query retry, doubling after each
+    #[serde(default = "default_a2s_retry_delay_ms")]
+    pub a2s_retry_delay_ms: u64,
+
+    /// Retries of a status message edit Discord failed to answer
+    #[serde(default = "default_status_edit_retries")]
+    pub status_edit_retries: u32,
+
+    /// Delay before the first edit retry, doubling after each
+    #[serde(default = "default_retry_delay_ms")]
+    pub status_edit_retry_delay_ms: u64,
+
+    /// Look at the status message for changes by others every this many edits; 0 only after a
+    /// failed edit
+    #[serde(default = "default_status_check_every")]
+    pub status_check_every: u32,
+
+    /// Show the status message's embeds again when someone hid them
+    #[serde(default = "default_true")]
+    pub status_unsuppress_embeds: bool,
+
+    /// Remove text others added to the status message instead of keeping it
+    #[serde(default)]
+    pub own_content_only: bool,
+
+    /// Consecutive successful polls before a server that was offline counts as back online
+    #[serde(default = "default_recovery_polls")]
+    pub recovery_polls: u32,
+
+    /// What else a recovering server must show: `answer` (nothing more) or `joinable`
+    #[serde(default)]
+    pub recovery_check: monitor::RecoveryCheck,
+
+    /// Whether to show the in-game time line: `auto`, `always` or `never`
+    #[serde(default)]
+    pub time_field: TimeField,
+
+    /// How the player count is shown: `normal`, `hidden` or `approximate`
+    #[serde(default)]
+    pub player_count_mode: player_count::PlayerCountMode,
+
+    /// Optional: JSON lines file recording every state transition and alert dispatch
+    #[serde(default)]
+    pub audit_file: Option<PathBuf>,
+
+    /// How long audit entries are kept
+    #[serde(default = "default_audit_retention_hours")]
+    pub audit_retention_hours: u64,
+
+    /// Optional: channel every state transition is logged in, one message per poll
+    #[serde(default)]
+    pub log_channel_id: Option<u64>,
+
+    /// Optional: channel whose topic shows a one-line status
+    #[serde(default)]
+    pub topic_channel_id: Option<u64>,
+
+    /// Optional: the topic line, e.g. `{status_emoji} {players}/{max_players}`
+    #[serde(default)]
+    pub topic_template: Option<String>,
+
+    /// Least time between two topic edits; Discord allows only a few channel edits per 10 minutes
+    #[serde(default = "default_topic_min_interval_secs")]
+    pub topic_min_interval_secs: u64,
+
+    /// Optional: status title while the server is full, e.g. `🟠 {server_name} — Full (queue {queue})`
+    #[serde(default)]
+    pub title_full_template: Option<String>,
+
+    /// Recurring windows such as `daily 04:00-04:20 UTC`, during which the server is shown as
+    /// under maintenance and alerts are suppressed
+    #[serde(default)]
+    pub maintenance_windows: Vec<String>,
+
+    /// Timezone for maintenance windows that do not name their own (default UTC)
+    #[serde(default)]
+    pub maintenance_timezone: Option<String>,
+
+    /// Timezone whose midnight starts a new day for daily stats (default UTC)
+    #[serde(default)]
+    pub stats_timezone: Option<String>,
+
+    /// Compare the player count during the guild's Discord scheduled events with a typical week,
+    /// in `/stats`
+    #[serde(default)]
+    pub event_impact: bool,
+
+    /// Show the player-hours served today in the status embed
+    #[serde(default)]
+    pub player_hours_field: bool,
+
+    /// Annotate player and queue counts with how they changed since the previous update
+    #[serde(default)]
+    pub change_annotations: bool,
+
+    /// Query the player list every poll and check it backs up the reported player count
+    #[serde(default)]
+    pub verify_player_count: bool,
+
+    /// Players the reported count may be off by before it counts as a mismatch
+    #[serde(default = "default_player_count_tolerance")]
+    pub player_count_tolerance: u32,
+
+    /// Consecutive mismatching polls before the player count is flagged
+    #[serde(default = "default_player_count_mismatch_polls")]
+    pub player_count_mismatch_polls: u32,
+
+    /// Show a flagged player count next to the verified one in the status embed
+    #[serde(default)]
+    pub player_count_annotation: bool,
+
+    /// Names of today's players kept in the state; past that they are only counted
+    #[serde(default = "default_unique_names_per_day")]
+    pub unique_names_per_day: usize,
+
+    /// Players whose session length is tracked at once; past that the longest-unlisted are
+    /// forgotten
+    #[serde(default = "default_tracked_sessions")]
+    pub tracked_sessions: usize,
+
+    /// Probe the game port every poll, to catch it being blocked while queries still answer
+    #[serde(default)]
+    pub game_port_probe: gameport::GamePortProbe,
+
+    /// Optional: the game port to probe, when it isn't the one the server reports
+    #[serde(default)]
+    pub game_port: Option<u16>,
+
+    /// Consecutive polls the game port must be unreachable before it is flagged
+    #[serde(default = "default_game_port_unreachable_polls")]
+    pub game_port_unreachable_polls: u32,
+
+    /// Optional: once the server is at least this many percent full and players keep arriving,
+    /// show an estimate of when it will be full
+    #[serde(default)]
+    pub full_forecast_occupancy: Option<u32>,
+
+    /// Optional: message of the day shown in the status embed, `\n` for line breaks; admins can
+    /// override it with `/motd edit`
+    #[serde(default)]
+    pub motd: Option<String>,
+
+    /// Extra status messages fed by the same poll, each `<channel_id>[/<message_id>] <layout>
+    /// [language] [+feature|-feature ...]` with layout `compact`, `detailed` or `text`
+    #[serde(default)]
+    pub outputs: Vec<String>,
+
+    /// Delete status messages from earlier runs that are no longer kept up to date, once a day
+    #[serde(default)]
+    pub cleanup_orphans: bool,
+
+    /// Hours an untracked status message must be old before the automatic cleanup deletes it
+    #[serde(default = "default_cleanup_min_age_hours")]
+    pub cleanup_min_age_hours: u64,
+
+    /// What startup does with saved message ids that don't check out: `adopt`, `recreate` or
+    /// `strict`
+    #[serde(default)]
+    pub reconcile_policy: reconcile::ReconcilePolicy,
+
+    /// How many saved messages startup looks up at once
+    #[serde(default = "default_reconcile_concurrency")]
+    pub reconcile_concurrency: usize,
+
+    /// Optional: TOML file of changes to the status embed, such as extra fields
+    #[serde(default)]
+    pub embed_patch_file: Option<PathBuf>,
+
+    /// Optional: when the server is next wiped, as `YYYY-MM-DD HH:MM [timezone]` (default UTC)
+    #[serde(default)]
+    pub next_wipe_date: Option<String>,
+
+    /// How long before the wipe to announce it, e.g. `7d`, `1d` and `1h`
+    #[serde(default = "default_wipe_announce_offsets")]
+    pub wipe_announce_offsets: Vec<String>,
+
+    /// Where wipe announcements go: `alert` (the status channel without one) or `status`
+    #[serde(default)]
+    pub wipe_announce_channel: wipe::WipeChannel,
+
+    /// What to do with announcements missed while the bot was not running: `skip` or `catch_up`
+    #[serde(default)]
+    pub wipe_missed_announcements: wipe::MissedAnnouncements,
+
+    /// Start the statistics over once the wipe happens
+    #[serde(default)]
+    pub wipe_reset_stats: bool,
+}
+
+impl DayzMonitorConfig {
+    /// Checks the parts of the config that deserialization alone cannot.
+    pub fn validate(&self) -> Result<(), DayzMonitorError> {
+        alerts::validate_alert_templates(self)?;
+        title::validate_title_template(self)?;
+        topic::validate_topic_template(self)?;
+        self.maintenance_schedule()?;
+        self.stats_timezone()?;
+        self.outputs()?;
+        self.wipe_plan()?;
+        notify::backends(self)?;
+        Ok(())
+    }
+
+    pub fn maintenance_schedule(&self) -> Result<Vec<schedule::Window>, DayzMonitorError> {
+        let tz = match &self.maintenance_timezone {
+            Some(name) => schedule::parse_timezone(name)?,
+            None => chrono_tz::UTC,
+        };
+
+        self.maintenance_windows
+            .iter()
+            .map(|spec| schedule::Window::parse(spec, tz))
+            .collect()
+    }
+
+    pub fn wipe_plan(&self) -> Result<Option<wipe::WipePlan>, DayzMonitorError> {
+        self.next_wipe_date
+            .as_deref()
+            .map(|date| wipe::WipePlan::parse(date, &self.wipe_announce_offsets, chrono_tz::UTC))
+            .transpose()
+    }
+
+    pub fn outputs(&self) -> Result<Vec<output::Output>, DayzMonitorError> {
+        self.outputs
+            .iter()
+            .map(|spec| output::Output::parse(spec))
+            .collect()
+    }
+
+    /// The identity persisted history belongs to.
+    pub fn server_identity(&self) -> &str {
+        self.server_id.as_deref().unwrap_or(&self.server_name)
+    }
+
+    pub fn stats_timezone(&self) -> Result<chrono_tz::Tz, DayzMonitorError> {
+        match &self.stats_timezone {
+            Some(name) => schedule::parse_timezone(name),
+            None => Ok(chrono_tz::UTC),
+        }
+    }
+
+    pub fn audit_log(&self) -> Option<audit::AuditLog> {
+        self.audit_file
+            .clone()
+            .map(|path| audit::AuditLog::new(path, self.audit_retention_hours * 3600))
+    }
+
+    pub fn latency_alert_threshold(&self) -> Option<LatencyAlertThreshold> {
+        let alert = self.alert_latency_ms?;
+        let clear = self.alert_latency_clear_ms.unwrap_or(alert * 4 / 5);
+
+        Some(LatencyAlertThreshold {
+            alert: Duration::from_millis(alert),
+            clear: Duration::from_millis(clear.min(alert)),
+        })
+    }
+
+    pub fn recovery_criterion(&self) -> monitor::RecoveryCriterion {
+        monitor::RecoveryCriterion {
+            polls: self.recovery_polls,
+            check: self.recovery_check,
+        }
+    }
+
+    pub fn number_locale(&self) -> numbers::NumberLocale {
+        self.number_locale
+            .unwrap_or_else(|| numbers::NumberLocale::from_language(self.bot_language))
+    }
+
+    /// Retries within one update must leave time for the rest of it.
+    fn update_retry_budget(&self) -> Duration {
+        Duration::from_secs(self.update_interval_secs) / 2
+    }
+
+    pub fn a2s_retry(&self) -> retry::RetryPolicy {
+        retry::RetryPolicy::retries(
+            self.a2s_retries,
+            Duration::from_millis(self.a2s_retry_delay_ms),
+        )
+        .with_budget(self.update_retry_budget())
+    }
+
+    pub fn status_edit_retry(&self) -> retry::RetryPolicy {
+        retry::RetryPolicy::retries(
+            self.status_edit_retries,
+            Duration::from_millis(self.status_edit_retry_delay_ms),
+        )
+        .with_budget(self.update_retry_budget())
+    }
+
+    pub fn status_edit_guard(&self) -> transport::EditGuard {
+        transport::EditGuard {
+            check_every: self.status_check_every,
+            unsuppress: self.status_unsuppress_embeds,
+            own_content_only: self.own_content_only,
+        }
+    }
+
+    pub fn sanity_bounds(&self) -> SanityBounds {
+        SanityBounds {
+            max_players: self.sanity_max_players,
+            max_slots: self.sanity_max_slots,
+            max_queue: self.sanity_max_queue,
+        }
+    }
+
+    pub fn latency_thresholds(&self) -> probe::LatencyThresholds {
+        probe::LatencyThresholds {
+            warn: Duration::from_millis(self.ping_warn_ms),
+            bad: Duration::from_millis(self.ping_bad_ms),
+        }
+    }
+
+    /// Whether alerts have anywhere to go: the alert channel, or a notification backend this
+    /// build has.
+    pub fn has_alert_destination(&self) -> bool {
+        self.alert_channel_id.is_some()
+            || (cfg!(feature = "telegram") && self.telegram_bot_token.is_some())
+            || (cfg!(feature = "ntfy") && self.ntfy_url.is_some())
+    }
+
+    /// Whether event impact data is collected. Only `/stats` shows it, and that needs the
+    /// gateway.
+    pub fn tracks_event_impact(&self) -> bool {
+        self.event_impact && self.gateway
+    }
+
+    /// Names of the optional features this config turns on.
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        features::registry(self)
+            .into_iter()
+            .filter(|f| f.activation == features::Activation::On)
+            .map(|f| f.name)
+            .collect()
+    }
+}
+
+/// Hysteresis bounds for the latency rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyAlertThreshold {
+    pub alert: Duration,
+    pub clear: Duration,
+}
+
+/// When the status embed shows the server's in-game time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeField {
+    /// Only once the server has reported a time at least once
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl TimeField {
+    /// Whether to show the line, given whether a time has ever been seen.
+    pub fn shows_line(self, time_seen: bool) -> bool {
+        match self {
+            TimeField::Auto => time_seen,
+            TimeField::Always => true,
+            TimeField::Never => false,
+        }
+    }
+}
+
+/// What the monitor currently believes about the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorState {
+    /// Not polled yet: nothing is known until the first poll after startup completes
+    #[default]
+    Unknown,
+    Online,
+    /// Reachable, but the response could not be fully trusted
+    Degraded,
+    Offline,
+    /// Answering again after an outage, but not yet meeting the recovery criterion
+    Starting,
+    /// Deliberately taken down; alerts are suppressed
+    Maintenance,
 }
 
 #[derive(Debug, Clone)]
@@ -51,56 +828,244 @@ pub struct ServerInfo {
     pub players: u32,
     pub max_players: u32,
 
+    /// Map name as reported by the server, e.g. `chernarusplus`
+    pub map: String,
+
+    /// Round-trip time of the info query that produced this sample
+    pub latency: Option<Duration>,
+
     /// Unix timestamp of the most recent successful query
     pub last_updated_unix: u64,
+
+    /// Sorted mod list, when mod list querying is enabled and succeeded
+    pub mods: Option<Vec<mods::Mod>>,
+
+    /// The player list, when player count verification is enabled and the list query succeeded
+    pub headcount: Option<headcount::Headcount>,
+
+    /// Names on that player list; players still connecting have none yet
+    pub player_names: Option<Vec<String>>,
+
+    /// The game port the server reports in its info response
+    pub game_port: Option<u16>,
+
+    /// What probing the game port heard, when the probe is enabled
+    pub join_port: Option<gameport::PortProbe>,
+
+    /// Keywords the parser passed over
+    pub ignored_tokens: Vec<IgnoredToken>,
+}
+
+/// Limits beyond which an A2S response is assumed to be corrupt rather than real.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SanityBounds {
+    pub max_players: u32,
+    pub max_slots: u32,
+    pub max_queue: u32,
+}
+
+/// The bounds the bot uses when none are configured.
+impl Default for SanityBounds {
+    fn default() -> Self {
+        Self {
+            max_players: default_sanity_max_players(),
+            max_slots: default_sanity_max_players(),
+            max_queue: default_sanity_max_queue(),
+        }
+    }
+}
+
+impl SanityBounds {
+    /// Describes the first implausible value in `info`, if any.
+    pub fn check(&self, info: &ServerInfo) -> Result<(), String> {
+        if info.max_players == 0 || info.max_players > self.max_slots {
+            return Err(format!(
+                "max players {} outside 1..={}",
+                info.max_players, self.max_slots
+            ));
+        }
+
+        if info.players > self.max_players {
+            return Err(format!(
+                "players {} above {}",
+                info.players, self.max_players
+            ));
+        }
+
+        if let Some(queue) = info.players_in_queue.filter(|q| *q > self.max_queue) {
+            return Err(format!("queue {queue} above {}", self.max_queue));
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves the configured `host:port` to every address it points at.
+pub async fn resolve_server_address(address: &str) -> Result<Vec<SocketAddr>, DayzMonitorError> {
+    Ok(tokio::net::lookup_host(address).await?.collect())
 }
 
 pub async fn retrieve_server_info(
-    client: &A2SClient,
     addr: SocketAddr,
+    bounds: &SanityBounds,
 ) -> Result<ServerInfo, DayzMonitorError> {
     tracing::debug!("Querying server info for '{addr}'");
 
-    let info = client.info(addr).await?;
+    let started = std::time::Instant::now();
+    let info = query::info(addr, query::QUERY_TIMEOUT).await?;
+    let latency = started.elapsed();
 
     let mut server_info = extract_time_and_queue(info.extended_server_info)
         .ok_or(DayzMonitorError::ExtractServerInfoKeywordsMissing)?;
 
     server_info.players = info.players as u32;
     server_info.max_players = info.max_players as u32;
+    server_info.map = info.map;
+    server_info.latency = Some(latency);
+
+    server_info.last_updated_unix = unix_now();
+
+    if let Err(reason) = bounds.check(&server_info) {
+        tracing::warn!(
+            "Discarding implausible response from '{addr}': {reason} (raw players={}, max_players={}, queue={:?})",
+            server_info.players,
+            server_info.max_players,
+            server_info.players_in_queue
+        );
+        return Err(DayzMonitorError::ImplausibleResponse(reason));
+    }
+
+    Ok(server_info)
+}
+
+/// Writes `bytes` to a sibling temp file and renames it over `path`, so readers never observe
+/// a partially written file.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), DayzMonitorError> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp = path.with_file_name(name);
+
+    let written = fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
 
-    server_info.last_updated_unix = SystemTime::now()
+    // A half-written temp file, e.g. on a full disk, would only take up more space.
+    if let Err(err) = written.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
-        .as_secs();
+        .as_secs()
+}
 
-    Ok(server_info)
+/// What the bot understands from a server's A2S keywords string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParsedKeywords {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_time: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<u32>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignored_tokens: Vec<IgnoredToken>,
 }
 
-fn extract_time_and_queue(info: ExtendedServerInfo) -> Option<ServerInfo> {
-    let values = info.keywords?;
-    let split: Vec<&str> = values.split(',').collect();
+/// Ignored tokens kept per parse; a server sending thousands of odd tokens gets no more.
+pub const MAX_IGNORED_TOKENS: usize = 16;
 
-    let mut server_info = ServerInfo {
-        server_time: None,
-        players_in_queue: None,
-        players: 0,
-        max_players: 0,
-        last_updated_unix: 0,
+/// Ignored tokens are cut to this many characters, as only their start explains anything.
+pub const MAX_IGNORED_TOKEN_CHARS: usize = 64;
+
+/// Parses a raw keywords string such as `battleye,no3rd,lqs0,etm4.000000,12:04`.
+pub fn parse_keywords(keywords: &str) -> ParsedKeywords {
+    let mut parsed = ParsedKeywords::default();
+
+    let mut ignore = |token: &str, reason| {
+        if parsed.ignored_tokens.len() < MAX_IGNORED_TOKENS {
+            parsed.ignored_tokens.push(IgnoredToken {
+                token: token.chars().take(MAX_IGNORED_TOKEN_CHARS).collect(),
+                reason,
+            })
+        }
     };
+    let mut queue = None;
+    let mut server_time = None;
 
-    for value in split {
+    for value in keywords.split(',') {
         // Queue is usually in keywords as lqs<number>
-        if value.starts_with("lqs") {
-            server_info.players_in_queue = value.replace("lqs", "").parse::<u32>().ok();
+        if let Some(digits) = value.strip_prefix("lqs") {
+            queue = digits.parse::<u32>().ok();
+            if queue.is_none() {
+                ignore(value, IgnoreReason::MalformedQueue);
+            }
             continue;
         }
 
         // Time looks like HH:MM:SS or HH:MM (keep it conservative)
-        if value.contains(':') && server_info.server_time.is_none() && value.len() <= 8 {
-            server_info.server_time = Some(value.to_owned());
+        if value.contains(':') {
+            match (&server_time, value.len() <= 8) {
+                (_, false) => ignore(value, IgnoreReason::TooLongForTime),
+                _ if parse_server_time(value).is_none() => {
+                    ignore(value, IgnoreReason::MalformedTime)
+                }
+                (Some(_), true) => ignore(value, IgnoreReason::DuplicateTime),
+                (None, true) => server_time = Some(value.to_owned()),
+            }
         }
     }
 
-    Some(server_info)
+    parsed.queue = queue;
+    parsed.server_time = server_time;
+
+    parsed
+}
+
+/// Reads an in-game time token, `H:MM`, `HH:MM` or `HH:MM:SS`, as `(hours, minutes,
+/// seconds)`. Anything else, such as `99:99` or `a:b`, is `None`.
+pub fn parse_server_time(token: &str) -> Option<(u8, u8, u8)> {
+    let number = |part: &str, digits: std::ops::RangeInclusive<usize>, max: u8| {
+        let valid = digits.contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit());
+        part.parse::<u8>().ok().filter(|n| valid && *n <= max)
+    };
+
+    let mut parts = token.split(':');
+    let hours = number(parts.next()?, 1..=2, 23)?;
+    let minutes = number(parts.next()?, 2..=2, 59)?;
+    let seconds = match parts.next() {
+        Some(part) => number(part, 2..=2, 59)?,
+        None => 0,
+    };
+
+    parts.next().is_none().then_some((hours, minutes, seconds))
+}
+
+fn extract_time_and_queue(info: ExtendedServerInfo) -> Option<ServerInfo> {
+    let game_port = info.port;
+    let parsed = parse_keywords(&info.keywords?);
+
+    Some(ServerInfo {
+        server_time: parsed.server_time,
+        players_in_queue: parsed.queue,
+        players: 0,
+        max_players: 0,
+        map: String::new(),
+        latency: None,
+        last_updated_unix: 0,
+        mods: None,
+        headcount: None,
+        player_names: None,
+        game_port,
+        join_port: None,
+        ignored_tokens: parsed.ignored_tokens,
+    })
 }
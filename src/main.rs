@@ -1,23 +1,122 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+mod commands;
+mod discord;
+mod embeds;
 
-use a2s::A2SClient;
-use dayz_monitor::{retrieve_server_info, DayzMonitorConfig, ServerInfo};
+use dayz_monitor::{
+    alerts::format_duration,
+    audit::AuditLog,
+    cleanup::Orphan,
+    clock::SystemClock,
+    cycle::{A2sPoller, Cycle, CycleReport, SharedState},
+    daily::StatsReport,
+    env_template,
+    features::{self, Activation},
+    gaps,
+    hook::{EmbedPatch, PostRender},
+    i18n::Language,
+    maps, notify,
+    numbers::NumberLocale,
+    parse_keywords, playtime, resolve_server_address,
+    shutdown::{Shutdown, TaskGroup},
+    snapshot::{self, AuditRestore},
+    state::{claim_history, load_state, FileStore, GuardedStore, PersistentState},
+    status_file::wait_for_status,
+    subsystem::{self, probe_writable, RestartPolicy, Subsystems},
+    transport::StatusMessage,
+    unix_now, write_atomic, DayzMonitorConfig,
+};
 use serenity::{
-    all::{
-        ChannelId, Client, CreateEmbed, CreateMessage, EditMessage,
-        GatewayIntents, MessageId,
-    },
+    all::{ChannelId, Client, GatewayIntents, GuildId, Http, Interaction},
     async_trait,
+    gateway::ShardManager,
     model::gateway::Ready,
     prelude::*,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{watch, Mutex, Notify};
 use tracing_subscriber::EnvFilter;
 
+use crate::discord::{DiscordHost, DiscordTransport};
+
+/// How long a shutdown waits for the cycle in progress, which may be retrying a query, to end.
+const STATUS_LOOP_STOP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// How long a shutdown waits for alerts and charts still on their way out.
+const ALERT_DELIVERY_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
 struct BotState {
     config: DayzMonitorConfig,
-    a2s: Arc<A2SClient>,
-    status_message_id: Arc<RwLock<Option<MessageId>>>,
+
+    /// What the status loop shares with slash commands
+    shared: SharedState,
+
+    /// Where the status loop and slash commands read the time from
+    clock: SystemClock,
+
+    /// Set once the gateway is ready and an HTTP client is available
+    status: OnceLock<StatusMessage<DiscordTransport>>,
+
+    /// Set once the client is built; used to read gateway heartbeat latency
+    shard_manager: OnceLock<Arc<ShardManager>>,
+
+    /// Set by the first `ready`, so reconnects don't start a second status loop
+    loop_started: AtomicBool,
+
+    /// Alert destinations besides the Discord alert channel
+    #[cfg(any(feature = "telegram", feature = "ntfy"))]
+    notifiers: Vec<notify::Backend>,
+    #[cfg(any(feature = "telegram", feature = "ntfy"))]
+    notify_client: reqwest::Client,
+
+    /// Recent `/stats` results per guild, so repeated invocations reuse them
+    stats_cache: Mutex<HashMap<Option<GuildId>, (Instant, StatsReport)>>,
+
+    /// Wakes the status loop early, so an admin's change shows without waiting for the next poll
+    refresh: Notify,
+
+    /// Timing of the last finished cycle, which `/refresh verbose` waits for
+    cycle_report: watch::Sender<Option<CycleReport>>,
+
+    /// Status messages the last `/cleanup` listed, deleted once an admin confirms
+    cleanup_pending: Mutex<Vec<Orphan>>,
+
+    /// Stops the status loop and waits for deliveries on a signal
+    shutdown: Shutdown,
+
+    /// Why the status loop gave up, which shuts the bot down as a signal would
+    failed: watch::Sender<Option<String>>,
+
+    /// Alert deliveries and charts sent off the status loop
+    #[cfg_attr(
+        not(any(feature = "charts", feature = "telegram", feature = "ntfy")),
+        allow(dead_code)
+    )]
+    alert_tasks: TaskGroup,
+}
+
+impl BotState {
+    /// The address commands should query: the last one that worked, else a fresh lookup.
+    async fn server_addr(&self) -> Result<SocketAddr, dayz_monitor::DayzMonitorError> {
+        if let Some(addr) = *self.shared.query_addr.read().await {
+            return Ok(addr);
+        }
+
+        resolve_server_address(&self.config.server_address)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound).into())
+    }
 }
 
 struct Handler {
@@ -26,121 +125,560 @@ struct Handler {
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, ctx: Context, _: Ready) {
-        let state = self.state.clone();
-        let http = ctx.http.clone();
-        let channel_id = ChannelId::new(state.config.text_channel_id);
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        commands::register(&ctx).await;
 
-        if let Some(mid) = state.config.status_message_id {
-            *state.status_message_id.write().await = Some(MessageId::new(mid));
+        // `ready` fires again after every reconnect, but one loop is all it takes.
+        if self.state.loop_started.swap(true, Ordering::SeqCst) {
+            return;
         }
 
-        tokio::spawn(async move {
-            loop {
-                let msg_id = ensure_status_message(&state, &http, channel_id).await;
-
-                let edit = match retrieve_server_info(&state.a2s, state.config.server_address).await {
-                    Ok(info) => build_online(&state, &info),
-                    Err(err) => build_offline(&state, &err.to_string()),
-                };
-
-                let _ = channel_id.edit_message(&http, msg_id, edit).await;
+        tokio::spawn(run_status_loop(
+            self.state.clone(),
+            ctx.http.clone(),
+            ready.user.id.get(),
+        ));
+    }
 
-                tokio::time::sleep(Duration::from_secs(state.config.update_interval_secs)).await;
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(cmd) => commands::handle(&self.state, &ctx, &cmd).await,
+            Interaction::Modal(modal) => commands::handle_modal(&self.state, &ctx, &modal).await,
+            Interaction::Component(component) => {
+                commands::handle_component(&self.state, &ctx, &component).await
             }
-        });
+            _ => {}
+        }
     }
 }
 
-async fn ensure_status_message(
-    state: &BotState,
-    http: &serenity::http::Http,
-    channel_id: ChannelId,
-) -> MessageId {
-    if let Some(id) = *state.status_message_id.read().await {
-        return id;
+/// Adopts or posts the status message and keeps it updated until the shutdown. Runs from
+/// `ready` with the gateway, and straight from `main` without it. If it gives up, the bot shuts
+/// down and exits with an error.
+async fn run_status_loop(state: Arc<BotState>, http: Arc<Http>, bot_user_id: u64) {
+    if let Err(err) = status_loop(state.clone(), http, bot_user_id).await {
+        tracing::error!("{err}");
+        state.failed.send_replace(Some(err.to_string()));
     }
+}
 
-    let embed = CreateEmbed::new()
-        .title("Starting…")
-        .description("Fetching server status…")
-        .colour(0x5865F2);
-
-    let msg = CreateMessage::new().add_embed(embed);
+async fn status_loop(
+    state: Arc<BotState>,
+    http: Arc<Http>,
+    bot_user_id: u64,
+) -> Result<(), dayz_monitor::DayzMonitorError> {
+    // Only checked between cycles, so a shutdown never cuts off an edit or a state write.
+    let mut stop = state
+        .shutdown
+        .register("status_loop", STATUS_LOOP_STOP_TIMEOUT);
+    let status = state.status.get_or_init(|| {
+        StatusMessage::new(DiscordTransport {
+            http: http.clone(),
+            channel_id: ChannelId::new(state.config.text_channel_id),
+            edit_retry: state.config.status_edit_retry(),
+        })
+    });
+    let host = DiscordHost {
+        state: state.clone(),
+        http,
+    };
 
-    match channel_id.send_message(http, msg).await {
-        Ok(sent) => {
-            let id = sent.id;
-            *state.status_message_id.write().await = Some(id);
-            id
+    let started = Cycle::start(
+        &state.config,
+        A2sPoller::new(&state.config),
+        status,
+        &host,
+        &state.shared,
+        bot_user_id,
+        &state.clock,
+    )
+    .await;
+    let mut cycle = match started {
+        Ok(cycle) => cycle,
+        Err(err) => {
+            stop.done();
+            return Err(err);
         }
-        Err(_) => {
-            tokio::time::sleep(Duration::from_secs(state.config.update_interval_secs)).await;
-            MessageId::new(0)
+    };
+
+    loop {
+        let report = cycle.run(status, &host, &state.shared, &state.clock).await;
+        state.cycle_report.send_replace(Some(report));
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(state.config.update_interval_secs)) => {}
+            _ = state.refresh.notified() => {}
+            _ = stop.cancelled() => break,
         }
     }
+
+    // The cycle just finished saved the state and its edits are out.
+    tracing::info!("Status loop stopped");
+    stop.done();
+    Ok(())
+}
+
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn arg_secs(args: &[String], flag: &str, default: u64) -> eyre::Result<Duration> {
+    match arg_value(args, flag) {
+        Some(v) => Ok(Duration::from_secs(v.parse()?)),
+        None => Ok(Duration::from_secs(default)),
+    }
+}
+
+/// `--wait-for-status <field> [--status-file <path>] [--max-age <secs>] [--timeout <secs>]`
+///
+/// Blocks until the status file is fresh, then prints the requested field.
+async fn wait_for_status_cli(args: &[String], field: &str) -> eyre::Result<()> {
+    let path = match arg_value(args, "--status-file") {
+        Some(p) => PathBuf::from(p),
+        None => std::env::var_os("STATUS_FILE")
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                eyre::eyre!("No status file given (use --status-file or STATUS_FILE)")
+            })?,
+    };
+
+    let max_age = arg_secs(args, "--max-age", 120)?;
+    let timeout = arg_secs(args, "--timeout", 300)?;
+
+    let status = wait_for_status(&path, max_age, timeout).await?;
+    println!("{}", status.field(field)?);
+
+    Ok(())
+}
+
+#[cfg(feature = "schema")]
+fn print_schema() -> eyre::Result<()> {
+    let schema = dayz_monitor::status_file::status_file_schema();
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "schema"))]
+fn print_schema() -> eyre::Result<()> {
+    Err(eyre::eyre!(
+        "This build has no schema; rebuild with `cargo build --features schema`."
+    ))
+}
+
+/// `stats [--state-file <path>] [--language <code>]`
+///
+/// Prints the locally collected usage counters, in `--language` or else `BOT_LANGUAGE`.
+fn stats_cli(args: &[String]) -> eyre::Result<()> {
+    let path = match arg_value(args, "--state-file") {
+        Some(p) => PathBuf::from(p),
+        None => std::env::var_os("STATE_FILE")
+            .map(PathBuf::from)
+            .ok_or_else(|| eyre::eyre!("No state file given (use --state-file or STATE_FILE)"))?,
+    };
+
+    let state = load_state(&path)?;
+    let lang = arg_value(args, "--language")
+        .map(str::to_string)
+        .or_else(|| std::env::var("BOT_LANGUAGE").ok())
+        .and_then(|code| Language::from_locale(&code))
+        .unwrap_or_default();
+    let locale = std::env::var("NUMBER_LOCALE")
+        .ok()
+        .and_then(|code| NumberLocale::from_code(&code))
+        .unwrap_or(NumberLocale::from_language(lang));
+
+    print!("{}", state.usage.summary(unix_now(), locale, lang));
+    print!(
+        "\n{}",
+        maps::summary(&state.map_stats, &state.map_rotation, locale, lang)
+    );
+    print!("\n{}", playtime::summary(&state.player_hours, locale, lang));
+    print!("\n{}", gaps::summary(&state.monitoring_gaps, lang));
+
+    Ok(())
+}
+
+/// `audit [--hours <n>] [--audit-file <path>]`
+///
+/// Prints the audit log of the last `n` hours (default 24), oldest first.
+fn audit_cli(args: &[String]) -> eyre::Result<()> {
+    let path = match arg_value(args, "--audit-file") {
+        Some(p) => PathBuf::from(p),
+        None => std::env::var_os("AUDIT_FILE")
+            .map(PathBuf::from)
+            .ok_or_else(|| eyre::eyre!("No audit file given (use --audit-file or AUDIT_FILE)"))?,
+    };
+
+    let hours: u64 = match arg_value(args, "--hours") {
+        Some(v) => v.parse()?,
+        None => 24,
+    };
+
+    // Retention only matters when pruning, which the CLI never does.
+    let audit = AuditLog::new(path, 0);
+    for entry in audit.read_since(unix_now().saturating_sub(hours * 3600))? {
+        println!("{}", entry.to_line());
+    }
+
+    Ok(())
 }
 
-fn rel_ts(secs: u64) -> String {
-    format!("<t:{}:R>", secs)
+/// `snapshot --out <path> [--state-file <path>] [--audit-file <path>]`
+///
+/// Bundles the state file, the audit log and the redacted effective settings into a tar archive.
+fn snapshot_cli(args: &[String]) -> eyre::Result<()> {
+    let out = arg_value(args, "--out")
+        .ok_or_else(|| eyre::eyre!("Usage: dayz-monitor snapshot --out <path>"))?;
+    let state_path = match arg_value(args, "--state-file") {
+        Some(p) => PathBuf::from(p),
+        None => std::env::var_os("STATE_FILE")
+            .map(PathBuf::from)
+            .ok_or_else(|| eyre::eyre!("No state file given (use --state-file or STATE_FILE)"))?,
+    };
+    let audit_path = arg_value(args, "--audit-file")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("AUDIT_FILE").map(PathBuf::from));
+
+    // Settings are only for reference, so an incomplete environment still gets a snapshot.
+    let config = match serde_env::from_env::<DayzMonitorConfig>() {
+        Ok(config) => Some(snapshot::render_settings(&features::settings(&config))),
+        Err(err) => {
+            eprintln!("Leaving out the settings, the configuration does not load: {err}");
+            None
+        }
+    };
+
+    let archive = snapshot::create(
+        &state_path,
+        audit_path.as_deref(),
+        config.as_deref(),
+        unix_now(),
+    )?;
+    write_atomic(std::path::Path::new(out), &archive)?;
+    println!("Wrote {out}");
+
+    Ok(())
 }
 
-fn build_online(state: &BotState, info: &ServerInfo) -> EditMessage {
-    let players_line = match info.players_in_queue {
-        Some(q) if q > 0 => format!("**{} / {}** • ⏳ Queue: **{}**", info.players, info.max_players, q),
-        _ => format!("**{} / {}**", info.players, info.max_players),
+/// `restore --in <path> [--state-file <path>] [--audit-file <path>] [--force]`
+///
+/// Validates a snapshot and restores the state and audit log from it, with the bot stopped.
+fn restore_cli(args: &[String]) -> eyre::Result<()> {
+    let input = arg_value(args, "--in")
+        .ok_or_else(|| eyre::eyre!("Usage: dayz-monitor restore --in <path>"))?;
+    let state_path = match arg_value(args, "--state-file") {
+        Some(p) => PathBuf::from(p),
+        None => std::env::var_os("STATE_FILE")
+            .map(PathBuf::from)
+            .ok_or_else(|| eyre::eyre!("No state file given (use --state-file or STATE_FILE)"))?,
     };
+    let audit_path = arg_value(args, "--audit-file")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("AUDIT_FILE").map(PathBuf::from));
+    let force = args.iter().any(|a| a == "--force");
 
-    let time_line = info.server_time.clone().unwrap_or_else(|| "Unknown".to_string());
+    let snapshot = snapshot::read(&std::fs::read(input)?)?;
+    let audit = snapshot::restore(
+        &snapshot,
+        &state_path,
+        audit_path.as_deref(),
+        unix_now(),
+        force,
+    )?;
 
-    let embed = CreateEmbed::new()
-        .title(format!("🟢 {} — Online", state.config.server_name))
-        .description(format!(
-            "👥 Players: {}\n🕒 Server Time: **{}**",
-            players_line, time_line
-        ))
-        .colour(0x57F287)
-        .field("📍 Address", format!("`{}`", state.config.server_address), true)
-        .field("🔄 Update", format!("`{}s`", state.config.update_interval_secs), true)
-        .field("🕐 Last Updated", rel_ts(info.last_updated_unix), false);
+    println!(
+        "Restored the state from a snapshot made by version {}",
+        snapshot.manifest.bot_version
+    );
+    match audit {
+        AuditRestore::Restored => println!("Restored the audit log"),
+        AuditRestore::NotInSnapshot => println!("The snapshot has no audit log; left it as is"),
+        AuditRestore::NoAuditFile => {
+            println!("Skipped the audit log in the snapshot (use --audit-file or AUDIT_FILE)")
+        }
+    }
 
-    EditMessage::new().embed(embed)
+    Ok(())
 }
 
-fn build_offline(state: &BotState, err: &str) -> EditMessage {
-    let embed = CreateEmbed::new()
-        .title(format!("🔴 {} — Offline", state.config.server_name))
-        .description("⚠️ Could not query the server.")
-        .colour(0xED4245)
-        .field("📍 Address", format!("`{}`", state.config.server_address), true)
-        .field("🧾 Error", format!("`{}`", err), false);
+/// `parse-keywords "<keywords>"`
+///
+/// Prints what the parser makes of a keywords string, in the fixture sidecar format.
+fn parse_keywords_cli(args: &[String]) -> eyre::Result<()> {
+    let keywords = args
+        .get(1)
+        .ok_or_else(|| eyre::eyre!("Usage: dayz-monitor parse-keywords \"<keywords>\""))?;
+
+    print!("{}", toml::to_string(&parse_keywords(keywords))?);
 
-    EditMessage::new().embed(embed)
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     let _ = dotenv::dotenv();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--print-env-template") {
+        print!("{}", env_template::render());
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--print-schema") {
+        return print_schema();
+    }
+    if let Some(field) = arg_value(&args, "--wait-for-status") {
+        return wait_for_status_cli(&args, field).await;
+    }
+    if args.first().is_some_and(|a| a == "stats") {
+        return stats_cli(&args);
+    }
+    if args.first().is_some_and(|a| a == "audit") {
+        return audit_cli(&args);
+    }
+    if args.first().is_some_and(|a| a == "snapshot") {
+        return snapshot_cli(&args);
+    }
+    if args.first().is_some_and(|a| a == "restore") {
+        return restore_cli(&args);
+    }
+    if args.first().is_some_and(|a| a == "parse-keywords") {
+        return parse_keywords_cli(&args);
+    }
+
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
     let config: DayzMonitorConfig = serde_env::from_env()?;
-    let a2s = Arc::new(A2SClient::new().await?);
+    config.validate()?;
+    for feature in features::registry(&config) {
+        match feature.activation {
+            Activation::On => tracing::info!("Feature {} is on", feature.name),
+            Activation::Inactive(reason) => {
+                tracing::warn!(
+                    "Feature {} is configured but inactive: {reason}",
+                    feature.name
+                )
+            }
+            Activation::NotBuilt(cargo_feature) => {
+                tracing::warn!(
+                    "Feature {} is configured but not built into this binary; rebuild with \
+                     `--features {cargo_feature}`",
+                    feature.name
+                )
+            }
+            Activation::Off => {}
+        }
+    }
+
+    // Optional subsystems that fail here are disabled or retried, never fatal.
+    let subsystems = Subsystems::default();
+    let mut persistent = PersistentState::default();
+    let mut state_store = None;
+    if let Some(path) = &config.state_file {
+        // A state file that can't be loaded is left alone rather than overwritten.
+        let loaded = load_state(path).and_then(|loaded| {
+            probe_writable(path)?;
+            Ok(loaded)
+        });
+        match loaded {
+            Ok(loaded) => {
+                persistent = loaded;
+                state_store = Some(Mutex::new(GuardedStore::new(FileStore {
+                    path: path.clone(),
+                })));
+                subsystems.add(subsystem::STATE_FILE, RestartPolicy::Disable);
+            }
+            Err(err) => subsystems.start(subsystem::STATE_FILE, RestartPolicy::Disable, Err(err)),
+        }
+    }
+    if let Some(path) = &config.audit_file {
+        subsystems.start(
+            subsystem::AUDIT_LOG,
+            RestartPolicy::Disable,
+            probe_writable(path),
+        );
+    }
+    if let Some(path) = &config.status_file {
+        subsystems.start(
+            subsystem::STATUS_FILE,
+            RestartPolicy::Disable,
+            probe_writable(path),
+        );
+    }
+    if config.region_pings_dir.is_some() && config.bot_region.is_some() {
+        subsystems.add(subsystem::REGION_PINGS, RestartPolicy::Retry);
+    }
+    // Validated above.
+    let notifiers = notify::backends(&config).unwrap_or_default();
+    for backend in &notifiers {
+        subsystems.add(backend.name(), RestartPolicy::Retry);
+    }
+    persistent
+        .usage
+        .record_start(unix_now(), &config.enabled_features());
+    let identity_changes = claim_history(
+        &mut persistent,
+        config.server_identity(),
+        &config.server_address,
+    );
+    // A few missed polls are just a restart; more means the bot was down.
+    let started = unix_now();
+    if let Some(gap) = gaps::detect(
+        persistent.heartbeat_unix,
+        started,
+        config.update_interval_secs * 3,
+    ) {
+        tracing::warn!(
+            "The bot was not running for {}, stats won't cover that time",
+            format_duration(gap.secs())
+        );
+        gaps::record(&mut persistent.monitoring_gaps, gap, started);
+    }
+    persistent.stopped = false;
+
+    let mut post_render = PostRender::new();
+    if let Some(path) = &config.embed_patch_file {
+        match EmbedPatch::load(path) {
+            Ok(patch) => post_render.register(move |_, embed| patch.apply(embed)),
+            Err(err) => tracing::error!("Not applying EMBED_PATCH_FILE: {err}"),
+        }
+    }
+
+    // Registered before the status loop, so the loop stops first and sends nothing new while
+    // deliveries drain.
+    let shutdown = Shutdown::default();
+    let alert_tasks = shutdown.register_tasks("alert_delivery", ALERT_DELIVERY_STOP_TIMEOUT);
 
     let state = Arc::new(BotState {
         config: config.clone(),
-        a2s,
-        status_message_id: Arc::new(RwLock::new(None)),
+        shared: SharedState {
+            state_store,
+            audit: config.audit_log(),
+            subsystems,
+            post_render,
+            ..SharedState::new(persistent)
+        },
+        clock: SystemClock,
+        status: OnceLock::new(),
+        shard_manager: OnceLock::new(),
+        loop_started: AtomicBool::new(false),
+        stats_cache: Mutex::new(HashMap::new()),
+        #[cfg(any(feature = "telegram", feature = "ntfy"))]
+        notifiers,
+        #[cfg(any(feature = "telegram", feature = "ntfy"))]
+        notify_client: reqwest::Client::new(),
+        refresh: Notify::new(),
+        cycle_report: watch::Sender::new(None),
+        cleanup_pending: Mutex::new(Vec::new()),
+        shutdown,
+        failed: watch::Sender::new(None),
+        alert_tasks,
     });
 
+    for change in identity_changes {
+        tracing::info!("{}", change.describe());
+        state.shared.record_audit(change, &state.clock);
+    }
+
+    if !config.gateway {
+        let skipped = features::skipped_without_gateway(&config);
+        if skipped.is_empty() {
+            tracing::info!("GATEWAY is false, updating the status over HTTP only");
+        } else {
+            tracing::warn!(
+                "GATEWAY is false, updating the status over HTTP only; not running: {}",
+                skipped.join(", ")
+            );
+        }
+
+        let http = Arc::new(Http::new(&config.discord_token));
+        let bot_user = http.get_current_user().await?;
+        tokio::spawn(run_status_loop(state.clone(), http, bot_user.id.get()));
+        wait_for_stop(&state).await;
+        stop(&state).await;
+        return failure(&state);
+    }
+
     let intents = GatewayIntents::GUILDS;
 
     let mut client = Client::builder(config.discord_token, intents)
-        .event_handler(Handler { state })
+        .event_handler(Handler {
+            state: state.clone(),
+        })
         .await?;
 
+    let _ = state.shard_manager.set(client.shard_manager.clone());
+
+    let signalled = state.clone();
+    tokio::spawn(async move {
+        wait_for_stop(&signalled).await;
+        stop(&signalled).await;
+        // Closes the gateway connection, which ends `client.start`.
+        if let Some(shards) = signalled.shard_manager.get() {
+            shards.shutdown_all().await;
+        }
+    });
+
     client.start().await?;
-    Ok(())
+    failure(&state)
+}
+
+/// Resolves on a signal, or once the status loop gives up.
+async fn wait_for_stop(state: &BotState) {
+    let mut failed = state.failed.subscribe();
+    tokio::select! {
+        _ = wait_for_signal() => {}
+        _ = failed.wait_for(Option::is_some) => {}
+    }
+}
+
+/// Why the status loop gave up, as the error `main` exits with.
+fn failure(state: &BotState) -> eyre::Result<()> {
+    match &*state.failed.borrow() {
+        Some(err) => Err(eyre::eyre!("Status loop failed: {err}")),
+        None => Ok(()),
+    }
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM as sent by `docker stop` and systemd.
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+                return;
+            }
+            Err(err) => tracing::warn!("Can't listen for SIGTERM, only Ctrl+C: {err}"),
+        }
+    }
+
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        tracing::error!("Can't listen for Ctrl+C, the bot can only be killed: {err}");
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Stops the background components in order and saves the state one last time, marked as
+/// stopped so a restore needn't wait for the heartbeat to age.
+async fn stop(state: &BotState) {
+    tracing::info!("Shutting down");
+    let summary = state.shutdown.run().await;
+    if summary.is_clean() {
+        tracing::info!("Shut down: {summary}");
+    } else {
+        tracing::warn!("Shut down: {summary}");
+    }
+
+    state.shared.persistent.write().await.stopped = true;
+    state.shared.save_persistent(&state.clock).await;
 }
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    i18n::{Language, Text},
+    monitor::Observation,
+    numbers::{format_decimal, format_number, NumberLocale},
+    MonitorState,
+};
+
+/// Consecutive polls a new map must be seen for before it counts as a rotation, so a single
+/// odd response can't split the statistics.
+pub const MAP_CHANGE_POLLS: u32 = 3;
+
+/// Readable names for the official maps; anything else is shown as reported.
+pub fn display_name(map: &str) -> &str {
+    match map.to_ascii_lowercase().as_str() {
+        "chernarusplus" => "Chernarus",
+        "enoch" => "Livonia",
+        "sakhal" => "Sakhal",
+        _ => map,
+    }
+}
+
+/// The map currently in rotation and when it started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapRotation {
+    pub current: Option<String>,
+    pub started_unix: Option<u64>,
+
+    /// A different map seen recently, and for how many polls in a row
+    #[serde(skip)]
+    candidate: Option<(String, u32)>,
+}
+
+impl MapRotation {
+    /// Feeds the map of one successful poll. Returns `true` when a new rotation starts.
+    pub fn observe(&mut self, map: &str, now: u64) -> bool {
+        if map.is_empty() || self.current.as_deref() == Some(map) {
+            self.candidate = None;
+            return false;
+        }
+
+        let seen = match &mut self.candidate {
+            Some((candidate, seen)) if candidate == map => {
+                *seen += 1;
+                *seen
+            }
+            _ => {
+                self.candidate = Some((map.to_owned(), 1));
+                1
+            }
+        };
+
+        // The very first map needs no confirmation.
+        if self.current.is_none() || seen >= MAP_CHANGE_POLLS {
+            self.current = Some(map.to_owned());
+            self.started_unix = Some(now);
+            self.candidate = None;
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Running totals for one map, accumulated while it is in rotation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapStats {
+    pub cycles: u64,
+    pub online_cycles: u64,
+    pub player_sum: u64,
+    pub peak_players: u32,
+}
+
+impl MapStats {
+    /// Records one poll; `players` is `None` when the server was down.
+    pub fn record(&mut self, players: Option<u32>) {
+        self.cycles = self.cycles.saturating_add(1);
+
+        if let Some(players) = players {
+            self.online_cycles = self.online_cycles.saturating_add(1);
+            self.player_sum = self.player_sum.saturating_add(players.into());
+            self.peak_players = self.peak_players.max(players);
+        }
+    }
+
+    pub fn average_players(&self) -> f64 {
+        match self.online_cycles {
+            0 => 0.0,
+            n => self.player_sum as f64 / n as f64,
+        }
+    }
+
+    pub fn uptime_percent(&self) -> f64 {
+        match self.cycles {
+            0 => 0.0,
+            n => self.online_cycles as f64 * 100.0 / n as f64,
+        }
+    }
+}
+
+/// Per-map section of the `stats` subcommand.
+pub fn summary(
+    stats: &BTreeMap<String, MapStats>,
+    rotation: &MapRotation,
+    locale: NumberLocale,
+    lang: Language,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}:", lang.tr(Text::Maps));
+
+    if stats.is_empty() {
+        let _ = writeln!(out, "  {}", lang.tr(Text::NoneYet));
+    }
+
+    for (map, s) in stats {
+        let current = if rotation.current.as_deref() == Some(map) {
+            format!(" ({})", lang.tr(Text::Current))
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            out,
+            "  {:<16}{} {}, {} {}, {} {}%{current}",
+            display_name(map),
+            lang.tr(Text::AveragePlayers),
+            format_decimal(s.average_players(), 1, locale),
+            lang.tr(Text::Peak),
+            format_number(s.peak_players.into(), locale),
+            lang.tr(Text::Uptime),
+            format_decimal(s.uptime_percent(), 1, locale),
+        );
+    }
+
+    out
+}
+
+/// Updates the rotation and per-map totals from one observation, and tags it with when its
+/// map came into rotation.
+pub fn track(
+    rotation: &mut MapRotation,
+    stats: &mut BTreeMap<String, MapStats>,
+    observation: &mut Observation,
+    now: u64,
+) {
+    let players = match (observation.state, &observation.info) {
+        (MonitorState::Online, Some(info)) => {
+            if rotation.observe(&info.map, now) {
+                tracing::info!("Map rotation started: {}", display_name(&info.map));
+            }
+            Some(info.players)
+        }
+        (MonitorState::Offline | MonitorState::Starting, _) => None,
+        // None of these says anything reliable about the map's uptime.
+        (MonitorState::Unknown | MonitorState::Degraded | MonitorState::Maintenance, _)
+        | (MonitorState::Online, None) => return,
+    };
+
+    if let Some(map) = &rotation.current {
+        stats.entry(map.clone()).or_default().record(players);
+    }
+
+    if observation
+        .info
+        .as_ref()
+        .is_some_and(|info| rotation.current.as_deref() == Some(info.map.as_str()))
+    {
+        observation.map_since_unix = rotation.started_unix;
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, time::Duration};
+
+use crate::{
+    query::{self, Reader},
+    DayzMonitorError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mod {
+    pub name: String,
+    pub workshop_id: u64,
+}
+
+impl Mod {
+    pub fn workshop_url(&self) -> String {
+        format!(
+            "https://steamcommunity.com/sharedfiles/filedetails/?id={}",
+            self.workshop_id
+        )
+    }
+}
+
+/// Queries the server's mod list.
+pub async fn query_mods(addr: SocketAddr, timeout: Duration) -> Result<Vec<Mod>, DayzMonitorError> {
+    let rules = query::raw_rules(addr, timeout).await?;
+    decode_mods(&rules).ok_or(DayzMonitorError::A2SError(
+        a2s::errors::Error::InvalidResponse,
+    ))
+}
+
+/// Decodes the DayZ mod list from raw rules.
+///
+/// The binary blob is split across rules whose two-byte key is `[fragment number, fragment
+/// count]` (1-based), and escapes `0x00`, `0x01` and `0xFF` as `01 02`, `01 01` and `01 03`.
+pub fn decode_mods(rules: &[(Vec<u8>, Vec<u8>)]) -> Option<Vec<Mod>> {
+    let mut fragments: Vec<(u8, &[u8])> = rules
+        .iter()
+        .filter_map(|(key, value)| match key.as_slice() {
+            [number, total] if *number >= 1 && number <= total => Some((*number, value.as_slice())),
+            _ => None,
+        })
+        .collect();
+
+    if fragments.is_empty() {
+        return None;
+    }
+
+    fragments.sort_by_key(|(number, _)| *number);
+    let data = unescape(
+        fragments
+            .iter()
+            .flat_map(|(_, value)| value.iter().copied()),
+    );
+
+    let mut reader = Reader(&data);
+    let _protocol_version = reader.u8()?;
+    let _overflow_flags = reader.u8()?;
+
+    let dlc_flags = reader.u16()?;
+    for _ in 0..dlc_flags.count_ones() {
+        reader.u32()?;
+    }
+
+    let count = reader.u8()?;
+    let mut mods = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let _hash = reader.u32()?;
+
+        // Low nibble is the id length; bit 4 flags official DLC.
+        let id_len = (reader.u8()? & 0x0F) as usize;
+        let id_bytes = reader.take(id_len)?;
+        let workshop_id = id_bytes
+            .iter()
+            .rev()
+            .fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+
+        let name_len = reader.u8()? as usize;
+        let name = String::from_utf8_lossy(reader.take(name_len)?).into_owned();
+
+        mods.push(Mod { name, workshop_id });
+    }
+
+    Some(mods)
+}
+
+fn unescape(bytes: impl Iterator<Item = u8>) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut escaped = false;
+
+    for b in bytes {
+        if escaped {
+            out.push(match b {
+                0x02 => 0x00,
+                0x03 => 0xFF,
+                other => other,
+            });
+            escaped = false;
+        } else if b == 0x01 {
+            escaped = true;
+        } else {
+            out.push(b);
+        }
+    }
+
+    out
+}
+
+/// Sorts mods alphabetically (case-insensitive) so rendering and hashing are stable.
+pub fn sort_mods(mods: &mut [Mod]) {
+    mods.sort_by(|a, b| {
+        a.name
+            .to_lowercase()
+            .cmp(&b.name.to_lowercase())
+            .then(a.workshop_id.cmp(&b.workshop_id))
+    });
+}
+
+/// FNV-1a over the sorted list; stable across builds, so it can be persisted.
+pub fn mod_list_hash(mods: &[Mod]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for m in mods {
+        for b in m.name.bytes().chain(m.workshop_id.to_le_bytes()).chain([0]) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    hash
+}
+
+/// One markdown line per mod, linking to its Workshop page.
+pub fn mod_lines(mods: &[Mod]) -> Vec<String> {
+    mods.iter()
+        .map(|m| {
+            let name = crate::template::sanitize(&m.name).replace(['[', ']'], "");
+            format!("• [{}]({})", name, m.workshop_url())
+        })
+        .collect()
+}
+
+/// Greedily packs lines into chunks of at most `limit` bytes. A single line longer than the
+/// limit is truncated rather than dropped.
+pub fn chunk_lines(lines: &[String], limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in lines {
+        let line = truncate(line, limit);
+
+        if !current.is_empty() && current.len() + 1 + line.len() > limit {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn truncate(s: &str, limit: usize) -> &str {
+    if s.len() <= limit {
+        return s;
+    }
+
+    let mut end = limit;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// What to do with the companion messages to make them show `chunks`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompanionPlan {
+    /// Existing messages to edit, paired with their new content
+    pub edit: Vec<(u64, String)>,
+    /// New messages to send, in order, after the edited ones
+    pub send: Vec<String>,
+    /// Surplus messages to delete because the list shrank
+    pub delete: Vec<u64>,
+}
+
+/// Reuses existing companion messages in order, appending or deleting the difference.
+pub fn plan_companion_sync(existing: &[u64], chunks: Vec<String>) -> CompanionPlan {
+    let mut plan = CompanionPlan::default();
+    let mut chunks = chunks.into_iter();
+
+    for id in existing {
+        match chunks.next() {
+            Some(chunk) => plan.edit.push((*id, chunk)),
+            None => plan.delete.push(*id),
+        }
+    }
+
+    plan.send.extend(chunks);
+    plan
+}
@@ -0,0 +1,248 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::{
+    diagnostics::Diagnostics, headcount::CountMismatch, regions::RegionPing, wipe::WipeField,
+    DayzMonitorError, MonitorState, ServerInfo,
+};
+
+/// What one poll cycle concluded about the server.
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub state: MonitorState,
+
+    /// Data to display: the fresh sample when online, the last good one when degraded
+    pub info: Option<ServerInfo>,
+
+    /// Why the poll did not produce a trustworthy sample
+    pub error: Option<String>,
+
+    /// Unix timestamp the current maintenance window ends at
+    pub maintenance_until: Option<u64>,
+
+    /// When the map in `info` came into rotation, if known
+    pub map_since_unix: Option<u64>,
+
+    /// Whether the server has ever reported its in-game time, in this or an earlier run
+    pub server_time_seen: bool,
+
+    /// Rolling p95 query latency, while it is above the latency alert threshold
+    pub high_latency: Option<Duration>,
+
+    /// Player-seconds served so far today
+    pub player_secs_today: u64,
+
+    /// Counts of the poll before this one, when both were online
+    pub previous_counts: Option<Counts>,
+
+    /// A reported player count the player list keeps contradicting
+    pub count_mismatch: Option<CountMismatch>,
+
+    /// The game port, while probes keep finding it unreachable
+    pub join_port_unreachable: Option<u16>,
+
+    /// What this poll could not make sense of
+    pub diagnostics: Diagnostics,
+
+    /// The message of the day in effect
+    pub motd: Option<String>,
+
+    /// Estimated minutes until the server is full, while players are steadily arriving
+    pub full_in_minutes: Option<u32>,
+
+    /// The wipe countdown or the days since the last wipe, when known
+    pub wipe: Option<WipeField>,
+
+    /// Pings that bots in other regions shared, shown next to this bot's own
+    pub other_regions: Vec<RegionPing>,
+}
+
+impl Observation {
+    /// What the bot knows before its first poll completes: nothing.
+    pub fn unknown() -> Self {
+        Observation {
+            state: MonitorState::Unknown,
+            info: None,
+            error: None,
+            maintenance_until: None,
+            map_since_unix: None,
+            server_time_seen: false,
+            high_latency: None,
+            player_secs_today: 0,
+            previous_counts: None,
+            count_mismatch: None,
+            join_port_unreachable: None,
+            diagnostics: Diagnostics::default(),
+            motd: None,
+            full_in_minutes: None,
+            wipe: None,
+            other_regions: Vec::new(),
+        }
+    }
+}
+
+/// The numbers change annotations are computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    pub players: u32,
+    pub queue: u32,
+}
+
+impl Counts {
+    pub fn of(info: &ServerInfo) -> Self {
+        Self {
+            players: info.players,
+            queue: info.players_in_queue.unwrap_or(0),
+        }
+    }
+}
+
+/// The extra evidence a recovering server must show besides answering queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryCheck {
+    /// Answering queries is enough
+    #[default]
+    Answer,
+    /// The server must also answer a rules query, or its player count must change (somebody
+    /// actually got in)
+    Joinable,
+}
+
+/// When a server that went offline counts as online again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryCriterion {
+    /// Consecutive successful polls required
+    pub polls: u32,
+    pub check: RecoveryCheck,
+}
+
+impl Default for RecoveryCriterion {
+    fn default() -> Self {
+        Self {
+            polls: 1,
+            check: RecoveryCheck::Answer,
+        }
+    }
+}
+
+/// Evidence gathered since the server started answering again after an outage.
+#[derive(Debug, Default)]
+struct Recovery {
+    polls: u32,
+    first_players: Option<u32>,
+    players_changed: bool,
+    rules_answered: bool,
+}
+
+/// Folds successive poll results into the monitor's view of the server.
+#[derive(Debug, Default)]
+pub struct Monitor {
+    criterion: RecoveryCriterion,
+    last_good: Option<ServerInfo>,
+
+    /// Set while the server is offline or starting up
+    recovery: Option<Recovery>,
+}
+
+impl Monitor {
+    pub fn new(criterion: RecoveryCriterion) -> Self {
+        Self {
+            criterion,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the next poll should also try a rules query, because only that can complete
+    /// the recovery.
+    pub fn needs_rules_probe(&self) -> bool {
+        self.criterion.check == RecoveryCheck::Joinable
+            && self.recovery.as_ref().is_some_and(|r| !r.rules_answered)
+    }
+
+    /// Folds in one poll. `rules_answered` says whether a rules query this cycle succeeded.
+    pub fn observe(
+        &mut self,
+        result: Result<ServerInfo, DayzMonitorError>,
+        rules_answered: bool,
+    ) -> Observation {
+        match result {
+            Ok(info) => {
+                self.last_good = Some(info.clone());
+
+                let state = match &mut self.recovery {
+                    Some(recovery) => {
+                        recovery.polls = recovery.polls.saturating_add(1);
+                        recovery.rules_answered |= rules_answered;
+                        match recovery.first_players {
+                            Some(first) => recovery.players_changed |= first != info.players,
+                            None => recovery.first_players = Some(info.players),
+                        }
+
+                        if self.criterion.is_met(recovery) {
+                            self.recovery = None;
+                            MonitorState::Online
+                        } else {
+                            MonitorState::Starting
+                        }
+                    }
+                    None => MonitorState::Online,
+                };
+
+                let diagnostics = Diagnostics {
+                    ignored_tokens: info.ignored_tokens.clone(),
+                    rejected: None,
+                };
+
+                Observation {
+                    state,
+                    info: Some(info),
+                    diagnostics,
+                    ..Observation::unknown()
+                }
+            }
+            // Neither advances nor resets a recovery in progress.
+            Err(err) if err.is_soft() => Observation {
+                state: if self.recovery.is_some() {
+                    MonitorState::Starting
+                } else {
+                    MonitorState::Degraded
+                },
+                info: self.last_good.clone(),
+                error: Some(err.to_string()),
+                diagnostics: Diagnostics {
+                    rejected: err.rejection().map(str::to_owned),
+                    ..Diagnostics::default()
+                },
+                ..Observation::unknown()
+            },
+            Err(err) => {
+                self.recovery = Some(Recovery::default());
+                Observation {
+                    state: MonitorState::Offline,
+                    error: Some(err.to_string()),
+                    ..Observation::unknown()
+                }
+            }
+        }
+    }
+
+    /// The server is inside a scheduled maintenance window and is not queried.
+    pub fn maintenance(&self, until_unix: u64) -> Observation {
+        Observation {
+            state: MonitorState::Maintenance,
+            maintenance_until: Some(until_unix),
+            ..Observation::unknown()
+        }
+    }
+}
+
+impl RecoveryCriterion {
+    fn is_met(&self, recovery: &Recovery) -> bool {
+        recovery.polls >= self.polls
+            && match self.check {
+                RecoveryCheck::Answer => true,
+                RecoveryCheck::Joinable => recovery.rules_answered || recovery.players_changed,
+            }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::i18n::Text;
+
+/// Fits in one embed field.
+pub const MOTD_LIMIT: usize = 1024;
+
+/// More lines than this would push the server details off screen.
+pub const MOTD_MAX_LINES: usize = 15;
+
+/// The MOTD the status embed shows. One set with `/motd edit` takes precedence over `MOTD`, and
+/// an empty one hides the configured text. `MOTD` writes line breaks as `\n`.
+pub fn effective_motd(configured: Option<&str>, edited: Option<&str>) -> Option<String> {
+    let text = match edited {
+        Some(edited) => edited.to_string(),
+        None => configured?.replace("\\n", "\n"),
+    };
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Why a submitted MOTD was not accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotdRejection {
+    TooLong { chars: usize },
+    TooManyLines { lines: usize },
+}
+
+impl MotdRejection {
+    pub fn text(self) -> Text {
+        match self {
+            MotdRejection::TooLong { .. } => Text::MotdTooLong,
+            MotdRejection::TooManyLines { .. } => Text::MotdTooManyLines,
+        }
+    }
+
+    /// The offending count and its limit.
+    pub fn counts(self) -> (usize, usize) {
+        match self {
+            MotdRejection::TooLong { chars } => (chars, MOTD_LIMIT),
+            MotdRejection::TooManyLines { lines } => (lines, MOTD_MAX_LINES),
+        }
+    }
+}
+
+/// Cleans up a submitted MOTD and checks that it fits. Control characters are dropped, line
+/// endings normalized and trailing whitespace trimmed; an empty result is valid and hides the
+/// MOTD.
+pub fn sanitize_motd(input: &str) -> Result<String, MotdRejection> {
+    let text = input
+        .replace("\r\n", "\n")
+        .lines()
+        .map(|line| {
+            line.chars()
+                .filter(|c| !c.is_control() || *c == '\t')
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim().to_string();
+
+    let chars = text.chars().count();
+    if chars > MOTD_LIMIT {
+        return Err(MotdRejection::TooLong { chars });
+    }
+
+    let lines = text.lines().count();
+    if lines > MOTD_MAX_LINES {
+        return Err(MotdRejection::TooManyLines { lines });
+    }
+
+    Ok(text)
+}
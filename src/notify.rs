@@ -0,0 +1,271 @@
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+use reqwest::Client;
+#[cfg(feature = "telegram")]
+use serde_json::json;
+
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+use crate::alerts::Alert;
+#[cfg(feature = "ntfy")]
+use crate::alerts::Severity;
+use crate::{alerts::AlertKind, retry::RetryPolicy, DayzMonitorConfig, DayzMonitorError};
+
+/// Characters Telegram's MarkdownV2 requires escaping outside of formatting.
+const MARKDOWN_V2_SPECIAL: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+/// Where the Telegram Bot API is served.
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// How long retrying one alert may take at most.
+pub const NOTIFY_RETRY_BUDGET: Duration = Duration::from_secs(120);
+
+/// Sends alerts to a Telegram chat through a bot.
+#[cfg(feature = "telegram")]
+#[derive(Debug, Clone)]
+pub struct Telegram {
+    /// Bot API base URL, [`TELEGRAM_API_URL`] unless pointed elsewhere
+    pub api_url: String,
+    pub bot_token: String,
+
+    /// Numeric chat id, or `@channelname`
+    pub chat_id: String,
+}
+
+#[cfg(feature = "telegram")]
+impl Telegram {
+    async fn send(&self, client: &Client, alert: &Alert) -> Result<(), reqwest::Error> {
+        let url = format!("{}/bot{}/sendMessage", self.api_url, self.bot_token);
+
+        client
+            .post(url)
+            .json(&json!({
+                "chat_id": self.chat_id,
+                "text": to_markdown_v2(&alert.message),
+                "parse_mode": "MarkdownV2",
+            }))
+            .send()
+            .await?
+            .error_for_status()
+            .map(|_| ())
+    }
+}
+
+/// Publishes alerts to an ntfy topic.
+#[cfg(feature = "ntfy")]
+#[derive(Debug, Clone)]
+pub struct Ntfy {
+    /// Topic URL, e.g. `https://ntfy.sh/my-dayz-server`
+    pub url: String,
+
+    /// Optional access token for protected topics
+    pub token: Option<String>,
+
+    /// Notification title, the server name
+    pub title: String,
+}
+
+#[cfg(feature = "ntfy")]
+impl Ntfy {
+    async fn send(&self, client: &Client, alert: &Alert) -> Result<(), reqwest::Error> {
+        let mut request = client
+            .post(&self.url)
+            .header("Title", &self.title)
+            .header("Priority", ntfy_priority(alert.kind.severity()))
+            .header("Tags", alert.kind.name())
+            .body(to_plain_text(&alert.message));
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        request.send().await?.error_for_status().map(|_| ())
+    }
+}
+
+/// The backends built in; a build with neither has no alert destination besides Discord.
+#[derive(Debug, Clone)]
+pub enum BackendKind {
+    #[cfg(feature = "telegram")]
+    Telegram(Telegram),
+    #[cfg(feature = "ntfy")]
+    Ntfy(Ntfy),
+}
+
+/// An alert destination besides the Discord alert channel.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    pub kind: BackendKind,
+
+    /// Alert rules routed here; `None` routes all of them
+    pub rules: Option<Vec<AlertKind>>,
+    pub retry: RetryPolicy,
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            #[cfg(feature = "telegram")]
+            BackendKind::Telegram(_) => "telegram",
+            #[cfg(feature = "ntfy")]
+            BackendKind::Ntfy(_) => "ntfy",
+        }
+    }
+
+    pub fn routes(&self, kind: AlertKind) -> bool {
+        self.rules
+            .as_ref()
+            .is_none_or(|rules| rules.contains(&kind))
+    }
+
+    /// Delivers `alert`, retrying according to the backend's policy.
+    #[cfg(any(feature = "telegram", feature = "ntfy"))]
+    pub async fn send(&self, client: &Client, alert: &Alert) -> Result<(), DayzMonitorError> {
+        match self.kind {
+            #[cfg(feature = "telegram")]
+            BackendKind::Telegram(ref telegram) => {
+                self.deliver(|| telegram.send(client, alert)).await
+            }
+            #[cfg(feature = "ntfy")]
+            BackendKind::Ntfy(ref ntfy) => self.deliver(|| ntfy.send(client, alert)).await,
+        }
+    }
+
+    #[cfg(any(feature = "telegram", feature = "ntfy"))]
+    async fn deliver<F, Fut>(&self, send: F) -> Result<(), DayzMonitorError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), reqwest::Error>>,
+    {
+        self.retry.run_if(send, is_transient).await.map_err(|err| {
+            DayzMonitorError::NotificationFailed {
+                backend: self.name(),
+                reason: err.without_url().to_string(),
+            }
+        })
+    }
+}
+
+/// The backends `config` sets up. Half-configured backends are an error rather than silently
+/// disabled.
+pub fn backends(config: &DayzMonitorConfig) -> Result<Vec<Backend>, DayzMonitorError> {
+    #[cfg_attr(not(any(feature = "telegram", feature = "ntfy")), allow(unused_mut))]
+    let mut backends = Vec::new();
+
+    match (&config.telegram_bot_token, &config.telegram_chat_id) {
+        #[cfg(feature = "telegram")]
+        (Some(bot_token), Some(chat_id)) => backends.push(Backend {
+            kind: BackendKind::Telegram(Telegram {
+                api_url: TELEGRAM_API_URL.to_string(),
+                bot_token: bot_token.clone(),
+                chat_id: chat_id.clone(),
+            }),
+            rules: config.telegram_rules.clone(),
+            retry: RetryPolicy::retries(
+                config.telegram_retries,
+                Duration::from_millis(config.telegram_retry_delay_ms),
+            )
+            .with_budget(NOTIFY_RETRY_BUDGET),
+        }),
+        // Reported as not built by the feature registry
+        #[cfg(not(feature = "telegram"))]
+        (Some(_), Some(_)) => {}
+        (None, None) => {}
+        _ => {
+            return Err(DayzMonitorError::IncompleteBackend(
+                "TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID must be set together",
+            ))
+        }
+    }
+
+    if config.ntfy_url.is_none() && config.ntfy_token.is_some() {
+        return Err(DayzMonitorError::IncompleteBackend(
+            "NTFY_TOKEN needs NTFY_URL",
+        ));
+    }
+    #[cfg(feature = "ntfy")]
+    if let Some(url) = &config.ntfy_url {
+        backends.push(Backend {
+            kind: BackendKind::Ntfy(Ntfy {
+                url: url.clone(),
+                token: config.ntfy_token.clone(),
+                title: config.server_name.clone(),
+            }),
+            rules: config.ntfy_rules.clone(),
+            retry: RetryPolicy::retries(
+                config.ntfy_retries,
+                Duration::from_millis(config.ntfy_retry_delay_ms),
+            )
+            .with_budget(NOTIFY_RETRY_BUDGET),
+        });
+    }
+
+    Ok(backends)
+}
+
+/// Rejected requests stay rejected, except when rate limited; failed connections and server
+/// errors may go through on another try.
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.status()
+        .is_none_or(|status| status.is_server_error() || status.as_u16() == 429)
+}
+
+/// ntfy priorities run from 1 (min) to 5 (urgent).
+#[cfg(feature = "ntfy")]
+fn ntfy_priority(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Low => "2",
+        Severity::Normal => "3",
+        Severity::High => "4",
+    }
+}
+
+/// Strips the Discord markdown of a rendered alert, keeping the text it formats. Values were
+/// escaped for Discord when the template was rendered, so escapes are resolved here too.
+pub fn to_plain_text(message: &str) -> String {
+    convert(message, "", |c, out| out.push(c))
+}
+
+/// Re-expresses a rendered alert in Telegram's MarkdownV2: bold stays bold, other Discord
+/// formatting is dropped and everything else is escaped.
+pub fn to_markdown_v2(message: &str) -> String {
+    convert(message, "*", |c, out| {
+        if MARKDOWN_V2_SPECIAL.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    })
+}
+
+fn convert(message: &str, bold: &str, push_text: impl Fn(char, &mut String)) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    push_text(escaped, &mut out);
+                }
+            }
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                out.push_str(bold);
+            }
+            '_' | '~' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+            }
+            '*' | '`' => {}
+            // Inserted after `@` to defuse Discord mass mentions.
+            '\u{200B}' => {}
+            _ => push_text(c, &mut out),
+        }
+    }
+
+    out
+}
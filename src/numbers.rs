@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Language;
+
+/// Digit grouping and decimal conventions for numbers shown to users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberLocale {
+    /// `1,024.5`
+    #[default]
+    En,
+    /// `1.024,5`
+    De,
+    /// `1 024,5` with a narrow no-break space
+    Fr,
+    /// `1 024,5` with a no-break space
+    Ru,
+}
+
+impl NumberLocale {
+    pub const ALL: [NumberLocale; 4] = [
+        NumberLocale::En,
+        NumberLocale::De,
+        NumberLocale::Fr,
+        NumberLocale::Ru,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            NumberLocale::En => "en",
+            NumberLocale::De => "de",
+            NumberLocale::Fr => "fr",
+            NumberLocale::Ru => "ru",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<NumberLocale> {
+        let code = code.trim().to_ascii_lowercase();
+        NumberLocale::ALL.into_iter().find(|l| l.code() == code)
+    }
+
+    pub fn from_language(lang: Language) -> NumberLocale {
+        match lang {
+            Language::En => NumberLocale::En,
+            Language::De => NumberLocale::De,
+            Language::Fr => NumberLocale::Fr,
+            Language::Ru => NumberLocale::Ru,
+        }
+    }
+
+    fn group_separator(self) -> &'static str {
+        match self {
+            NumberLocale::En => ",",
+            NumberLocale::De => ".",
+            NumberLocale::Fr => "\u{202F}",
+            NumberLocale::Ru => "\u{00A0}",
+        }
+    }
+
+    fn decimal_separator(self) -> &'static str {
+        match self {
+            NumberLocale::En => ".",
+            NumberLocale::De | NumberLocale::Fr | NumberLocale::Ru => ",",
+        }
+    }
+}
+
+/// Formats an integer with the locale's thousands grouping, e.g. `12345` as `12.345` in `de`.
+pub fn format_number(n: u64, locale: NumberLocale) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 * 3);
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(locale.group_separator());
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Formats a non-negative number with `decimals` places, grouping the integer part.
+pub fn format_decimal(x: f64, decimals: usize, locale: NumberLocale) -> String {
+    let fixed = format!("{:.*}", decimals, x.max(0.0));
+
+    match fixed.split_once('.') {
+        Some((int, frac)) => format!(
+            "{}{}{}",
+            format_number(int.parse().unwrap_or(0), locale),
+            locale.decimal_separator(),
+            frac
+        ),
+        None => format_number(fixed.parse().unwrap_or(0), locale),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_are_grouped_per_locale() {
+        let cases = [
+            (0, ["0", "0", "0", "0"]),
+            (999, ["999", "999", "999", "999"]),
+            (1_000, ["1,000", "1.000", "1\u{202F}000", "1\u{00A0}000"]),
+            (
+                1_234_567,
+                [
+                    "1,234,567",
+                    "1.234.567",
+                    "1\u{202F}234\u{202F}567",
+                    "1\u{00A0}234\u{00A0}567",
+                ],
+            ),
+        ];
+
+        for (n, expected) in cases {
+            for (locale, expected) in NumberLocale::ALL.into_iter().zip(expected) {
+                assert_eq!(format_number(n, locale), expected, "{n} in {locale:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn largest_integer_is_grouped() {
+        assert_eq!(
+            format_number(u64::MAX, NumberLocale::En),
+            "18,446,744,073,709,551,615"
+        );
+    }
+
+    #[test]
+    fn decimals_use_the_locale_separator() {
+        assert_eq!(format_decimal(1_024.5, 1, NumberLocale::En), "1,024.5");
+        assert_eq!(format_decimal(1_024.5, 1, NumberLocale::De), "1.024,5");
+        assert_eq!(
+            format_decimal(1_024.5, 2, NumberLocale::Ru),
+            "1\u{00A0}024,50"
+        );
+        assert_eq!(format_decimal(0.96, 1, NumberLocale::Fr), "1,0");
+        assert_eq!(format_decimal(12.7, 0, NumberLocale::En), "13");
+        assert_eq!(format_decimal(-3.0, 1, NumberLocale::En), "0.0");
+    }
+
+    #[test]
+    fn locale_codes_round_trip() {
+        for locale in NumberLocale::ALL {
+            assert_eq!(NumberLocale::from_code(locale.code()), Some(locale));
+        }
+        assert_eq!(NumberLocale::from_code(" DE "), Some(NumberLocale::De));
+        assert_eq!(NumberLocale::from_code("es"), None);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{monitor::Observation, MonitorState};
+
+/// Discord's limit on embeds per message.
+pub const EMBEDS_PER_MESSAGE: usize = 10;
+
+/// Something the monitor concluded or did, as shown in the log channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpEvent {
+    /// The monitor state changed; `from` is [`MonitorState::Unknown`] for the first observation
+    /// after start
+    Transition {
+        from: MonitorState,
+        to: MonitorState,
+
+        /// Why the poll did not produce a trustworthy sample
+        cause: Option<String>,
+
+        /// `(players, max_players)` of the sample shown in the new state
+        players: Option<(u32, u32)>,
+        maintenance_until: Option<u64>,
+    },
+    /// A new status message was posted; `replaced` is the message it takes over from
+    StatusMessagePosted {
+        message_id: u64,
+        replaced: Option<u64>,
+    },
+}
+
+impl OpEvent {
+    pub fn transition(from: MonitorState, observation: &Observation) -> Self {
+        OpEvent::Transition {
+            from,
+            to: observation.state,
+            cause: observation.error.clone(),
+            players: observation
+                .info
+                .as_ref()
+                .map(|info| (info.players, info.max_players)),
+            maintenance_until: observation.maintenance_until,
+        }
+    }
+}
+
+/// The events of one poll cycle, posted together rather than one message each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpLogBatch {
+    pub at_unix: u64,
+    pub events: Vec<OpEvent>,
+}
+
+impl OpLogBatch {
+    pub fn new(at_unix: u64) -> Self {
+        Self {
+            at_unix,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, event: OpEvent) {
+        self.events.push(event);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The events grouped into as few messages as Discord allows, in order.
+    pub fn messages(&self) -> impl Iterator<Item = &[OpEvent]> {
+        self.events.chunks(EMBEDS_PER_MESSAGE)
+    }
+}
@@ -0,0 +1,358 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    cleanup::STATUS_MARKER,
+    i18n::Language,
+    monitor::Observation,
+    render::EmbedRepresentation,
+    title::TitleState,
+    topic::render_topic,
+    transport::{
+        verify_own_message, EditOutcome, Priority, SendRequest, StatusMessage, StatusTransport,
+        StatusUpdate,
+    },
+    DayzMonitorConfig, DayzMonitorError,
+};
+
+/// How an output draws the status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A one-line embed
+    Compact,
+    /// The full status embed, as in the status channel
+    Detailed,
+    /// A one-line plain message
+    Text,
+}
+
+impl Layout {
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Compact => "compact",
+            Layout::Detailed => "detailed",
+            Layout::Text => "text",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Layout> {
+        [Layout::Compact, Layout::Detailed, Layout::Text]
+            .into_iter()
+            .find(|l| l.name().eq_ignore_ascii_case(s))
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Embed settings an output can turn on with `+name` or off with `-name`, whatever the global
+/// config says.
+pub const OUTPUT_FEATURES: &[&str] = &[
+    "player_hours_field",
+    "change_annotations",
+    "player_count_annotation",
+];
+
+/// An extra status message, such as a compact one in a public channel next to the detailed one
+/// in a staff channel. Every output shows the same poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub channel_id: u64,
+    /// Message to edit instead of posting a new one
+    pub message_id: Option<u64>,
+    pub layout: Layout,
+    /// Falls back to `BOT_LANGUAGE`
+    pub language: Option<Language>,
+    /// `OUTPUT_FEATURES` this output overrides, and whether it turns them on
+    pub features: Vec<(&'static str, bool)>,
+}
+
+impl Output {
+    /// Parses `<channel_id>[/<message_id>] <layout> [language] [+feature|-feature ...]`, e.g.
+    /// `123456789/987654321 detailed de +player_hours_field`.
+    pub fn parse(spec: &str) -> Result<Output, DayzMonitorError> {
+        let invalid = |reason: String| DayzMonitorError::InvalidOutput {
+            spec: spec.to_string(),
+            reason,
+        };
+
+        let mut tokens = spec.split_whitespace();
+
+        let target = tokens
+            .next()
+            .ok_or_else(|| invalid("missing channel id".to_string()))?;
+        let (channel, message) = match target.split_once('/') {
+            Some((channel, message)) => (channel, Some(message)),
+            None => (target, None),
+        };
+        let id = |s: &str| {
+            s.parse::<u64>()
+                .ok()
+                .filter(|id| *id > 0)
+                .ok_or_else(|| invalid(format!("'{s}' is not a Discord id")))
+        };
+        let channel_id = id(channel)?;
+        let message_id = message.map(id).transpose()?;
+
+        let layout = tokens
+            .next()
+            .ok_or_else(|| invalid("missing layout".to_string()))?;
+        let layout = Layout::parse(layout)
+            .ok_or_else(|| invalid(format!("'{layout}' is not compact, detailed or text")))?;
+
+        let mut language = None;
+        let mut features = Vec::new();
+
+        for token in tokens {
+            let (on, name) = match (token.strip_prefix('+'), token.strip_prefix('-')) {
+                (Some(name), _) => (true, name),
+                (_, Some(name)) => (false, name),
+                _ if language.is_none() && features.is_empty() => {
+                    language = Some(
+                        Language::from_locale(token)
+                            .ok_or_else(|| invalid(format!("unsupported language '{token}'")))?,
+                    );
+                    continue;
+                }
+                _ => return Err(invalid(format!("unexpected '{token}'"))),
+            };
+
+            let name = OUTPUT_FEATURES
+                .iter()
+                .find(|f| f.eq_ignore_ascii_case(name))
+                .ok_or_else(|| invalid(format!("unknown feature '{name}'")))?;
+            features.push((*name, on));
+        }
+
+        Ok(Output {
+            channel_id,
+            message_id,
+            layout,
+            language,
+            features,
+        })
+    }
+
+    /// Identifies the output in the state file. Outputs are told apart by where they post and
+    /// how they look, so reordering `OUTPUTS` keeps their messages.
+    pub fn key(&self) -> String {
+        match self.language {
+            Some(lang) => format!("{}:{}:{}", self.channel_id, self.layout, lang.code()),
+            None => format!("{}:{}", self.channel_id, self.layout),
+        }
+    }
+
+    /// The config this output renders with: the global one, with its language and features
+    /// applied.
+    pub fn config(&self, base: &DayzMonitorConfig) -> DayzMonitorConfig {
+        let mut config = base.clone();
+
+        if let Some(lang) = self.language {
+            config.bot_language = lang;
+        }
+
+        for &(name, on) in &self.features {
+            match name {
+                "player_hours_field" => config.player_hours_field = on,
+                "change_annotations" => config.change_annotations = on,
+                "player_count_annotation" => config.player_count_annotation = on,
+                _ => {}
+            }
+        }
+
+        config
+    }
+}
+
+/// What an output shows for one observation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rendered {
+    Embed(EmbedRepresentation),
+    Text(String),
+}
+
+/// The status as a one-line embed, e.g. `🟢 44/60 • queue 0 • updated 14:32 UTC`. Uses the
+/// topic line, so `TOPIC_TEMPLATE` applies.
+pub fn compact_embed(
+    config: &DayzMonitorConfig,
+    observation: &Observation,
+    now_unix: u64,
+) -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .description(render_topic(config, observation, now_unix))
+        .colour(TitleState::of(observation).colour())
+}
+
+/// Renders an observation in `layout`. The detailed embed belongs to the frontend, so
+/// `detailed` draws it. Embeds carry the [`STATUS_MARKER`] footer like the status message.
+pub fn render_output(
+    config: &DayzMonitorConfig,
+    layout: Layout,
+    observation: &Observation,
+    now_unix: u64,
+    detailed: impl FnOnce(&DayzMonitorConfig) -> EmbedRepresentation,
+) -> Rendered {
+    match layout {
+        Layout::Compact => {
+            Rendered::Embed(compact_embed(config, observation, now_unix).footer(STATUS_MARKER))
+        }
+        Layout::Detailed => Rendered::Embed(detailed(config).footer(STATUS_MARKER)),
+        Layout::Text => Rendered::Text(render_topic(config, observation, now_unix)),
+    }
+}
+
+/// How the transport's message types are built from a render.
+pub struct Encoders<T: StatusTransport> {
+    pub post: fn(&Rendered) -> T::Message,
+    pub edit: fn(&Rendered) -> T::Payload,
+}
+
+struct Slot {
+    output: Output,
+    config: DayzMonitorConfig,
+    message_id: Option<u64>,
+    last_rendered: Option<Rendered>,
+}
+
+/// The extra outputs and the message each one keeps up to date.
+///
+/// Posting is per output, while edits go through the shared [`StatusMessage`] under the
+/// cycle's render sequence number, like the main status message.
+pub struct Outputs {
+    slots: Vec<Slot>,
+}
+
+impl Outputs {
+    /// `saved` holds the message ids from the state file, by [`Output::key`]; an id given in
+    /// the output itself takes precedence.
+    pub fn new(
+        base: &DayzMonitorConfig,
+        outputs: Vec<Output>,
+        saved: &BTreeMap<String, u64>,
+    ) -> Self {
+        let slots = outputs
+            .into_iter()
+            .map(|output| Slot {
+                config: output.config(base),
+                message_id: output.message_id.or(saved.get(&output.key()).copied()),
+                last_rendered: None,
+                output,
+            })
+            .collect();
+
+        Self { slots }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// The message each output currently edits, for the state file.
+    pub fn message_ids(&self) -> BTreeMap<String, u64> {
+        self.slots
+            .iter()
+            .filter_map(|s| Some((s.output.key(), s.message_id?)))
+            .collect()
+    }
+
+    /// Drops message ids given in `OUTPUTS` that turn out to belong to someone else, so a new
+    /// message is posted in their place. Ids that can't be checked right now are kept; saved ids
+    /// were already checked by [`crate::reconcile`].
+    pub async fn adopt<T>(&mut self, transport: &T, own_user_id: u64)
+    where
+        T: StatusTransport,
+        T::Error: fmt::Display,
+    {
+        for slot in &mut self.slots {
+            let Some(id) = slot.output.message_id else {
+                continue;
+            };
+
+            match verify_own_message(transport, slot.output.channel_id, id, own_user_id).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    tracing::error!(
+                        "Not using message {id} for output {}: {err}",
+                        slot.output.key()
+                    );
+                    slot.message_id = None;
+                }
+                Err(err) => {
+                    tracing::warn!("Could not check who authored message {id}: {err}");
+                }
+            }
+        }
+    }
+
+    /// Brings every output up to date with one observation: posts a message for outputs that
+    /// have none yet, and edits the others if their render changed.
+    ///
+    /// `render` draws one output under its own config, e.g. through [`render_output`]. Returns
+    /// the ids of messages posted.
+    pub async fn publish<T>(
+        &mut self,
+        status: &StatusMessage<T>,
+        seq: u64,
+        priority: Priority,
+        encoders: &Encoders<T>,
+        render: impl Fn(&Output, &DayzMonitorConfig) -> Rendered,
+    ) -> Vec<u64>
+    where
+        T: StatusTransport,
+        T::Error: fmt::Display,
+    {
+        let mut posted = Vec::new();
+
+        for slot in &mut self.slots {
+            let rendered = render(&slot.output, &slot.config);
+            let channel_id = slot.output.channel_id;
+
+            let Some(id) = slot.message_id else {
+                let request = SendRequest {
+                    channel_id,
+                    message: (encoders.post)(&rendered),
+                    priority,
+                };
+
+                match status.transport().send(request).await {
+                    Ok(id) => {
+                        slot.message_id = Some(id);
+                        slot.last_rendered = Some(rendered);
+                        posted.push(id);
+                    }
+                    Err(err) => tracing::warn!(
+                        "Failed to post status message for output {}: {err}",
+                        slot.output.key()
+                    ),
+                }
+                continue;
+            };
+
+            if slot.last_rendered.as_ref() == Some(&rendered) {
+                tracing::debug!("Output {} unchanged", slot.output.key());
+                continue;
+            }
+
+            let update = StatusUpdate {
+                seq,
+                payload: (encoders.edit)(&rendered),
+            };
+
+            match status.apply(channel_id, id, update).await {
+                Ok(EditOutcome::Applied) => slot.last_rendered = Some(rendered),
+                Ok(EditOutcome::Stale) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to edit message {id} for output {}: {err}",
+                        slot.output.key()
+                    );
+                    slot.last_rendered = None;
+                }
+            }
+        }
+
+        posted
+    }
+}
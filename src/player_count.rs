@@ -0,0 +1,58 @@
+use serde::Deserialize;
+
+use crate::numbers::{format_number, NumberLocale};
+
+/// Players per range in [`PlayerCountMode::Approximate`].
+pub const APPROXIMATE_STEP: u32 = 10;
+
+/// Shown for the player count in templates while it is hidden, e.g. `?/60`.
+pub const HIDDEN_COUNT: &str = "?";
+
+/// How the player count is shown. Stats and history always record the real count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerCountMode {
+    #[default]
+    Normal,
+    /// For servers that deliberately hide or zero their count: no count is shown, and alerts
+    /// and estimates built on it are off
+    Hidden,
+    /// Only a range such as `20–30`, for communities that prefer not to show exact numbers
+    Approximate,
+}
+
+impl PlayerCountMode {
+    /// Whether the reported count means anything, so features built on it can run.
+    pub fn counts_known(self) -> bool {
+        self != PlayerCountMode::Hidden
+    }
+
+    /// Whether the exact count may be shown, e.g. in change or mismatch annotations.
+    pub fn shows_exact(self) -> bool {
+        self == PlayerCountMode::Normal
+    }
+
+    /// The player count as shown: `47`, `40–50`, or `None` while hidden.
+    pub fn display(self, players: u32, max_players: u32, locale: NumberLocale) -> Option<String> {
+        let num = |n: u32| format_number(n.into(), locale);
+
+        match self {
+            PlayerCountMode::Normal => Some(num(players)),
+            PlayerCountMode::Hidden => None,
+            PlayerCountMode::Approximate => {
+                let (low, high) = approximate_range(players, max_players);
+                Some(format!("{}–{}", num(low), num(high)))
+            }
+        }
+    }
+}
+
+/// The range of [`APPROXIMATE_STEP`] players `players` falls in, e.g. `(20, 30)` for 23. The
+/// top range ends at `max_players`, so a full server doesn't show `60–70` of 60.
+pub fn approximate_range(players: u32, max_players: u32) -> (u32, u32) {
+    let players = players.min(max_players.saturating_sub(1));
+    let low = players / APPROXIMATE_STEP * APPROXIMATE_STEP;
+    let high = (low + APPROXIMATE_STEP).min(max_players.max(low + 1));
+
+    (low, high)
+}
@@ -0,0 +1,99 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    calendar::{local_date, next_midnight},
+    clock,
+    i18n::{Language, Text},
+    numbers::{format_decimal, NumberLocale},
+};
+
+/// Days kept in the state file: today and the full week before it.
+pub const DAYS_KEPT: usize = 8;
+
+/// Player-seconds served per local day, integrated from the sampled player count.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerHours {
+    /// Player-seconds per day, keyed by local date (`YYYY-MM-DD`)
+    pub days: BTreeMap<String, u64>,
+
+    /// The previous sample as `(unix, players)`; `None` after an outage
+    pub last_sample: Option<(u64, u32)>,
+}
+
+impl PlayerHours {
+    /// Folds in one poll; `players` is `None` while the server is down.
+    ///
+    /// Each sample's player count is held until the next sample, so the interval since the
+    /// previous sample is credited with the previous count, split at local midnights. Intervals
+    /// longer than `max_gap_secs` mean the bot itself was not polling and are not credited.
+    pub fn record(&mut self, now: u64, players: Option<u32>, max_gap_secs: u64, tz: Tz) {
+        if let Some((since, previous)) = self.last_sample {
+            if now > since && now - since <= max_gap_secs {
+                self.credit(since, now, previous, tz);
+            }
+        }
+
+        self.last_sample = players.map(|p| (now, p));
+    }
+
+    /// Re-anchors the previous sample after the system clock was stepped by `step` seconds, so
+    /// the next interval credits the time that really passed.
+    pub fn shift_clock(&mut self, step: i64) {
+        if let Some((since, _)) = &mut self.last_sample {
+            *since = clock::shift(*since, step);
+        }
+    }
+
+    /// Player-seconds served so far on the local day containing `now`.
+    pub fn today(&self, now: u64, tz: Tz) -> u64 {
+        self.days
+            .get(&local_date(now, tz).to_string())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn credit(&mut self, mut from: u64, to: u64, players: u32, tz: Tz) {
+        while from < to {
+            let date = local_date(from, tz);
+            let end = next_midnight(date, tz)
+                .map_or(to, |t| t.timestamp() as u64)
+                .clamp(from + 1, to);
+
+            let total = self.days.entry(date.to_string()).or_default();
+            *total = total.saturating_add(u64::from(players) * (end - from));
+            from = end;
+        }
+
+        while self.days.len() > DAYS_KEPT {
+            self.days.pop_first();
+        }
+    }
+}
+
+/// Player-seconds as hours, the unit shown to users.
+pub fn hours(player_secs: u64) -> f64 {
+    player_secs as f64 / 3600.0
+}
+
+/// Per-day section of the `stats` subcommand.
+pub fn summary(player_hours: &PlayerHours, locale: NumberLocale, lang: Language) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}:", lang.tr(Text::PlayerHours));
+
+    if player_hours.days.is_empty() {
+        let _ = writeln!(out, "  {}", lang.tr(Text::NoneYet));
+    }
+
+    for (date, secs) in &player_hours.days {
+        let _ = writeln!(
+            out,
+            "  {date:<16}{}",
+            format_decimal(hours(*secs), 1, locale)
+        );
+    }
+
+    out
+}
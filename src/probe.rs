@@ -0,0 +1,111 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// How a measured latency compares to the configured thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LatencyGrade {
+    Good,
+    Warn,
+    Bad,
+    /// The hop failed or timed out
+    Unavailable,
+}
+
+impl LatencyGrade {
+    pub fn emoji(self) -> &'static str {
+        match self {
+            LatencyGrade::Good => "🟢",
+            LatencyGrade::Warn => "🟡",
+            LatencyGrade::Bad => "🟠",
+            LatencyGrade::Unavailable => "🔴",
+        }
+    }
+
+    pub fn colour(self) -> u32 {
+        match self {
+            LatencyGrade::Good => 0x57F287,
+            LatencyGrade::Warn => 0xFEE75C,
+            LatencyGrade::Bad => 0xE67E22,
+            LatencyGrade::Unavailable => 0xED4245,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyThresholds {
+    pub warn: Duration,
+    pub bad: Duration,
+}
+
+impl LatencyThresholds {
+    pub fn grade(&self, latency: Option<Duration>) -> LatencyGrade {
+        match latency {
+            None => LatencyGrade::Unavailable,
+            Some(l) if l >= self.bad => LatencyGrade::Bad,
+            Some(l) if l >= self.warn => LatencyGrade::Warn,
+            Some(_) => LatencyGrade::Good,
+        }
+    }
+}
+
+/// Times `fut`, returning `None` if it fails or does not finish within `timeout`.
+pub async fn measure<F, T, E>(fut: F, timeout: Duration) -> Option<Duration>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(Ok(_)) => Some(start.elapsed()),
+        _ => None,
+    }
+}
+
+/// Round-trip time of a fresh A2S info query.
+pub async fn probe_a2s(addr: SocketAddr, timeout: Duration) -> Option<Duration> {
+    measure(crate::query::info(addr, timeout), timeout).await
+}
+
+/// The last `capacity` latency samples, for percentiles over a sliding window.
+#[derive(Debug, Clone)]
+pub struct RollingPercentile {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl RollingPercentile {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, sample: Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.samples.len() == self.capacity
+    }
+
+    /// Nearest-rank percentile (`p` in 0..=100) of the current window, `None` when empty.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+}
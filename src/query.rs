@@ -0,0 +1,178 @@
+use a2s::{info::Info, players::Player};
+use std::{io::Cursor, net::SocketAddr, time::Duration};
+use tokio::net::UdpSocket;
+
+use crate::DayzMonitorError;
+
+/// Matches the timeout the `a2s` client used before queries moved here.
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+
+const INFO_REQUEST: &[u8] = b"\xFF\xFF\xFF\xFFTSource Engine Query\0";
+const PLAYER_REQUEST: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, 0x55];
+const RULES_REQUEST: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, 0x56];
+
+const CHALLENGE_RESPONSE: u8 = 0x41;
+const RULES_RESPONSE: u8 = 0x45;
+const SINGLE_PACKET: i32 = -1;
+const MULTI_PACKET: i32 = -2;
+const MAX_FRAGMENTS: usize = 32;
+
+// The `a2s` client always binds an IPv4 socket, so requests are sent from here over a socket
+// of the target's family, reusing `a2s` only to parse the responses.
+
+pub async fn info(addr: SocketAddr, timeout: Duration) -> Result<Info, DayzMonitorError> {
+    let data = request(addr, INFO_REQUEST, false, timeout).await?;
+    Ok(Info::from_cursor(Cursor::new(data))?)
+}
+
+pub async fn players(addr: SocketAddr, timeout: Duration) -> Result<Vec<Player>, DayzMonitorError> {
+    let data = request(addr, PLAYER_REQUEST, true, timeout).await?;
+    Ok(Player::from_cursor(Cursor::new(data), 0)?)
+}
+
+/// Rules as raw `(key, value)` byte pairs; DayZ stores binary data in them.
+pub async fn raw_rules(
+    addr: SocketAddr,
+    timeout: Duration,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, DayzMonitorError> {
+    let data = request(addr, RULES_REQUEST, true, timeout).await?;
+    parse_rules(&data).ok_or_else(invalid)
+}
+
+fn invalid() -> DayzMonitorError {
+    DayzMonitorError::A2SError(a2s::errors::Error::InvalidResponse)
+}
+
+/// Sends `base`, answering a challenge if the server issues one. Requests that take a challenge
+/// (`with_placeholder`) are first sent with the `-1` placeholder in its place.
+async fn request(
+    addr: SocketAddr,
+    base: &[u8],
+    with_placeholder: bool,
+    timeout: Duration,
+) -> Result<Vec<u8>, DayzMonitorError> {
+    let bind = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(bind).await?;
+    socket.connect(addr).await?;
+
+    let mut first = base.to_vec();
+    if with_placeholder {
+        first.extend([0xFF; 4]);
+    }
+
+    let response = exchange(&socket, &first, timeout).await?;
+
+    match response.as_slice() {
+        [CHALLENGE_RESPONSE, challenge @ ..] if challenge.len() >= 4 => {
+            let mut second = base.to_vec();
+            second.extend(&challenge[..4]);
+            exchange(&socket, &second, timeout).await
+        }
+        _ => Ok(response),
+    }
+}
+
+async fn exchange(
+    socket: &UdpSocket,
+    request: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, DayzMonitorError> {
+    socket.send(request).await?;
+
+    let mut fragments: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut buf = vec![0u8; 65_535];
+
+    loop {
+        let read = tokio::time::timeout(timeout, socket.recv(&mut buf))
+            .await
+            .map_err(|_| DayzMonitorError::A2SError(a2s::errors::Error::ErrTimeout))??;
+        let packet = &buf[..read];
+
+        let header = packet
+            .get(..4)
+            .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(invalid)?;
+
+        match header {
+            SINGLE_PACKET => return Ok(packet[4..].to_vec()),
+            // ID (4), total (1), number (1), max size (2), payload
+            MULTI_PACKET if packet.len() > 12 => {
+                let total = packet[8] as usize;
+                if total == 0 || total > MAX_FRAGMENTS {
+                    return Err(invalid());
+                }
+
+                fragments.push((packet[9], packet[12..].to_vec()));
+
+                if fragments.len() == total {
+                    fragments.sort_by_key(|(number, _)| *number);
+                    let joined: Vec<u8> = fragments.into_iter().flat_map(|(_, p)| p).collect();
+
+                    // The reassembled payload starts with its own single-packet header.
+                    return match joined.strip_prefix(&[0xFF, 0xFF, 0xFF, 0xFF]) {
+                        Some(rest) => Ok(rest.to_vec()),
+                        None => Ok(joined),
+                    };
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+}
+
+/// Reads an A2S_RULES response without its packet header; `None` if it is cut short or is not
+/// a rules response.
+pub fn parse_rules(payload: &[u8]) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut reader = Reader(payload);
+
+    if reader.u8()? != RULES_RESPONSE {
+        return None;
+    }
+
+    let count = reader.u16()?;
+    let mut rules = Vec::new();
+
+    for _ in 0..count {
+        rules.push((reader.cstring()?.to_vec(), reader.cstring()?.to_vec()));
+    }
+
+    Some(rules)
+}
+
+/// Bounds-checked little-endian reader; every read returns `None` past the end.
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn cstring(&mut self) -> Option<&'a [u8]> {
+        let end = self.0.iter().position(|b| *b == 0)?;
+        let s = self.take(end)?;
+        self.take(1)?;
+        Some(s)
+    }
+}
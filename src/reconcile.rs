@@ -0,0 +1,350 @@
+use std::{collections::BTreeSet, fmt};
+
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+
+use crate::{
+    output::Output, state::PersistentState, transport::StatusTransport, DayzMonitorConfig,
+    DayzMonitorError,
+};
+
+/// How many message lookups run at once unless `RECONCILE_CONCURRENCY` says otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// What startup reconciliation does with a persisted message it can't confirm as the bot's own,
+/// from `RECONCILE_POLICY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcilePolicy {
+    /// Missing and foreign messages are posted anew; one that couldn't be looked up is kept
+    #[default]
+    Adopt,
+    /// Every message not confirmed as the bot's own is posted anew
+    Recreate,
+    /// Any mismatch stops the bot, leaving the state file as it was
+    Strict,
+}
+
+impl ReconcilePolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            ReconcilePolicy::Adopt => "adopt",
+            ReconcilePolicy::Recreate => "recreate",
+            ReconcilePolicy::Strict => "strict",
+        }
+    }
+}
+
+/// What a persisted message id is used for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Use {
+    /// The message of the output with this key
+    Output(String),
+    /// A page of the mod list, counting from 1
+    ModListPage(usize),
+    /// The message of an output with this key that is no longer configured
+    Orphan(String),
+}
+
+impl fmt::Display for Use {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Use::Output(key) => write!(f, "output {key}"),
+            Use::ModListPage(page) => write!(f, "mod list page {page}"),
+            Use::Orphan(key) => write!(f, "removed output {key}"),
+        }
+    }
+}
+
+/// A message id from the state file, with the channel it is expected in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedMessage {
+    pub used_as: Use,
+    pub channel_id: u64,
+    pub message_id: u64,
+}
+
+/// The message ids `state` holds. Outputs whose key is not in `configured` are orphans, found
+/// in the channel their key starts with; mod list pages are in `mod_list_channel`.
+pub fn persisted(
+    state: &PersistentState,
+    configured: &BTreeSet<String>,
+    mod_list_channel: u64,
+) -> Vec<PersistedMessage> {
+    let outputs = state.output_message_ids.iter().filter_map(|(key, &id)| {
+        let channel_id = key.split(':').next()?.parse().ok()?;
+        let used_as = if configured.contains(key) {
+            Use::Output(key.clone())
+        } else {
+            Use::Orphan(key.clone())
+        };
+
+        Some(PersistedMessage {
+            used_as,
+            channel_id,
+            message_id: id,
+        })
+    });
+    let pages = state
+        .mod_list_message_ids
+        .iter()
+        .enumerate()
+        .map(|(i, &id)| PersistedMessage {
+            used_as: Use::ModListPage(i + 1),
+            channel_id: mod_list_channel,
+            message_id: id,
+        });
+
+    outputs.chain(pages).collect()
+}
+
+/// What looking up a persisted message found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// It is in its channel and was posted by the bot
+    Own,
+    /// It is not in its channel: deleted, or the channel changed since
+    Missing,
+    /// It was posted by someone else, named here
+    Foreign(String),
+    /// The lookup failed for another reason, given here
+    Unknown(String),
+}
+
+impl Check {
+    fn describe(&self, message: &PersistedMessage) -> String {
+        let PersistedMessage {
+            used_as,
+            channel_id,
+            message_id,
+        } = message;
+
+        match self {
+            Check::Own => format!("{used_as}: message {message_id} is fine"),
+            Check::Missing => format!("{used_as}: message {message_id} is not in {channel_id}"),
+            Check::Foreign(author) => {
+                format!("{used_as}: message {message_id} was posted by {author}")
+            }
+            Check::Unknown(err) => {
+                format!("{used_as}: message {message_id} could not be looked up ({err})")
+            }
+        }
+    }
+}
+
+/// Looks up every message in `messages`, at most `concurrency` at a time, returning what was
+/// found in the same order. `is_not_found` tells a message that isn't there from a failed
+/// lookup.
+pub async fn check<T>(
+    transport: &T,
+    messages: &[PersistedMessage],
+    own_user_id: u64,
+    concurrency: usize,
+    is_not_found: impl Fn(&T::Error) -> bool,
+) -> Vec<Check>
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let is_not_found = &is_not_found;
+    // Collected first: a lazily mapped stream of borrowing futures is not `Send` enough for
+    // the spawned status loop.
+    let lookups: Vec<_> = messages
+        .iter()
+        .map(|message| async move {
+            match transport
+                .author(message.channel_id, message.message_id)
+                .await
+            {
+                Ok(author) if author.id == own_user_id => Check::Own,
+                Ok(author) => Check::Foreign(author.name),
+                Err(err) if is_not_found(&err) => Check::Missing,
+                Err(err) => Check::Unknown(err.to_string()),
+            }
+        })
+        .collect();
+
+    stream::iter(lookups)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// What to do about one persisted message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    Keep,
+    /// Forget the id, so the first cycle posts a new message in its place
+    Recreate,
+    /// Delete the message of a removed output, and forget it
+    DeleteOrphan,
+    /// Forget the id of a removed output whose message can't be deleted
+    ForgetOrphan,
+}
+
+/// The repair for a message `used_as` after `check`, under `policy`. Strict mode never gets
+/// here with a mismatch.
+pub fn repair(policy: ReconcilePolicy, used_as: &Use, check: &Check) -> Repair {
+    match (used_as, check) {
+        (Use::Orphan(_), Check::Own) => Repair::DeleteOrphan,
+        (Use::Orphan(_), _) => Repair::ForgetOrphan,
+        (_, Check::Own) => Repair::Keep,
+        (_, Check::Unknown(_)) if policy == ReconcilePolicy::Adopt => Repair::Keep,
+        _ => Repair::Recreate,
+    }
+}
+
+/// How reconciliation went, for the log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub ok: usize,
+    /// Kept without being confirmed, since the lookup failed
+    pub unverified: usize,
+    pub recreated: usize,
+    pub orphans_deleted: usize,
+    /// Orphans forgotten without deleting them
+    pub orphans_forgotten: usize,
+}
+
+impl Summary {
+    /// Whether any saved id was dropped, so the state needs saving.
+    pub fn changed(&self) -> bool {
+        self.recreated + self.orphans_deleted + self.orphans_forgotten > 0
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let orphans = |n: usize| if n == 1 { "orphan" } else { "orphans" };
+
+        write!(f, "{} ok", self.ok)?;
+        if self.unverified > 0 {
+            write!(f, ", {} unverified", self.unverified)?;
+        }
+        if self.recreated > 0 {
+            write!(f, ", {} recreated", self.recreated)?;
+        }
+        if self.orphans_deleted > 0 {
+            let n = self.orphans_deleted;
+            write!(f, ", {n} {} deleted", orphans(n))?;
+        }
+        if self.orphans_forgotten > 0 {
+            let n = self.orphans_forgotten;
+            write!(f, ", {n} {} forgotten", orphans(n))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the message ids persisted in `state` against Discord and repairs them under
+/// `RECONCILE_POLICY`, before the first cycle uses them.
+///
+/// Missing and foreign messages are forgotten, so the first cycle posts new ones; a dropped mod
+/// list page makes the whole list sync again. Messages of outputs no longer configured are
+/// deleted if they are the bot's own. Under [`ReconcilePolicy::Strict`], any message in use that
+/// isn't confirmed fails instead, with `state` untouched.
+pub async fn reconcile<T>(
+    transport: &T,
+    config: &DayzMonitorConfig,
+    state: &mut PersistentState,
+    own_user_id: u64,
+    is_not_found: impl Fn(&T::Error) -> bool,
+) -> Result<Summary, DayzMonitorError>
+where
+    T: StatusTransport,
+    T::Error: fmt::Display,
+{
+    let policy = config.reconcile_policy;
+    // Validated at startup.
+    let configured: BTreeSet<String> = config
+        .outputs()
+        .unwrap_or_default()
+        .iter()
+        .map(Output::key)
+        .collect();
+    let messages = persisted(state, &configured, config.text_channel_id);
+    let checks = check(
+        transport,
+        &messages,
+        own_user_id,
+        config.reconcile_concurrency,
+        is_not_found,
+    )
+    .await;
+
+    if policy == ReconcilePolicy::Strict {
+        let mismatches: Vec<String> = messages
+            .iter()
+            .zip(&checks)
+            .filter(|(m, c)| !matches!(m.used_as, Use::Orphan(_)) && **c != Check::Own)
+            .map(|(m, c)| c.describe(m))
+            .collect();
+        if !mismatches.is_empty() {
+            return Err(DayzMonitorError::MessageMismatch(mismatches.join("; ")));
+        }
+    }
+
+    let mut summary = Summary::default();
+    let mut pages = Vec::new();
+    let mut pages_dropped = false;
+    state.output_message_ids.clear();
+
+    for (message, check) in messages.iter().zip(&checks) {
+        let repair = repair(policy, &message.used_as, check);
+
+        match repair {
+            Repair::Keep if *check == Check::Own => summary.ok += 1,
+            Repair::Keep => {
+                tracing::warn!("Keeping {}", check.describe(message));
+                summary.unverified += 1;
+            }
+            Repair::Recreate => {
+                tracing::warn!("{}, posting a new one", check.describe(message));
+                summary.recreated += 1;
+            }
+            Repair::DeleteOrphan => {
+                match transport
+                    .delete(message.channel_id, message.message_id)
+                    .await
+                {
+                    Ok(()) => {
+                        tracing::info!(
+                            "Deleted message {} of {}",
+                            message.message_id,
+                            message.used_as
+                        );
+                        summary.orphans_deleted += 1;
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to delete message {} of {}: {err}",
+                            message.message_id,
+                            message.used_as
+                        );
+                        summary.orphans_forgotten += 1;
+                    }
+                }
+            }
+            Repair::ForgetOrphan => summary.orphans_forgotten += 1,
+        }
+
+        let kept = repair == Repair::Keep;
+        match &message.used_as {
+            Use::Output(key) if kept => {
+                state
+                    .output_message_ids
+                    .insert(key.clone(), message.message_id);
+            }
+            Use::ModListPage(_) if kept => pages.push(message.message_id),
+            Use::ModListPage(_) => pages_dropped = true,
+            _ => {}
+        }
+    }
+
+    state.mod_list_message_ids = pages;
+    if pages_dropped {
+        state.mod_list_hash = None;
+    }
+
+    Ok(summary)
+}
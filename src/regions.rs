@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::{Language, Text},
+    numbers::{format_number, NumberLocale},
+    template::sanitize,
+    write_atomic, DayzMonitorError,
+};
+
+/// Pings older than this many update intervals are from an instance that stopped publishing.
+pub const STALE_INTERVALS: u64 = 3;
+
+/// One bot instance's latency to the server, labelled with the region it runs in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionPing {
+    pub region: String,
+    pub latency_ms: u32,
+    pub measured_unix: u64,
+}
+
+/// Where instances in different regions share their pings.
+pub trait PingStore {
+    /// Replaces this instance's ping.
+    fn publish(&self, ping: &RegionPing) -> Result<(), DayzMonitorError>;
+
+    /// Every instance's last ping, in no particular order.
+    fn read_all(&self) -> Result<Vec<RegionPing>, DayzMonitorError>;
+}
+
+/// A directory every instance can write to, e.g. a network mount, holding one `<region>.json`
+/// per region.
+#[derive(Debug, Clone)]
+pub struct DirStore {
+    pub dir: PathBuf,
+}
+
+impl DirStore {
+    fn path(&self, region: &str) -> PathBuf {
+        let name: String = region
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.json"))
+    }
+}
+
+impl PingStore for DirStore {
+    fn publish(&self, ping: &RegionPing) -> Result<(), DayzMonitorError> {
+        write_atomic(&self.path(&ping.region), &serde_json::to_vec(ping)?)
+    }
+
+    fn read_all(&self) -> Result<Vec<RegionPing>, DayzMonitorError> {
+        let mut pings = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                // Another instance's file mid-write or of another version is skipped, not fatal.
+                if let Some(ping) = read_ping(&path) {
+                    pings.push(ping);
+                }
+            }
+        }
+        Ok(pings)
+    }
+}
+
+fn read_ping(path: &Path) -> Option<RegionPing> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// The other regions' pings worth showing next to this instance's own: measured within
+/// [`STALE_INTERVALS`] update intervals, the newest per region, sorted by region.
+pub fn other_regions(
+    pings: Vec<RegionPing>,
+    own_region: &str,
+    now: u64,
+    update_interval_secs: u64,
+) -> Vec<RegionPing> {
+    let max_age = update_interval_secs * STALE_INTERVALS;
+    let mut fresh: Vec<RegionPing> = pings
+        .into_iter()
+        .filter(|p| p.region != own_region && now.saturating_sub(p.measured_unix) <= max_age)
+        .collect();
+
+    fresh.sort_by(|a, b| {
+        a.region
+            .cmp(&b.region)
+            .then(b.measured_unix.cmp(&a.measured_unix))
+    });
+    fresh.dedup_by(|later, first| later.region == first.region);
+    fresh
+}
+
+/// The ping line without its icon, e.g. `Ping from EU: **18 ms** • NA: **95 ms**`: this
+/// instance's region first, then the others.
+pub fn ping_line(
+    own: &RegionPing,
+    others: &[RegionPing],
+    lang: Language,
+    locale: NumberLocale,
+) -> String {
+    let label = |ping: &RegionPing| {
+        format!(
+            "{}: **{} ms**",
+            sanitize(&ping.region),
+            format_number(ping.latency_ms.into(), locale)
+        )
+    };
+
+    let mut line = format!("{} {}", lang.tr(Text::PingFrom), label(own));
+    for other in others {
+        line.push_str(" • ");
+        line.push_str(&label(other));
+    }
+    line
+}
@@ -0,0 +1,275 @@
+/// A rendered embed as plain data, so renders can be compared without going through serenity.
+///
+/// The builder methods mirror serenity's `CreateEmbed`, which it is converted to just before
+/// sending.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedRepresentation {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub colour: Option<u32>,
+    pub fields: Vec<EmbedField>,
+    pub footer: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+impl EmbedRepresentation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn colour(mut self, colour: u32) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        inline: bool,
+    ) -> Self {
+        self.fields.push(EmbedField {
+            name: name.into(),
+            value: value.into(),
+            inline,
+        });
+        self
+    }
+}
+
+/// The description of an embed left with nothing else to show.
+pub const EMPTY_DESCRIPTION: &str = "—";
+
+/// Stands in for a title or field name that came out empty, which Discord rejects.
+pub const BLANK_NAME: &str = "\u{200B}";
+
+impl EmbedRepresentation {
+    /// Tidies a render that templates or disabled features may have left with empty parts, so
+    /// Discord neither rejects nor mangles it:
+    ///
+    /// - blank fields are dropped, and runs of blank lines collapse into one
+    /// - a blank description is replaced by the first field
+    /// - the embed always ends up with a title (`fallback_title` if needed) and either a
+    ///   description or a field
+    ///
+    /// Text is blank when it holds nothing but whitespace and markdown markers, like `** **`.
+    pub fn normalized(mut self, fallback_title: &str) -> Self {
+        self.title = self.title.as_deref().and_then(tidy).or_else(|| {
+            let fallback = tidy(fallback_title);
+            Some(fallback.unwrap_or_else(|| BLANK_NAME.to_string()))
+        });
+        self.description = self.description.as_deref().and_then(tidy);
+        self.footer = self.footer.as_deref().and_then(tidy);
+
+        self.fields = self
+            .fields
+            .into_iter()
+            .filter_map(|field| {
+                Some(EmbedField {
+                    name: tidy(&field.name).unwrap_or_else(|| BLANK_NAME.to_string()),
+                    value: tidy(&field.value)?,
+                    inline: field.inline,
+                })
+            })
+            .collect();
+
+        if self.description.is_none() {
+            self.description = if self.fields.is_empty() {
+                Some(EMPTY_DESCRIPTION.to_string())
+            } else {
+                let first = self.fields.remove(0);
+                Some(match first.name.as_str() {
+                    BLANK_NAME => first.value,
+                    name => format!("**{name}**\n{}", first.value),
+                })
+            };
+        }
+
+        self
+    }
+}
+
+/// Whether `text` shows nothing once Discord renders it.
+pub fn is_blank(text: &str) -> bool {
+    text.chars().all(|c| {
+        c.is_whitespace() || matches!(c, '*' | '_' | '~' | '`' | '|' | '>' | '#' | '\u{200B}')
+    })
+}
+
+/// `text` without surrounding blank lines and with inner runs of them collapsed, or `None` if
+/// nothing is left.
+fn tidy(text: &str) -> Option<String> {
+    if is_blank(text) {
+        return None;
+    }
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        let blank = line.trim().is_empty();
+        if blank && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(if blank { "" } else { line });
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+
+    Some(lines.join("\n"))
+}
+
+/// One part of an embed that differs between two renders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbedChange {
+    Title,
+    Description,
+    Colour,
+    /// The field at `index` changed, appeared or disappeared; `name` is its newest name
+    Field {
+        index: usize,
+        name: String,
+    },
+    Footer,
+}
+
+/// Compares two renders part by part. Fields are matched by position, since their order is
+/// fixed per embed kind and names may be translated.
+pub fn diff(prev: &EmbedRepresentation, next: &EmbedRepresentation) -> Vec<EmbedChange> {
+    let mut changes = Vec::new();
+
+    if prev.title != next.title {
+        changes.push(EmbedChange::Title);
+    }
+    if prev.description != next.description {
+        changes.push(EmbedChange::Description);
+    }
+    if prev.colour != next.colour {
+        changes.push(EmbedChange::Colour);
+    }
+    if prev.footer != next.footer {
+        changes.push(EmbedChange::Footer);
+    }
+
+    for index in 0..prev.fields.len().max(next.fields.len()) {
+        let (before, after) = (prev.fields.get(index), next.fields.get(index));
+
+        if before != after {
+            let name = after.or(before).map(|f| f.name.clone()).unwrap_or_default();
+            changes.push(EmbedChange::Field { index, name });
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(players: u32) -> EmbedRepresentation {
+        EmbedRepresentation::new()
+            .title("🟢 Chernarus 1")
+            .description(format!("👥 Players: **{players} / 60**"))
+            .colour(0x57F287)
+            .field("📍 Address", "127.0.0.1:2302", true)
+            .field("🗺️ Map", "chernarusplus", true)
+    }
+
+    #[test]
+    fn identical_renders_have_no_changes() {
+        assert_eq!(diff(&status(12), &status(12)), []);
+        assert_eq!(
+            diff(&EmbedRepresentation::new(), &EmbedRepresentation::new()),
+            []
+        );
+    }
+
+    #[test]
+    fn each_changed_part_is_reported() {
+        let next = status(13)
+            .title("🟡 Chernarus 1")
+            .colour(0xFEE75C)
+            .footer("Updated");
+
+        assert_eq!(
+            diff(&status(12), &next),
+            [
+                EmbedChange::Title,
+                EmbedChange::Description,
+                EmbedChange::Colour,
+                EmbedChange::Footer,
+            ]
+        );
+    }
+
+    #[test]
+    fn fields_are_matched_by_position() {
+        let mut next = status(12);
+        next.fields[1].value = "livonia".to_string();
+
+        assert_eq!(
+            diff(&status(12), &next),
+            [EmbedChange::Field {
+                index: 1,
+                name: "🗺️ Map".to_string()
+            }]
+        );
+
+        // A translated name is a change too, reported under the new name.
+        let mut next = status(12);
+        next.fields[0].name = "📍 Adresse".to_string();
+        assert_eq!(
+            diff(&status(12), &next),
+            [EmbedChange::Field {
+                index: 0,
+                name: "📍 Adresse".to_string()
+            }]
+        );
+
+        let mut next = status(12);
+        next.fields[0].inline = false;
+        assert_eq!(diff(&status(12), &next).len(), 1);
+    }
+
+    #[test]
+    fn added_and_removed_fields_are_changes() {
+        let more = status(12).field("🔕 Snoozed", "for 1h", false);
+
+        assert_eq!(
+            diff(&status(12), &more),
+            [EmbedChange::Field {
+                index: 2,
+                name: "🔕 Snoozed".to_string()
+            }]
+        );
+        // A removed field keeps the name it had.
+        assert_eq!(
+            diff(&more, &status(12)),
+            [EmbedChange::Field {
+                index: 2,
+                name: "🔕 Snoozed".to_string()
+            }]
+        );
+    }
+}
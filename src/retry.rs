@@ -0,0 +1,130 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// Default spread of the delays, so clients that failed together don't retry together.
+pub const DEFAULT_JITTER: f64 = 0.2;
+
+/// How a network operation is retried: how often, how long to wait in between, and for which
+/// errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included
+    pub max_attempts: u32,
+    /// The delay before the first retry
+    pub base_delay: Duration,
+    /// Each delay is this many times the one before
+    pub multiplier: f64,
+    /// How far each delay may randomly deviate, as a fraction of it from 0 to 1
+    pub jitter: f64,
+    /// Time all attempts and delays together may take; no retry starts that would wait past it
+    pub budget: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// A single attempt.
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+        multiplier: 1.0,
+        jitter: 0.0,
+        budget: None,
+    };
+
+    /// `retries` after the first attempt, with jittered delays doubling from `base_delay`.
+    pub fn retries(retries: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: retries.saturating_add(1),
+            base_delay,
+            multiplier: 2.0,
+            jitter: DEFAULT_JITTER,
+            budget: None,
+        }
+    }
+
+    /// Attempts every `interval` until one succeeds or `budget` is spent.
+    pub fn every(interval: Duration, budget: Duration) -> Self {
+        Self {
+            max_attempts: u32::MAX,
+            base_delay: interval,
+            multiplier: 1.0,
+            jitter: 0.0,
+            budget: Some(budget),
+        }
+    }
+
+    pub fn with_budget(self, budget: Duration) -> Self {
+        Self {
+            budget: Some(budget),
+            ..self
+        }
+    }
+
+    /// The delay before retry number `retry` (from 1), given a random `sample` in `[0, 1)` that
+    /// picks where in the jitter range it falls.
+    pub fn delay(&self, retry: u32, sample: f64) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let nominal = self.base_delay.as_secs_f64() * self.multiplier.max(0.0).powi(exponent);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (2.0 * sample.clamp(0.0, 1.0) - 1.0);
+
+        Duration::try_from_secs_f64(nominal * factor).unwrap_or(Duration::MAX)
+    }
+
+    /// Runs `attempt` until it succeeds, retrying every error.
+    pub async fn run<F, Fut, T, E>(&self, attempt: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.run_if(attempt, |_| true).await
+    }
+
+    /// Runs `attempt` until it succeeds, fails with an error `retryable` rejects, runs out of
+    /// attempts or would wait past the budget. The last error is returned.
+    pub async fn run_if<F, Fut, T, E>(
+        &self,
+        mut attempt: F,
+        retryable: impl Fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let started = Instant::now();
+        let mut attempts = 0;
+
+        loop {
+            let err = match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            attempts += 1;
+
+            if attempts >= self.max_attempts || !retryable(&err) {
+                return Err(err);
+            }
+
+            let delay = self.delay(attempts, jitter_sample());
+            if self
+                .budget
+                .is_some_and(|budget| started.elapsed() + delay > budget)
+            {
+                return Err(err);
+            }
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// A random number in `[0, 1)`, from the standard library's randomly keyed hasher.
+fn jitter_sample() -> f64 {
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
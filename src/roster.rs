@@ -0,0 +1,104 @@
+use a2s::players::Player;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::local_date,
+    capped::{CappedNames, LruMap},
+    clock,
+};
+
+/// The names on a player list; players still connecting have none yet and are left out.
+pub fn listed_names(players: &[Player]) -> Vec<String> {
+    players
+        .iter()
+        .filter(|p| !p.name.is_empty())
+        .map(|p| p.name.clone())
+        .collect()
+}
+
+/// The players on today's player lists, with `VERIFY_PLAYER_COUNT`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayRoster {
+    /// Local date (`YYYY-MM-DD`) the names belong to
+    pub day: String,
+    pub seen: CappedNames,
+}
+
+impl DayRoster {
+    /// Folds in one player list, starting over on a new local day. At most `cap` names are
+    /// kept; returns how many new names were only counted.
+    pub fn record(&mut self, names: &[String], now: u64, tz: Tz, cap: usize) -> usize {
+        let today = local_date(now, tz).to_string();
+        if self.day != today {
+            *self = DayRoster {
+                day: today,
+                ..Default::default()
+            };
+        }
+
+        let dropped = self.seen.dropped();
+        for name in names {
+            self.seen.insert(name, cap);
+        }
+
+        self.seen.dropped() - dropped
+    }
+}
+
+/// When each listed player joined, for at most a given number of players; past that the
+/// players listed longest ago are forgotten first.
+#[derive(Debug, Clone)]
+pub struct Sessions {
+    joined: LruMap<String, u64>,
+}
+
+impl Sessions {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            joined: LruMap::new(cap),
+        }
+    }
+
+    /// Folds in one player list: players missing from it have left. Returns the longest
+    /// session still going, in seconds.
+    pub fn observe(&mut self, names: &[String], now: u64) -> Option<u64> {
+        self.joined.retain(|name, _| names.contains(name));
+
+        for name in names {
+            if self.joined.get(name).is_none() {
+                self.joined.insert(name.clone(), now);
+            }
+        }
+
+        self.joined
+            .iter()
+            .map(|(_, joined)| now.saturating_sub(*joined))
+            .max()
+    }
+
+    pub fn shift_clock(&mut self, step: i64) {
+        for joined in self.joined.values_mut() {
+            *joined = clock::shift(*joined, step);
+        }
+    }
+
+    /// Ends every session, e.g. when the server went down.
+    pub fn clear(&mut self) {
+        self.joined.retain(|_, _| false);
+    }
+
+    pub fn len(&self) -> usize {
+        self.joined.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.joined.is_empty()
+    }
+
+    /// Sessions forgotten to make room so far.
+    pub fn evictions(&self) -> u64 {
+        self.joined.evictions()
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::{calendar::localize, DayzMonitorError};
+
+/// Which week of the month a [`Recurrence::NthWeekday`] falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nth {
+    /// 1-based: the first to fourth occurrence
+    Ordinal(u32),
+    Last,
+}
+
+/// Which local dates something recurs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly(Weekday),
+    /// A fixed day of the month; months without that day are skipped
+    MonthDay(u32),
+    /// e.g. the first Monday or last Sunday of the month
+    NthWeekday(Nth, Weekday),
+}
+
+impl Recurrence {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match *self {
+            Recurrence::Daily => true,
+            Recurrence::Weekly(weekday) => date.weekday() == weekday,
+            Recurrence::MonthDay(day) => date.day() == day,
+            Recurrence::NthWeekday(nth, weekday) => {
+                date.weekday() == weekday
+                    && match nth {
+                        Nth::Ordinal(n) => (date.day() - 1) / 7 + 1 == n,
+                        Nth::Last => (date + TimeDelta::days(7)).month() != date.month(),
+                    }
+            }
+        }
+    }
+
+    /// Parses `daily`, `weekly <weekday>`, `monthly <day>` or `monthly <first..fourth|last>
+    /// <weekday>`.
+    fn parse(tokens: &[&str]) -> Result<Recurrence, String> {
+        let lower: Vec<String> = tokens.iter().map(|t| t.to_ascii_lowercase()).collect();
+        let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+
+        match lower.as_slice() {
+            ["daily"] => Ok(Recurrence::Daily),
+            ["weekly", weekday] => Ok(Recurrence::Weekly(parse_weekday(weekday)?)),
+            ["monthly", day] => match day.parse::<u32>() {
+                Ok(day @ 1..=31) => Ok(Recurrence::MonthDay(day)),
+                _ => Err(format!("'{day}' is not a day of the month")),
+            },
+            ["monthly", nth, weekday] => {
+                let nth = match *nth {
+                    "first" => Nth::Ordinal(1),
+                    "second" => Nth::Ordinal(2),
+                    "third" => Nth::Ordinal(3),
+                    "fourth" => Nth::Ordinal(4),
+                    "last" => Nth::Last,
+                    other => {
+                        return Err(format!("'{other}' is not first/second/third/fourth/last"))
+                    }
+                };
+                Ok(Recurrence::NthWeekday(nth, parse_weekday(weekday)?))
+            }
+            _ => Err("expected 'daily', 'weekly <day>' or 'monthly ...'".to_string()),
+        }
+    }
+}
+
+/// A recurring local time range, such as `daily 04:00-04:20 Europe/Berlin`.
+///
+/// A range whose end is not after its start wraps past midnight; the recurrence then decides
+/// the day the window starts on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    pub recurrence: Recurrence,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub tz: Tz,
+}
+
+impl Window {
+    /// Parses `<recurrence> HH:MM-HH:MM [timezone]`, falling back to `default_tz`.
+    pub fn parse(spec: &str, default_tz: Tz) -> Result<Window, DayzMonitorError> {
+        let invalid = |reason: String| DayzMonitorError::InvalidSchedule {
+            spec: spec.to_string(),
+            reason,
+        };
+
+        let tokens: Vec<&str> = spec.split_whitespace().collect();
+        let range_at = tokens
+            .iter()
+            .position(|t| t.contains(':'))
+            .ok_or_else(|| invalid("missing HH:MM-HH:MM time range".to_string()))?;
+
+        let recurrence = Recurrence::parse(&tokens[..range_at]).map_err(invalid)?;
+        let (start, end) = parse_range(tokens[range_at]).map_err(invalid)?;
+
+        let tz = match &tokens[range_at + 1..] {
+            [] => default_tz,
+            [tz] => tz
+                .parse()
+                .map_err(|_| invalid(format!("unknown timezone '{tz}'")))?,
+            _ => return Err(invalid("unexpected text after the timezone".to_string())),
+        };
+
+        if start == end {
+            return Err(invalid("window is empty".to_string()));
+        }
+
+        Ok(Window {
+            recurrence,
+            start,
+            end,
+            tz,
+        })
+    }
+
+    /// When the occurrence of this window covering `now` ends, if one does.
+    pub fn active_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = now.with_timezone(&self.tz).date_naive();
+        let wraps = self.end <= self.start;
+
+        // Yesterday's occurrence may still be running if the window wraps past midnight.
+        for date in [today.pred_opt()?, today] {
+            if !self.recurrence.matches(date) {
+                continue;
+            }
+
+            let end_date = if wraps { date.succ_opt()? } else { date };
+            let start = localize(self.tz, date.and_time(self.start));
+            let end = localize(self.tz, end_date.and_time(self.end));
+
+            if start <= now && now < end {
+                return Some(end);
+            }
+        }
+
+        None
+    }
+}
+
+/// The latest end among the windows active at `now`.
+pub fn active_until(windows: &[Window], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    windows.iter().filter_map(|w| w.active_until(now)).max()
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, DayzMonitorError> {
+    name.parse().map_err(|_| DayzMonitorError::InvalidSchedule {
+        spec: name.to_string(),
+        reason: "unknown timezone".to_string(),
+    })
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, String> {
+    s.parse().map_err(|_| format!("'{s}' is not a weekday"))
+}
+
+fn parse_range(s: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let (start, end) = s
+        .split_once(['-', '–'])
+        .ok_or_else(|| format!("'{s}' is not a HH:MM-HH:MM range"))?;
+
+    let time = |t: &str| {
+        NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| format!("'{t}' is not a HH:MM time"))
+    };
+
+    Ok((time(start)?, time(end)?))
+}
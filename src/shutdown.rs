@@ -0,0 +1,234 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    sync::{oneshot, watch},
+    time::Instant,
+};
+
+/// How a component went down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// It reported done this long after it was told to stop
+    Finished(Duration),
+    /// It had not reported done when its time was up
+    TimedOut(Duration),
+    /// Its handle was dropped without reporting done, e.g. because its task panicked
+    Abandoned,
+}
+
+/// One component's part in a shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stopped {
+    pub name: &'static str,
+    pub outcome: Outcome,
+}
+
+/// Every component's outcome, in the order they were stopped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary(pub Vec<Stopped>);
+
+impl Summary {
+    /// Whether every component finished in time.
+    pub fn is_clean(&self) -> bool {
+        self.0
+            .iter()
+            .all(|s| matches!(s.outcome, Outcome::Finished(_)))
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "nothing to stop");
+        }
+
+        for (i, Stopped { name, outcome }) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match outcome {
+                Outcome::Finished(took) => write!(f, "{name} stopped in {}ms", took.as_millis())?,
+                Outcome::TimedOut(after) => {
+                    write!(f, "{name} timed out after {}ms", after.as_millis())?
+                }
+                Outcome::Abandoned => write!(f, "{name} quit without reporting")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A registered component's end of the coordinator: tells it when to stop, and takes its
+/// report that it did.
+#[derive(Debug)]
+pub struct Component {
+    cancel: watch::Receiver<bool>,
+    done: oneshot::Sender<()>,
+}
+
+impl Component {
+    /// Resolves once the component is told to stop. Cancellation-safe, for use in a `select!`.
+    pub async fn cancelled(&mut self) {
+        // An error means the coordinator is gone, which stops everything just the same.
+        let _ = self.cancel.wait_for(|cancelled| *cancelled).await;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancel.borrow()
+    }
+
+    /// Reports that the component has flushed what it had and stopped.
+    pub fn done(self) {
+        let _ = self.done.send(());
+    }
+}
+
+/// Short-lived tasks, such as alert deliveries, that a shutdown waits for as one component.
+/// They aren't told to stop; a shutdown lets those already running finish.
+#[derive(Debug, Clone)]
+pub struct TaskGroup {
+    running: Arc<watch::Sender<usize>>,
+}
+
+impl TaskGroup {
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.running.send_modify(|n| *n += 1);
+        let running = self.running.clone();
+
+        tokio::spawn(async move {
+            // Counted out even if the task panics
+            let _running = RunningTask(running);
+            task.await;
+        });
+    }
+
+    pub fn running(&self) -> usize {
+        *self.running.borrow()
+    }
+}
+
+struct RunningTask(Arc<watch::Sender<usize>>);
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.0.send_modify(|n| *n -= 1);
+    }
+}
+
+type Finished = Pin<Box<dyn Future<Output = bool> + Send>>;
+
+struct Registered {
+    name: &'static str,
+    timeout: Duration,
+    cancel: watch::Sender<bool>,
+    /// Resolves to whether the component reported done, rather than dropping its handle
+    finished: Finished,
+}
+
+/// Stops the bot's background components in an orderly way instead of dropping them
+/// mid-write.
+///
+/// Components are stopped one at a time, the last registered first, so one that feeds another
+/// is stopped before what it feeds. Each gets its own timeout; one that misses it is given up
+/// on and the next is stopped anyway.
+#[derive(Default)]
+pub struct Shutdown {
+    components: Mutex<Vec<Registered>>,
+    started: AtomicBool,
+}
+
+impl Shutdown {
+    /// Registers a component that is given `timeout` to report done once told to stop. One
+    /// registered after the shutdown started is told to stop right away and not waited for.
+    pub fn register(&self, name: &'static str, timeout: Duration) -> Component {
+        let (cancel, cancel_rx) = watch::channel(self.is_started());
+        let (done, done_rx) = oneshot::channel();
+
+        self.add(Registered {
+            name,
+            timeout,
+            cancel,
+            finished: Box::pin(async move { done_rx.await.is_ok() }),
+        });
+
+        Component {
+            cancel: cancel_rx,
+            done,
+        }
+    }
+
+    /// Registers a group of tasks that is given `timeout` for all of them to finish.
+    pub fn register_tasks(&self, name: &'static str, timeout: Duration) -> TaskGroup {
+        let running = Arc::new(watch::Sender::new(0));
+        let mut count = running.subscribe();
+
+        self.add(Registered {
+            name,
+            timeout,
+            cancel: watch::Sender::new(false),
+            finished: Box::pin(async move {
+                let _ = count.wait_for(|n| *n == 0).await;
+                true
+            }),
+        });
+
+        TaskGroup { running }
+    }
+
+    fn add(&self, component: Registered) {
+        if self.is_started() {
+            return;
+        }
+        self.components
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(component);
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    /// Stops every registered component, returning how each went. Only the first call stops
+    /// anything.
+    pub async fn run(&self) -> Summary {
+        self.started.store(true, Ordering::SeqCst);
+        let components =
+            std::mem::take(&mut *self.components.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let mut stopped = Vec::with_capacity(components.len());
+        for component in components.into_iter().rev() {
+            let Registered {
+                name,
+                timeout,
+                cancel,
+                finished,
+            } = component;
+
+            tracing::debug!("Stopping {name}");
+            cancel.send_replace(true);
+            let started = Instant::now();
+            let outcome = match tokio::time::timeout(timeout, finished).await {
+                Ok(true) => Outcome::Finished(started.elapsed()),
+                Ok(false) => Outcome::Abandoned,
+                Err(_) => Outcome::TimedOut(timeout),
+            };
+
+            stopped.push(Stopped { name, outcome });
+        }
+
+        Summary(stopped)
+    }
+}
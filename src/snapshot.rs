@@ -0,0 +1,292 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    features::Setting,
+    state::{load_state, PersistentState, STATE_VERSION},
+    DayzMonitorError,
+};
+
+/// Bumped whenever the archive layout changes incompatibly.
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+/// A state heartbeat younger than this means the bot is probably still running, when the state
+/// doesn't say how often the bot writes it.
+pub const RUNNING_HEARTBEAT_SECS: u64 = 300;
+
+/// Leeway on top of two update intervals, for a cycle that runs long.
+pub const HEARTBEAT_SLACK_SECS: u64 = 60;
+
+/// How old a heartbeat written every `interval_secs` can be while the bot is still running:
+/// two missed cycles and some slack.
+pub fn running_heartbeat_secs(interval_secs: Option<u64>) -> u64 {
+    interval_secs.map_or(RUNNING_HEARTBEAT_SECS, |interval| {
+        interval
+            .saturating_mul(2)
+            .saturating_add(HEARTBEAT_SLACK_SECS)
+    })
+}
+
+pub const MANIFEST_MEMBER: &str = "manifest.json";
+pub const STATE_MEMBER: &str = "state.json";
+pub const AUDIT_MEMBER: &str = "audit.jsonl";
+pub const CONFIG_MEMBER: &str = "config.env";
+
+/// What an archive holds, written as its first member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    /// Version of the bot that wrote the archive
+    pub bot_version: String,
+    pub created_unix: u64,
+    /// `version` of the state inside
+    pub state_version: u32,
+    /// CRC32 of every other member, by name
+    pub members: BTreeMap<String, u32>,
+}
+
+/// A validated archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub manifest: Manifest,
+    pub state: PersistentState,
+    /// The audit log, when `AUDIT_FILE` was set at snapshot time
+    pub audit: Option<Vec<u8>>,
+    /// The effective settings, secrets redacted; for reference only, never restored
+    pub config: Option<String>,
+}
+
+/// Effective settings as `NAME=value` lines.
+pub fn render_settings(settings: &[Setting]) -> String {
+    settings
+        .iter()
+        .map(|setting| format!("{}={}\n", setting.name, setting.value))
+        .collect()
+}
+
+/// Bundles the state file, the audit log if any and the redacted settings into a tar archive.
+/// A missing state file is archived as a fresh state, a missing audit log is left out.
+pub fn create(
+    state_path: &Path,
+    audit_path: Option<&Path>,
+    config: Option<&str>,
+    now_unix: u64,
+) -> Result<Vec<u8>, DayzMonitorError> {
+    let state = load_state(state_path)?;
+
+    let mut members = vec![(STATE_MEMBER, serde_json::to_vec_pretty(&state)?)];
+    if let Some(path) = audit_path {
+        match std::fs::read(path) {
+            Ok(bytes) => members.push((AUDIT_MEMBER, bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if let Some(config) = config {
+        members.push((CONFIG_MEMBER, config.as_bytes().to_vec()));
+    }
+
+    let manifest = Manifest {
+        format: SNAPSHOT_FORMAT,
+        bot_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_unix: now_unix,
+        state_version: state.version,
+        members: members
+            .iter()
+            .map(|(name, bytes)| (name.to_string(), crc32fast::hash(bytes)))
+            .collect(),
+    };
+
+    let mut archive = tar::Builder::new(Vec::new());
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+    for (name, bytes) in std::iter::once((MANIFEST_MEMBER, manifest)).chain(members) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(now_unix);
+        header.set_cksum();
+        archive.append_data(&mut header, name, bytes.as_slice())?;
+    }
+
+    Ok(archive.into_inner()?)
+}
+
+/// Reads and validates an archive: its format, the checksum of every member the manifest
+/// lists, and the state. State from an older bot is read the way the bot reads an old state
+/// file; state from a newer one is refused.
+pub fn read(archive: &[u8]) -> Result<Snapshot, DayzMonitorError> {
+    let invalid = |reason: String| DayzMonitorError::InvalidSnapshot(reason);
+
+    let mut files = BTreeMap::new();
+    let mut entries = tar::Archive::new(archive);
+    for entry in entries
+        .entries()
+        .map_err(|err| invalid(format!("not a tar archive ({err})")))?
+    {
+        let mut entry = entry.map_err(|err| invalid(format!("unreadable member ({err})")))?;
+        let name = entry
+            .path()
+            .map_err(|err| invalid(format!("unreadable member name ({err})")))?
+            .to_string_lossy()
+            .into_owned();
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|err| invalid(format!("unreadable member {name} ({err})")))?;
+        files.insert(name, bytes);
+    }
+
+    let manifest: Manifest = files
+        .remove(MANIFEST_MEMBER)
+        .ok_or_else(|| invalid(format!("no {MANIFEST_MEMBER}")))
+        .and_then(|bytes| {
+            serde_json::from_slice(&bytes)
+                .map_err(|err| invalid(format!("{MANIFEST_MEMBER} does not parse ({err})")))
+        })?;
+    if manifest.format > SNAPSHOT_FORMAT {
+        return Err(invalid(format!(
+            "format {} is newer than this bot's {SNAPSHOT_FORMAT}",
+            manifest.format
+        )));
+    }
+
+    for (name, crc) in &manifest.members {
+        let bytes = files
+            .get(name)
+            .ok_or_else(|| invalid(format!("{name} is listed but missing")))?;
+        if crc32fast::hash(bytes) != *crc {
+            return Err(invalid(format!("{name} is corrupted (checksum mismatch)")));
+        }
+    }
+    // Anything else in the archive was not written by `create`, so it isn't trusted either.
+    let member = |name: &str| {
+        manifest
+            .members
+            .contains_key(name)
+            .then(|| files.get(name).cloned())
+            .flatten()
+    };
+
+    let state: PersistentState = member(STATE_MEMBER)
+        .ok_or_else(|| invalid(format!("no {STATE_MEMBER}")))
+        .and_then(|bytes| {
+            serde_json::from_slice(&bytes)
+                .map_err(|err| invalid(format!("{STATE_MEMBER} does not parse ({err})")))
+        })?;
+    if state.version > STATE_VERSION {
+        return Err(invalid(format!(
+            "state version {} is newer than this bot's {STATE_VERSION}",
+            state.version
+        )));
+    }
+
+    Ok(Snapshot {
+        state,
+        audit: member(AUDIT_MEMBER),
+        config: member(CONFIG_MEMBER).map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+        manifest,
+    })
+}
+
+/// What [`restore`] did with the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditRestore {
+    Restored,
+    /// The archive has none, e.g. `AUDIT_FILE` was unset when it was made
+    NotInSnapshot,
+    /// The archive has one, but there is no audit file to restore it to
+    NoAuditFile,
+}
+
+/// Writes a snapshot's state, and its audit log when both it and `audit_path` are there.
+///
+/// Refuses while the current state's heartbeat is younger than [`running_heartbeat_secs`] and
+/// the bot did not record a clean shutdown, since a running bot would overwrite the restored
+/// state on its next cycle; `force` skips that check. A state file that doesn't parse is taken
+/// for a stopped bot's. Both files are written
+/// out next to their targets before either is replaced, so a failed write restores neither, and
+/// the state is written in the current layout.
+pub fn restore(
+    snapshot: &Snapshot,
+    state_path: &Path,
+    audit_path: Option<&Path>,
+    now_unix: u64,
+    force: bool,
+) -> Result<AuditRestore, DayzMonitorError> {
+    if !force {
+        let current = match load_state(state_path) {
+            Ok(current) => Some(current),
+            Err(DayzMonitorError::JsonError(err)) => {
+                tracing::warn!("Replacing a state file that does not parse: {err}");
+                None
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(age) = current.filter(|c| !c.stopped).and_then(|current| {
+            let running = running_heartbeat_secs(current.heartbeat_interval_secs);
+            current
+                .heartbeat_unix
+                .map(|at| now_unix.saturating_sub(at))
+                .filter(|age| *age < running)
+        }) {
+            return Err(DayzMonitorError::BotRunning(age));
+        }
+    }
+
+    let state = PersistentState {
+        version: STATE_VERSION,
+        ..snapshot.state.clone()
+    };
+    let mut files = vec![(state_path, serde_json::to_vec_pretty(&state)?)];
+
+    let audit = match (&snapshot.audit, audit_path) {
+        (Some(bytes), Some(path)) => {
+            files.push((path, bytes.clone()));
+            AuditRestore::Restored
+        }
+        (Some(_), None) => AuditRestore::NoAuditFile,
+        (None, _) => AuditRestore::NotInSnapshot,
+    };
+
+    let mut staged = Vec::new();
+    for (path, bytes) in &files {
+        match stage(path, bytes) {
+            Ok(tmp) => staged.push((tmp, *path)),
+            Err(err) => {
+                for (tmp, _) in &staged {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(err.into());
+            }
+        }
+    }
+    for (tmp, path) in &staged {
+        fs::rename(tmp, path)?;
+    }
+
+    Ok(audit)
+}
+
+/// Writes `bytes` to a file next to `path`, to be renamed over it.
+fn stage(path: &Path, bytes: &[u8]) -> std::io::Result<PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".restore");
+    let tmp = path.with_file_name(name);
+
+    let written = fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(err);
+    }
+
+    Ok(tmp)
+}
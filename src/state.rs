@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    audit::AuditEvent,
+    daily::DailyStats,
+    events::{EventLog, HourlyPlayers},
+    gaps::MonitoringGap,
+    maps::{MapRotation, MapStats},
+    playtime::PlayerHours,
+    roster::DayRoster,
+    subsystem::probe_writable,
+    usage::UsageCounters,
+    wipe::WipeProgress,
+    write_atomic, DayzMonitorError,
+};
+
+/// Bumped whenever the state file layout changes incompatibly.
+pub const STATE_VERSION: u32 = 1;
+
+/// Failed saves in a row after which the state is kept in memory only.
+pub const SUSPEND_AFTER_FAILURES: u32 = 3;
+
+/// How often a suspended store checks whether writes work again.
+pub const WRITE_PROBE_INTERVAL_SECS: u64 = 600;
+
+/// Everything the bot remembers across restarts, stored as JSON at `STATE_FILE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistentState {
+    pub version: u32,
+    pub usage: UsageCounters,
+
+    /// Hash of the mod list the companion messages currently show
+    pub mod_list_hash: Option<u64>,
+
+    /// Companion messages holding a mod list too long for the status embed, in order
+    pub mod_list_message_ids: Vec<u64>,
+
+    /// Alerts are not sent before this Unix timestamp, set by `/snooze`
+    pub snoozed_until_unix: Option<u64>,
+
+    pub map_rotation: MapRotation,
+
+    /// Totals per map name, for servers that rotate maps
+    pub map_stats: BTreeMap<String, MapStats>,
+
+    /// Whether the server has ever reported its in-game time, for `TIME_FIELD=auto`
+    pub server_time_seen: bool,
+
+    pub player_hours: PlayerHours,
+
+    pub daily: DailyStats,
+
+    /// Set by `/motd edit`, taking precedence over `MOTD` until `/motd reset`
+    pub motd_override: Option<String>,
+
+    /// The message each extra output edits, by output key
+    pub output_message_ids: BTreeMap<String, u64>,
+
+    /// The server everything above belongs to; unset in state files from before identities
+    pub server: Option<ServerRecord>,
+
+    /// Written every cycle, so the next start can tell how long the bot was gone
+    pub heartbeat_unix: Option<u64>,
+
+    /// `UPDATE_INTERVAL_SECS` of the bot writing the heartbeat
+    pub heartbeat_interval_secs: Option<u64>,
+
+    /// Set by a clean shutdown and cleared by the next start, so a stopped bot's recent
+    /// heartbeat isn't taken for a running one
+    pub stopped: bool,
+
+    /// Times the bot was not running, merged and kept for the last [`DAYS_KEPT`] days
+    ///
+    /// [`DAYS_KEPT`]: crate::playtime::DAYS_KEPT
+    pub monitoring_gaps: Vec<MonitoringGap>,
+
+    /// Announcements made for the planned wipe, and when the last one happened
+    pub wipe: WipeProgress,
+
+    /// Average player count per hour, for `EVENT_IMPACT`
+    pub hourly_players: HourlyPlayers,
+
+    /// Discord scheduled events of the last week and to come, for `EVENT_IMPACT`
+    pub events: EventLog,
+
+    /// Who was on today's player lists, for `VERIFY_PLAYER_COUNT`
+    pub roster: DayRoster,
+}
+
+impl Default for PersistentState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            usage: UsageCounters::default(),
+            mod_list_hash: None,
+            mod_list_message_ids: Vec::new(),
+            snoozed_until_unix: None,
+            map_rotation: MapRotation::default(),
+            map_stats: BTreeMap::new(),
+            server_time_seen: false,
+            player_hours: PlayerHours::default(),
+            daily: DailyStats::default(),
+            motd_override: None,
+            output_message_ids: BTreeMap::new(),
+            server: None,
+            heartbeat_unix: None,
+            heartbeat_interval_secs: None,
+            stopped: false,
+            monitoring_gaps: Vec::new(),
+            wipe: WipeProgress::default(),
+            hourly_players: HourlyPlayers::default(),
+            events: EventLog::default(),
+            roster: DayRoster::default(),
+        }
+    }
+}
+
+/// Which server a state file's history belongs to, and where that server was last queried.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerRecord {
+    pub id: String,
+    pub address: String,
+}
+
+/// Claims the state's history for the configured server, returning what changed for the audit
+/// log.
+///
+/// History follows the identity, not the address: a server that moved keeps its stats, and so
+/// does one whose identity was renamed, since a state file only ever holds one server. State
+/// without a record, whether fresh or written before identities, is claimed as is.
+pub fn claim_history(state: &mut PersistentState, id: &str, address: &str) -> Vec<AuditEvent> {
+    let mut changes = Vec::new();
+
+    if let Some(recorded) = &state.server {
+        if recorded.id != id {
+            changes.push(AuditEvent::ServerRenamed {
+                from: recorded.id.clone(),
+                to: id.to_string(),
+            });
+        }
+        if recorded.address != address {
+            changes.push(AuditEvent::AddressChanged {
+                server_id: id.to_string(),
+                from: recorded.address.clone(),
+                to: address.to_string(),
+            });
+        }
+    }
+
+    state.server = Some(ServerRecord {
+        id: id.to_string(),
+        address: address.to_string(),
+    });
+
+    changes
+}
+
+/// Loads the state file, starting fresh if it does not exist yet. State written by a newer
+/// bot is refused rather than read with whatever it added dropped.
+pub fn load_state(path: &Path) -> Result<PersistentState, DayzMonitorError> {
+    let state: PersistentState = match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(PersistentState::default()),
+        Err(err) => return Err(err.into()),
+    };
+
+    if state.version > STATE_VERSION {
+        return Err(DayzMonitorError::NewerState(state.version));
+    }
+
+    Ok(state)
+}
+
+pub fn save_state(path: &Path, state: &PersistentState) -> Result<(), DayzMonitorError> {
+    write_atomic(path, &serde_json::to_vec_pretty(state)?)
+}
+
+/// Where the state is persisted.
+pub trait StateStore {
+    fn save(&self, state: &PersistentState) -> Result<(), DayzMonitorError>;
+
+    /// Checks that saving would work, without writing the state.
+    fn probe(&self) -> Result<(), DayzMonitorError>;
+}
+
+/// The JSON file at `STATE_FILE`.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    pub path: PathBuf,
+}
+
+impl StateStore for FileStore {
+    fn save(&self, state: &PersistentState) -> Result<(), DayzMonitorError> {
+        save_state(&self.path, state)
+    }
+
+    fn probe(&self) -> Result<(), DayzMonitorError> {
+        Ok(probe_writable(&self.path)?)
+    }
+}
+
+/// What one [`GuardedStore::save`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveOutcome {
+    Saved,
+    /// The save failed, fewer than [`SUSPEND_AFTER_FAILURES`] times in a row
+    Failed(String),
+    /// The save failed once too often; the state is kept in memory only from now on
+    Suspended(String),
+    /// Not attempted while suspended
+    Skipped,
+    /// A probe found writes working again, and the state was saved
+    Resumed,
+}
+
+/// Stops a store that keeps failing, such as on a full disk or a filesystem gone read-only,
+/// from being written every cycle.
+///
+/// After [`SUSPEND_AFTER_FAILURES`] failed saves in a row the state is kept in memory only.
+/// Every [`WRITE_PROBE_INTERVAL_SECS`] a probe checks whether writes work again, and once they
+/// do, the state is saved and persisting resumes.
+#[derive(Debug)]
+pub struct GuardedStore<S> {
+    store: S,
+    failures: u32,
+    suspended_since: Option<u64>,
+    last_probe_unix: u64,
+}
+
+impl<S: StateStore> GuardedStore<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            failures: 0,
+            suspended_since: None,
+            last_probe_unix: 0,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// When persisting was suspended, while it is.
+    pub fn suspended_since(&self) -> Option<u64> {
+        self.suspended_since
+    }
+
+    pub fn save(&mut self, state: &PersistentState, now_unix: u64) -> SaveOutcome {
+        if self.suspended_since.is_some() {
+            if now_unix.saturating_sub(self.last_probe_unix) < WRITE_PROBE_INTERVAL_SECS {
+                return SaveOutcome::Skipped;
+            }
+            self.last_probe_unix = now_unix;
+
+            return match self.store.probe().and_then(|_| self.store.save(state)) {
+                Ok(()) => {
+                    self.suspended_since = None;
+                    self.failures = 0;
+                    SaveOutcome::Resumed
+                }
+                Err(_) => SaveOutcome::Skipped,
+            };
+        }
+
+        match self.store.save(state) {
+            Ok(()) => {
+                self.failures = 0;
+                SaveOutcome::Saved
+            }
+            Err(err) => {
+                self.failures += 1;
+                if self.failures < SUSPEND_AFTER_FAILURES {
+                    return SaveOutcome::Failed(err.to_string());
+                }
+
+                self.suspended_since = Some(now_unix);
+                self.last_probe_unix = now_unix;
+                SaveOutcome::Suspended(err.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+
+use crate::{
+    diagnostics::Diagnostics, retry::RetryPolicy, unix_now, write_atomic, DayzMonitorError,
+    MonitorState, ServerInfo,
+};
+
+/// Bumped whenever a field is removed or changes meaning. Adding fields does not bump it.
+pub const STATUS_FILE_VERSION: u32 = 2;
+
+/// The JSON document written to `STATUS_FILE` every cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StatusFile {
+    pub version: u32,
+    pub state: MonitorState,
+
+    /// Whether the server answers queries; unset while the state is unknown
+    pub online: Option<bool>,
+    pub players: Option<u32>,
+    pub max_players: Option<u32>,
+    pub queue: Option<u32>,
+
+    /// Unix timestamp of the most recent successful query, if any
+    pub last_update_unix: Option<u64>,
+
+    /// Unix timestamp of when this document was written
+    pub written_unix: u64,
+
+    /// What the last poll could not make sense of
+    #[serde(default)]
+    pub diagnostics: Diagnostics,
+}
+
+impl StatusFile {
+    pub fn new(state: MonitorState, info: Option<&ServerInfo>, diagnostics: Diagnostics) -> Self {
+        Self {
+            version: STATUS_FILE_VERSION,
+            state,
+            online: match state {
+                MonitorState::Unknown => None,
+                MonitorState::Online | MonitorState::Degraded => Some(true),
+                MonitorState::Offline | MonitorState::Starting | MonitorState::Maintenance => {
+                    Some(false)
+                }
+            },
+            players: info.map(|i| i.players),
+            max_players: info.map(|i| i.max_players),
+            queue: info.and_then(|i| i.players_in_queue),
+            last_update_unix: info.map(|i| i.last_updated_unix),
+            written_unix: unix_now(),
+            diagnostics,
+        }
+    }
+
+    /// Whether the document was written within `max_age` of `now_unix`.
+    pub fn is_fresh(&self, max_age: Duration, now_unix: u64) -> bool {
+        now_unix.saturating_sub(self.written_unix) <= max_age.as_secs()
+    }
+
+    /// Renders a single top-level field for shell consumption; missing values print as `null`.
+    pub fn field(&self, name: &str) -> Result<String, DayzMonitorError> {
+        let value = serde_json::to_value(self)?;
+
+        match value.get(name) {
+            Some(serde_json::Value::String(s)) => Ok(s.clone()),
+            Some(other) => Ok(other.to_string()),
+            None => Err(DayzMonitorError::UnknownStatusField(name.to_owned())),
+        }
+    }
+}
+
+/// The JSON Schema of the status file. `version` is pinned to [`STATUS_FILE_VERSION`], so a
+/// document of another version does not validate.
+#[cfg(feature = "schema")]
+pub fn status_file_schema() -> serde_json::Value {
+    let mut schema = schemars::schema_for!(StatusFile).to_value();
+    schema["properties"]["version"]["const"] = STATUS_FILE_VERSION.into();
+    schema
+}
+
+/// Writes the status file atomically, so readers only ever observe a complete document.
+pub fn write_status_file(path: &Path, status: &StatusFile) -> Result<(), DayzMonitorError> {
+    write_atomic(path, &serde_json::to_vec_pretty(status)?)
+}
+
+pub fn read_status_file(path: &Path) -> Result<StatusFile, DayzMonitorError> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Polls `path` until it contains a document no older than `max_age` from after the first poll,
+/// giving up after `timeout`.
+pub async fn wait_for_status(
+    path: &Path,
+    max_age: Duration,
+    timeout: Duration,
+) -> Result<StatusFile, DayzMonitorError> {
+    RetryPolicy::every(Duration::from_millis(500), timeout)
+        .run(|| async {
+            read_status_file(path)
+                .ok()
+                .filter(|status| {
+                    status.is_fresh(max_age, unix_now()) && status.state != MonitorState::Unknown
+                })
+                .ok_or(DayzMonitorError::StatusFileWaitTimeout(timeout.as_secs()))
+        })
+        .await
+}
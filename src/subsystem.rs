@@ -0,0 +1,182 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io,
+    path::Path,
+    sync::Mutex,
+};
+
+pub const AUDIT_LOG: &str = "audit_log";
+pub const REGION_PINGS: &str = "region_pings";
+pub const STATE_FILE: &str = "state_file";
+pub const STATUS_FILE: &str = "status_file";
+
+/// What an optional subsystem does once it fails. Neither ever stops the status loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Local resources such as files: failing at startup disables the subsystem for the rest of
+    /// the run, since retrying would only fail the same way
+    Disable,
+    /// Network services: stays enabled, every later use is another attempt, and the first
+    /// success brings it back up
+    Retry,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Health {
+    Up,
+    /// Its last attempt failed, for this reason
+    Degraded(String),
+    /// Not used again this run, for this reason
+    Disabled(String),
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Health::Up => write!(f, "up"),
+            Health::Degraded(reason) => write!(f, "degraded ({reason})"),
+            Health::Disabled(reason) => write!(f, "disabled ({reason})"),
+        }
+    }
+}
+
+/// An optional part of the bot that can fail without taking the status loop down with it.
+///
+/// Failures are logged when the health changes, not on every attempt, so a subsystem that stays
+/// down warns once instead of every cycle.
+#[derive(Debug, Clone)]
+pub struct Subsystem {
+    pub name: &'static str,
+    pub policy: RestartPolicy,
+    health: Health,
+}
+
+impl Subsystem {
+    pub fn new(name: &'static str, policy: RestartPolicy) -> Self {
+        Self {
+            name,
+            policy,
+            health: Health::Up,
+        }
+    }
+
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.health, Health::Disabled(_))
+    }
+
+    /// Folds in the startup attempt.
+    pub fn start(&mut self, result: Result<(), impl fmt::Display>) {
+        let Err(err) = result else {
+            return;
+        };
+
+        match self.policy {
+            RestartPolicy::Disable => {
+                tracing::error!(
+                    "{} is disabled for this run: {err}. Everything else keeps running.",
+                    self.name
+                );
+                self.health = Health::Disabled(err.to_string());
+            }
+            RestartPolicy::Retry => {
+                tracing::warn!("{} is unavailable, will keep trying: {err}", self.name);
+                self.health = Health::Degraded(err.to_string());
+            }
+        }
+    }
+
+    /// Folds in a later attempt. Results are ignored once disabled.
+    pub fn record(&mut self, result: Result<(), impl fmt::Display>) {
+        match (&self.health, result) {
+            (Health::Disabled(_), _) => {}
+            (Health::Up, Ok(())) => {}
+            (Health::Degraded(_), Ok(())) => {
+                tracing::info!("{} recovered", self.name);
+                self.health = Health::Up;
+            }
+            (Health::Up, Err(err)) => {
+                tracing::warn!("{} failed: {err}", self.name);
+                self.health = Health::Degraded(err.to_string());
+            }
+            (Health::Degraded(_), Err(err)) => {
+                tracing::debug!("{} is still failing: {err}", self.name);
+                self.health = Health::Degraded(err.to_string());
+            }
+        }
+    }
+}
+
+/// The bot's optional subsystems, shared between the status loop and commands.
+#[derive(Debug, Default)]
+pub struct Subsystems(Mutex<Vec<Subsystem>>);
+
+impl Subsystems {
+    /// Adds a subsystem that needs no startup check.
+    pub fn add(&self, name: &'static str, policy: RestartPolicy) {
+        self.lock().push(Subsystem::new(name, policy));
+    }
+
+    /// Adds a subsystem and folds in its startup attempt.
+    pub fn start(
+        &self,
+        name: &'static str,
+        policy: RestartPolicy,
+        result: Result<(), impl fmt::Display>,
+    ) {
+        let mut subsystem = Subsystem::new(name, policy);
+        subsystem.start(result);
+        self.lock().push(subsystem);
+    }
+
+    /// Whether `name` should still be used; unknown names are.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.lock()
+            .iter()
+            .find(|s| s.name == name)
+            .is_none_or(Subsystem::is_enabled)
+    }
+
+    pub fn record(&self, name: &str, result: Result<(), impl fmt::Display>) {
+        if let Some(subsystem) = self.lock().iter_mut().find(|s| s.name == name) {
+            subsystem.record(result);
+        }
+    }
+
+    /// Every subsystem and its health, in the order they started.
+    pub fn snapshot(&self) -> Vec<(&'static str, Health)> {
+        self.lock()
+            .iter()
+            .map(|s| (s.name, s.health.clone()))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Subsystem>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Checks that a file can be written with [`crate::write_atomic`] or appended to, without
+/// touching its contents.
+pub fn probe_writable(path: &Path) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".probe");
+    let probe = path.with_file_name(name);
+
+    OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(&probe)?;
+    fs::remove_file(&probe)?;
+
+    if path.exists() {
+        OpenOptions::new().append(true).open(path)?;
+    }
+
+    Ok(())
+}
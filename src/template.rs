@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crate::{
+    maps::display_name,
+    numbers::{format_number, NumberLocale},
+    player_count::HIDDEN_COUNT,
+    DayzMonitorConfig, ServerInfo,
+};
+
+/// Placeholders available wherever live server info is rendered (embeds, alerts).
+pub const SERVER_PLACEHOLDERS: &[&str] = &[
+    "server_name",
+    "address",
+    "players",
+    "max_players",
+    "queue",
+    "map",
+    "server_time",
+];
+
+/// Placeholders that only need the configuration, available even while the server is offline.
+pub const CONFIG_PLACEHOLDERS: &[&str] = &["server_name", "address"];
+
+/// Suffix that makes a numeric placeholder render without locale formatting, e.g. `{queue:raw}`.
+pub const RAW_SUFFIX: &str = ":raw";
+
+/// Values substituted into a template, keyed by placeholder name (without braces).
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    values: HashMap<&'static str, String>,
+
+    /// Unformatted numbers, for the `:raw` form of a placeholder
+    raw: HashMap<&'static str, String>,
+
+    locale: NumberLocale,
+}
+
+impl TemplateValues {
+    pub fn from_config(config: &DayzMonitorConfig) -> Self {
+        let mut values = Self {
+            locale: config.number_locale(),
+            ..Self::default()
+        };
+        values.set("server_name", &config.server_name);
+        values.set("address", &config.server_address);
+        values
+    }
+
+    pub fn from_server_info(config: &DayzMonitorConfig, info: &ServerInfo) -> Self {
+        let mut values = Self::from_config(config);
+        let mode = config.player_count_mode;
+        match mode.display(info.players, info.max_players, values.locale) {
+            Some(_) if mode.shows_exact() => values.set_number("players", info.players.into()),
+            Some(shown) => values.set("players", shown),
+            None => values.set("players", HIDDEN_COUNT),
+        }
+        values.set_number("max_players", info.max_players.into());
+        values.set_number("queue", info.players_in_queue.unwrap_or(0).into());
+        values.set("map", display_name(&info.map));
+        values.set(
+            "server_time",
+            info.server_time.as_deref().unwrap_or("Unknown"),
+        );
+        values
+    }
+
+    pub fn set(&mut self, name: &'static str, value: impl ToString) {
+        self.values.insert(name, sanitize(&value.to_string()));
+    }
+
+    /// Sets a number, formatted for the configured locale unless used as `{name:raw}`.
+    pub fn set_number(&mut self, name: &'static str, value: u64) {
+        self.values.insert(name, format_number(value, self.locale));
+        self.raw.insert(name, value.to_string());
+    }
+
+    fn get(&self, placeholder: &str) -> Option<&String> {
+        match placeholder.strip_suffix(RAW_SUFFIX) {
+            Some(name) => self.raw.get(name).or_else(|| self.values.get(name)),
+            None => self.values.get(placeholder),
+        }
+    }
+}
+
+/// Escapes Discord markdown and defuses mass mentions in an interpolated value.
+///
+/// Only values are sanitized; the template text itself is trusted config, so it may contain
+/// formatting and role mentions.
+pub fn sanitize(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '*' | '_' | '~' | '`' | '|' | '>' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '@' => out.push_str("@\u{200B}"),
+            '\n' | '\r' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Returns the placeholder names referenced by `template`, in order of appearance.
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                names.push(&after[..end]);
+                rest = &after[end + 1..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+/// Returns the first placeholder in `template` that is not in `allowed`, ignoring `:raw`.
+pub fn find_unknown_placeholder<'a>(template: &'a str, allowed: &[&str]) -> Option<&'a str> {
+    placeholders(template).into_iter().find(|name| {
+        let base = name.strip_suffix(RAW_SUFFIX).unwrap_or(name);
+        !allowed.contains(&base)
+    })
+}
+
+/// Substitutes every `{name}` in `template`. Unknown placeholders are left untouched, which
+/// config validation is expected to have ruled out already.
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match values.get(name) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
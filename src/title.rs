@@ -0,0 +1,157 @@
+use crate::{
+    i18n::{Language, Text},
+    monitor::Observation,
+    numbers::format_number,
+    template::{self, TemplateValues, SERVER_PLACEHOLDERS},
+    DayzMonitorConfig, DayzMonitorError, MonitorState, ServerInfo,
+};
+
+/// Discord's limit on embed titles.
+const TITLE_LIMIT: usize = 256;
+
+/// The headline state of the status embed. Both its title and colour come from here, so the
+/// two always agree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleState {
+    /// Not polled yet
+    Unknown,
+    Online,
+    /// Online, but every slot is taken or players are queueing
+    Full,
+    Degraded,
+    Offline,
+    /// Answering again after an outage, not yet confirmed joinable
+    Starting,
+    Maintenance,
+}
+
+impl TitleState {
+    pub fn of(observation: &Observation) -> TitleState {
+        match (observation.state, &observation.info) {
+            (MonitorState::Online, Some(info)) => TitleState::of_online(info),
+            (state, _) => TitleState::of_state(state),
+        }
+    }
+
+    /// The state without looking at the sample, so never [`TitleState::Full`].
+    pub fn of_state(state: MonitorState) -> TitleState {
+        match state {
+            MonitorState::Unknown => TitleState::Unknown,
+            MonitorState::Online => TitleState::Online,
+            MonitorState::Degraded => TitleState::Degraded,
+            MonitorState::Offline => TitleState::Offline,
+            MonitorState::Starting => TitleState::Starting,
+            MonitorState::Maintenance => TitleState::Maintenance,
+        }
+    }
+
+    /// The state for a trusted sample.
+    pub fn of_online(info: &ServerInfo) -> TitleState {
+        if is_full(info) {
+            TitleState::Full
+        } else {
+            TitleState::Online
+        }
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            TitleState::Unknown => "⚪",
+            TitleState::Online => "🟢",
+            TitleState::Full => "🟠",
+            TitleState::Degraded => "🟡",
+            TitleState::Offline => "🔴",
+            TitleState::Starting => "🔵",
+            TitleState::Maintenance => "🛠️",
+        }
+    }
+
+    pub fn colour(self) -> u32 {
+        match self {
+            TitleState::Unknown => 0x95A5A6,
+            TitleState::Online => 0x57F287,
+            TitleState::Full => 0xE67E22,
+            TitleState::Degraded => 0xFEE75C,
+            TitleState::Offline => 0xED4245,
+            TitleState::Starting => 0x3498DB,
+            TitleState::Maintenance => 0xE67E22,
+        }
+    }
+
+    pub fn text(self) -> Text {
+        match self {
+            TitleState::Unknown => Text::Checking,
+            TitleState::Online => Text::Online,
+            TitleState::Full => Text::Full,
+            TitleState::Degraded => Text::Degraded,
+            TitleState::Offline => Text::Offline,
+            TitleState::Starting => Text::StartingUp,
+            TitleState::Maintenance => Text::Maintenance,
+        }
+    }
+}
+
+/// Whether a new player would have to wait to get in.
+pub fn is_full(info: &ServerInfo) -> bool {
+    info.players >= info.max_players || info.players_in_queue.is_some_and(|q| q > 0)
+}
+
+/// The status embed title, e.g. `🟢 MyServer — Online` or `🟠 MyServer — Full (Queue: 6)`.
+///
+/// The full title can be replaced with `title_full_template`; `info` supplies its values.
+pub fn render_title(
+    config: &DayzMonitorConfig,
+    state: TitleState,
+    info: Option<&ServerInfo>,
+    lang: Language,
+) -> String {
+    let title = match (state, &config.title_full_template, info) {
+        (TitleState::Full, Some(custom), Some(info)) => {
+            template::render(custom, &TemplateValues::from_server_info(config, info))
+        }
+        (TitleState::Full, _, Some(info)) => match info.players_in_queue {
+            Some(q) if q > 0 => format!(
+                "{} {} — {} ({}: {})",
+                state.emoji(),
+                config.server_name,
+                lang.tr(Text::Full),
+                lang.tr(Text::Queue),
+                format_number(q.into(), config.number_locale())
+            ),
+            _ => default_title(config, state, lang),
+        },
+        _ => default_title(config, state, lang),
+    };
+
+    truncate(title, TITLE_LIMIT)
+}
+
+fn default_title(config: &DayzMonitorConfig, state: TitleState, lang: Language) -> String {
+    format!(
+        "{} {} — {}",
+        state.emoji(),
+        config.server_name,
+        lang.tr(state.text())
+    )
+}
+
+/// Cuts `s` to `limit` characters, ending in `…` when anything was cut.
+pub fn truncate(mut s: String, limit: usize) -> String {
+    if s.chars().count() > limit {
+        s = s.chars().take(limit - 1).chain(['…']).collect();
+    }
+    s
+}
+
+pub fn validate_title_template(config: &DayzMonitorConfig) -> Result<(), DayzMonitorError> {
+    let Some(custom) = &config.title_full_template else {
+        return Ok(());
+    };
+
+    match template::find_unknown_placeholder(custom, SERVER_PLACEHOLDERS) {
+        Some(placeholder) => Err(DayzMonitorError::InvalidTitleTemplate(
+            placeholder.to_string(),
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::DateTime;
+
+use crate::{
+    i18n::Language,
+    monitor::Observation,
+    template::{self, TemplateValues, CONFIG_PLACEHOLDERS, SERVER_PLACEHOLDERS},
+    title::{truncate, TitleState},
+    transport::StatusTransport,
+    DayzMonitorConfig, DayzMonitorError,
+};
+
+/// Discord's limit on channel topics.
+pub const TOPIC_LIMIT: usize = 1024;
+
+pub const DEFAULT_TOPIC_TEMPLATE: &str =
+    "{status_emoji} {players}/{max_players} • queue {queue} • updated {updated}";
+
+/// The topic template used without `TOPIC_TEMPLATE`, in `lang`; [`DEFAULT_TOPIC_TEMPLATE`] is
+/// the English one.
+pub fn default_topic_template(lang: Language) -> &'static str {
+    match lang {
+        Language::En => DEFAULT_TOPIC_TEMPLATE,
+        Language::De => {
+            "{status_emoji} {players}/{max_players} • Warteschlange {queue} • aktualisiert {updated}"
+        }
+        Language::Fr => "{status_emoji} {players}/{max_players} • file {queue} • mis à jour {updated}",
+        Language::Ru => "{status_emoji} {players}/{max_players} • очередь {queue} • обновлено {updated}",
+    }
+}
+
+/// Placeholders a topic template may use besides [`SERVER_PLACEHOLDERS`].
+pub const TOPIC_PLACEHOLDERS: &[&str] = &["status_emoji", "status", "updated"];
+
+/// Shown for server values while there is no sample, e.g. `🔴 –/– • queue –`.
+const NO_VALUE: &str = "–";
+
+/// The channel topic for an observation. Topics are a single line, so line breaks become
+/// spaces.
+pub fn render_topic(
+    config: &DayzMonitorConfig,
+    observation: &Observation,
+    now_unix: u64,
+) -> String {
+    let mut values = match &observation.info {
+        Some(info) => TemplateValues::from_server_info(config, info),
+        None => {
+            let mut values = TemplateValues::from_config(config);
+            for name in SERVER_PLACEHOLDERS {
+                if !CONFIG_PLACEHOLDERS.contains(name) {
+                    values.set(name, NO_VALUE);
+                }
+            }
+            values
+        }
+    };
+
+    let state = TitleState::of(observation);
+    values.set("status_emoji", state.emoji());
+    values.set("status", config.bot_language.tr(state.text()));
+    values.set(
+        "updated",
+        DateTime::from_timestamp(now_unix as i64, 0)
+            .map(|t| t.format("%H:%M UTC").to_string())
+            .unwrap_or_default(),
+    );
+
+    let template = config
+        .topic_template
+        .as_deref()
+        .unwrap_or(default_topic_template(config.bot_language));
+    let topic = template::render(template, &values).replace(['\n', '\r'], " ");
+
+    truncate(topic, TOPIC_LIMIT)
+}
+
+pub fn validate_topic_template(config: &DayzMonitorConfig) -> Result<(), DayzMonitorError> {
+    let Some(custom) = &config.topic_template else {
+        return Ok(());
+    };
+
+    let allowed: Vec<&str> = SERVER_PLACEHOLDERS
+        .iter()
+        .chain(TOPIC_PLACEHOLDERS)
+        .copied()
+        .collect();
+
+    match template::find_unknown_placeholder(custom, &allowed) {
+        Some(placeholder) => Err(DayzMonitorError::InvalidTopicTemplate(
+            placeholder.to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicOutcome {
+    Set,
+    /// The channel already shows this topic
+    Unchanged,
+    /// The topic changed, but too recently edited to edit again; a later update retries
+    Throttled,
+}
+
+/// Keeps a channel topic in step with the rendered one, within Discord's budget for channel
+/// edits.
+#[derive(Debug, Clone)]
+pub struct TopicUpdater {
+    channel_id: u64,
+    min_interval_secs: u64,
+    current: Option<String>,
+    last_edit_unix: Option<u64>,
+}
+
+impl TopicUpdater {
+    pub fn new(channel_id: u64, min_interval_secs: u64) -> Self {
+        Self {
+            channel_id,
+            min_interval_secs,
+            current: None,
+            last_edit_unix: None,
+        }
+    }
+
+    /// Sets `topic` unless the channel already shows it or the last edit was too recent.
+    pub async fn update<T: StatusTransport>(
+        &mut self,
+        transport: &T,
+        topic: String,
+        now_unix: u64,
+    ) -> Result<TopicOutcome, T::Error> {
+        if self.current.as_ref() == Some(&topic) {
+            return Ok(TopicOutcome::Unchanged);
+        }
+
+        let throttled = self
+            .last_edit_unix
+            .is_some_and(|last| now_unix.saturating_sub(last) < self.min_interval_secs);
+        if throttled {
+            return Ok(TopicOutcome::Throttled);
+        }
+
+        // A failed edit still spends budget, and is retried after the interval.
+        self.last_edit_unix = Some(now_unix);
+        transport.set_topic(self.channel_id, &topic).await?;
+        self.current = Some(topic);
+
+        Ok(TopicOutcome::Set)
+    }
+}
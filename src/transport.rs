@@ -0,0 +1,435 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::DayzMonitorError;
+
+/// How much attention a newly posted message should draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Priority {
+    /// Notifies channel members as usual
+    #[default]
+    Normal,
+    /// Posted without a push notification
+    Low,
+}
+
+impl Priority {
+    pub fn silent_if(silent: bool) -> Self {
+        if silent {
+            Priority::Low
+        } else {
+            Priority::Normal
+        }
+    }
+}
+
+/// A new message to post.
+#[derive(Debug, Clone)]
+pub struct SendRequest<M> {
+    pub channel_id: u64,
+    pub message: M,
+    pub priority: Priority,
+}
+
+/// Who posted a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAuthor {
+    pub id: u64,
+    pub name: String,
+}
+
+/// A message found when looking through a channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelMessage {
+    pub id: u64,
+    pub author_id: u64,
+    pub created_unix: u64,
+    /// Footer text of its first embed
+    pub footer: Option<String>,
+}
+
+/// What a message shows besides its embeds, which others may have changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MessageState {
+    pub content: String,
+    /// Its embeds are hidden, as anyone allowed to manage messages can do
+    pub embeds_suppressed: bool,
+}
+
+/// Changes to undo on a message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Restore {
+    /// Show the embeds again
+    pub unsuppress: bool,
+    /// Remove the text content
+    pub clear_content: bool,
+}
+
+impl Restore {
+    pub fn is_empty(&self) -> bool {
+        !self.unsuppress && !self.clear_content
+    }
+}
+
+/// How edits of an embed-only message treat changes someone else made to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditGuard {
+    /// Look at the message before every this many edits; 0 only after a failed edit. The
+    /// first edit of a message always looks.
+    pub check_every: u32,
+    /// Show embeds again that were hidden
+    pub unsuppress: bool,
+    /// Remove text that was added, instead of keeping it
+    pub own_content_only: bool,
+}
+
+impl EditGuard {
+    /// What to undo on a message found in `state`.
+    pub fn restore(&self, state: &MessageState) -> Restore {
+        Restore {
+            unsuppress: self.unsuppress && state.embeds_suppressed,
+            clear_content: self.own_content_only && !state.content.trim().is_empty(),
+        }
+    }
+}
+
+/// Something that can replace the content of a previously sent status message, and post new
+/// messages.
+///
+/// The payload types are left to the implementation so this module stays independent of any
+/// particular Discord library.
+pub trait StatusTransport: Send + Sync {
+    type Payload: Send;
+    type Message: Send;
+    type Error: Send;
+
+    fn edit(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        payload: Self::Payload,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Looks up who posted a message.
+    fn author(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> impl Future<Output = Result<MessageAuthor, Self::Error>> + Send;
+
+    /// Posts a message and returns its id.
+    fn send(
+        &self,
+        request: SendRequest<Self::Message>,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+
+    /// Lists up to `limit` of the latest messages in a channel, newest first.
+    fn recent_messages(
+        &self,
+        channel_id: u64,
+        limit: u8,
+    ) -> impl Future<Output = Result<Vec<ChannelMessage>, Self::Error>> + Send;
+
+    fn delete(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Replaces the topic of a channel.
+    fn set_topic(
+        &self,
+        channel_id: u64,
+        topic: &str,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Looks up what a message shows besides its embeds.
+    fn inspect(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+    ) -> impl Future<Output = Result<MessageState, Self::Error>> + Send;
+
+    /// Undoes changes others made to a message.
+    fn restore(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        restore: Restore,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Checks that `message_id` was posted by `own_user_id`, since Discord only lets a bot edit
+/// its own messages. The outer error is a failed lookup, the inner one a foreign author.
+pub async fn verify_own_message<T: StatusTransport>(
+    transport: &T,
+    channel_id: u64,
+    message_id: u64,
+    own_user_id: u64,
+) -> Result<Result<(), DayzMonitorError>, T::Error> {
+    let author = transport.author(channel_id, message_id).await?;
+
+    if author.id == own_user_id {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(DayzMonitorError::ForeignStatusMessage {
+            message_id,
+            author: author.name,
+        }))
+    }
+}
+
+/// How long the first render may take before a placeholder is posted in its place.
+pub const PLACEHOLDER_BUDGET: Duration = Duration::from_secs(3);
+
+/// Awaits `render`, running `placeholder` alongside it once `render` has taken longer than
+/// `budget`. A quick first render is thus posted directly, and a slow one still shows something
+/// early.
+pub async fn render_or_placeholder<T>(
+    render: impl Future<Output = T>,
+    budget: Duration,
+    placeholder: impl Future<Output = ()>,
+) -> T {
+    tokio::pin!(render);
+
+    tokio::select! {
+        out = &mut render => return out,
+        _ = tokio::time::sleep(budget) => {}
+    }
+
+    tokio::join!(placeholder, render).1
+}
+
+/// Hands out render sequence numbers. Take one *before* reading the data a render is built
+/// from, so that the number orders renders by the age of their data.
+#[derive(Debug, Default)]
+pub struct RenderSequence(AtomicU64);
+
+impl RenderSequence {
+    pub fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// A rendered status, tagged with the sequence number it was rendered under.
+#[derive(Debug, Clone)]
+pub struct StatusUpdate<P> {
+    pub seq: u64,
+    pub payload: P,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOutcome {
+    Applied,
+    /// A newer update was already applied to the message, so this one was dropped unsent
+    Stale,
+}
+
+/// What [`StatusMessage`] tracks per message.
+#[derive(Debug)]
+struct Slot {
+    /// Sequence of the last update applied
+    last_applied: Option<u64>,
+    edits_since_check: u32,
+    /// Look at the message before the next guarded edit
+    check_due: bool,
+    /// Hash of the changes by someone else last warned about and left in place, so they are
+    /// only logged at debug level while they stay
+    kept_changes: Option<u64>,
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Self {
+            last_applied: None,
+            edits_since_check: 0,
+            check_due: true,
+            kept_changes: None,
+        }
+    }
+}
+
+/// Applies status updates to messages, never letting an older render overwrite a newer one.
+///
+/// Edits to the same message are serialized, and an update whose sequence is lower than the
+/// last one successfully applied to that message is dropped instead of sent.
+pub struct StatusMessage<T> {
+    transport: T,
+    applied: Mutex<HashMap<u64, Arc<tokio::sync::Mutex<Slot>>>>,
+}
+
+impl<T: StatusTransport> StatusMessage<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            applied: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub async fn apply(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        update: StatusUpdate<T::Payload>,
+    ) -> Result<EditOutcome, T::Error> {
+        self.apply_with(channel_id, message_id, update, None).await
+    }
+
+    /// Like [`apply`](Self::apply), for a message that holds nothing but embeds of the bot's,
+    /// like the main status message. Before the first edit, every `guard.check_every` edits and
+    /// after a failed edit, the message is looked at for changes someone else made; those are
+    /// logged and undone as `guard` says.
+    pub async fn apply_guarded(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        update: StatusUpdate<T::Payload>,
+        guard: &EditGuard,
+    ) -> Result<EditOutcome, T::Error> {
+        self.apply_with(channel_id, message_id, update, Some(guard))
+            .await
+    }
+
+    async fn apply_with(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        update: StatusUpdate<T::Payload>,
+        guard: Option<&EditGuard>,
+    ) -> Result<EditOutcome, T::Error> {
+        let slot = self
+            .applied
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(message_id)
+            .or_default()
+            .clone();
+
+        let mut slot = slot.lock().await;
+
+        if slot.last_applied.is_some_and(|last| update.seq < last) {
+            tracing::debug!(
+                "Dropping stale render #{} for message {message_id} (#{} already applied)",
+                update.seq,
+                slot.last_applied.unwrap_or_default()
+            );
+            return Ok(EditOutcome::Stale);
+        }
+
+        if let Some(guard) = guard {
+            let periodic = guard.check_every > 0 && slot.edits_since_check >= guard.check_every;
+            // A failed check stays due; the edit below reports whatever is wrong.
+            if (slot.check_due || periodic)
+                && self
+                    .check(channel_id, message_id, guard, &mut slot.kept_changes)
+                    .await
+                    .is_ok()
+            {
+                slot.check_due = false;
+                slot.edits_since_check = 0;
+            }
+        }
+
+        let edited = self
+            .transport
+            .edit(channel_id, message_id, update.payload)
+            .await;
+        if edited.is_err() {
+            slot.check_due = true;
+        }
+        edited?;
+        slot.last_applied = Some(update.seq);
+        slot.edits_since_check = slot.edits_since_check.saturating_add(1);
+
+        Ok(EditOutcome::Applied)
+    }
+
+    /// Looks at a message for changes someone else made, and undoes them as `guard` says.
+    /// Changes left in place are warned about once, and logged at debug level while they stay.
+    async fn check(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        guard: &EditGuard,
+        kept_changes: &mut Option<u64>,
+    ) -> Result<(), T::Error> {
+        let state = self.transport.inspect(channel_id, message_id).await?;
+        let restore = guard.restore(&state);
+
+        let mut changes = Vec::new();
+        if state.embeds_suppressed {
+            let action = if restore.unsuppress {
+                "showing them again"
+            } else {
+                "leaving them hidden"
+            };
+            changes.push(format!(
+                "Someone hid the embeds of message {message_id}, {action}"
+            ));
+        }
+        if !state.content.trim().is_empty() {
+            let action = if restore.clear_content {
+                "removing it"
+            } else {
+                "keeping it"
+            };
+            changes.push(format!(
+                "Someone added text to message {message_id}, {action}: {:?}",
+                state.content
+            ));
+        }
+
+        let seen = *kept_changes == Some(hash(&state));
+        for change in &changes {
+            if seen {
+                tracing::debug!("{change}");
+            } else {
+                tracing::warn!("{change}");
+            }
+        }
+
+        let kept = MessageState {
+            content: if restore.clear_content {
+                String::new()
+            } else {
+                state.content.clone()
+            },
+            embeds_suppressed: state.embeds_suppressed && !restore.unsuppress,
+        };
+        *kept_changes = (kept != MessageState::default()).then(|| hash(&kept));
+
+        if !restore.is_empty() {
+            self.transport
+                .restore(channel_id, message_id, restore)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Forgets the sequencing state of a message that no longer exists.
+    pub fn forget(&self, message_id: u64) {
+        self.applied
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&message_id);
+    }
+}
+
+fn hash(state: &MessageState) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
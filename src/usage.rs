@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    i18n::{Language, Noun, Text},
+    numbers::{format_decimal, format_number, NumberLocale},
+    MonitorState,
+};
+
+/// Local-only usage counters kept in the state file. Nothing here is ever sent anywhere; they
+/// exist so operators can see how their own instance behaves via `dayz-monitor stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageCounters {
+    pub first_started_unix: Option<u64>,
+    pub starts: u64,
+    pub cycles: u64,
+    pub cycles_online: u64,
+    pub cycles_offline: u64,
+
+    /// Alerts fired, keyed by rule name, whether or not they could be delivered
+    pub alerts_fired: BTreeMap<String, u64>,
+
+    /// Number of starts with each feature enabled, keyed by feature name
+    pub feature_starts: BTreeMap<String, u64>,
+
+    /// Players on a list only counted, past `UNIQUE_NAMES_PER_DAY`
+    pub names_dropped: u64,
+
+    /// Sessions forgotten to make room, past `TRACKED_SESSIONS`
+    pub sessions_evicted: u64,
+}
+
+impl UsageCounters {
+    pub fn record_start(&mut self, now: u64, features: &[&str]) {
+        self.first_started_unix.get_or_insert(now);
+        self.starts = self.starts.saturating_add(1);
+
+        for feature in features {
+            bump(&mut self.feature_starts, feature);
+        }
+    }
+
+    /// Counts one cycle. Maintenance, degraded and not-yet-known cycles count towards neither
+    /// side.
+    pub fn record_cycle(&mut self, state: MonitorState) {
+        self.cycles = self.cycles.saturating_add(1);
+
+        match state {
+            MonitorState::Online => self.cycles_online = self.cycles_online.saturating_add(1),
+            MonitorState::Offline | MonitorState::Starting => {
+                self.cycles_offline = self.cycles_offline.saturating_add(1)
+            }
+            MonitorState::Maintenance | MonitorState::Degraded | MonitorState::Unknown => {}
+        }
+    }
+
+    pub fn record_alert(&mut self, rule: &str) {
+        bump(&mut self.alerts_fired, rule);
+    }
+
+    /// Counts what the player list caps left out this cycle.
+    pub fn record_caps(&mut self, names_dropped: usize, sessions_evicted: u64) {
+        self.names_dropped = self.names_dropped.saturating_add(names_dropped as u64);
+        self.sessions_evicted = self.sessions_evicted.saturating_add(sessions_evicted);
+    }
+
+    /// Human-readable summary for the `stats` subcommand.
+    pub fn summary(&self, now: u64, locale: NumberLocale, lang: Language) -> String {
+        let mut out = String::new();
+
+        match self.first_started_unix {
+            Some(first) => {
+                let days = now.saturating_sub(first) / 86_400;
+                let _ = writeln!(
+                    out,
+                    "{}{} {} ({} {})",
+                    lang.report_label(Text::RunningFor),
+                    format_number(days, locale),
+                    lang.noun(Noun::Day, days),
+                    format_number(self.starts, locale),
+                    lang.noun(Noun::Start, self.starts)
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "{}{}",
+                    lang.report_label(Text::RunningFor),
+                    lang.tr(Text::NeverStarted)
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "{}{}",
+            lang.report_label(Text::UpdateCycles),
+            format_number(self.cycles, locale)
+        );
+
+        if self.cycles > 0 {
+            let offline_pct = self.cycles_offline as f64 * 100.0 / self.cycles as f64;
+            let _ = writeln!(
+                out,
+                "{}{}% {}",
+                lang.report_label(Text::Offline),
+                format_decimal(offline_pct, 1, locale),
+                lang.tr(Text::OfCycles)
+            );
+        }
+
+        // Only worth mentioning once a cap was hit.
+        for (label, count) in [
+            (Text::NamesDropped, self.names_dropped),
+            (Text::SessionsEvicted, self.sessions_evicted),
+        ] {
+            if count > 0 {
+                let _ = writeln!(
+                    out,
+                    "{}{}",
+                    lang.report_label(label),
+                    format_number(count, locale)
+                );
+            }
+        }
+
+        let _ = writeln!(out, "\n{}:", lang.tr(Text::AlertsFired));
+        write_counts(&mut out, &self.alerts_fired, locale, lang);
+
+        let _ = writeln!(out, "\n{}:", lang.tr(Text::FeaturesEnabled));
+        write_counts(&mut out, &self.feature_starts, locale, lang);
+
+        out
+    }
+}
+
+fn bump(map: &mut BTreeMap<String, u64>, key: &str) {
+    let count = map.entry(key.to_owned()).or_default();
+    *count = count.saturating_add(1);
+}
+
+fn write_counts(
+    out: &mut String,
+    counts: &BTreeMap<String, u64>,
+    locale: NumberLocale,
+    lang: Language,
+) {
+    if counts.is_empty() {
+        let _ = writeln!(out, "  {}", lang.tr(Text::NoneYet));
+    }
+
+    for (name, count) in counts {
+        let _ = writeln!(out, "  {name:<16}{}", format_number(*count, locale));
+    }
+}
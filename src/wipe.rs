@@ -0,0 +1,260 @@
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alerts::parse_duration,
+    calendar::localize,
+    i18n::{Language, Text},
+    state::PersistentState,
+    DayzMonitorError,
+};
+
+/// The countdown embed field shows during this long before a wipe.
+pub const COUNTDOWN_FIELD_SECS: u64 = 24 * 3600;
+
+/// Where wipe announcements are posted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WipeChannel {
+    /// `ALERT_CHANNEL_ID`, or the status channel without one
+    #[default]
+    Alert,
+    Status,
+}
+
+/// What happens to announcements whose moment passed while the bot was not running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedAnnouncements {
+    /// Leave them out
+    #[default]
+    Skip,
+    /// Post the latest one late; the ones before it are out of date and left out
+    CatchUp,
+}
+
+impl MissedAnnouncements {
+    /// As written in `WIPE_MISSED_ANNOUNCEMENTS`.
+    pub fn name(self) -> &'static str {
+        match self {
+            MissedAnnouncements::Skip => "skip",
+            MissedAnnouncements::CatchUp => "catch_up",
+        }
+    }
+}
+
+/// A planned wipe and when to announce it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WipePlan {
+    pub at_unix: u64,
+    /// Seconds before the wipe to announce it at, longest first
+    pub offsets_secs: Vec<u64>,
+}
+
+impl WipePlan {
+    /// Parses `YYYY-MM-DD HH:MM [timezone]`, falling back to `default_tz`, and offsets such
+    /// as `7d` or `1h`.
+    pub fn parse(
+        date: &str,
+        offsets: &[String],
+        default_tz: Tz,
+    ) -> Result<WipePlan, DayzMonitorError> {
+        let invalid = |spec: &str, reason: &str| DayzMonitorError::InvalidWipe {
+            spec: spec.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let tokens: Vec<&str> = date.split_whitespace().collect();
+        let (local, tz) = match tokens.as_slice() {
+            [day, time] => (format!("{day} {time}"), default_tz),
+            [day, time, tz] => (
+                format!("{day} {time}"),
+                tz.parse().map_err(|_| invalid(date, "unknown timezone"))?,
+            ),
+            _ => return Err(invalid(date, "expected YYYY-MM-DD HH:MM [timezone]")),
+        };
+        let local = NaiveDateTime::parse_from_str(&local, "%Y-%m-%d %H:%M")
+            .map_err(|_| invalid(date, "expected YYYY-MM-DD HH:MM [timezone]"))?;
+
+        let mut offsets_secs = offsets
+            .iter()
+            .map(|offset| {
+                parse_duration(offset)
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| invalid(offset, "not a duration such as 7d or 1h"))
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
+        offsets_secs.sort_unstable_by(|a, b| b.cmp(a));
+        offsets_secs.dedup();
+
+        Ok(WipePlan {
+            at_unix: localize(tz, local).timestamp().max(0) as u64,
+            offsets_secs,
+        })
+    }
+}
+
+/// How far the announcements of the planned wipe got, kept across restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WipeProgress {
+    /// The wipe `announced_secs` belongs to; a new date starts the series over
+    pub scheduled_unix: Option<u64>,
+
+    /// Offsets already announced or skipped, in seconds before the wipe
+    pub announced_secs: Vec<u64>,
+
+    /// When the server was last wiped, for the days since wipe
+    pub last_wipe_unix: Option<u64>,
+}
+
+impl WipeProgress {
+    /// Whole days since the last wipe, once there was one.
+    pub fn days_since(&self, now_unix: u64) -> Option<u64> {
+        self.last_wipe_unix
+            .filter(|at| *at <= now_unix)
+            .map(|at| (now_unix - at) / 86_400)
+    }
+}
+
+/// Something the wipe schedule asks for in one cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WipeEvent {
+    /// Post the announcement due `before_secs` ahead of the wipe
+    Countdown { before_secs: u64 },
+    /// The wipe moment passed; `announce` is false when it passed while the bot was not running
+    /// and missed announcements are skipped
+    Wiped { announce: bool },
+}
+
+/// Advances `progress` to `now_unix` and returns what is due.
+///
+/// A moment counts as on time up to `grace_secs` after it; later ones were missed, and are
+/// posted late or skipped per `missed`. At most one countdown is due per call: when several
+/// offsets passed at once, only the latest says how long is really left. A wipe date already
+/// in the past when first seen is taken as the last wipe, without announcing or resetting
+/// anything.
+pub fn due(
+    plan: &WipePlan,
+    missed: MissedAnnouncements,
+    progress: &mut WipeProgress,
+    now_unix: u64,
+    grace_secs: u64,
+) -> Option<WipeEvent> {
+    let at = plan.at_unix;
+
+    if progress.scheduled_unix != Some(at) {
+        progress.scheduled_unix = Some(at);
+        progress.announced_secs.clear();
+
+        if at <= now_unix {
+            progress.last_wipe_unix = progress.last_wipe_unix.max(Some(at));
+            return None;
+        }
+    }
+
+    if progress.last_wipe_unix.is_some_and(|last| last >= at) {
+        return None;
+    }
+
+    let on_time = |moment: u64| {
+        now_unix.saturating_sub(moment) <= grace_secs || missed == MissedAnnouncements::CatchUp
+    };
+
+    if now_unix >= at {
+        progress.last_wipe_unix = Some(at);
+        progress.announced_secs = plan.offsets_secs.clone();
+        return Some(WipeEvent::Wiped {
+            announce: on_time(at),
+        });
+    }
+
+    let passed: Vec<u64> = plan
+        .offsets_secs
+        .iter()
+        .copied()
+        .filter(|off| at.saturating_sub(*off) <= now_unix)
+        .filter(|off| !progress.announced_secs.contains(off))
+        .collect();
+    let latest = passed.iter().copied().min()?;
+    progress.announced_secs.extend(passed);
+
+    on_time(at.saturating_sub(latest)).then_some(WipeEvent::Countdown {
+        before_secs: latest,
+    })
+}
+
+/// After the clock was stepped forward by `step` seconds, skips the moments the step jumped
+/// over rather than leaving them to [`due`] as missed: their countdowns count as announced, and
+/// a jumped-over wipe is returned as wiped without an announcement.
+pub fn skip_stepped(
+    plan: &WipePlan,
+    progress: &mut WipeProgress,
+    now_unix: u64,
+    step: i64,
+) -> Option<WipeEvent> {
+    let at = plan.at_unix;
+    let before_step = now_unix.saturating_sub(step.max(0) as u64);
+    let jumped = |moment: u64| moment > before_step && moment <= now_unix;
+
+    if progress.scheduled_unix != Some(at) || progress.last_wipe_unix.is_some_and(|l| l >= at) {
+        return None;
+    }
+
+    if jumped(at) {
+        progress.last_wipe_unix = Some(at);
+        progress.announced_secs = plan.offsets_secs.clone();
+        return Some(WipeEvent::Wiped { announce: false });
+    }
+
+    for off in &plan.offsets_secs {
+        if jumped(at.saturating_sub(*off)) && !progress.announced_secs.contains(off) {
+            progress.announced_secs.push(*off);
+        }
+    }
+    None
+}
+
+/// What the status embed shows about wipes at `now_unix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WipeField {
+    /// The wipe is less than [`COUNTDOWN_FIELD_SECS`] away
+    Countdown {
+        at_unix: u64,
+    },
+    DaysSince(u64),
+}
+
+pub fn field(plan: Option<&WipePlan>, progress: &WipeProgress, now_unix: u64) -> Option<WipeField> {
+    match plan {
+        Some(plan)
+            if plan.at_unix > now_unix && plan.at_unix - now_unix <= COUNTDOWN_FIELD_SECS =>
+        {
+            Some(WipeField::Countdown {
+                at_unix: plan.at_unix,
+            })
+        }
+        _ => progress.days_since(now_unix).map(WipeField::DaysSince),
+    }
+}
+
+/// The announcement text for `event`.
+pub fn announcement(event: WipeEvent, server_name: &str, at_unix: u64, lang: Language) -> String {
+    match event {
+        WipeEvent::Countdown { .. } => format!(
+            "🧹 **{server_name}**: {} <t:{at_unix}:R> (<t:{at_unix}:F>)",
+            lang.tr(Text::Wipe)
+        ),
+        WipeEvent::Wiped { .. } => format!("🧹 **{server_name}**: {}", lang.tr(Text::Wiped)),
+    }
+}
+
+/// Starts the statistics over for a wiped server: per-map averages and peaks, daily stats and
+/// player-hours. Usage counters and monitoring gaps are about the bot, and are kept.
+pub fn reset_stats(state: &mut PersistentState) {
+    state.map_stats.clear();
+    state.daily = Default::default();
+    state.player_hours = Default::default();
+    state.roster = Default::default();
+}
@@ -0,0 +1,177 @@
+//! Alerts with a chart: the text goes out first and the chart is edited in afterwards, or left
+//! out when drawing it takes too long.
+
+#![cfg(feature = "charts")]
+
+mod common;
+
+use std::{io::Read, time::Duration};
+
+use dayz_monitor::{
+    chart::{self, ChartOutcome, CHART_HEIGHT, CHART_SPAN_SECS, CHART_WIDTH},
+    transport::{Priority, SendRequest, StatusTransport},
+};
+use flate2::read::ZlibDecoder;
+
+use common::{Call, FakeDiscord, NOW};
+
+const ALERTS: u64 = 333;
+
+type Recorder = FakeDiscord<Vec<u8>, String>;
+
+fn sent() -> Call<Vec<u8>, String> {
+    Call::Send {
+        channel_id: ALERTS,
+        message: "🔴 offline".to_string(),
+        priority: Priority::Normal,
+    }
+}
+
+async fn send_alert(
+    transport: &Recorder,
+    render: impl FnOnce() -> Vec<u8> + Send + 'static,
+    budget: Duration,
+) -> ChartOutcome {
+    let request = SendRequest {
+        channel_id: ALERTS,
+        message: "🔴 offline".to_string(),
+        priority: Priority::Normal,
+    };
+    let id = transport.send(request).await.unwrap();
+
+    chart::attach(transport, ALERTS, id, render, budget, |png| png).await
+}
+
+#[tokio::test]
+async fn text_goes_out_before_the_chart() {
+    let transport = Recorder::default();
+
+    let outcome = send_alert(&transport, || b"png".to_vec(), Duration::from_secs(1)).await;
+
+    assert_eq!(outcome, ChartOutcome::Attached);
+    assert_eq!(
+        transport.calls(),
+        vec![
+            sent(),
+            Call::Edit {
+                channel_id: ALERTS,
+                message_id: 1001,
+                payload: b"png".to_vec(),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn slow_chart_is_left_out() {
+    let transport = Recorder::default();
+    let slow = || {
+        std::thread::sleep(Duration::from_millis(300));
+        b"png".to_vec()
+    };
+
+    let outcome = send_alert(&transport, slow, Duration::from_millis(20)).await;
+
+    assert_eq!(outcome, ChartOutcome::TimedOut);
+    assert_eq!(transport.calls(), vec![sent()]);
+}
+
+#[tokio::test]
+async fn failed_edit_keeps_the_text_alert() {
+    let transport = Recorder::default();
+    transport.fail_edits(true);
+
+    let outcome = send_alert(&transport, || b"png".to_vec(), Duration::from_secs(1)).await;
+
+    assert_eq!(
+        outcome,
+        ChartOutcome::Failed("503 Service Unavailable".to_string())
+    );
+    assert_eq!(transport.calls(), vec![sent()]);
+}
+
+/// Decodes a PNG written by `chart::encode_png` into its size and RGB pixels.
+fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    let mut at = 8;
+    let (mut width, mut height, mut data) = (0, 0, Vec::new());
+    while at < png.len() {
+        let len = u32::from_be_bytes(png[at..at + 4].try_into().unwrap()) as usize;
+        let kind = &png[at + 4..at + 8];
+        let body = &png[at + 8..at + 8 + len];
+        let crc = u32::from_be_bytes(png[at + 8 + len..at + 12 + len].try_into().unwrap());
+        assert_eq!(crc, crc32fast::hash(&png[at + 4..at + 8 + len]));
+
+        match kind {
+            b"IHDR" => {
+                width = u32::from_be_bytes(body[..4].try_into().unwrap());
+                height = u32::from_be_bytes(body[4..8].try_into().unwrap());
+            }
+            b"IDAT" => data.extend_from_slice(body),
+            _ => {}
+        }
+        at += 12 + len;
+    }
+
+    let mut raw = Vec::new();
+    ZlibDecoder::new(&data[..]).read_to_end(&mut raw).unwrap();
+    let pixels = raw
+        .chunks(width as usize * 3 + 1)
+        .flat_map(|row| row[1..].to_vec())
+        .collect();
+    (width, height, pixels)
+}
+
+fn pixel(pixels: &[u8], x: u32, y: u32) -> &[u8] {
+    let at = (y * CHART_WIDTH + x) as usize * 3;
+    &pixels[at..at + 3]
+}
+
+#[test]
+fn chart_shows_the_drop() {
+    // Full for the first half of the window, down for the second.
+    let start = NOW - CHART_SPAN_SECS;
+    let samples: Vec<(u64, u32)> = (0..=CHART_SPAN_SECS / 60)
+        .map(|minute| {
+            let players = if minute < CHART_SPAN_SECS / 120 {
+                60
+            } else {
+                0
+            };
+            (start + minute * 60, players)
+        })
+        .collect();
+
+    let (width, height, pixels) = decode(&chart::render_png(&samples, Some(60), NOW));
+
+    assert_eq!((width, height), (CHART_WIDTH, CHART_HEIGHT));
+    assert_eq!(pixels.len(), (width * height * 3) as usize);
+
+    let bottom = CHART_HEIGHT - 1;
+    let early = CHART_WIDTH / 4;
+    let late = CHART_WIDTH * 3 / 4;
+    // Filled to the top while full, only the baseline once down.
+    assert_eq!(pixel(&pixels, early, 0), pixel(&pixels, late, bottom));
+    assert_eq!(
+        pixel(&pixels, early, bottom),
+        pixel(&pixels, early, bottom / 2 + 1)
+    );
+    assert_ne!(
+        pixel(&pixels, late, bottom - 1),
+        pixel(&pixels, early, bottom - 1)
+    );
+}
+
+#[test]
+fn time_before_the_first_sample_is_blank() {
+    let samples = vec![(NOW - 600, 30), (NOW, 30)];
+
+    let (_, _, pixels) = decode(&chart::render_png(&samples, Some(60), NOW));
+
+    let bottom = CHART_HEIGHT - 1;
+    assert_ne!(
+        pixel(&pixels, 0, bottom),
+        pixel(&pixels, CHART_WIDTH - 1, bottom)
+    );
+}
@@ -0,0 +1,223 @@
+//! Alert templates: placeholder substitution, escaping of the substituted values, and the
+//! startup check that every placeholder is one its rule provides.
+
+mod common;
+
+use dayz_monitor::{
+    alerts::{alert_rules, validate_alert_templates, AlertKind, AlertTracker},
+    template::{find_unknown_placeholder, placeholders, render, TemplateValues},
+    DayzMonitorConfig, DayzMonitorError,
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+fn alerting(extra: serde_json::Value) -> DayzMonitorConfig {
+    let mut config = config(extra);
+    config.alert_channel_id = Some(2);
+    config
+}
+
+fn messages(
+    tracker: &mut AlertTracker,
+    config: &DayzMonitorConfig,
+    players: Option<u32>,
+    now: u64,
+) -> Vec<String> {
+    let info = players.map(info);
+    tracker
+        .observe(config, info.as_ref(), now)
+        .into_iter()
+        .map(|alert| alert.message)
+        .collect()
+}
+
+#[test]
+fn placeholders_are_found_in_order() {
+    assert_eq!(
+        placeholders("{server_name} has {players}/{max_players:raw}"),
+        ["server_name", "players", "max_players:raw"]
+    );
+    assert_eq!(placeholders("no placeholders"), Vec::<&str>::new());
+    assert_eq!(placeholders("{players} and an open {brace"), ["players"]);
+}
+
+#[test]
+fn render_substitutes_known_placeholders_only() {
+    let mut values = TemplateValues::default();
+    values.set("server_name", "Chernarus 1");
+
+    assert_eq!(
+        render("**{server_name}** {unknown} {open", &values),
+        "**Chernarus 1** {unknown} {open"
+    );
+}
+
+#[test]
+fn substituted_values_are_escaped_but_the_template_is_not() {
+    let mut values = TemplateValues::default();
+    values.set("server_name", "*Best* @everyone\nserver");
+
+    assert_eq!(
+        render("<@&123> **{server_name}**", &values),
+        "<@&123> **\\*Best\\* @\u{200B}everyone server**"
+    );
+}
+
+#[test]
+fn raw_suffix_is_not_an_unknown_placeholder() {
+    let allowed = ["players", "queue"];
+
+    assert_eq!(
+        find_unknown_placeholder("{players:raw} {queue}", &allowed),
+        None
+    );
+    assert_eq!(
+        find_unknown_placeholder("{players} {duration}", &allowed),
+        Some("duration")
+    );
+}
+
+#[test]
+fn each_rule_only_accepts_its_own_placeholders() {
+    let cases = [
+        (
+            "alert_offline_template",
+            "{server_name} down for {duration}",
+            None,
+        ),
+        (
+            "alert_offline_template",
+            "{players} were online",
+            Some(("offline", "players")),
+        ),
+        (
+            "alert_online_template",
+            "back after {outage_minutes} min, {players} on",
+            None,
+        ),
+        (
+            "alert_online_template",
+            "{duration}",
+            Some(("online", "duration")),
+        ),
+        ("alert_queue_template", "{queue:raw} over {threshold}", None),
+        (
+            "alert_queue_template",
+            "{p95_ms}",
+            Some(("queue", "p95_ms")),
+        ),
+        ("alert_latency_template", "{p95_ms}/{threshold_ms} ms", None),
+        ("alert_join_port_template", "port {game_port}", None),
+    ];
+
+    for (key, template, expected) in cases {
+        let config = alerting(json!({ key: template }));
+        let result = validate_alert_templates(&config);
+
+        match expected {
+            None => assert!(result.is_ok(), "{key}: {result:?}"),
+            Some((rule, name)) => assert!(
+                matches!(
+                    &result,
+                    Err(DayzMonitorError::InvalidAlertTemplate { rule: r, placeholder })
+                        if *r == rule && placeholder == name
+                ),
+                "{key}: {result:?}"
+            ),
+        }
+    }
+}
+
+#[test]
+fn invalid_template_fails_config_validation() {
+    let config = alerting(json!({ "alert_offline_template": "{players} gone" }));
+
+    let err = config.validate().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Alert rule 'offline' template uses '{players}', which that rule does not provide."
+    );
+}
+
+#[test]
+fn rules_need_a_destination_and_their_threshold() {
+    let kinds = |config: &DayzMonitorConfig| -> Vec<AlertKind> {
+        alert_rules(config).into_iter().map(|r| r.kind).collect()
+    };
+
+    assert!(kinds(&config(json!({ "alert_queue_threshold": 5 }))).is_empty());
+    assert_eq!(
+        kinds(&alerting(json!({}))),
+        [AlertKind::Offline, AlertKind::Online]
+    );
+    assert_eq!(
+        kinds(&alerting(
+            json!({ "alert_queue_threshold": 5, "alert_latency_ms": 200 })
+        )),
+        [
+            AlertKind::Offline,
+            AlertKind::Online,
+            AlertKind::Queue,
+            AlertKind::Latency
+        ]
+    );
+}
+
+#[test]
+fn configured_template_replaces_the_default() {
+    let config = alerting(json!({ "alert_online_template": "{server_name} is up" }));
+
+    let rules = alert_rules(&config);
+
+    let template = |kind| &rules.iter().find(|r| r.kind == kind).unwrap().template;
+    assert_eq!(template(AlertKind::Online), "{server_name} is up");
+    assert_eq!(
+        template(AlertKind::Offline),
+        AlertKind::Offline.default_template()
+    );
+}
+
+#[test]
+fn alerts_render_their_rule_values() {
+    let config = alerting(json!({
+        "alert_offline_template": "{server_name} down, last seen {duration} ago",
+        "alert_online_template": "{server_name} up after {outage_minutes} min, {players}/{max_players}",
+    }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+
+    assert!(messages(&mut tracker, &config, Some(12), NOW).is_empty());
+    assert_eq!(
+        messages(&mut tracker, &config, None, NOW + 300),
+        ["Chernarus 1 down, last seen 5m ago"]
+    );
+    assert_eq!(
+        messages(&mut tracker, &config, Some(3), NOW + 300 + 25 * 60),
+        ["Chernarus 1 up after 25 min, 3/60"]
+    );
+}
+
+#[test]
+fn queue_alert_fires_once_until_the_queue_drains() {
+    let config = alerting(json!({
+        "alert_queue_threshold": 5,
+        "alert_queue_template": "{queue} queued, over {threshold}",
+    }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let mut poll = |queue: u32, minute: u64| {
+        let mut info = info(60);
+        info.players_in_queue = Some(queue);
+        tracker
+            .observe(&config, Some(&info), NOW + minute * 60)
+            .into_iter()
+            .map(|alert| alert.message)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(poll(3, 0).is_empty());
+    assert_eq!(poll(8, 1), ["8 queued, over 5"]);
+    assert!(poll(9, 2).is_empty());
+    assert!(poll(5, 3).is_empty());
+    assert_eq!(poll(6, 4), ["6 queued, over 5"]);
+}
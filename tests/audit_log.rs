@@ -0,0 +1,232 @@
+//! The audit log: appending entries, reading a time range back, pruning to the retention
+//! period, and the lines `/audit` and `dayz-monitor audit` print.
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use dayz_monitor::{
+    audit::{AuditEntry, AuditEvent, AuditLog},
+    MonitorState,
+};
+use serde_json::json;
+
+use common::{config, NOW};
+
+fn log_path(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("dayz-monitor-audit-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("audit.jsonl")
+}
+
+fn transition(at_unix: u64, from: MonitorState, to: MonitorState) -> AuditEntry {
+    AuditEntry {
+        at_unix,
+        event: AuditEvent::Transition {
+            from,
+            to,
+            error: None,
+        },
+    }
+}
+
+fn alert(suppressed: Option<&str>, error: Option<&str>, message_id: Option<u64>) -> AuditEvent {
+    AuditEvent::Alert {
+        rule: "offline".to_string(),
+        channel_id: Some(2),
+        message_id,
+        suppressed: suppressed.map(str::to_string),
+        error: error.map(str::to_string),
+    }
+}
+
+#[test]
+fn appended_entries_read_back_in_order() {
+    let path = log_path("append");
+    let log = AuditLog::new(path.clone(), 3600);
+    let entries = [
+        transition(NOW, MonitorState::Unknown, MonitorState::Online),
+        transition(NOW + 60, MonitorState::Online, MonitorState::Offline),
+        transition(NOW + 120, MonitorState::Offline, MonitorState::Online),
+    ];
+
+    for entry in &entries {
+        log.append(entry).unwrap();
+    }
+
+    assert_eq!(log.read_since(0).unwrap(), entries);
+    assert_eq!(log.read_since(NOW + 60).unwrap(), entries[1..]);
+    assert_eq!(log.read_since(NOW + 121).unwrap(), []);
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn missing_log_reads_as_empty() {
+    let path = log_path("missing");
+    let log = AuditLog::new(path.clone(), 3600);
+
+    assert_eq!(log.read_since(0).unwrap(), []);
+    assert_eq!(log.prune(NOW).unwrap(), 0);
+    assert!(!path.exists());
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn lines_cut_short_are_skipped() {
+    let path = log_path("torn");
+    let log = AuditLog::new(path.clone(), 3600);
+    let first = transition(NOW, MonitorState::Unknown, MonitorState::Online);
+    log.append(&first).unwrap();
+    fs::write(
+        &path,
+        format!(
+            "{}{{\"at_unix\":{},\"event\":\"trans",
+            fs::read_to_string(&path).unwrap(),
+            NOW + 60
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(log.read_since(0).unwrap(), [first]);
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn pruning_keeps_the_retention_period() {
+    let path = log_path("prune");
+    let log = AuditLog::new(path.clone(), 3600);
+    let entries = [
+        transition(NOW - 7200, MonitorState::Unknown, MonitorState::Online),
+        transition(NOW - 3600, MonitorState::Online, MonitorState::Offline),
+        transition(NOW - 60, MonitorState::Offline, MonitorState::Online),
+    ];
+    for entry in &entries {
+        log.append(entry).unwrap();
+    }
+
+    assert_eq!(log.prune(NOW).unwrap(), 1);
+    assert_eq!(log.read_since(0).unwrap(), entries[1..]);
+    assert_eq!(log.prune(NOW).unwrap(), 0);
+
+    // Appending after a prune continues the same file.
+    let next = transition(NOW, MonitorState::Online, MonitorState::Degraded);
+    log.append(&next).unwrap();
+    assert_eq!(log.read_since(0).unwrap().last(), Some(&next));
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn entries_are_one_flat_json_line_each() {
+    let entry = AuditEntry {
+        at_unix: NOW,
+        event: alert(Some("maintenance"), None, None),
+    };
+
+    let line = serde_json::to_value(&entry).unwrap();
+
+    assert_eq!(
+        line,
+        json!({
+            "at_unix": NOW,
+            "event": "alert",
+            "rule": "offline",
+            "channel_id": 2,
+            "message_id": null,
+            "suppressed": "maintenance",
+            "error": null,
+        })
+    );
+}
+
+#[test]
+fn events_describe_themselves() {
+    let cases = [
+        (
+            AuditEvent::Transition {
+                from: MonitorState::Unknown,
+                to: MonitorState::Starting,
+                error: None,
+            },
+            "state unknown → starting",
+        ),
+        (
+            AuditEvent::Transition {
+                from: MonitorState::Online,
+                to: MonitorState::Offline,
+                error: Some("timed out".to_string()),
+            },
+            "state online → offline (timed out)",
+        ),
+        (
+            alert(Some("snoozed until 5"), Some("ignored"), None),
+            "alert offline suppressed (snoozed until 5)",
+        ),
+        (
+            alert(None, Some("403 Forbidden"), None),
+            "alert offline failed (403 Forbidden)",
+        ),
+        (
+            alert(None, None, Some(900)),
+            "alert offline sent to channel 2 as message 900",
+        ),
+        (
+            alert(None, None, None),
+            "alert offline not sent (no alert channel)",
+        ),
+        (
+            AuditEvent::Notification {
+                backend: "ntfy".to_string(),
+                rule: "online".to_string(),
+                error: None,
+            },
+            "alert online sent to ntfy",
+        ),
+        (
+            AuditEvent::Notification {
+                backend: "telegram".to_string(),
+                rule: "online".to_string(),
+                error: Some("timeout".to_string()),
+            },
+            "alert online failed on telegram (timeout)",
+        ),
+    ];
+
+    for (event, expected) in cases {
+        assert_eq!(event.describe(), expected);
+    }
+}
+
+#[test]
+fn printed_lines_carry_a_utc_timestamp() {
+    let entry = transition(NOW, MonitorState::Online, MonitorState::Offline);
+
+    assert_eq!(
+        entry.to_line(),
+        "2025-10-09 08:53:20 UTC  state online → offline"
+    );
+}
+
+#[test]
+fn audit_log_is_only_kept_when_configured() {
+    assert!(config(json!({})).audit_log().is_none());
+
+    let path = log_path("config");
+    let config = config(json!({ "audit_file": path, "audit_retention_hours": 1 }));
+    let log = config.audit_log().unwrap();
+    log.append(&transition(
+        NOW - 3601,
+        MonitorState::Unknown,
+        MonitorState::Online,
+    ))
+    .unwrap();
+
+    assert_eq!(log.prune(NOW).unwrap(), 1);
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
@@ -0,0 +1,162 @@
+//! The capped collections behind the player list: names kept up to a cap and only counted past
+//! it, and a map evicting its least recently used entry. Checked against plain std collections
+//! over many generated sequences.
+
+use std::collections::BTreeSet;
+
+use dayz_monitor::capped::{CappedNames, LruMap};
+
+/// Xorshift, so every run generates the same sequences.
+struct Sequence(u64);
+
+impl Sequence {
+    fn next(&mut self, below: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % below
+    }
+}
+
+#[test]
+fn names_past_the_cap_are_counted_but_not_kept() {
+    let mut seq = Sequence(0x2545_f491_4f6c_dd1d);
+
+    for round in 0..200 {
+        let cap = seq.next(40) as usize;
+        let pool = 1 + seq.next(120);
+        let mut names = CappedNames::default();
+        let mut seen = BTreeSet::new();
+
+        for _ in 0..seq.next(400) {
+            let name = format!("Survivor{}", seq.next(pool));
+            assert_eq!(
+                names.insert(&name, cap),
+                seen.insert(name.clone()),
+                "round {round}: {name}"
+            );
+        }
+
+        assert_eq!(names.len(), seen.len(), "round {round}");
+        assert_eq!(names.names().count(), seen.len().min(cap), "round {round}");
+        assert_eq!(
+            names.dropped(),
+            seen.len().saturating_sub(cap),
+            "round {round}"
+        );
+        assert!(
+            seen.iter().all(|name| names.contains(name)),
+            "round {round}"
+        );
+        assert!(
+            names.names().all(|name| seen.contains(name)),
+            "round {round}"
+        );
+    }
+}
+
+#[test]
+fn first_names_seen_are_the_ones_kept() {
+    let mut names = CappedNames::default();
+    for name in ["Bandit", "Survivor", "Medic", "Bandit", "Hunter"] {
+        names.insert(name, 2);
+    }
+
+    assert_eq!(names.names().collect::<Vec<_>>(), ["Bandit", "Survivor"]);
+    assert_eq!((names.len(), names.dropped()), (4, 2));
+    assert!(names.contains("Hunter"));
+    assert!(!names.contains("Trader"));
+}
+
+#[test]
+fn capped_names_survive_a_round_trip() {
+    let mut names = CappedNames::default();
+    for name in ["Bandit", "Survivor", "Medic"] {
+        names.insert(name, 1);
+    }
+
+    let json = serde_json::to_string(&names).unwrap();
+    let mut loaded: CappedNames = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, names);
+    // Dropped names are still recognized after a restart.
+    assert!(!loaded.insert("Medic", 1));
+    assert_eq!(loaded.len(), 3);
+}
+
+#[test]
+fn lru_map_matches_a_model_and_never_exceeds_its_cap() {
+    let mut seq = Sequence(0x9e37_79b9_7f4a_7c15);
+
+    for round in 0..200 {
+        let cap = seq.next(12) as usize;
+        let mut map = LruMap::new(cap);
+        // Keys from least to most recently used
+        let mut model: Vec<u64> = Vec::new();
+        let mut evictions = 0;
+
+        for step in 0..seq.next(300) {
+            let key = seq.next(20);
+            match seq.next(3) {
+                0 => {
+                    let found = map.get(&key).copied();
+                    match model.iter().position(|k| *k == key) {
+                        Some(at) => {
+                            model.remove(at);
+                            model.push(key);
+                            assert_eq!(found, Some(key * 10), "round {round} step {step}");
+                        }
+                        None => assert_eq!(found, None, "round {round} step {step}"),
+                    }
+                }
+                1 => {
+                    let removed = map.remove(&key);
+                    let at = model.iter().position(|k| *k == key);
+                    assert_eq!(removed.is_some(), at.is_some(), "round {round} step {step}");
+                    if let Some(at) = at {
+                        model.remove(at);
+                    }
+                }
+                _ => {
+                    let evicted = map.insert(key, key * 10);
+                    model.retain(|k| *k != key);
+                    model.push(key);
+                    let expected = (model.len() > cap).then(|| model.remove(0));
+                    if expected.is_some() {
+                        evictions += 1;
+                    }
+                    assert_eq!(
+                        evicted.map(|(k, _)| k),
+                        expected,
+                        "round {round} step {step}"
+                    );
+                }
+            }
+
+            assert!(map.len() <= cap, "round {round} step {step}");
+            assert_eq!(map.len(), model.len(), "round {round} step {step}");
+        }
+
+        let mut keys: Vec<u64> = map.iter().map(|(k, _)| *k).collect();
+        let mut expected = model.clone();
+        keys.sort();
+        expected.sort();
+        assert_eq!(keys, expected, "round {round}");
+        assert_eq!(map.evictions(), evictions, "round {round}");
+    }
+}
+
+#[test]
+fn retained_entries_are_not_evictions() {
+    let mut map = LruMap::new(3);
+    for key in 1..=3 {
+        map.insert(key, ());
+    }
+
+    map.retain(|key, _| *key != 1);
+    map.insert(4, ());
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.evictions(), 0);
+    assert!(!map.contains_key(&1));
+}
@@ -0,0 +1,163 @@
+//! The cargo features heavy integrations are built with: that each stands alone and is
+//! documented, and that one left out of the build is reported as such rather than as off.
+//!
+//! CI builds every feature alone with `cargo hack`; these hold in whichever build runs them.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use dayz_monitor::{
+    alerts::alert_rules,
+    features::{self, Activation},
+    notify, DayzMonitorConfig,
+};
+use serde_json::json;
+
+use common::config;
+
+/// Integrations with a cargo feature of their own.
+const INTEGRATIONS: [&str; 3] = ["charts", "telegram", "ntfy"];
+
+fn manifest_features() -> BTreeMap<String, Vec<String>> {
+    let manifest = include_str!("../Cargo.toml");
+    let manifest: toml::Table = toml::from_str(manifest).expect("Cargo.toml parses");
+
+    manifest["features"]
+        .as_table()
+        .expect("Cargo.toml has features")
+        .iter()
+        .map(|(name, enables)| {
+            let enables = enables
+                .as_array()
+                .expect("a feature lists what it enables")
+                .iter()
+                .map(|e| e.as_str().expect("feature entries are strings").to_string())
+                .collect();
+            (name.clone(), enables)
+        })
+        .collect()
+}
+
+fn activation(config: &DayzMonitorConfig, name: &str) -> Activation {
+    features::registry(config)
+        .into_iter()
+        .find(|f| f.name == name)
+        .map(|f| f.activation)
+        .unwrap()
+}
+
+/// What a configured feature reports in this build.
+fn configured(cargo_feature: &'static str, built: bool) -> Activation {
+    if built {
+        Activation::On
+    } else {
+        Activation::NotBuilt(cargo_feature)
+    }
+}
+
+#[test]
+fn default_build_is_just_the_status_loop() {
+    assert_eq!(manifest_features()["default"], Vec::<String>::new());
+}
+
+#[test]
+fn integrations_enable_only_their_own_dependencies() {
+    let features = manifest_features();
+
+    for name in INTEGRATIONS {
+        let enables = &features[name];
+        assert!(!enables.is_empty(), "{name} enables nothing");
+        assert!(
+            enables.iter().all(|e| e.starts_with("dep:")),
+            "{name} enables another feature: {enables:?}"
+        );
+    }
+}
+
+#[test]
+fn full_build_has_every_integration() {
+    assert_eq!(manifest_features()["full"], INTEGRATIONS.map(String::from));
+}
+
+#[test]
+fn readme_lists_every_feature() {
+    let readme = include_str!("../README.md");
+    let building = readme
+        .split("### Building")
+        .nth(1)
+        .and_then(|section| section.split("\n### ").next())
+        .expect("README has a Building section");
+
+    for name in manifest_features().keys().filter(|n| *n != "default") {
+        assert!(
+            building.contains(&format!("| `{name}` |")),
+            "{name} is missing from the README"
+        );
+    }
+}
+
+#[test]
+fn configured_integrations_report_whether_they_are_built() {
+    let config = config(json!({
+        "alert_channel_id": 2,
+        "alert_chart_rules": ["offline"],
+        "telegram_bot_token": "123:abc",
+        "telegram_chat_id": "@dayz",
+        "ntfy_url": "https://ntfy.sh/dayz",
+    }));
+
+    assert_eq!(
+        activation(&config, "alert_chart"),
+        configured("charts", cfg!(feature = "charts"))
+    );
+    assert_eq!(
+        activation(&config, "telegram"),
+        configured("telegram", cfg!(feature = "telegram"))
+    );
+    assert_eq!(
+        activation(&config, "ntfy"),
+        configured("ntfy", cfg!(feature = "ntfy"))
+    );
+
+    let built = [cfg!(feature = "telegram"), cfg!(feature = "ntfy")];
+    assert_eq!(
+        notify::backends(&config).unwrap().len(),
+        built.iter().filter(|b| **b).count()
+    );
+}
+
+#[test]
+fn unconfigured_integrations_are_off_in_any_build() {
+    let config = config(json!({}));
+
+    for name in ["alert_chart", "telegram", "ntfy"] {
+        assert_eq!(activation(&config, name), Activation::Off, "{name}");
+    }
+}
+
+#[test]
+fn missing_backend_is_no_alert_destination() {
+    let config = config(json!({
+        "telegram_bot_token": "123:abc",
+        "telegram_chat_id": "@dayz",
+    }));
+
+    assert_eq!(config.has_alert_destination(), cfg!(feature = "telegram"));
+    assert_eq!(alert_rules(&config).is_empty(), !cfg!(feature = "telegram"));
+}
+
+#[test]
+fn half_configured_backend_is_rejected_in_any_build() {
+    let config = config(json!({ "telegram_bot_token": "123:abc" }));
+
+    assert!(notify::backends(&config).is_err());
+}
+
+#[test]
+fn not_built_names_the_cargo_feature() {
+    assert_eq!(
+        Activation::NotBuilt("charts").to_string(),
+        "not built, needs the `charts` cargo feature"
+    );
+}
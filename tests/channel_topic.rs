@@ -0,0 +1,151 @@
+//! The channel topic: the one-line status rendered into it, and the updater that only edits
+//! it when it changed, and not too often.
+
+mod common;
+
+use dayz_monitor::{
+    monitor::{Monitor, Observation},
+    topic::{render_topic, validate_topic_template, TopicOutcome, TopicUpdater, TOPIC_LIMIT},
+    DayzMonitorConfig, DayzMonitorError, MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info, FakeDiscord, NOW};
+
+const TOPIC_CHANNEL: u64 = 5;
+
+fn online(config: &DayzMonitorConfig, players: u32) -> Observation {
+    Monitor::new(config.recovery_criterion()).observe(Ok(info(players)), true)
+}
+
+fn offline() -> Observation {
+    let mut observation = Observation::unknown();
+    observation.state = MonitorState::Offline;
+    observation
+}
+
+fn config_with_template(template: &str) -> DayzMonitorConfig {
+    config(json!({ "topic_template": template }))
+}
+
+#[test]
+fn topic_follows_the_template_and_language() {
+    let cases = [
+        (json!({}), "🟢 44/60 • queue 0 • updated 08:53 UTC"),
+        (
+            json!({ "bot_language": "de", "number_locale": "de" }),
+            "🟢 44/60 • Warteschlange 0 • aktualisiert 08:53 UTC",
+        ),
+        (
+            json!({ "topic_template": "{server_name}: {status} ({players})" }),
+            "Chernarus 1: Online (44)",
+        ),
+        // Topics are one line.
+        (
+            json!({ "topic_template": "{status_emoji}\n{players}\r\nplayers" }),
+            "🟢 44  players",
+        ),
+    ];
+
+    for (extra, expected) in cases {
+        let config = config(extra.clone());
+        assert_eq!(
+            render_topic(&config, &online(&config, 44), NOW),
+            expected,
+            "{extra}"
+        );
+    }
+}
+
+#[test]
+fn topic_without_a_sample_shows_dashes() {
+    let config = config(json!({}));
+
+    assert_eq!(
+        render_topic(&config, &offline(), NOW),
+        "🔴 –/– • queue – • updated 08:53 UTC"
+    );
+
+    // Config values are still known.
+    let config = config_with_template("{server_name} {address} {map}");
+    assert_eq!(
+        render_topic(&config, &offline(), NOW),
+        "Chernarus 1 127.0.0.1:2303 –"
+    );
+}
+
+#[test]
+fn long_topic_is_cut_at_the_limit() {
+    let config = config_with_template(&format!("{} {{players}}", "x".repeat(2 * TOPIC_LIMIT)));
+
+    let topic = render_topic(&config, &online(&config, 44), NOW);
+
+    assert_eq!(topic.chars().count(), TOPIC_LIMIT);
+    assert!(topic.ends_with('…'));
+}
+
+#[test]
+fn unknown_placeholders_are_rejected() {
+    assert!(validate_topic_template(&config(json!({}))).is_ok());
+    assert!(validate_topic_template(&config_with_template("{status} {updated} {queue}")).is_ok());
+    assert!(matches!(
+        validate_topic_template(&config_with_template("{status} {uptime}")),
+        Err(DayzMonitorError::InvalidTopicTemplate(p)) if p == "uptime"
+    ));
+}
+
+#[tokio::test]
+async fn topic_is_only_edited_when_it_changed_and_not_too_often() {
+    let fake: FakeDiscord<()> = FakeDiscord::default();
+    let mut updater = TopicUpdater::new(TOPIC_CHANNEL, 300);
+
+    let steps = [
+        ("🟢 44/60", 0, TopicOutcome::Set),
+        ("🟢 44/60", 60, TopicOutcome::Unchanged),
+        ("🟢 45/60", 120, TopicOutcome::Throttled),
+        ("🟢 45/60", 299, TopicOutcome::Throttled),
+        ("🟢 45/60", 300, TopicOutcome::Set),
+        // Long after the last edit, but already shown.
+        ("🟢 45/60", 900, TopicOutcome::Unchanged),
+        ("🔴 –/–", 900, TopicOutcome::Set),
+    ];
+
+    for (topic, after, expected) in steps {
+        let outcome = updater
+            .update(&fake, topic.to_string(), NOW + after)
+            .await
+            .unwrap();
+        assert_eq!(outcome, expected, "{topic} after {after}s");
+    }
+
+    assert_eq!(
+        fake.topics(),
+        [
+            (TOPIC_CHANNEL, "🟢 44/60".to_string()),
+            (TOPIC_CHANNEL, "🟢 45/60".to_string()),
+            (TOPIC_CHANNEL, "🔴 –/–".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failed_edit_is_retried_after_the_interval() {
+    let fake: FakeDiscord<()> = FakeDiscord::default();
+    let mut updater = TopicUpdater::new(TOPIC_CHANNEL, 300);
+
+    fake.fail_edits(true);
+    assert!(updater
+        .update(&fake, "🟢 44/60".to_string(), NOW)
+        .await
+        .is_err());
+    fake.fail_edits(false);
+
+    for (after, expected) in [(60, TopicOutcome::Throttled), (300, TopicOutcome::Set)] {
+        let outcome = updater
+            .update(&fake, "🟢 44/60".to_string(), NOW + after)
+            .await
+            .unwrap();
+        assert_eq!(outcome, expected, "after {after}s");
+    }
+    assert_eq!(fake.topics().len(), 1);
+}
@@ -0,0 +1,141 @@
+//! Finding and deleting status messages of earlier runs, over channels holding a mix of the
+//! bot's current and old status messages, its other messages and other people's.
+
+mod common;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use dayz_monitor::{
+    cleanup::{self, find_orphans, CleanupScope, Orphan, STATUS_MARKER},
+    transport::ChannelMessage,
+};
+
+use common::{FakeDiscord, BOT, NOW};
+
+const SOMEONE: u64 = 2;
+const STATUS_CHANNEL: u64 = 10;
+const STAFF_CHANNEL: u64 = 20;
+const HOUR: u64 = 3600;
+
+fn message(id: u64, author_id: u64, age_secs: u64, footer: Option<&str>) -> ChannelMessage {
+    ChannelMessage {
+        id,
+        author_id,
+        created_unix: NOW - age_secs,
+        footer: footer.map(str::to_string),
+    }
+}
+
+type FakeChannels = FakeDiscord<()>;
+
+/// The status channel: the current status message, an old one from yesterday and one from an
+/// hour ago, plus an unmarked mod list page, an alert and someone else's message quoting the
+/// marker.
+fn mixed_channels() -> FakeChannels {
+    let status_channel = vec![
+        message(106, BOT, 60, Some(STATUS_MARKER)),
+        message(105, SOMEONE, 120, Some(STATUS_MARKER)),
+        message(104, BOT, HOUR, Some(STATUS_MARKER)),
+        message(103, BOT, 2 * HOUR, Some("Mods 1/2")),
+        message(102, BOT, 3 * HOUR, None),
+        message(101, BOT, 30 * HOUR, Some(STATUS_MARKER)),
+    ];
+    let staff_channel = vec![
+        message(202, BOT, 60, Some(STATUS_MARKER)),
+        message(201, BOT, 48 * HOUR, Some(STATUS_MARKER)),
+    ];
+
+    FakeChannels {
+        channels: Mutex::new(BTreeMap::from([
+            (STATUS_CHANNEL, status_channel),
+            (STAFF_CHANNEL, staff_channel),
+        ])),
+        ..Default::default()
+    }
+}
+
+fn scope() -> CleanupScope {
+    CleanupScope {
+        channels: BTreeSet::from([STATUS_CHANNEL, STAFF_CHANNEL]),
+        tracked: BTreeSet::from([106, 103, 202]),
+    }
+}
+
+fn orphan(channel_id: u64, message_id: u64, age_secs: u64) -> Orphan {
+    Orphan {
+        channel_id,
+        message_id,
+        created_unix: NOW - age_secs,
+    }
+}
+
+#[test]
+fn only_untracked_marked_messages_of_the_bot_match() {
+    let messages = mixed_channels().channels.into_inner().unwrap()[&STATUS_CHANNEL].clone();
+
+    assert_eq!(
+        find_orphans(STATUS_CHANNEL, &messages, BOT, &scope().tracked),
+        vec![
+            orphan(STATUS_CHANNEL, 104, HOUR),
+            orphan(STATUS_CHANNEL, 101, 30 * HOUR),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn scan_covers_every_channel() {
+    let channels = mixed_channels();
+
+    assert_eq!(
+        cleanup::scan(&channels, &scope(), BOT).await,
+        vec![
+            orphan(STATUS_CHANNEL, 104, HOUR),
+            orphan(STATUS_CHANNEL, 101, 30 * HOUR),
+            orphan(STAFF_CHANNEL, 201, 48 * HOUR),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unreadable_channel_is_skipped() {
+    let mut channels = mixed_channels();
+    channels.unreadable.insert(STATUS_CHANNEL);
+
+    assert_eq!(
+        cleanup::scan(&channels, &scope(), BOT).await,
+        vec![orphan(STAFF_CHANNEL, 201, 48 * HOUR)]
+    );
+}
+
+#[tokio::test]
+async fn automatic_cleanup_deletes_only_old_orphans() {
+    let channels = mixed_channels();
+
+    let deleted = cleanup::cleanup(&channels, &scope(), BOT, NOW, 24 * HOUR).await;
+
+    assert_eq!(deleted, 2);
+    assert_eq!(channels.ids(STATUS_CHANNEL), vec![106, 105, 104, 103, 102]);
+    assert_eq!(channels.ids(STAFF_CHANNEL), vec![202]);
+}
+
+#[tokio::test]
+async fn confirmed_listing_is_deleted() {
+    let channels = mixed_channels();
+    let listed = cleanup::scan(&channels, &scope(), BOT).await;
+
+    assert_eq!(cleanup::delete(&channels, &listed).await, 3);
+    assert_eq!(channels.ids(STATUS_CHANNEL), vec![106, 105, 103, 102]);
+    assert_eq!(channels.ids(STAFF_CHANNEL), vec![202]);
+}
+
+#[tokio::test]
+async fn already_deleted_messages_are_not_counted() {
+    let channels = mixed_channels();
+    let listed = cleanup::scan(&channels, &scope(), BOT).await;
+    cleanup::delete(&channels, &listed[..1]).await;
+
+    assert_eq!(cleanup::delete(&channels, &listed).await, 2);
+}
@@ -0,0 +1,224 @@
+//! Steps of the system clock, driven through a manual clock: which are reported, that the
+//! outage and player-hours timers re-anchored after one measure the time that really passed, and
+//! that maintenance windows and wipe announcements follow the stepped clock without firing for
+//! moments it jumped over.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use chrono_tz::UTC;
+use dayz_monitor::{
+    alerts::{alert_rules, AlertKind, AlertTracker},
+    clock::{Clock, StepDetector, STEP_THRESHOLD_SECS},
+    output::Rendered,
+    playtime::PlayerHours,
+    DayzMonitorConfig, MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info, Bot, ManualClock, NOW};
+
+/// Polls a minute apart, the wall clock stepped by the given seconds before each.
+fn steps(stepped: &[i64]) -> Vec<Option<i64>> {
+    let mut clock = ManualClock::new(NOW);
+    let mut detector = StepDetector::default();
+
+    stepped
+        .iter()
+        .map(|&step| {
+            clock.advance(60);
+            clock.step(step);
+            detector.read(&clock)
+        })
+        .collect()
+}
+
+#[test]
+fn first_reading_reports_nothing() {
+    let mut clock = ManualClock::new(NOW);
+    clock.step(-86_400);
+
+    assert_eq!(StepDetector::default().read(&clock), None);
+}
+
+#[test]
+fn forward_and_backward_steps_are_reported_once() {
+    assert_eq!(
+        steps(&[0, 300, 0, -600, 0]),
+        [None, Some(300), None, Some(-600), None]
+    );
+}
+
+#[test]
+fn drift_within_the_threshold_is_ignored() {
+    let threshold = STEP_THRESHOLD_SECS as i64;
+
+    assert_eq!(
+        steps(&[0, threshold, -threshold, threshold + 1, -threshold - 1]),
+        [None, None, None, Some(threshold + 1), Some(-threshold - 1)]
+    );
+}
+
+#[test]
+fn long_sleep_without_a_step_is_no_step() {
+    let mut clock = ManualClock::new(NOW);
+    let mut detector = StepDetector::default();
+    detector.read(&clock);
+
+    clock.advance(6 * 3600);
+
+    assert_eq!(detector.read(&clock), None);
+}
+
+/// Takes the server down, steps the clock by `step` halfway through a five minute outage and
+/// brings it back, returning the message of the alert that it is back.
+fn back_online_after_stepping(step: i64, re_anchor: bool) -> String {
+    let config = config(json!({ "alert_channel_id": 2 }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let mut clock = ManualClock::new(NOW);
+    let mut detector = StepDetector::default();
+    let info = info(12);
+
+    let mut poll = |tracker: &mut AlertTracker, clock: &ManualClock, online: bool| {
+        let stepped = detector.read(clock);
+        if let Some(step) = stepped.filter(|_| re_anchor) {
+            tracker.shift_clock(step);
+        }
+        tracker.observe(&config, online.then_some(&info), clock.unix())
+    };
+
+    poll(&mut tracker, &clock, true);
+    clock.advance(60);
+    let down = poll(&mut tracker, &clock, false);
+    assert_eq!(down[0].kind, AlertKind::Offline);
+
+    clock.advance(150);
+    clock.step(step);
+    poll(&mut tracker, &clock, false);
+    clock.advance(150);
+    let back = poll(&mut tracker, &clock, true);
+
+    assert_eq!(back[0].kind, AlertKind::Online);
+    back[0].message.clone()
+}
+
+#[test]
+fn outage_length_survives_a_step_either_way() {
+    for step in [-3600, 3600] {
+        assert!(
+            back_online_after_stepping(step, true).ends_with("after 5 min."),
+            "{step}"
+        );
+    }
+
+    // What re-anchoring prevents
+    assert!(back_online_after_stepping(3600, false).ends_with("after 65 min."));
+    assert!(back_online_after_stepping(-3600, false).ends_with("after 0 min."));
+}
+
+#[test]
+fn player_hours_credit_the_real_interval_across_a_step() {
+    for step in [-3600, 3600] {
+        let mut clock = ManualClock::new(NOW);
+        let mut detector = StepDetector::default();
+        let mut hours = PlayerHours::default();
+
+        detector.read(&clock);
+        hours.record(clock.unix(), Some(10), 180, UTC);
+        clock.advance(60);
+        clock.step(step);
+
+        let mut unanchored = hours.clone();
+        unanchored.record(clock.unix(), Some(10), 180, UTC);
+        assert_eq!(unanchored.days.values().sum::<u64>(), 0, "{step}");
+
+        hours.shift_clock(detector.read(&clock).unwrap());
+        hours.record(clock.unix(), Some(10), 180, UTC);
+        assert_eq!(hours.days.values().sum::<u64>(), 600, "{step}");
+    }
+}
+
+/// The wipe announcements posted so far.
+fn wipe_announcements(bot: &Bot) -> Vec<String> {
+    bot.fake()
+        .sent()
+        .into_iter()
+        .filter_map(|(_, message)| match message {
+            Rendered::Text(text) if text.starts_with("🧹") => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn maintenance_window_follows_a_stepped_clock() {
+    // 08:53 UTC; the window is from 10:00 to 10:30.
+    let config = config(json!({ "maintenance_windows": ["daily 10:00-10:30 UTC"] }));
+    let mut bot = Bot::start(&config, vec![Some(info(12))]).await;
+
+    bot.cycles(1).await;
+    bot.clock.step(4200);
+    bot.cycles(1).await;
+
+    let observation = bot.shared.last_observation.read().await.clone();
+    assert_eq!(observation.state, MonitorState::Maintenance);
+    assert_eq!(observation.maintenance_until, Some(NOW + 5800));
+    assert_eq!(bot.polls.load(Ordering::SeqCst), 1);
+
+    // Stepped back out of the window, the server is polled again.
+    bot.clock.step(-4200);
+    bot.cycles(1).await;
+
+    assert_eq!(
+        bot.shared.last_observation.read().await.state,
+        MonitorState::Online
+    );
+    assert_eq!(bot.polls.load(Ordering::SeqCst), 2);
+}
+
+/// A wipe at 11:00 UTC, announced an hour ahead; missed announcements would be caught up on.
+fn wipe_config() -> DayzMonitorConfig {
+    config(json!({
+        "next_wipe_date": "2025-10-09 11:00 UTC",
+        "wipe_announce_offsets": ["1h"],
+        "wipe_missed_announcements": "catch_up",
+    }))
+}
+
+#[tokio::test]
+async fn countdown_jumped_over_by_a_step_is_skipped() {
+    let mut bot = Bot::start(&wipe_config(), vec![Some(info(12))]).await;
+
+    // From 08:54 to 10:04, past the countdown at 10:00.
+    bot.cycles(1).await;
+    bot.clock.step(4200);
+    bot.cycles(1).await;
+
+    assert!(wipe_announcements(&bot).is_empty());
+    assert_eq!(
+        bot.shared.persistent.read().await.wipe.announced_secs,
+        [3600]
+    );
+
+    // The wipe itself still comes on time.
+    bot.clock.advance(NOW + 7560 - bot.clock.unix());
+    bot.cycles(2).await;
+
+    let announced = wipe_announcements(&bot);
+    assert_eq!(announced.len(), 1);
+    assert!(!announced[0].contains("<t:"), "{announced:?}");
+}
+
+#[tokio::test]
+async fn wipe_jumped_over_by_a_step_counts_without_an_announcement() {
+    let mut bot = Bot::start(&wipe_config(), vec![Some(info(12))]).await;
+
+    bot.cycles(1).await;
+    bot.clock.step(8000);
+    bot.cycles(1).await;
+
+    assert!(wipe_announcements(&bot).is_empty());
+    let wipe = bot.shared.persistent.read().await.wipe.clone();
+    assert_eq!(wipe.last_wipe_unix, Some(NOW + 7600));
+}
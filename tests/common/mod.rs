@@ -0,0 +1,658 @@
+//! Fakes and builders shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    io,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use dayz_monitor::{
+    alerts::{Alert, AlertKind},
+    audit::state_name,
+    clock::Clock,
+    cycle::{Cycle, CycleHost, CycleTimer, Polled, Poller, SharedState},
+    events::ScheduledEvent,
+    mods::Mod,
+    monitor::Observation,
+    oplog::OpEvent,
+    output::{Encoders, Rendered},
+    render::EmbedRepresentation,
+    state::PersistentState,
+    transport::{
+        ChannelMessage, MessageAuthor, MessageState, Priority, Restore, SendRequest, StatusMessage,
+        StatusTransport,
+    },
+    DayzMonitorConfig, DayzMonitorError, ServerInfo,
+};
+use serde_json::json;
+use tracing::subscriber::DefaultGuard;
+
+pub const NOW: u64 = 1_760_000_000;
+
+/// User id of the bot itself.
+pub const BOT: u64 = 1;
+
+/// A minimal valid config for "Chernarus 1", with the settings in `extra` added or replaced.
+pub fn config(extra: serde_json::Value) -> DayzMonitorConfig {
+    let mut config = json!({
+        "discord_token": "token",
+        "server_address": "127.0.0.1:2303",
+        "server_name": "Chernarus 1",
+        "text_channel_id": 1,
+    });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+
+    serde_json::from_value(config).expect("config deserializes")
+}
+
+/// A successful poll at [`NOW`] finding `players` of 60 online and nobody queued.
+pub fn info(players: u32) -> ServerInfo {
+    ServerInfo {
+        server_time: Some("14:32".to_string()),
+        players_in_queue: Some(0),
+        players,
+        max_players: 60,
+        map: "chernarusplus".to_string(),
+        latency: Some(Duration::from_millis(38)),
+        last_updated_unix: NOW,
+        mods: None,
+        headcount: None,
+        player_names: None,
+        game_port: None,
+        join_port: None,
+        ignored_tokens: Vec::new(),
+    }
+}
+
+/// Clocks that only move when told to.
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Duration,
+    wall_unix: u64,
+}
+
+impl ManualClock {
+    pub fn new(wall_unix: u64) -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+            wall_unix,
+        }
+    }
+
+    /// Lets `secs` pass on both clocks.
+    pub fn advance(&mut self, secs: u64) {
+        self.elapsed += Duration::from_secs(secs);
+        self.wall_unix += secs;
+    }
+
+    /// Steps the wall clock alone, as NTP does.
+    pub fn step(&mut self, secs: i64) {
+        self.wall_unix = self.wall_unix.saturating_add_signed(secs);
+    }
+}
+
+impl Clock for ManualClock {
+    fn instant(&self) -> Instant {
+        self.start + self.elapsed
+    }
+
+    fn unix(&self) -> u64 {
+        self.wall_unix
+    }
+}
+
+/// Log output of this thread, from [`Logs::capture`] until it is dropped.
+pub struct Logs {
+    written: Arc<Mutex<Vec<u8>>>,
+    _guard: DefaultGuard,
+}
+
+impl Logs {
+    /// Captures every level; a `#[tokio::test]` runs on one thread, so that covers its tasks.
+    pub fn capture() -> Self {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let writer = LogWriter(written.clone());
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .without_time()
+            .with_target(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        Logs {
+            written,
+            _guard: tracing::subscriber::set_default(subscriber),
+        }
+    }
+
+    /// Lines logged at `level`, e.g. `"WARN"`, without the level.
+    pub fn at(&self, level: &str) -> Vec<String> {
+        let written = self.written.lock().unwrap();
+        String::from_utf8_lossy(&written)
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix(level))
+            .map(|line| line.trim().to_string())
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+struct LogWriter(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// What a [`FakeDiscord`] was asked to change, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call<P, M> {
+    Send {
+        channel_id: u64,
+        message: M,
+        priority: Priority,
+    },
+    Edit {
+        channel_id: u64,
+        message_id: u64,
+        payload: P,
+    },
+    Delete {
+        channel_id: u64,
+        message_id: u64,
+    },
+    Topic {
+        channel_id: u64,
+        topic: String,
+    },
+    Restore {
+        message_id: u64,
+        restore: Restore,
+    },
+}
+
+/// Channels as Discord would hold them, recording every change the bot makes.
+///
+/// Posted messages get the ids 1001, 1002, … and are not added to the channels.
+pub struct FakeDiscord<P, M = P> {
+    /// Messages of each channel, newest first
+    pub channels: Mutex<BTreeMap<u64, Vec<ChannelMessage>>>,
+    pub calls: Mutex<Vec<Call<P, M>>>,
+    /// Channels the bot may not read
+    pub unreadable: BTreeSet<u64>,
+    /// Messages whose lookup fails as if Discord were down
+    pub unavailable: BTreeSet<u64>,
+    /// Messages whose deletion is refused
+    pub undeletable: BTreeSet<u64>,
+    /// Message and topic edits fail while set
+    pub failing_edits: AtomicBool,
+    /// Posting fails while set
+    pub failing_sends: AtomicBool,
+    /// What the edited messages show besides their embeds
+    pub state: Mutex<MessageState>,
+    /// How long each lookup and edit takes
+    pub delay: Duration,
+    /// How long the edit with a payload takes, instead of `delay`
+    pub edit_delay: Option<fn(&P) -> Duration>,
+    pub inspections: AtomicUsize,
+    pub in_flight: AtomicUsize,
+    pub max_in_flight: AtomicUsize,
+    /// Messages posted so far
+    pub sent: AtomicU64,
+}
+
+impl<P, M> Default for FakeDiscord<P, M> {
+    fn default() -> Self {
+        Self {
+            channels: Mutex::default(),
+            calls: Mutex::default(),
+            unreadable: BTreeSet::new(),
+            unavailable: BTreeSet::new(),
+            undeletable: BTreeSet::new(),
+            failing_edits: AtomicBool::new(false),
+            failing_sends: AtomicBool::new(false),
+            state: Mutex::default(),
+            delay: Duration::ZERO,
+            edit_delay: None,
+            inspections: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+            sent: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<P: Clone, M: Clone> FakeDiscord<P, M> {
+    /// Adds a message posted by `author_id` to a channel.
+    pub fn with(self, channel_id: u64, message_id: u64, author_id: u64) -> Self {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(channel_id)
+            .or_default()
+            .insert(
+                0,
+                ChannelMessage {
+                    id: message_id,
+                    author_id,
+                    created_unix: NOW,
+                    footer: None,
+                },
+            );
+        self
+    }
+
+    /// A fake whose messages show `state` besides their embeds.
+    pub fn showing(state: MessageState) -> Self {
+        Self {
+            state: Mutex::new(state),
+            ..Default::default()
+        }
+    }
+
+    pub fn fail_edits(&self, failing: bool) {
+        self.failing_edits.store(failing, Ordering::SeqCst);
+    }
+
+    pub fn fail_sends(&self, failing: bool) {
+        self.failing_sends.store(failing, Ordering::SeqCst);
+    }
+
+    pub fn calls(&self) -> Vec<Call<P, M>> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Channel and message of everything posted.
+    pub fn sent(&self) -> Vec<(u64, M)> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Send {
+                    channel_id,
+                    message,
+                    ..
+                } => Some((channel_id, message)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Channel, message id and payload of every successful edit.
+    pub fn edits(&self) -> Vec<(u64, u64, P)> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Edit {
+                    channel_id,
+                    message_id,
+                    payload,
+                } => Some((channel_id, message_id, payload)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Payloads of every successful edit.
+    pub fn payloads(&self) -> Vec<P> {
+        self.edits().into_iter().map(|(_, _, p)| p).collect()
+    }
+
+    pub fn deleted(&self) -> Vec<(u64, u64)> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Delete {
+                    channel_id,
+                    message_id,
+                } => Some((channel_id, message_id)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn topics(&self) -> Vec<(u64, String)> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Topic { channel_id, topic } => Some((channel_id, topic)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn restores(&self) -> Vec<Restore> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Restore { restore, .. } => Some(restore),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn inspections(&self) -> usize {
+        self.inspections.load(Ordering::SeqCst)
+    }
+
+    /// Ids of the messages left in a channel, newest first.
+    pub fn ids(&self, channel_id: u64) -> Vec<u64> {
+        self.channels.lock().unwrap()[&channel_id]
+            .iter()
+            .map(|m| m.id)
+            .collect()
+    }
+}
+
+impl<P, M> FakeDiscord<P, M> {
+    /// Waits `delay` as one of the calls currently in flight.
+    async fn busy(&self, delay: Duration) {
+        let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(delay).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn record(&self, call: Call<P, M>) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl<P: Send + 'static, M: Send + 'static> StatusTransport for FakeDiscord<P, M> {
+    type Payload = P;
+    type Message = M;
+    type Error = String;
+
+    async fn edit(&self, channel_id: u64, message_id: u64, payload: P) -> Result<(), String> {
+        let delay = self.edit_delay.map_or(self.delay, |delay| delay(&payload));
+        self.busy(delay).await;
+
+        if self.failing_edits.load(Ordering::SeqCst) {
+            return Err("503 Service Unavailable".to_string());
+        }
+        self.record(Call::Edit {
+            channel_id,
+            message_id,
+            payload,
+        });
+        Ok(())
+    }
+
+    async fn author(&self, channel_id: u64, message_id: u64) -> Result<MessageAuthor, String> {
+        self.busy(self.delay).await;
+
+        if self.unavailable.contains(&message_id) {
+            return Err("503 Service Unavailable".to_string());
+        }
+        let channels = self.channels.lock().unwrap();
+        let message = channels
+            .get(&channel_id)
+            .and_then(|messages| messages.iter().find(|m| m.id == message_id))
+            .ok_or("404 Unknown Message")?;
+
+        Ok(MessageAuthor {
+            id: message.author_id,
+            name: if message.author_id == BOT {
+                "monitor"
+            } else {
+                "someone"
+            }
+            .to_string(),
+        })
+    }
+
+    async fn send(&self, request: SendRequest<M>) -> Result<u64, String> {
+        if self.failing_sends.load(Ordering::SeqCst) {
+            return Err("503 Service Unavailable".to_string());
+        }
+        let id = 1000 + self.sent.fetch_add(1, Ordering::SeqCst) + 1;
+        self.record(Call::Send {
+            channel_id: request.channel_id,
+            message: request.message,
+            priority: request.priority,
+        });
+        Ok(id)
+    }
+
+    async fn recent_messages(
+        &self,
+        channel_id: u64,
+        limit: u8,
+    ) -> Result<Vec<ChannelMessage>, String> {
+        if self.unreadable.contains(&channel_id) {
+            return Err("403 Missing Access".to_string());
+        }
+
+        let channels = self.channels.lock().unwrap();
+        let messages = channels.get(&channel_id).cloned().unwrap_or_default();
+        Ok(messages.into_iter().take(limit.into()).collect())
+    }
+
+    async fn delete(&self, channel_id: u64, message_id: u64) -> Result<(), String> {
+        if self.undeletable.contains(&message_id) {
+            return Err("403 Missing Permissions".to_string());
+        }
+
+        {
+            let mut channels = self.channels.lock().unwrap();
+            let messages = channels.get_mut(&channel_id).ok_or("404 Unknown Channel")?;
+            let before = messages.len();
+            messages.retain(|m| m.id != message_id);
+            if messages.len() == before {
+                return Err("404 Unknown Message".to_string());
+            }
+        }
+        self.record(Call::Delete {
+            channel_id,
+            message_id,
+        });
+        Ok(())
+    }
+
+    async fn set_topic(&self, channel_id: u64, topic: &str) -> Result<(), String> {
+        if self.failing_edits.load(Ordering::SeqCst) {
+            return Err("503 Service Unavailable".to_string());
+        }
+        self.record(Call::Topic {
+            channel_id,
+            topic: topic.to_string(),
+        });
+        Ok(())
+    }
+
+    async fn inspect(&self, _channel_id: u64, _message_id: u64) -> Result<MessageState, String> {
+        self.inspections.fetch_add(1, Ordering::SeqCst);
+        Ok(self.state.lock().unwrap().clone())
+    }
+
+    async fn restore(
+        &self,
+        _channel_id: u64,
+        message_id: u64,
+        restore: Restore,
+    ) -> Result<(), String> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if restore.unsuppress {
+                state.embeds_suppressed = false;
+            }
+            if restore.clear_content {
+                state.content.clear();
+            }
+        }
+        self.record(Call::Restore {
+            message_id,
+            restore,
+        });
+        Ok(())
+    }
+}
+
+/// Answers polls with the given samples in turn, `None` being a server that doesn't answer,
+/// and keeps repeating the last one. Every answer comes from 127.0.0.1:2303.
+pub struct ScriptedPoller {
+    samples: VecDeque<Option<ServerInfo>>,
+    last: Option<ServerInfo>,
+    /// Polls so far, shared with the test while a cycle owns the poller
+    pub polls: Arc<AtomicUsize>,
+}
+
+impl ScriptedPoller {
+    pub fn new(samples: impl IntoIterator<Item = Option<ServerInfo>>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+            last: None,
+            polls: Arc::default(),
+        }
+    }
+}
+
+impl Poller for ScriptedPoller {
+    async fn poll(&mut self, _needs_rules: bool, _timer: &CycleTimer) -> Polled {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        if let Some(sample) = self.samples.pop_front() {
+            self.last = sample;
+        }
+
+        let result = match &self.last {
+            Some(info) => Ok((info.clone(), "127.0.0.1:2303".parse().unwrap())),
+            None => Err(DayzMonitorError::TokioIOError(
+                io::ErrorKind::TimedOut.into(),
+            )),
+        };
+        Polled {
+            result,
+            rules_answered: false,
+        }
+    }
+}
+
+pub type Recorder = FakeDiscord<Rendered>;
+
+const RENDERED: Encoders<Recorder> = Encoders {
+    post: Rendered::clone,
+    edit: Rendered::clone,
+};
+
+/// The cycle's Discord side for a [`FakeDiscord`] of renders. The status embed is titled with
+/// the state and describes the player count; a log message lists its events.
+#[derive(Default)]
+pub struct FakeHost {
+    pub event_fetches: AtomicUsize,
+    pub notified: Mutex<Vec<AlertKind>>,
+}
+
+impl CycleHost for FakeHost {
+    type Transport = Recorder;
+
+    fn encoders(&self) -> &Encoders<Recorder> {
+        &RENDERED
+    }
+
+    fn status_embed(
+        &self,
+        _config: &DayzMonitorConfig,
+        observation: &Observation,
+    ) -> EmbedRepresentation {
+        let players = observation.info.as_ref().map_or("–".to_string(), |i| {
+            format!("{}/{}", i.players, i.max_players)
+        });
+
+        EmbedRepresentation::new()
+            .title(state_name(observation.state))
+            .description(players)
+    }
+
+    fn status_edit(&self, embed: &EmbedRepresentation) -> Rendered {
+        Rendered::Embed(embed.clone())
+    }
+
+    fn log_message(
+        &self,
+        _config: &DayzMonitorConfig,
+        events: &[OpEvent],
+        _at_unix: u64,
+    ) -> Rendered {
+        let lines: Vec<String> = events.iter().map(|e| format!("{e:?}")).collect();
+        Rendered::Text(lines.join("\n"))
+    }
+
+    fn is_not_found(&self, err: &String) -> bool {
+        err.starts_with("404")
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<ScheduledEvent>, String> {
+        self.event_fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(Vec::new())
+    }
+
+    async fn sync_mod_list(&self, _transport: &Recorder, _mods: &[Mod]) {}
+
+    fn notify(&self, alert: &Alert) {
+        self.notified.lock().unwrap().push(alert.kind);
+    }
+}
+
+/// A bot between two cycles, driven by a manual clock starting at [`NOW`].
+pub struct Bot {
+    pub status: StatusMessage<Recorder>,
+    pub host: FakeHost,
+    pub shared: SharedState,
+    pub clock: ManualClock,
+    pub cycle: Cycle<ScriptedPoller>,
+    /// The server's polls so far
+    pub polls: Arc<AtomicUsize>,
+}
+
+impl Bot {
+    pub async fn start(config: &DayzMonitorConfig, samples: Vec<Option<ServerInfo>>) -> Bot {
+        let status = StatusMessage::new(Recorder::default());
+        let host = FakeHost::default();
+        let shared = SharedState::new(PersistentState::default());
+        let poller = ScriptedPoller::new(samples);
+        let polls = poller.polls.clone();
+        let clock = ManualClock::new(NOW);
+        let cycle = Cycle::start(config, poller, &status, &host, &shared, BOT, &clock)
+            .await
+            .expect("cycle starts");
+
+        Bot {
+            status,
+            host,
+            shared,
+            clock,
+            cycle,
+            polls,
+        }
+    }
+
+    /// Runs `n` cycles a minute apart.
+    pub async fn cycles(&mut self, n: usize) {
+        for _ in 0..n {
+            self.cycle
+                .run(&self.status, &self.host, &self.shared, &self.clock)
+                .await;
+            self.clock.advance(60);
+        }
+    }
+
+    pub fn fake(&self) -> &Recorder {
+        self.status.transport()
+    }
+}
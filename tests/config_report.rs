@@ -0,0 +1,205 @@
+//! What `/config` reports: the feature registry with each feature's activation, and the
+//! effective settings with secrets kept out.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use dayz_monitor::{
+    features::{registry, settings, skipped_without_gateway, Activation, Area},
+    DayzMonitorConfig,
+};
+use serde_json::json;
+
+use common::config;
+
+fn activation(config: &DayzMonitorConfig, name: &str) -> Activation {
+    registry(config)
+        .into_iter()
+        .find(|f| f.name == name)
+        .map(|f| f.activation)
+        .unwrap_or_else(|| panic!("no feature {name}"))
+}
+
+fn setting(config: &DayzMonitorConfig, name: &str) -> Option<String> {
+    settings(config)
+        .into_iter()
+        .find(|s| s.name == name)
+        .map(|s| s.value)
+}
+
+#[test]
+fn features_have_unique_names_and_every_area_has_some() {
+    let features = registry(&config(json!({})));
+
+    let names: BTreeSet<&str> = features.iter().map(|f| f.name).collect();
+    assert_eq!(names.len(), features.len());
+
+    for area in Area::ALL {
+        assert!(features.iter().any(|f| f.area == area), "{}", area.name());
+    }
+}
+
+#[test]
+fn activation_explains_itself() {
+    let cases = [
+        (Activation::On, "on"),
+        (Activation::Off, "off"),
+        (
+            Activation::Inactive("GATEWAY is false"),
+            "inactive, GATEWAY is false",
+        ),
+        (
+            Activation::NotBuilt("charts"),
+            "not built, needs the `charts` cargo feature",
+        ),
+    ];
+
+    for (activation, expected) in cases {
+        assert_eq!(activation.to_string(), expected);
+    }
+}
+
+#[test]
+fn features_depending_on_other_settings_say_what_is_missing() {
+    let cases = [
+        ("change_annotations", json!({}), Activation::Off),
+        (
+            "change_annotations",
+            json!({ "change_annotations": true }),
+            Activation::On,
+        ),
+        (
+            "region_ping_sharing",
+            json!({ "region_pings_dir": "/tmp/pings" }),
+            Activation::Inactive("BOT_REGION is unset"),
+        ),
+        (
+            "region_ping_sharing",
+            json!({ "region_pings_dir": "/tmp/pings", "bot_region": "EU" }),
+            Activation::On,
+        ),
+        (
+            "player_count_annotation",
+            json!({ "player_count_annotation": true }),
+            Activation::Inactive("VERIFY_PLAYER_COUNT is off"),
+        ),
+        (
+            "latency_alert",
+            json!({ "alert_latency_ms": 200 }),
+            Activation::Inactive("no alert channel or notification backend"),
+        ),
+        (
+            "latency_alert",
+            json!({ "alert_latency_ms": 200, "alert_channel_id": 2 }),
+            Activation::On,
+        ),
+        (
+            "queue_alert",
+            json!({
+                "alert_queue_threshold": 10,
+                "alert_channel_id": 2,
+                "player_count_mode": "hidden",
+            }),
+            Activation::Inactive("PLAYER_COUNT_MODE is hidden"),
+        ),
+        (
+            "event_impact",
+            json!({ "event_impact": true, "gateway": false }),
+            Activation::Inactive("GATEWAY is false, so there is no /stats"),
+        ),
+        (
+            "slash_commands",
+            json!({ "gateway": false }),
+            Activation::Inactive("GATEWAY is false"),
+        ),
+    ];
+
+    for (name, extra, expected) in cases {
+        assert_eq!(
+            activation(&config(extra.clone()), name),
+            expected,
+            "{name} {extra}"
+        );
+    }
+}
+
+#[test]
+fn without_the_gateway_slash_commands_and_event_impact_are_skipped() {
+    let cases = [
+        (json!({ "gateway": false }), vec!["slash_commands"]),
+        (
+            json!({ "gateway": false, "event_impact": true }),
+            vec!["slash_commands", "event_impact"],
+        ),
+        (json!({ "event_impact": true }), vec![]),
+    ];
+
+    for (extra, expected) in cases {
+        let config = config(extra.clone());
+        assert_eq!(skipped_without_gateway(&config), expected, "{extra}");
+        assert_eq!(config.tracks_event_impact(), expected.is_empty(), "{extra}");
+    }
+}
+
+#[test]
+fn event_impact_is_only_tracked_while_config_reports_it_on() {
+    for (event_impact, gateway) in [(false, false), (false, true), (true, false), (true, true)] {
+        let config = config(json!({ "event_impact": event_impact, "gateway": gateway }));
+
+        assert_eq!(
+            config.tracks_event_impact(),
+            activation(&config, "event_impact") == Activation::On,
+            "EVENT_IMPACT={event_impact} GATEWAY={gateway}"
+        );
+    }
+}
+
+#[test]
+fn secrets_are_only_reported_as_set() {
+    let config = config(json!({
+        "discord_token": "discord-secret",
+        "telegram_bot_token": "123:telegram-secret",
+        "telegram_chat_id": "-100",
+        "ntfy_url": "https://ntfy.sh/secret-topic",
+        "ntfy_token": "tk_ntfy-secret",
+    }));
+
+    for setting in settings(&config) {
+        assert!(!setting.value.contains("secret"), "{setting:?}");
+    }
+    assert_eq!(
+        setting(&config, "TELEGRAM_BOT_TOKEN").as_deref(),
+        Some("set (hidden)")
+    );
+    assert_eq!(
+        setting(&config, "NTFY_URL").as_deref(),
+        Some("set (hidden)")
+    );
+}
+
+#[test]
+fn dependent_settings_only_show_when_they_apply() {
+    let cases = [
+        ("A2S_RETRY_DELAY_MS", json!({ "a2s_retries": 0 }), None),
+        (
+            "A2S_RETRY_DELAY_MS",
+            json!({ "a2s_retries": 2, "a2s_retry_delay_ms": 250 }),
+            Some("250"),
+        ),
+        ("PLAYER_COUNT_TOLERANCE", json!({}), None),
+        ("STATUS_MESSAGE_ID", json!({}), Some("unset")),
+        ("MAINTENANCE_TIMEZONE", json!({}), Some("UTC (default)")),
+        ("MAINTENANCE_WINDOWS", json!({}), Some("none")),
+        ("PLAYER_COUNT_MODE", json!({}), Some("normal")),
+        ("TELEGRAM_RULES", json!({}), Some("all")),
+    ];
+
+    for (name, extra, expected) in cases {
+        assert_eq!(
+            setting(&config(extra.clone()), name).as_deref(),
+            expected,
+            "{name} {extra}"
+        );
+    }
+}
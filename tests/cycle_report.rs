@@ -0,0 +1,165 @@
+//! Timing a cycle: phases and query attempts recorded against fakes with injected delays.
+
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use dayz_monitor::{
+    cycle::{AttemptOutcome, CycleReport, CycleTimer, Phase, QueryAttempt},
+    dualstack::DualStack,
+    DayzMonitorError,
+};
+
+const MS: Duration = Duration::from_millis(1);
+
+fn v4() -> SocketAddr {
+    "203.0.113.7:2303".parse().unwrap()
+}
+
+fn v6() -> SocketAddr {
+    "[2001:db8::7]:2303".parse().unwrap()
+}
+
+/// A query that answers after `delay`, or fails when `fails`.
+async fn fake_query(delay: Duration, fails: bool) -> Result<u32, DayzMonitorError> {
+    tokio::time::sleep(delay).await;
+    match fails {
+        true => Err(DayzMonitorError::ImplausibleResponse(
+            "timed out".to_string(),
+        )),
+        false => Ok(44),
+    }
+}
+
+/// Resolves, races both families, renders and edits, each with its own delay.
+async fn fake_cycle(v4_delay: Duration, v4_fails: bool, v6_delay: Duration) -> CycleReport {
+    let timer = CycleTimer::start();
+    let mut dual_stack = DualStack::default();
+
+    let addrs = timer
+        .time(Phase::Resolve, async {
+            tokio::time::sleep(20 * MS).await;
+            vec![v4(), v6()]
+        })
+        .await;
+    let result = dual_stack
+        .query(&addrs, |addr| {
+            let (delay, fails) = match addr.is_ipv4() {
+                true => (v4_delay, v4_fails),
+                false => (v6_delay, false),
+            };
+            timer.attempt("info", addr, fake_query(delay, fails))
+        })
+        .await;
+    assert!(result.is_ok());
+
+    let render_started = Instant::now();
+    std::thread::sleep(5 * MS);
+    timer.record(Phase::Render, render_started.elapsed());
+
+    timer.time(Phase::Edit, tokio::time::sleep(30 * MS)).await;
+
+    timer.finish()
+}
+
+#[tokio::test]
+async fn failed_attempt_is_followed_by_the_other_family() {
+    let report = fake_cycle(10 * MS, true, 40 * MS).await;
+
+    assert!(report.resolve.unwrap() >= 20 * MS);
+    assert!(report.render.unwrap() >= 5 * MS);
+    assert!(report.edit.unwrap() >= 30 * MS);
+
+    let attempts: Vec<(SocketAddr, &AttemptOutcome)> = report
+        .attempts
+        .iter()
+        .map(|a| (a.addr, &a.outcome))
+        .collect();
+    assert_eq!(
+        attempts,
+        [
+            (
+                v4(),
+                &AttemptOutcome::Failed("Implausible A2S response (timed out).".to_string())
+            ),
+            (v6(), &AttemptOutcome::Answered),
+        ]
+    );
+    assert!(report.attempts[1].took >= 40 * MS);
+
+    // The race waits for the slower family, so the total covers it.
+    assert!(report.total >= (20 + 40 + 5 + 30) * MS);
+}
+
+#[tokio::test]
+async fn losing_family_is_recorded_as_cancelled() {
+    let report = fake_cycle(10 * MS, false, 500 * MS).await;
+
+    let outcomes: Vec<(SocketAddr, &AttemptOutcome)> = report
+        .attempts
+        .iter()
+        .map(|a| (a.addr, &a.outcome))
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            (v4(), &AttemptOutcome::Answered),
+            (v6(), &AttemptOutcome::Cancelled),
+        ]
+    );
+    assert!(report.attempts[1].took < 500 * MS);
+    assert!(report.total < 500 * MS);
+}
+
+#[tokio::test]
+async fn phases_that_did_not_run_stay_empty() {
+    let timer = CycleTimer::start();
+    timer.record(Phase::Render, 3 * MS);
+    timer.record(Phase::Render, 4 * MS);
+
+    let report = timer.finish();
+
+    assert_eq!(report.resolve, None);
+    assert_eq!(report.edit, None);
+    assert_eq!(report.render, Some(7 * MS));
+    assert!(report.attempts.is_empty());
+}
+
+#[test]
+fn report_reads_as_one_line() {
+    let report = CycleReport {
+        started: Instant::now(),
+        resolve: Some(12 * MS),
+        attempts: vec![
+            QueryAttempt {
+                query: "info",
+                addr: v6(),
+                took: 800 * MS,
+                outcome: AttemptOutcome::Cancelled,
+            },
+            QueryAttempt {
+                query: "info",
+                addr: v4(),
+                took: 41 * MS,
+                outcome: AttemptOutcome::Answered,
+            },
+            QueryAttempt {
+                query: "rules",
+                addr: v4(),
+                took: 5_000 * MS,
+                outcome: AttemptOutcome::Failed("timed out".to_string()),
+            },
+        ],
+        render: Some(2 * MS),
+        edit: None,
+        total: 5_061 * MS,
+    };
+
+    assert_eq!(
+        report.to_string(),
+        "resolve 12ms, queries [info [2001:db8::7]:2303 800ms cancelled; \
+         info 203.0.113.7:2303 41ms; rules 203.0.113.7:2303 5000ms failed: timed out], \
+         render 2ms, edit –, total 5061ms"
+    );
+}
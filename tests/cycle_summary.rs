@@ -0,0 +1,102 @@
+//! The per-cycle log line, over scripted pairs of consecutive cycles.
+
+use dayz_monitor::{
+    cycle::{CycleSnapshot, CycleSummary},
+    MonitorState,
+};
+
+fn online(players: u32, queue: u32) -> CycleSnapshot {
+    CycleSnapshot {
+        state: MonitorState::Online,
+        players: Some(players),
+        max_players: Some(60),
+        queue: Some(queue),
+        map: Some("chernarusplus".to_string()),
+    }
+}
+
+fn offline() -> CycleSnapshot {
+    CycleSnapshot {
+        state: MonitorState::Offline,
+        players: None,
+        max_players: None,
+        queue: None,
+        map: None,
+    }
+}
+
+fn line(prev: Option<CycleSnapshot>, next: CycleSnapshot) -> (String, bool) {
+    let summary = CycleSummary::new(prev.as_ref(), next);
+    (summary.to_string(), summary.is_transition())
+}
+
+#[test]
+fn first_cycle_has_no_change_list() {
+    assert_eq!(
+        line(None, online(44, 0)),
+        ("online 44/60, queue 0".to_string(), false)
+    );
+}
+
+#[test]
+fn unchanged_cycle_adds_nothing() {
+    assert_eq!(
+        line(Some(online(44, 0)), online(44, 0)),
+        ("online 44/60, queue 0".to_string(), false)
+    );
+}
+
+#[test]
+fn count_changes_are_listed() {
+    assert_eq!(
+        line(Some(online(44, 0)), online(51, 2)),
+        (
+            "online 51/60, queue 2 (players 44→51, queue 0→2, status unchanged)".to_string(),
+            false
+        )
+    );
+}
+
+#[test]
+fn going_offline_is_a_transition() {
+    assert_eq!(
+        line(Some(online(44, 0)), offline()),
+        (
+            "offline (status online→offline, players 44→–, slots 60→–, queue 0→–, map chernarusplus→–)"
+                .to_string(),
+            true
+        )
+    );
+}
+
+#[test]
+fn degrading_keeps_the_counts() {
+    let degraded = CycleSnapshot {
+        state: MonitorState::Degraded,
+        ..online(44, 0)
+    };
+
+    assert_eq!(
+        line(Some(online(44, 0)), degraded),
+        (
+            "degraded 44/60, queue 0 (status online→degraded)".to_string(),
+            true
+        )
+    );
+}
+
+#[test]
+fn map_change_is_listed() {
+    let next = CycleSnapshot {
+        map: Some("enoch".to_string()),
+        ..online(0, 0)
+    };
+
+    assert_eq!(
+        line(Some(online(0, 0)), next),
+        (
+            "online 0/60, queue 0 (map chernarusplus→enoch, status unchanged)".to_string(),
+            false
+        )
+    );
+}
@@ -0,0 +1,250 @@
+//! `/stats`: the per-day totals folded in from each poll, and the report built from today's
+//! and the last seven days' numbers.
+
+mod common;
+
+use chrono::TimeZone;
+use chrono_tz::{Europe::Berlin, Tz, UTC};
+use dayz_monitor::{
+    daily::{report, DailyStats, DayStats, StatsReport},
+    monitor::Observation,
+    playtime::{PlayerHours, DAYS_KEPT},
+    MonitorState,
+};
+
+use common::info;
+
+fn at(tz: Tz, y: i32, m: u32, d: u32, h: u32) -> u64 {
+    tz.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap().timestamp() as u64
+}
+
+fn observation(state: MonitorState, players: Option<u32>, queue: u32) -> Observation {
+    let mut observation = Observation::unknown();
+    observation.state = state;
+    observation.info = players.map(|players| {
+        let mut info = info(players);
+        info.players_in_queue = Some(queue);
+        info
+    });
+    observation
+}
+
+fn online(players: u32) -> Observation {
+    observation(MonitorState::Online, Some(players), 0)
+}
+
+fn offline() -> Observation {
+    observation(MonitorState::Offline, None, 0)
+}
+
+/// Records the observations one hour apart from `start`, each with the previous one's state.
+fn record_hourly(daily: &mut DailyStats, start: u64, observations: &[Observation], tz: Tz) {
+    let mut previous = MonitorState::Unknown;
+    for (i, observation) in observations.iter().enumerate() {
+        daily.record(observation, previous, start + i as u64 * 3600, tz);
+        previous = observation.state;
+    }
+}
+
+#[test]
+fn polls_count_towards_uptime_except_in_maintenance_or_before_a_result() {
+    let mut daily = DailyStats::default();
+    let observations = [
+        online(10),
+        observation(MonitorState::Degraded, Some(10), 0),
+        offline(),
+        observation(MonitorState::Starting, Some(0), 0),
+        observation(MonitorState::Maintenance, None, 0),
+        Observation::unknown(),
+    ];
+
+    record_hourly(&mut daily, at(UTC, 2025, 10, 9, 8), &observations, UTC);
+
+    let day = &daily.days["2025-10-09"];
+    assert_eq!((day.polls, day.online_polls), (4, 2));
+    assert_eq!(day.uptime_percent(), Some(50.0));
+    assert_eq!(DayStats::default().uptime_percent(), None);
+}
+
+#[test]
+fn restarts_count_comebacks_after_an_outage() {
+    let mut daily = DailyStats::default();
+    let observations = [
+        online(10),
+        offline(),
+        observation(MonitorState::Starting, Some(0), 0),
+        online(3),
+        offline(),
+        offline(),
+        online(5),
+        observation(MonitorState::Degraded, Some(5), 0),
+        online(5),
+    ];
+
+    record_hourly(&mut daily, at(UTC, 2025, 10, 9, 8), &observations, UTC);
+
+    assert_eq!(daily.days["2025-10-09"].restarts, 2);
+}
+
+#[test]
+fn peak_is_when_it_was_first_reached_and_ignores_degraded_samples() {
+    let start = at(UTC, 2025, 10, 9, 8);
+    let mut daily = DailyStats::default();
+    let observations = [
+        observation(MonitorState::Online, Some(0), 0),
+        observation(MonitorState::Online, Some(40), 2),
+        observation(MonitorState::Degraded, Some(99), 50),
+        observation(MonitorState::Online, Some(40), 7),
+        observation(MonitorState::Online, Some(12), 1),
+    ];
+
+    record_hourly(&mut daily, start, &observations, UTC);
+
+    let day = &daily.days["2025-10-09"];
+    assert_eq!(day.peak_players, 40);
+    assert_eq!(day.peak_at_unix, Some(start + 3600));
+    assert_eq!(day.longest_queue, 7);
+}
+
+#[test]
+fn empty_server_still_has_a_peak_time() {
+    let start = at(UTC, 2025, 10, 9, 8);
+    let mut daily = DailyStats::default();
+
+    record_hourly(&mut daily, start, &[online(0), online(0)], UTC);
+
+    assert_eq!(daily.days["2025-10-09"].peak_at_unix, Some(start));
+}
+
+#[test]
+fn days_follow_the_stats_timezone() {
+    // 21:00 and 23:00 UTC on July 1st are on either side of Berlin's midnight.
+    let mut daily = DailyStats::default();
+    daily.record(
+        &online(10),
+        MonitorState::Unknown,
+        at(UTC, 2025, 7, 1, 21),
+        Berlin,
+    );
+    daily.record(
+        &online(20),
+        MonitorState::Online,
+        at(UTC, 2025, 7, 1, 23),
+        Berlin,
+    );
+
+    let days: Vec<(&str, u32)> = daily
+        .days
+        .iter()
+        .map(|(date, day)| (date.as_str(), day.peak_players))
+        .collect();
+    assert_eq!(days, [("2025-07-01", 10), ("2025-07-02", 20)]);
+}
+
+#[test]
+fn last_days_covers_today_and_the_days_before() {
+    let mut daily = DailyStats::default();
+    for day in 1..=DAYS_KEPT as u32 + 3 {
+        daily.record(
+            &online(day),
+            MonitorState::Unknown,
+            at(UTC, 2025, 10, day, 12),
+            UTC,
+        );
+    }
+    assert_eq!(daily.days.len(), DAYS_KEPT);
+
+    let now = at(UTC, 2025, 10, 11, 18);
+    let peaks = |days| -> Vec<u32> {
+        daily
+            .last_days(days, now, UTC)
+            .map(|d| d.peak_players)
+            .collect()
+    };
+
+    assert_eq!(peaks(1), [11]);
+    assert_eq!(peaks(3), [9, 10, 11]);
+    assert_eq!(peaks(0), [11]);
+    // Only what is kept.
+    assert_eq!(peaks(30).len(), DAYS_KEPT);
+}
+
+#[test]
+fn report_without_data_is_empty() {
+    let report = report(
+        &DailyStats::default(),
+        &PlayerHours::default(),
+        &[],
+        at(UTC, 2025, 10, 9, 12),
+        UTC,
+    );
+
+    assert!(report.is_empty());
+    assert_eq!(report, StatsReport::default());
+}
+
+#[test]
+fn report_combines_today_and_the_week() {
+    let now = at(UTC, 2025, 10, 9, 12);
+    let mut daily = DailyStats::default();
+    // Eight days ago (outside the week): all offline.
+    record_hourly(
+        &mut daily,
+        at(UTC, 2025, 10, 1, 0),
+        &[offline(), offline()],
+        UTC,
+    );
+    // Yesterday: 3 of 4 polls answered.
+    record_hourly(
+        &mut daily,
+        at(UTC, 2025, 10, 8, 0),
+        &[online(5), online(5), offline(), online(5)],
+        UTC,
+    );
+    // Today: a restart and a queue.
+    record_hourly(
+        &mut daily,
+        at(UTC, 2025, 10, 9, 0),
+        &[
+            offline(),
+            online(30),
+            observation(MonitorState::Online, Some(60), 4),
+            online(50),
+        ],
+        UTC,
+    );
+    let mut player_hours = PlayerHours::default();
+    player_hours.days.insert("2025-10-09".to_string(), 5_400);
+
+    let report = report(&daily, &player_hours, &[], now, UTC);
+
+    assert_eq!(
+        report,
+        StatsReport {
+            peak: Some((60, at(UTC, 2025, 10, 9, 2))),
+            uptime_today: Some(75.0),
+            uptime_week: Some(75.0),
+            player_hours_today: Some(1.5),
+            longest_queue: Some(4),
+            restarts_today: Some(1),
+            unique_players_today: None,
+            longest_session_today_secs: None,
+            unmonitored_week_secs: None,
+            events: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn quiet_day_leaves_out_queue_and_player_hours() {
+    let now = at(UTC, 2025, 10, 9, 12);
+    let mut daily = DailyStats::default();
+    record_hourly(&mut daily, at(UTC, 2025, 10, 9, 0), &[online(0)], UTC);
+
+    let report = report(&daily, &PlayerHours::default(), &[], now, UTC);
+
+    assert_eq!(report.longest_queue, None);
+    assert_eq!(report.player_hours_today, None);
+    assert_eq!(report.restarts_today, Some(0));
+    assert_eq!(report.uptime_today, Some(100.0));
+}
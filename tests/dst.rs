@@ -0,0 +1,216 @@
+//! Local times across DST transitions: skipped times resolve to the end of the gap, repeated
+//! times to their first occurrence, and zones without DST are left alone. Maintenance windows,
+//! planned wipes and day boundaries all go through the same resolution.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::{
+    America::{New_York, Santiago},
+    Asia::{Kolkata, Tokyo},
+    Australia::{Lord_Howe, Sydney},
+    Europe::Berlin,
+    Pacific::Apia,
+    Tz, UTC,
+};
+use dayz_monitor::{
+    calendar::{local_date, localize, next_midnight},
+    schedule::Window,
+    wipe::WipePlan,
+};
+
+fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d)
+        .unwrap()
+        .and_hms_opt(h, min, 0)
+        .unwrap()
+}
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+#[test]
+fn local_times_resolve_once_across_transitions() {
+    let cases: [(&str, Tz, NaiveDateTime, DateTime<Utc>); 17] = [
+        // Berlin springs forward from 02:00 CET to 03:00 CEST.
+        (
+            "before the gap",
+            Berlin,
+            local(2025, 3, 30, 1, 59),
+            utc(2025, 3, 30, 0, 59),
+        ),
+        (
+            "gap start",
+            Berlin,
+            local(2025, 3, 30, 2, 0),
+            utc(2025, 3, 30, 1, 0),
+        ),
+        (
+            "inside the gap",
+            Berlin,
+            local(2025, 3, 30, 2, 30),
+            utc(2025, 3, 30, 1, 0),
+        ),
+        (
+            "last skipped minute",
+            Berlin,
+            local(2025, 3, 30, 2, 59),
+            utc(2025, 3, 30, 1, 0),
+        ),
+        (
+            "gap end",
+            Berlin,
+            local(2025, 3, 30, 3, 0),
+            utc(2025, 3, 30, 1, 0),
+        ),
+        // Berlin falls back from 03:00 CEST to 02:00 CET; 02:xx happens twice.
+        (
+            "overlap start",
+            Berlin,
+            local(2025, 10, 26, 2, 0),
+            utc(2025, 10, 26, 0, 0),
+        ),
+        (
+            "inside the overlap",
+            Berlin,
+            local(2025, 10, 26, 2, 30),
+            utc(2025, 10, 26, 0, 30),
+        ),
+        (
+            "after the overlap",
+            Berlin,
+            local(2025, 10, 26, 3, 0),
+            utc(2025, 10, 26, 2, 0),
+        ),
+        // US transitions at 02:00 and 01:00 local.
+        (
+            "inside the gap",
+            New_York,
+            local(2025, 3, 9, 2, 30),
+            utc(2025, 3, 9, 7, 0),
+        ),
+        (
+            "inside the overlap",
+            New_York,
+            local(2025, 11, 2, 1, 30),
+            utc(2025, 11, 2, 5, 30),
+        ),
+        // Southern hemisphere: forward in October, back in April.
+        (
+            "inside the gap",
+            Sydney,
+            local(2025, 10, 5, 2, 30),
+            utc(2025, 10, 4, 16, 0),
+        ),
+        (
+            "inside the overlap",
+            Sydney,
+            local(2025, 4, 6, 2, 30),
+            utc(2025, 4, 5, 15, 30),
+        ),
+        // Lord Howe only moves its clocks by half an hour.
+        (
+            "inside the gap",
+            Lord_Howe,
+            local(2025, 10, 5, 2, 15),
+            utc(2025, 10, 4, 15, 30),
+        ),
+        // Samoa skipped all of 2011-12-30 crossing the date line.
+        (
+            "skipped day",
+            Apia,
+            local(2011, 12, 30, 12, 0),
+            utc(2011, 12, 30, 10, 0),
+        ),
+        // Zones without DST: the same offset all year.
+        (
+            "no DST",
+            Tokyo,
+            local(2025, 3, 30, 2, 30),
+            utc(2025, 3, 29, 17, 30),
+        ),
+        (
+            "no DST",
+            Kolkata,
+            local(2025, 10, 26, 2, 30),
+            utc(2025, 10, 25, 21, 0),
+        ),
+        (
+            "no DST",
+            UTC,
+            local(2025, 3, 30, 2, 30),
+            utc(2025, 3, 30, 2, 30),
+        ),
+    ];
+
+    for (what, tz, local, expected) in cases {
+        assert_eq!(localize(tz, local), expected, "{what}: {local} {tz}");
+    }
+}
+
+#[test]
+fn days_start_at_midnight_or_when_it_was_skipped() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let cases = [
+        (Berlin, date(2025, 3, 29), utc(2025, 3, 29, 23, 0)),
+        (Berlin, date(2025, 3, 30), utc(2025, 3, 30, 22, 0)),
+        (Berlin, date(2025, 10, 25), utc(2025, 10, 25, 22, 0)),
+        (Berlin, date(2025, 10, 26), utc(2025, 10, 26, 23, 0)),
+        // Chile springs forward at midnight, so September 7th starts at 01:00.
+        (Santiago, date(2025, 9, 6), utc(2025, 9, 7, 4, 0)),
+        // Samoa's December 30th, 2011 never started; the 29th ran into the 31st.
+        (Apia, date(2011, 12, 29), utc(2011, 12, 30, 10, 0)),
+        (Tokyo, date(2025, 3, 29), utc(2025, 3, 29, 15, 0)),
+    ];
+
+    for (tz, date, expected) in cases {
+        let start = next_midnight(date, tz).unwrap();
+        assert_eq!(start, expected, "after {date} in {tz}");
+        assert_ne!(local_date(start.timestamp() as u64, tz), date);
+        assert_eq!(local_date(start.timestamp() as u64 - 1, tz), date);
+    }
+}
+
+#[test]
+fn maintenance_windows_run_once_on_transition_days() {
+    let window = Window::parse("daily 02:30-03:30 Europe/Berlin", UTC).unwrap();
+    let cases = [
+        // The skipped start moves to 03:00, leaving half an hour.
+        (utc(2025, 3, 30, 0, 59), None),
+        (utc(2025, 3, 30, 1, 0), Some(utc(2025, 3, 30, 1, 30))),
+        (utc(2025, 3, 30, 1, 30), None),
+        // The start happens at its first occurrence, the end after the repeated hour.
+        (utc(2025, 10, 26, 0, 29), None),
+        (utc(2025, 10, 26, 0, 30), Some(utc(2025, 10, 26, 2, 30))),
+        (utc(2025, 10, 26, 1, 45), Some(utc(2025, 10, 26, 2, 30))),
+        (utc(2025, 10, 26, 2, 30), None),
+        // An ordinary day for comparison.
+        (utc(2025, 7, 1, 0, 30), Some(utc(2025, 7, 1, 1, 30))),
+    ];
+
+    for (now, expected) in cases {
+        assert_eq!(window.active_until(now), expected, "at {now}");
+    }
+
+    // Without DST the window is an hour every day.
+    let tokyo = Window::parse("daily 02:30-03:30 Asia/Tokyo", UTC).unwrap();
+    assert_eq!(
+        tokyo.active_until(utc(2025, 3, 29, 17, 30)),
+        Some(utc(2025, 3, 29, 18, 30))
+    );
+}
+
+#[test]
+fn wipes_planned_at_a_skipped_or_repeated_time_happen_once() {
+    let cases = [
+        ("2026-03-29 02:30 Europe/Berlin", utc(2026, 3, 29, 1, 0)),
+        ("2026-10-25 02:30 Europe/Berlin", utc(2026, 10, 25, 0, 30)),
+        ("2026-03-08 02:15 America/New_York", utc(2026, 3, 8, 7, 0)),
+        ("2026-03-29 02:30 Asia/Tokyo", utc(2026, 3, 28, 17, 30)),
+        ("2026-03-29 02:30", utc(2026, 3, 29, 2, 30)),
+    ];
+
+    for (spec, expected) in cases {
+        let plan = WipePlan::parse(spec, &[], UTC).unwrap();
+        assert_eq!(plan.at_unix, expected.timestamp() as u64, "{spec}");
+    }
+}
@@ -0,0 +1,158 @@
+//! Dual-stack queries: racing IPv4 against IPv6, sticking to the winner, and racing again
+//! when it fails or after a while.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use dayz_monitor::{
+    dualstack::{DualStack, Family},
+    DayzMonitorError,
+};
+
+/// A server reachable over each family after the given delay, or not at all.
+#[derive(Clone)]
+struct Server {
+    v4: Option<Duration>,
+    v6: Option<Duration>,
+    queried: Arc<Mutex<Vec<Family>>>,
+}
+
+impl Server {
+    fn new(v4: Option<u64>, v6: Option<u64>) -> Self {
+        Self {
+            v4: v4.map(Duration::from_millis),
+            v6: v6.map(Duration::from_millis),
+            queried: Arc::default(),
+        }
+    }
+
+    async fn query(&self, addr: SocketAddr) -> Result<Family, DayzMonitorError> {
+        let family = Family::of(&addr);
+        self.queried.lock().unwrap().push(family);
+
+        let delay = match family {
+            Family::V4 => self.v4,
+            Family::V6 => self.v6,
+        };
+        match delay {
+            Some(delay) => {
+                tokio::time::sleep(delay).await;
+                Ok(family)
+            }
+            None => Err(DayzMonitorError::A2SError(
+                a2s::errors::Error::InvalidResponse,
+            )),
+        }
+    }
+
+    /// The families queried since the last call, IPv4 first: a race polls both in either
+    /// order.
+    fn queried(&self) -> Vec<Family> {
+        let mut queried = std::mem::take(&mut *self.queried.lock().unwrap());
+        queried.sort_by_key(|family| family.name());
+        queried
+    }
+}
+
+fn both() -> Vec<SocketAddr> {
+    vec![
+        "[::1]:2303".parse().unwrap(),
+        "127.0.0.1:2303".parse().unwrap(),
+    ]
+}
+
+async fn winner(
+    dual: &mut DualStack,
+    server: &Server,
+    addrs: &[SocketAddr],
+) -> Result<Family, DayzMonitorError> {
+    dual.query(addrs, |addr| server.query(addr))
+        .await
+        .map(|(family, _)| family)
+}
+
+#[tokio::test(start_paused = true)]
+async fn faster_family_wins_the_race() {
+    let server = Server::new(Some(80), Some(20));
+    let mut dual = DualStack::default();
+
+    assert_eq!(
+        winner(&mut dual, &server, &both()).await.unwrap(),
+        Family::V6
+    );
+    assert_eq!(server.queried(), [Family::V4, Family::V6]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn failing_family_loses_even_when_it_answers_first() {
+    let server = Server::new(Some(80), None);
+    let mut dual = DualStack::default();
+
+    assert_eq!(
+        winner(&mut dual, &server, &both()).await.unwrap(),
+        Family::V4
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn winner_is_queried_alone_until_the_next_race() {
+    let server = Server::new(Some(20), Some(80));
+    let mut dual = DualStack::default();
+    winner(&mut dual, &server, &both()).await.unwrap();
+    server.queried();
+
+    for _ in 0..30 {
+        assert_eq!(
+            winner(&mut dual, &server, &both()).await.unwrap(),
+            Family::V4
+        );
+    }
+    assert_eq!(server.queried(), [Family::V4; 30]);
+
+    winner(&mut dual, &server, &both()).await.unwrap();
+    assert_eq!(server.queried(), [Family::V4, Family::V6]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn failing_winner_is_raced_again() {
+    let mut server = Server::new(Some(20), Some(80));
+    let mut dual = DualStack::default();
+    winner(&mut dual, &server, &both()).await.unwrap();
+
+    server.v4 = None;
+    server.queried();
+    assert_eq!(
+        winner(&mut dual, &server, &both()).await.unwrap(),
+        Family::V6
+    );
+    assert_eq!(server.queried(), [Family::V4, Family::V4, Family::V6]);
+
+    // IPv6 is now the one remembered.
+    winner(&mut dual, &server, &both()).await.unwrap();
+    assert_eq!(server.queried(), [Family::V6]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn single_family_host_is_queried_directly() {
+    let server = Server::new(Some(20), Some(20));
+    let mut dual = DualStack::default();
+    let v4_only = ["127.0.0.1:2303".parse().unwrap()];
+
+    assert_eq!(
+        winner(&mut dual, &server, &v4_only).await.unwrap(),
+        Family::V4
+    );
+    assert_eq!(server.queried(), [Family::V4]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn both_families_failing_is_an_error() {
+    let server = Server::new(None, None);
+    let mut dual = DualStack::default();
+
+    assert!(winner(&mut dual, &server, &both()).await.is_err());
+    assert!(winner(&mut dual, &server, &[]).await.is_err());
+}
@@ -0,0 +1,247 @@
+//! Guarded edits of the status message: how often it is looked at for changes someone else made,
+//! and which of those are undone.
+
+mod common;
+
+use dayz_monitor::transport::{
+    EditGuard, EditOutcome, MessageState, Restore, StatusMessage, StatusUpdate,
+};
+
+use common::{FakeDiscord, Logs};
+
+const CHANNEL: u64 = 10;
+const MESSAGE: u64 = 100;
+
+type FakeMessage = FakeDiscord<u32, ()>;
+
+fn guard(check_every: u32) -> EditGuard {
+    EditGuard {
+        check_every,
+        unsuppress: true,
+        own_content_only: false,
+    }
+}
+
+fn hidden() -> MessageState {
+    MessageState {
+        content: String::new(),
+        embeds_suppressed: true,
+    }
+}
+
+fn annotated() -> MessageState {
+    MessageState {
+        content: "Restart at 18:00, see #announcements".to_string(),
+        embeds_suppressed: false,
+    }
+}
+
+/// Applies updates numbered `from..to` under `guard`.
+async fn edit(
+    status: &StatusMessage<FakeMessage>,
+    guard: &EditGuard,
+    seqs: std::ops::Range<u32>,
+) -> Vec<Result<EditOutcome, String>> {
+    let mut outcomes = Vec::new();
+    for seq in seqs {
+        let update = StatusUpdate {
+            seq: seq.into(),
+            payload: seq,
+        };
+        outcomes.push(status.apply_guarded(CHANNEL, MESSAGE, update, guard).await);
+    }
+    outcomes
+}
+
+#[test]
+fn restore_follows_the_guard() {
+    let both = MessageState {
+        embeds_suppressed: true,
+        ..annotated()
+    };
+
+    assert_eq!(
+        guard(10).restore(&MessageState::default()),
+        Restore::default()
+    );
+    assert_eq!(
+        guard(10).restore(&both),
+        Restore {
+            unsuppress: true,
+            clear_content: false
+        }
+    );
+
+    let strict = EditGuard {
+        unsuppress: false,
+        own_content_only: true,
+        ..guard(10)
+    };
+    assert_eq!(
+        strict.restore(&both),
+        Restore {
+            unsuppress: false,
+            clear_content: true
+        }
+    );
+
+    let whitespace = MessageState {
+        content: " \n".to_string(),
+        embeds_suppressed: false,
+    };
+    assert!(strict.restore(&whitespace).is_empty());
+}
+
+#[tokio::test]
+async fn hidden_embeds_are_shown_again() {
+    let status = StatusMessage::new(FakeMessage::showing(hidden()));
+
+    edit(&status, &guard(10), 1..2).await;
+
+    let message = status.transport();
+    assert_eq!(
+        message.restores(),
+        [Restore {
+            unsuppress: true,
+            clear_content: false
+        }]
+    );
+    assert_eq!(*message.state.lock().unwrap(), MessageState::default());
+    assert_eq!(message.payloads(), [1]);
+}
+
+#[tokio::test]
+async fn hidden_embeds_stay_hidden_when_unsuppressing_is_off() {
+    let status = StatusMessage::new(FakeMessage::showing(hidden()));
+    let guard = EditGuard {
+        unsuppress: false,
+        ..guard(10)
+    };
+
+    edit(&status, &guard, 1..2).await;
+
+    let message = status.transport();
+    assert_eq!(message.inspections(), 1);
+    assert!(message.restores().is_empty());
+    assert_eq!(*message.state.lock().unwrap(), hidden());
+}
+
+#[tokio::test]
+async fn added_text_is_kept_unless_only_own_content_is_allowed() {
+    let status = StatusMessage::new(FakeMessage::showing(annotated()));
+    edit(&status, &guard(10), 1..2).await;
+    assert!(status.transport().restores().is_empty());
+    assert_eq!(*status.transport().state.lock().unwrap(), annotated());
+
+    let status = StatusMessage::new(FakeMessage::showing(annotated()));
+    let guard = EditGuard {
+        own_content_only: true,
+        ..guard(10)
+    };
+    edit(&status, &guard, 1..2).await;
+    assert_eq!(
+        status.transport().restores(),
+        [Restore {
+            unsuppress: false,
+            clear_content: true
+        }]
+    );
+    assert_eq!(
+        *status.transport().state.lock().unwrap(),
+        MessageState::default()
+    );
+}
+
+#[tokio::test]
+async fn untouched_message_is_only_looked_at() {
+    let status = StatusMessage::new(FakeMessage::default());
+
+    edit(&status, &guard(10), 1..4).await;
+
+    let message = status.transport();
+    assert_eq!(message.inspections(), 1);
+    assert!(message.restores().is_empty());
+    assert_eq!(message.payloads(), [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn message_is_looked_at_first_and_then_every_n_edits() {
+    let status = StatusMessage::new(FakeMessage::default());
+
+    edit(&status, &guard(3), 1..8).await;
+
+    // Before edits 1, 4 and 7
+    assert_eq!(status.transport().inspections(), 3);
+}
+
+#[tokio::test]
+async fn failed_edit_makes_the_next_one_look() {
+    let status = StatusMessage::new(FakeMessage::default());
+    let guard = guard(0);
+
+    edit(&status, &guard, 1..3).await;
+    assert_eq!(status.transport().inspections(), 1);
+
+    status.transport().fail_edits(true);
+    let failed = edit(&status, &guard, 3..4).await;
+    assert!(failed[0].is_err());
+    assert_eq!(status.transport().inspections(), 1);
+
+    status.transport().fail_edits(false);
+    *status.transport().state.lock().unwrap() = hidden();
+    let outcomes = edit(&status, &guard, 4..7).await;
+    assert!(outcomes.iter().all(|o| *o == Ok(EditOutcome::Applied)));
+    assert_eq!(status.transport().inspections(), 2);
+    assert_eq!(status.transport().restores().len(), 1);
+}
+
+#[tokio::test]
+async fn unguarded_edits_never_look() {
+    let status = StatusMessage::new(FakeMessage::showing(hidden()));
+
+    for seq in 1..4 {
+        let update = StatusUpdate { seq, payload: 0 };
+        status.apply(CHANNEL, MESSAGE, update).await.unwrap();
+    }
+
+    assert_eq!(status.transport().inspections(), 0);
+}
+
+#[tokio::test]
+async fn kept_changes_are_warned_about_once() {
+    let logs = Logs::capture();
+    let status = StatusMessage::new(FakeMessage::showing(annotated()));
+
+    edit(&status, &guard(1), 1..5).await;
+
+    let added = "Someone added text to message 100, keeping it";
+    let warned = logs.at("WARN");
+    assert_eq!(warned.len(), 1, "{warned:?}");
+    assert!(warned[0].starts_with(added), "{warned:?}");
+    let repeated = logs.at("DEBUG");
+    assert_eq!(repeated.iter().filter(|l| l.starts_with(added)).count(), 3);
+
+    // Changed again, it is news again.
+    status.transport().state.lock().unwrap().content = "Wipe on Friday".to_string();
+    edit(&status, &guard(1), 5..7).await;
+    assert_eq!(logs.at("WARN").len(), 2);
+}
+
+#[tokio::test]
+async fn undone_changes_are_warned_about_each_time() {
+    let logs = Logs::capture();
+    let status = StatusMessage::new(FakeMessage::showing(hidden()));
+
+    edit(&status, &guard(1), 1..3).await;
+    *status.transport().state.lock().unwrap() = hidden();
+    edit(&status, &guard(1), 3..4).await;
+
+    let warned = logs.at("WARN");
+    assert_eq!(
+        warned,
+        [
+            "Someone hid the embeds of message 100, showing them again",
+            "Someone hid the embeds of message 100, showing them again",
+        ]
+    );
+}
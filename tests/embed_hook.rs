@@ -0,0 +1,227 @@
+//! Post-processing the rendered status embed, through registered closures and through a
+//! declarative patch file.
+
+mod common;
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use dayz_monitor::{
+    hook::{EmbedPatch, FieldPatch, PostRender, HOOK_BUDGET},
+    monitor::{Monitor, Observation},
+    render::EmbedRepresentation,
+    DayzMonitorError, MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info};
+
+fn observe(players: u32) -> Observation {
+    let config = config(json!({}));
+
+    Monitor::new(config.recovery_criterion()).observe(Ok(info(players)), true)
+}
+
+/// Shaped like the bundled status embed.
+fn status_embed() -> EmbedRepresentation {
+    EmbedRepresentation::new()
+        .title("🟢 Chernarus 1 — Online")
+        .description("👥 Players: **44 / 60**")
+        .colour(0x57F287)
+        .field("📍 Address", "`127.0.0.1:2303`", true)
+        .field("🔄 Update", "`60s`", true)
+        .field("🗺️ Map", "Chernarus", true)
+}
+
+#[tokio::test]
+async fn hooks_run_in_order_with_the_observation() {
+    let mut post_render = PostRender::new();
+    post_render.register(|observation, embed| {
+        let players = observation.info.as_ref().map_or(0, |i| i.players);
+        embed.field("Seats left", (60 - players).to_string(), true)
+    });
+    post_render.register(|_, embed| {
+        let title = embed.title.clone().unwrap_or_default();
+        embed.title(format!("{title} ★"))
+    });
+
+    let patched = post_render
+        .apply(&observe(44), status_embed(), HOOK_BUDGET)
+        .await;
+
+    assert_eq!(
+        patched,
+        status_embed()
+            .field("Seats left", "16", true)
+            .title("🟢 Chernarus 1 — Online ★")
+    );
+}
+
+#[tokio::test]
+async fn no_hooks_leave_the_embed_alone() {
+    let post_render = PostRender::new();
+
+    assert!(post_render.is_empty());
+    assert_eq!(
+        post_render
+            .apply(&observe(44), status_embed(), HOOK_BUDGET)
+            .await,
+        status_embed()
+    );
+}
+
+#[tokio::test]
+async fn panicking_hook_falls_back_to_the_unpatched_embed() {
+    let mut post_render = PostRender::new();
+    post_render.register(|_, embed| embed.title("changed"));
+    post_render.register(|observation, embed| {
+        assert_eq!(observation.state, MonitorState::Offline, "hook bug");
+        embed
+    });
+
+    let patched = post_render
+        .apply(&observe(44), status_embed(), HOOK_BUDGET)
+        .await;
+
+    assert_eq!(patched, status_embed());
+}
+
+#[tokio::test]
+async fn slow_hook_falls_back_to_the_unpatched_embed() {
+    let mut post_render = PostRender::new();
+    post_render.register(|_, embed| {
+        std::thread::sleep(Duration::from_millis(300));
+        embed.title("late")
+    });
+
+    let patched = post_render
+        .apply(&observe(44), status_embed(), Duration::from_millis(20))
+        .await;
+
+    assert_eq!(patched, status_embed());
+}
+
+#[tokio::test]
+async fn hung_hook_is_not_started_again_until_it_returns() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut post_render = PostRender::new();
+    let counted = runs.clone();
+    post_render.register(move |_, embed| {
+        counted.fetch_add(1, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(300));
+        embed.title("late")
+    });
+    let budget = Duration::from_millis(20);
+
+    for _ in 0..3 {
+        let patched = post_render
+            .apply(&observe(44), status_embed(), budget)
+            .await;
+        assert_eq!(patched, status_embed());
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    post_render
+        .apply(&observe(44), status_embed(), budget)
+        .await;
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn patch_is_parsed() {
+    let patch = EmbedPatch::parse(
+        r#"
+        title = "Event weekend"
+        colour = 0xE67E22
+        remove_fields = ["Update"]
+
+        [[field]]
+        name = "📣 Discord"
+        value = "discord.gg/example"
+        inline = true
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        patch,
+        EmbedPatch {
+            title: Some("Event weekend".to_string()),
+            description: None,
+            colour: Some(0xE67E22),
+            remove_fields: vec!["Update".to_string()],
+            fields: vec![FieldPatch {
+                name: "📣 Discord".to_string(),
+                value: "discord.gg/example".to_string(),
+                inline: true,
+            }],
+        }
+    );
+}
+
+#[test]
+fn patch_overrides_removes_and_adds() {
+    let patch = EmbedPatch::parse(
+        r#"
+        title = "Event weekend"
+        remove_fields = ["update"]
+
+        [[field]]
+        name = "Map"
+        value = "Livonia (event)"
+        inline = true
+
+        [[field]]
+        name = "📣 Discord"
+        value = "discord.gg/example"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        patch.apply(status_embed()),
+        EmbedRepresentation::new()
+            .title("Event weekend")
+            .description("👥 Players: **44 / 60**")
+            .colour(0x57F287)
+            .field("📍 Address", "`127.0.0.1:2303`", true)
+            .field("🗺️ Map", "Livonia (event)", true)
+            .field("📣 Discord", "discord.gg/example", false)
+    );
+}
+
+#[test]
+fn empty_patch_changes_nothing() {
+    let patch = EmbedPatch::parse("").unwrap();
+
+    assert_eq!(patch.apply(status_embed()), status_embed());
+}
+
+#[test]
+fn invalid_patches_are_rejected() {
+    for toml in [
+        "titel = \"typo\"",
+        "colour = \"orange\"",
+        "[[field]]\nname = \"no value\"",
+        "title = ",
+    ] {
+        assert!(EmbedPatch::parse(toml).is_err(), "'{toml}' was accepted");
+    }
+}
+
+#[test]
+fn missing_patch_file_names_the_path() {
+    let err = EmbedPatch::load(Path::new("/nonexistent/patch.toml")).unwrap_err();
+
+    assert!(matches!(
+        err,
+        DayzMonitorError::InvalidEmbedPatch { ref path, .. } if path == "/nonexistent/patch.toml"
+    ));
+}
@@ -0,0 +1,168 @@
+//! Tidying renders that templates and disabled features left with empty parts.
+
+use dayz_monitor::render::{is_blank, EmbedRepresentation, BLANK_NAME, EMPTY_DESCRIPTION};
+
+const TITLES: [Option<&str>; 4] = [None, Some(""), Some("  \n"), Some("🟢 Chernarus 1")];
+
+const DESCRIPTIONS: [Option<&str>; 7] = [
+    None,
+    Some(""),
+    Some("\n\n \n"),
+    Some("** **"),
+    Some("👥 Players: **44 / 60**"),
+    Some("\n\nfirst\n\n\n\n  \nsecond\n\n"),
+    Some("`\u{200B}`"),
+];
+
+const FIELDS: [(&str, &str); 6] = [
+    ("🗺️ Map", "Chernarus"),
+    ("🗺️ Map", ""),
+    ("", "`127.0.0.1:2303`"),
+    ("  ", " \n "),
+    ("Mods", "__ __"),
+    ("**", "line\n\n\n\nline"),
+];
+
+/// Every combination of the parts above, with up to 3 fields.
+fn all_embeds() -> Vec<EmbedRepresentation> {
+    let mut field_lists: Vec<Vec<(&str, &str)>> = vec![Vec::new()];
+    for _ in 0..3 {
+        let longer: Vec<_> = field_lists
+            .iter()
+            .filter(|list| list.len() == field_lists.last().unwrap().len())
+            .flat_map(|list| {
+                FIELDS.iter().map(move |field| {
+                    let mut list = list.clone();
+                    list.push(*field);
+                    list
+                })
+            })
+            .collect();
+        field_lists.extend(longer);
+    }
+
+    let mut embeds = Vec::new();
+    for title in TITLES {
+        for description in DESCRIPTIONS {
+            for footer in [None, Some(" "), Some("dayz-monitor")] {
+                for fields in &field_lists {
+                    let mut embed = EmbedRepresentation::new();
+                    embed.title = title.map(str::to_string);
+                    embed.description = description.map(str::to_string);
+                    embed.footer = footer.map(str::to_string);
+                    for (name, value) in fields {
+                        embed = embed.field(*name, *value, true);
+                    }
+                    embeds.push(embed);
+                }
+            }
+        }
+    }
+
+    embeds
+}
+
+#[test]
+fn every_combination_normalizes_to_a_valid_embed() {
+    let embeds = all_embeds();
+    assert_eq!(embeds.len(), 4 * 7 * 3 * (1 + 6 + 36 + 216));
+
+    for embed in embeds {
+        let normalized = embed.clone().normalized("Chernarus 1");
+        let context = format!("{embed:?} became {normalized:?}");
+
+        let title = normalized.title.as_deref().expect(&context);
+        assert!(!is_blank(title), "{context}");
+
+        let description = normalized.description.as_deref().expect(&context);
+        assert!(!is_blank(description), "{context}");
+        assert!(!description.contains("\n\n\n"), "{context}");
+
+        for field in &normalized.fields {
+            assert!(!field.name.trim().is_empty(), "{context}");
+            assert!(!is_blank(&field.value), "{context}");
+            assert!(!field.value.contains("\n\n\n"), "{context}");
+        }
+        assert!(
+            normalized.footer.as_deref().is_none_or(|f| !is_blank(f)),
+            "{context}"
+        );
+
+        assert_eq!(
+            normalized.clone().normalized("Chernarus 1"),
+            normalized,
+            "{context}"
+        );
+    }
+}
+
+#[test]
+fn complete_embed_is_unchanged() {
+    let embed = EmbedRepresentation::new()
+        .title("🟢 Chernarus 1 — Online")
+        .description("👥 Players: **44 / 60**\n\n⏱️ Server time: **14:32**")
+        .colour(0x57F287)
+        .field("🗺️ Map", "Chernarus", true)
+        .footer("dayz-monitor");
+
+    assert_eq!(embed.clone().normalized("Chernarus 1"), embed);
+}
+
+#[test]
+fn blank_description_takes_the_first_field() {
+    let embed = EmbedRepresentation::new()
+        .title("Chernarus 1")
+        .description("** **")
+        .field("📍 Address", "  ", true)
+        .field("🗺️ Map", "Chernarus", true)
+        .field("🔄 Update", "`60s`", true);
+
+    assert_eq!(
+        embed.normalized("Chernarus 1"),
+        EmbedRepresentation::new()
+            .title("Chernarus 1")
+            .description("**🗺️ Map**\nChernarus")
+            .field("🔄 Update", "`60s`", true)
+    );
+}
+
+#[test]
+fn unnamed_field_is_promoted_without_a_heading() {
+    let embed =
+        EmbedRepresentation::new()
+            .title("Chernarus 1")
+            .field("", "discord.gg/example", false);
+
+    assert_eq!(
+        embed.normalized("Chernarus 1"),
+        EmbedRepresentation::new()
+            .title("Chernarus 1")
+            .description("discord.gg/example")
+    );
+}
+
+#[test]
+fn blank_lines_collapse() {
+    let embed = EmbedRepresentation::new()
+        .title("Chernarus 1")
+        .description("\n\nfirst  \n\n \n\nsecond\n\n");
+
+    assert_eq!(
+        embed.normalized("Chernarus 1").description.as_deref(),
+        Some("first\n\nsecond")
+    );
+}
+
+#[test]
+fn empty_embed_gets_a_title_and_content() {
+    assert_eq!(
+        EmbedRepresentation::new().normalized("Chernarus 1"),
+        EmbedRepresentation::new()
+            .title("Chernarus 1")
+            .description(EMPTY_DESCRIPTION)
+    );
+    assert_eq!(
+        EmbedRepresentation::new().normalized(" ").title.as_deref(),
+        Some(BLANK_NAME)
+    );
+}
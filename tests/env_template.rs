@@ -0,0 +1,22 @@
+//! Keeps `--print-env-template` in step with the config.
+
+use dayz_monitor::env_template::{config_fields, env_vars};
+
+#[test]
+fn every_config_field_has_template_metadata() {
+    let fields = config_fields();
+    let names: Vec<String> = env_vars().iter().map(|v| v.name.to_string()).collect();
+
+    assert!(!fields.is_empty(), "no config fields were found");
+    assert_eq!(
+        names, fields,
+        "env_vars() must list every config field once, in declaration order"
+    );
+}
+
+#[test]
+fn secrets_have_no_default() {
+    for var in env_vars().iter().filter(|v| v.secret) {
+        assert_eq!(var.default, None, "{} is a secret with a default", var.name);
+    }
+}
@@ -0,0 +1,257 @@
+//! Event impact in `/stats`: an event's players against the same local hours of earlier weeks,
+//! from a seeded hourly history.
+
+use chrono::{TimeZone, Weekday};
+use chrono_tz::{Europe::Berlin, Tz, UTC};
+use dayz_monitor::{
+    events::{
+        impacts, weeks_before, window_hours, EventLog, HourlyPlayers, ScheduledEvent, HOURS_KEPT,
+    },
+    i18n::Language,
+};
+
+const HOUR: u64 = 3600;
+const WEEK: u64 = 7 * 86_400;
+
+fn at(tz: Tz, (y, m, d): (i32, u32, u32), h: u32, min: u32) -> u64 {
+    tz.with_ymd_and_hms(y, m, d, h, min, 0).unwrap().timestamp() as u64
+}
+
+/// Friday 2026-10-09 20:00 UTC.
+fn friday_evening() -> u64 {
+    at(UTC, (2026, 10, 9), 20, 0)
+}
+
+fn event(id: u64, start_unix: u64, end_unix: Option<u64>) -> ScheduledEvent {
+    ScheduledEvent {
+        id,
+        name: format!("Event {id}"),
+        start_unix,
+        end_unix,
+    }
+}
+
+/// One poll a minute for `hours` hours from `start`, each finding `players` online.
+fn seed(history: &mut HourlyPlayers, start: u64, hours: u64, players: u32) {
+    for minute in 0..hours * 60 {
+        history.record(start + minute * 60, players);
+    }
+}
+
+/// 10 players on the four Friday evenings before [`friday_evening`].
+fn earlier_fridays() -> HourlyPlayers {
+    let mut history = HourlyPlayers::default();
+    for weeks in 1..=4 {
+        seed(&mut history, friday_evening() - weeks * WEEK, 3, 10);
+    }
+    history
+}
+
+/// [`earlier_fridays`], and `players` on [`friday_evening`].
+fn fridays(players: u32) -> HourlyPlayers {
+    let mut history = earlier_fridays();
+    seed(&mut history, friday_evening(), 3, players);
+    history
+}
+
+#[test]
+fn hourly_history_averages_polls_and_is_capped() {
+    let mut history = HourlyPlayers::default();
+    history.record(friday_evening(), 10);
+    history.record(friday_evening() + 1800, 20);
+    assert_eq!(history.average(friday_evening() + 3599), Some(15.0));
+    assert_eq!(history.average(friday_evening() + HOUR), None);
+
+    for hour in 0..HOURS_KEPT as u64 + 5 {
+        history.record(friday_evening() + hour * HOUR, 1);
+    }
+    assert_eq!(history.hours.len(), HOURS_KEPT);
+    assert_eq!(history.average(friday_evening()), None);
+}
+
+#[test]
+fn window_covers_every_hour_the_event_touches() {
+    let start = friday_evening() + 30 * 60;
+
+    let hours: Vec<u64> = window_hours(start, friday_evening() + 2 * HOUR).collect();
+    assert_eq!(hours, [friday_evening(), friday_evening() + HOUR]);
+
+    let hours: Vec<u64> = window_hours(start, friday_evening() + 2 * HOUR + 60).collect();
+    assert_eq!(hours.len(), 3);
+}
+
+#[test]
+fn earlier_weeks_keep_the_local_time_across_dst() {
+    // Summer time started in Berlin on 2026-03-29.
+    let after = at(Berlin, (2026, 4, 3), 20, 0);
+    let before = at(Berlin, (2026, 3, 27), 20, 0);
+
+    assert_eq!(weeks_before(after, 1, Berlin), before);
+    assert_eq!(after - before, WEEK - HOUR);
+    assert_eq!(weeks_before(after, 1, UTC), after - WEEK);
+}
+
+#[test]
+fn event_is_compared_with_the_same_hours_of_earlier_weeks() {
+    let history = fridays(25);
+    let events = [event(
+        1,
+        friday_evening(),
+        Some(friday_evening() + 2 * HOUR),
+    )];
+
+    let impacts = impacts(&history, &events, friday_evening() + 3 * HOUR, UTC);
+
+    assert_eq!(impacts.len(), 1);
+    let impact = &impacts[0];
+    assert_eq!(impact.name, "Event 1");
+    assert_eq!(impact.players, 25.0);
+    assert_eq!(impact.baseline, 10.0);
+    assert_eq!(impact.delta(), 15);
+    assert_eq!(impact.weekday, Weekday::Fri);
+    assert_eq!(impact.time, "20:00");
+}
+
+#[test]
+fn weekday_and_time_are_local_to_the_stats_timezone() {
+    let history = fridays(25);
+    let events = [event(1, friday_evening(), None)];
+
+    let impacts = impacts(&history, &events, friday_evening() + 3 * HOUR, Berlin);
+
+    // 20:00 UTC is 22:00 in Berlin in October.
+    assert_eq!(impacts[0].time, "22:00");
+    assert_eq!(impacts[0].weekday, Weekday::Fri);
+}
+
+#[test]
+fn quiet_event_has_a_negative_delta() {
+    let history = fridays(4);
+    let events = [event(1, friday_evening(), None)];
+
+    let impacts = impacts(&history, &events, friday_evening() + 3 * HOUR, UTC);
+
+    assert_eq!(impacts[0].delta(), -6);
+}
+
+#[test]
+fn offline_weeks_are_left_out_of_the_baseline() {
+    let mut history = HourlyPlayers::default();
+    seed(&mut history, friday_evening() - WEEK, 2, 10);
+    seed(&mut history, friday_evening() - 3 * WEEK, 2, 20);
+    seed(&mut history, friday_evening(), 2, 30);
+    let events = [event(1, friday_evening(), None)];
+
+    let impacts = impacts(&history, &events, friday_evening() + 3 * HOUR, UTC);
+
+    assert_eq!(impacts[0].baseline, 15.0);
+}
+
+#[test]
+fn event_without_data_to_compare_is_left_out() {
+    let mut history = HourlyPlayers::default();
+    seed(&mut history, friday_evening(), 2, 30);
+    let events = [event(1, friday_evening(), None)];
+    assert!(impacts(&history, &events, friday_evening() + 3 * HOUR, UTC).is_empty());
+
+    // Baseline weeks but no polls while the event ran
+    let mut history = HourlyPlayers::default();
+    seed(&mut history, friday_evening() - WEEK, 2, 10);
+    assert!(impacts(&history, &events, friday_evening() + 3 * HOUR, UTC).is_empty());
+}
+
+#[test]
+fn only_events_of_the_last_week_that_started_are_listed() {
+    let history = fridays(25);
+    let now = friday_evening() + 3 * HOUR;
+    let events = [
+        event(1, friday_evening() - WEEK, None),
+        event(2, friday_evening(), None),
+        event(3, now + HOUR, None),
+    ];
+
+    let impacts = impacts(&history, &events, now, UTC);
+
+    assert_eq!(impacts.len(), 1);
+    assert_eq!(impacts[0].name, "Event 2");
+}
+
+#[test]
+fn event_without_an_end_runs_for_two_hours() {
+    // Players only in the event's third hour
+    let mut history = earlier_fridays();
+    seed(&mut history, friday_evening(), 2, 10);
+    seed(&mut history, friday_evening() + 2 * HOUR, 1, 100);
+    let now = friday_evening() + 4 * HOUR;
+
+    for end in [None, Some(friday_evening())] {
+        let events = [event(1, friday_evening(), end)];
+        assert_eq!(
+            impacts(&history, &events, now, UTC)[0].delta(),
+            0,
+            "{end:?}"
+        );
+    }
+
+    let events = [event(
+        1,
+        friday_evening(),
+        Some(friday_evening() + 3 * HOUR),
+    )];
+    assert_eq!(impacts(&history, &events, now, UTC)[0].delta(), 30);
+}
+
+#[test]
+fn running_event_counts_up_to_now() {
+    let mut history = earlier_fridays();
+    seed(&mut history, friday_evening(), 1, 40);
+    seed(&mut history, friday_evening() + HOUR, 1, 10);
+    let events = [event(1, friday_evening(), None)];
+
+    let impacts = impacts(&history, &events, friday_evening() + 30 * 60, UTC);
+
+    assert_eq!(impacts[0].players, 40.0);
+}
+
+#[test]
+fn past_events_are_remembered_after_discord_drops_them() {
+    let now = friday_evening();
+    let mut log = EventLog::default();
+    log.remember(
+        vec![
+            event(1, now - HOUR, None),
+            event(2, now + HOUR, None),
+            event(3, now + 2 * HOUR, None),
+        ],
+        now,
+    );
+    assert_eq!(log.events.len(), 3);
+
+    // Event 1 is over and no longer listed, event 2 was rescheduled and event 3 cancelled.
+    let later = now + 30 * 60;
+    log.remember(vec![event(2, now + 3 * HOUR, None)], later);
+    let ids: Vec<(u64, u64)> = log.events.iter().map(|e| (e.id, e.start_unix)).collect();
+    assert_eq!(ids, [(1, now - HOUR), (2, now + 3 * HOUR)]);
+
+    // Listed again while it runs, it isn't doubled.
+    log.remember(vec![event(2, now + 3 * HOUR, None)], now + 3 * HOUR);
+    log.remember(vec![event(2, now + 3 * HOUR, None)], now + 4 * HOUR);
+    assert_eq!(log.events.len(), 2);
+
+    log.remember(Vec::new(), now + 2 * WEEK);
+    assert!(log.events.is_empty());
+}
+
+#[test]
+fn comparison_is_worded_per_language() {
+    let cases = [
+        (Language::En, "vs typical Friday 20:00"),
+        (Language::De, "ggü. einem üblichen Freitag 20:00"),
+        (Language::Fr, "par rapport à un vendredi 20:00 habituel"),
+        (Language::Ru, "по сравнению с обычной пятницей 20:00"),
+    ];
+
+    for (lang, expected) in cases {
+        assert_eq!(lang.vs_typical(Weekday::Fri, "20:00"), expected);
+    }
+}
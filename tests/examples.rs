@@ -0,0 +1,132 @@
+//! Smoke tests for the programs under `examples/`, run against a fake server on localhost.
+//!
+//! `cargo test` builds the examples alongside the tests; these run the built binaries.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpStream, UdpSocket},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Answers every query with the same A2S_INFO response: 12 of 60 players on Chernarus, 3
+/// queueing, 14:32 in-game.
+fn fake_server() -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").expect("fake server binds");
+    let addr = socket.local_addr().expect("fake server has an address");
+
+    let mut response = vec![0xFF, 0xFF, 0xFF, 0xFF, 0x49, 0x11];
+    for s in ["Test Server", "chernarusplus", "dayz", "DayZ"] {
+        response.extend(s.as_bytes());
+        response.push(0);
+    }
+    response.extend(221u16.to_le_bytes());
+    response.extend([12, 60, 0, b'd', b'l', 0, 0]);
+    response.extend(b"1.26\0");
+    // Extra data flag: keywords follow
+    response.push(0x20);
+    response.extend(b"battleye,no3rd,lqs3,etm4.000000,entm6.000000,14:32\0");
+
+    thread::spawn(move || {
+        let mut buf = [0u8; 1400];
+        while let Ok((_, from)) = socket.recv_from(&mut buf) {
+            let _ = socket.send_to(&response, from);
+        }
+    });
+
+    addr
+}
+
+fn example(name: &str) -> Command {
+    // Test binaries live in target/<profile>/deps, examples in target/<profile>/examples.
+    let mut path: PathBuf = std::env::current_exe().expect("test binary has a path");
+    path.pop();
+    path.pop();
+    path.push("examples");
+    path.push(name);
+
+    Command::new(path)
+}
+
+#[test]
+fn headless_poller_prints_status_line() {
+    let server = fake_server();
+
+    let output = example("headless_poller")
+        .args([&server.to_string(), "--once"])
+        .output()
+        .expect("headless_poller runs");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("🟠 Full 12/60 • queue 3 • time 14:32 • "),
+        "unexpected output: {stdout}"
+    );
+}
+
+#[test]
+fn prometheus_only_serves_metrics() {
+    let server = fake_server();
+
+    let mut child = example("prometheus_only")
+        .args([&server.to_string(), "--listen", "127.0.0.1:0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("prometheus_only runs");
+
+    let mut banner = String::new();
+    BufReader::new(child.stdout.take().expect("stdout is piped"))
+        .read_line(&mut banner)
+        .expect("prometheus_only announces its address");
+    let listen = banner
+        .trim()
+        .trim_start_matches("Serving metrics on http://")
+        .trim_end_matches("/metrics")
+        .to_string();
+
+    // The first poll may still be running when the listener is up.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let body = loop {
+        let body = scrape(&listen);
+        if body.contains("dayz_players") || Instant::now() > deadline {
+            break body;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let _ = child.kill();
+    let _ = child.wait();
+
+    for line in [
+        "dayz_polls_total 1",
+        "dayz_state{state=\"online\"} 1",
+        "dayz_state{state=\"offline\"} 0",
+        "dayz_players 12",
+        "dayz_max_players 60",
+        "dayz_queue 3",
+    ] {
+        assert!(
+            body.lines().any(|l| l == line),
+            "{line:?} missing from:\n{body}"
+        );
+    }
+}
+
+fn scrape(addr: &str) -> String {
+    let mut stream = TcpStream::connect(addr).expect("metrics listener accepts");
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .expect("request is sent");
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("response is readable");
+
+    response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,65 @@
+//! The first status message: posted directly from a quick first poll, and preceded by a
+//! placeholder only when the first poll is slow.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use dayz_monitor::transport::{render_or_placeholder, PLACEHOLDER_BUDGET};
+use tokio::time::{sleep, Instant};
+
+/// Runs a render taking `render_secs` next to a placeholder taking `placeholder_secs`, and
+/// returns when the placeholder started and when it all finished, in seconds from the start.
+async fn run(render_secs: u64, placeholder_secs: u64) -> (Option<u64>, u64) {
+    let start = Instant::now();
+    let placeholder_at = Arc::new(Mutex::new(None));
+
+    let recorded = placeholder_at.clone();
+    let rendered = render_or_placeholder(
+        async {
+            sleep(Duration::from_secs(render_secs)).await;
+            "rendered"
+        },
+        PLACEHOLDER_BUDGET,
+        async move {
+            *recorded.lock().unwrap() = Some(start.elapsed().as_secs());
+            sleep(Duration::from_secs(placeholder_secs)).await;
+        },
+    )
+    .await;
+
+    assert_eq!(rendered, "rendered");
+    let placeholder_at = *placeholder_at.lock().unwrap();
+    (placeholder_at, start.elapsed().as_secs())
+}
+
+#[tokio::test(start_paused = true)]
+async fn placeholder_only_shows_for_a_slow_first_poll() {
+    let budget = PLACEHOLDER_BUDGET.as_secs();
+    let cases = [
+        // Quick polls are posted as they are.
+        (0, None, 0),
+        (budget - 1, None, budget - 1),
+        // Slow ones get a placeholder once the budget is spent, and still finish.
+        (budget + 1, Some(budget), budget + 1),
+        (60, Some(budget), 60),
+    ];
+
+    for (render_secs, placeholder_at, finished_at) in cases {
+        assert_eq!(
+            run(render_secs, 0).await,
+            (placeholder_at, finished_at),
+            "render taking {render_secs}s"
+        );
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_placeholder_is_waited_for() {
+    let budget = PLACEHOLDER_BUDGET.as_secs();
+
+    // The placeholder post is still going when the render is done, so the real status can't
+    // overtake it.
+    assert_eq!(run(budget + 1, 10).await, (Some(budget), budget + 10));
+}
@@ -0,0 +1,127 @@
+//! Estimating when the server is full, over synthetic player counts sampled once a minute.
+
+use dayz_monitor::forecast::{full_in_minutes, SampleWindow, RAMP_WINDOW_SECS};
+
+const START: u64 = 1_760_000_000;
+const SLOTS: u32 = 60;
+
+/// Samples `curve` at every minute in `0..=minutes`, returning the window and the last count.
+fn sampled(minutes: u64, curve: impl Fn(u64) -> u32) -> (SampleWindow, u32) {
+    let mut window = SampleWindow::new(RAMP_WINDOW_SECS);
+    for minute in 0..=minutes {
+        window.push(START + minute * 60, curve(minute));
+    }
+    (window, curve(minutes))
+}
+
+/// One player every two minutes, starting from `from`.
+fn ramp(from: u32) -> impl Fn(u64) -> u32 {
+    move |minute| from + minute as u32 / 2
+}
+
+#[test]
+fn steady_ramp_is_forecast() {
+    let (window, players) = sampled(30, ramp(30));
+
+    assert_eq!(players, 45);
+    // 15 slots left at half a player a minute.
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 70), Some(30));
+}
+
+#[test]
+fn forecast_is_rounded_up_to_five_minutes() {
+    let (window, players) = sampled(24, |minute| 40 + minute as u32 * 2 / 3);
+
+    assert_eq!(players, 56);
+    // 4 slots left at two thirds of a player a minute is 6 minutes.
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 70), Some(10));
+}
+
+#[test]
+fn nothing_below_the_occupancy_threshold() {
+    let (window, players) = sampled(30, ramp(20));
+
+    assert_eq!(players, 35);
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 70), None);
+    assert!(full_in_minutes(&window, players, SLOTS, 50).is_some());
+}
+
+#[test]
+fn nothing_before_the_window_has_filled() {
+    let (window, players) = sampled(8, |minute| 40 + minute as u32);
+
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), None);
+}
+
+#[test]
+fn plateau_is_not_forecast() {
+    let (window, players) = sampled(30, |_| 50);
+
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), None);
+}
+
+#[test]
+fn ramp_levelling_off_is_not_forecast() {
+    // Rising for the first half of the window, flat since.
+    let (window, players) = sampled(30, |minute| 40 + minute.min(15) as u32);
+
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), None);
+}
+
+#[test]
+fn sudden_drop_is_not_forecast() {
+    // A steady ramp until a crash kicked half the players a few minutes ago.
+    let (window, players) = sampled(
+        30,
+        |minute| {
+            if minute < 26 {
+                30 + minute as u32
+            } else {
+                28
+            }
+        },
+    );
+
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 40), None);
+}
+
+#[test]
+fn slow_trickle_is_beyond_the_horizon() {
+    // One player every eight minutes would take three hours to fill 24 slots.
+    let (window, players) = sampled(30, |minute| 33 + minute as u32 / 8);
+
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), None);
+}
+
+#[test]
+fn full_server_is_not_forecast() {
+    let (window, _) = sampled(30, ramp(30));
+
+    assert_eq!(full_in_minutes(&window, SLOTS, SLOTS, 50), None);
+}
+
+#[test]
+fn nearly_full_server_rounds_to_the_first_step() {
+    let (window, players) = sampled(30, |minute| 29 + minute as u32);
+
+    assert_eq!(players, 59);
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), Some(5));
+}
+
+#[test]
+fn old_samples_fall_out_of_the_window() {
+    // A fast ramp an hour ago, then nothing, must not count.
+    let (window, players) = sampled(90, |minute| 10 + minute.min(40) as u32);
+
+    assert_eq!(window.covered_secs(), RAMP_WINDOW_SECS);
+    assert_eq!(full_in_minutes(&window, players, SLOTS, 50), None);
+}
+
+#[test]
+fn clock_going_back_starts_over() {
+    let (mut window, _) = sampled(30, ramp(30));
+    window.push(START, 45);
+
+    assert_eq!(window.covered_secs(), 0);
+    assert_eq!(full_in_minutes(&window, 45, SLOTS, 50), None);
+}
@@ -0,0 +1,87 @@
+//! Configured status messages are only adopted if the bot posted them itself, since Discord
+//! refuses edits to anyone else's message.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use dayz_monitor::{
+    output::{Output, Outputs, Rendered},
+    transport::verify_own_message,
+    DayzMonitorError,
+};
+use serde_json::json;
+
+use common::{config, FakeDiscord, BOT};
+
+const CHANNEL: u64 = 111;
+const OWN: u64 = 900;
+const FOREIGN: u64 = 901;
+const DOWN: u64 = 902;
+const MISSING: u64 = 903;
+
+type Recorder = FakeDiscord<Rendered>;
+
+fn discord() -> Recorder {
+    let mut fake = Recorder::default()
+        .with(CHANNEL, OWN, BOT)
+        .with(CHANNEL, FOREIGN, 42)
+        .with(CHANNEL, DOWN, BOT);
+    fake.unavailable.insert(DOWN);
+    fake
+}
+
+#[tokio::test]
+async fn only_own_messages_pass_the_check() {
+    let fake = discord();
+
+    assert!(verify_own_message(&fake, CHANNEL, OWN, BOT)
+        .await
+        .unwrap()
+        .is_ok());
+
+    let foreign = verify_own_message(&fake, CHANNEL, FOREIGN, BOT)
+        .await
+        .unwrap();
+    assert!(matches!(
+        foreign,
+        Err(DayzMonitorError::ForeignStatusMessage {
+            message_id: FOREIGN,
+            ref author,
+        }) if author == "someone"
+    ));
+    assert_eq!(
+        foreign.unwrap_err().to_string(),
+        "Message 901 was authored by someone, not me, so it can't be edited."
+    );
+
+    // A failed lookup is reported apart from a foreign author.
+    for id in [DOWN, MISSING] {
+        assert!(
+            verify_own_message(&fake, CHANNEL, id, BOT).await.is_err(),
+            "{id}"
+        );
+    }
+    // Same message, but checked for another bot user.
+    assert!(verify_own_message(&fake, CHANNEL, OWN, 7)
+        .await
+        .unwrap()
+        .is_err());
+}
+
+#[tokio::test]
+async fn adopt_drops_foreign_ids_and_keeps_unchecked_ones() {
+    let base = config(json!({}));
+    // One language each, so the outputs don't share a key.
+    let specs = [(OWN, "en"), (FOREIGN, "de"), (DOWN, "fr")]
+        .into_iter()
+        .map(|(id, lang)| Output::parse(&format!("{CHANNEL}/{id} compact {lang}")).unwrap())
+        .collect();
+    let mut outputs = Outputs::new(&base, specs, &BTreeMap::new());
+
+    outputs.adopt(&discord(), BOT).await;
+
+    let mut kept: Vec<u64> = outputs.message_ids().into_values().collect();
+    kept.sort();
+    assert_eq!(kept, [OWN, DOWN]);
+}
@@ -0,0 +1,119 @@
+//! The status title's full state: when a server counts as full, how the title and colour
+//! show it, and the custom full title.
+
+mod common;
+
+use dayz_monitor::{
+    i18n::Language,
+    monitor::Observation,
+    title::{is_full, render_title, truncate, TitleState},
+    DayzMonitorError, MonitorState, ServerInfo,
+};
+use serde_json::json;
+
+use common::{config, info};
+
+fn sample(players: u32, queue: Option<u32>) -> ServerInfo {
+    let mut info = info(players);
+    info.players_in_queue = queue;
+    info
+}
+
+#[test]
+fn full_means_a_new_player_would_wait() {
+    let cases = [
+        (sample(59, Some(0)), false),
+        (sample(59, None), false),
+        (sample(60, Some(0)), true),
+        (sample(61, None), true),
+        (sample(40, Some(3)), true),
+    ];
+
+    for (info, expected) in cases {
+        assert_eq!(
+            is_full(&info),
+            expected,
+            "{} {:?}",
+            info.players,
+            info.players_in_queue
+        );
+    }
+}
+
+#[test]
+fn only_an_online_sample_can_be_full() {
+    let mut observation = Observation::unknown();
+    observation.info = Some(sample(60, Some(4)));
+
+    for (state, expected) in [
+        (MonitorState::Online, TitleState::Full),
+        (MonitorState::Degraded, TitleState::Degraded),
+        (MonitorState::Starting, TitleState::Starting),
+    ] {
+        observation.state = state;
+        assert_eq!(TitleState::of(&observation), expected, "{state:?}");
+    }
+}
+
+#[test]
+fn full_title_shows_the_queue() {
+    let config = config(json!({ "number_locale": "de" }));
+
+    let queueing = sample(60, Some(1_200));
+    assert_eq!(
+        render_title(&config, TitleState::Full, Some(&queueing), Language::En),
+        "🟠 Chernarus 1 — Full (Queue: 1.200)"
+    );
+
+    let no_queue = sample(60, Some(0));
+    assert_eq!(
+        render_title(&config, TitleState::Full, Some(&no_queue), Language::De),
+        "🟠 Chernarus 1 — Voll"
+    );
+}
+
+#[test]
+fn full_is_orange_and_distinct_from_online() {
+    assert_eq!(TitleState::Full.colour(), 0xE67E22);
+    assert_ne!(TitleState::Full.colour(), TitleState::Online.colour());
+    assert_ne!(TitleState::Full.emoji(), TitleState::Online.emoji());
+}
+
+#[test]
+fn custom_full_title_uses_the_sample() {
+    let config = config(json!({
+        "title_full_template": "⛔ {server_name} full: {players}/{max_players}, {queue} waiting",
+    }));
+    let info = sample(60, Some(6));
+
+    assert_eq!(
+        render_title(&config, TitleState::Full, Some(&info), Language::En),
+        "⛔ Chernarus 1 full: 60/60, 6 waiting"
+    );
+    // Other states keep the default title.
+    assert_eq!(
+        render_title(&config, TitleState::Online, Some(&info), Language::En),
+        "🟢 Chernarus 1 — Online"
+    );
+}
+
+#[test]
+fn custom_full_title_is_validated() {
+    let config = config(json!({ "title_full_template": "{server_name} {duration}" }));
+
+    assert!(matches!(
+        config.validate(),
+        Err(DayzMonitorError::InvalidTitleTemplate(name)) if name == "duration"
+    ));
+}
+
+#[test]
+fn long_titles_are_cut_to_the_discord_limit() {
+    let config = config(json!({ "server_name": "x".repeat(300) }));
+
+    let title = render_title(&config, TitleState::Online, None, Language::En);
+
+    assert_eq!(title.chars().count(), 256);
+    assert!(title.ends_with('…'));
+    assert_eq!(truncate("short".to_string(), 256), "short");
+}
@@ -0,0 +1,125 @@
+//! Implausible A2S samples: which values are rejected, and that a rejected sample degrades the
+//! status with the last good data instead of counting as offline.
+
+mod common;
+
+use dayz_monitor::{monitor::Monitor, DayzMonitorError, MonitorState, SanityBounds, ServerInfo};
+use serde_json::json;
+
+use common::{config, info};
+
+fn sample(players: u32, max_players: u32, queue: Option<u32>) -> ServerInfo {
+    let mut info = info(players);
+    info.max_players = max_players;
+    info.players_in_queue = queue;
+    info
+}
+
+fn implausible(reason: &str) -> Result<ServerInfo, DayzMonitorError> {
+    Err(DayzMonitorError::ImplausibleResponse(reason.to_string()))
+}
+
+fn timed_out() -> Result<ServerInfo, DayzMonitorError> {
+    Err(DayzMonitorError::A2SError(
+        a2s::errors::Error::InvalidResponse,
+    ))
+}
+
+#[test]
+fn bounds_default_from_the_config() {
+    assert_eq!(
+        config(json!({})).sanity_bounds(),
+        SanityBounds {
+            max_players: 200,
+            max_slots: 200,
+            max_queue: 500,
+        }
+    );
+    assert_eq!(
+        config(json!({ "sanity_max_slots": 127 }))
+            .sanity_bounds()
+            .max_slots,
+        127
+    );
+}
+
+#[test]
+fn values_outside_the_bounds_are_rejected() {
+    let bounds = config(json!({})).sanity_bounds();
+    let cases = [
+        (sample(60, 60, Some(0)), Ok(())),
+        (sample(200, 200, Some(500)), Ok(())),
+        (sample(0, 60, None), Ok(())),
+        (sample(0, 0, None), Err("max players 0 outside 1..=200")),
+        (
+            sample(10, 201, None),
+            Err("max players 201 outside 1..=200"),
+        ),
+        (sample(201, 200, None), Err("players 201 above 200")),
+        (sample(60, 60, Some(501)), Err("queue 501 above 500")),
+    ];
+
+    for (info, expected) in cases {
+        assert_eq!(
+            bounds.check(&info),
+            expected.map_err(str::to_owned),
+            "{} / {} / {:?}",
+            info.players,
+            info.max_players,
+            info.players_in_queue
+        );
+    }
+}
+
+#[test]
+fn implausible_response_is_a_soft_failure() {
+    let err = DayzMonitorError::ImplausibleResponse("players 65535 above 200".to_string());
+
+    assert!(err.is_soft());
+    assert_eq!(err.rejection(), Some("players 65535 above 200"));
+    assert_eq!(
+        err.to_string(),
+        "Implausible A2S response (players 65535 above 200)."
+    );
+
+    let err = timed_out().unwrap_err();
+    assert!(!err.is_soft());
+    assert_eq!(err.rejection(), None);
+}
+
+#[test]
+fn rejected_sample_shows_the_last_good_one_as_degraded() {
+    let mut monitor = Monitor::default();
+    monitor.observe(Ok(info(12)), false);
+
+    let observation = monitor.observe(implausible("players 65535 above 200"), false);
+
+    assert_eq!(observation.state, MonitorState::Degraded);
+    assert_eq!(observation.info.unwrap().players, 12);
+    assert_eq!(
+        observation.diagnostics.rejected.as_deref(),
+        Some("players 65535 above 200")
+    );
+}
+
+#[test]
+fn rejected_first_sample_is_degraded_without_data() {
+    let observation = Monitor::default().observe(implausible("queue 501 above 500"), false);
+
+    assert_eq!(observation.state, MonitorState::Degraded);
+    assert!(observation.info.is_none());
+}
+
+#[test]
+fn failed_query_is_offline_until_the_next_good_sample() {
+    let mut monitor = Monitor::default();
+    monitor.observe(Ok(info(12)), false);
+
+    let offline = monitor.observe(timed_out(), false);
+    assert_eq!(offline.state, MonitorState::Offline);
+    assert!(offline.info.is_none());
+
+    let online = monitor.observe(Ok(info(14)), false);
+    assert_eq!(online.state, MonitorState::Online);
+    assert_eq!(online.info.unwrap().players, 14);
+}
@@ -0,0 +1,199 @@
+//! The `unknown` state from startup until the first poll completes: how it is shown and written,
+//! and that an offline first poll is only alerted once the outage persists.
+
+mod common;
+
+use std::time::Duration;
+
+use chrono_tz::UTC;
+use dayz_monitor::{
+    alerts::{alert_rules, AlertKind, AlertTracker},
+    audit::{AuditEntry, AuditEvent},
+    daily::DailyStats,
+    diagnostics::Diagnostics,
+    i18n::Language,
+    monitor::Observation,
+    status_file::{wait_for_status, write_status_file, StatusFile},
+    title::{render_title, TitleState},
+    DayzMonitorConfig, MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+/// Feeds one poll a minute, `true` for online, returning the alerts each fired.
+fn alerts(config: &DayzMonitorConfig, polls: &[bool]) -> Vec<Vec<AlertKind>> {
+    let mut tracker = AlertTracker::new(alert_rules(config), 20);
+    let info = info(12);
+
+    polls
+        .iter()
+        .zip(0..)
+        .map(|(online, minute)| {
+            let info = online.then_some(&info);
+            tracker
+                .observe(config, info, NOW + minute * 60)
+                .into_iter()
+                .map(|alert| alert.kind)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn offline_from_the_start_is_alerted_once_it_persists() {
+    let fired = alerts(
+        &config(json!({ "alert_channel_id": 2 })),
+        &[false, false, false, false, false],
+    );
+
+    assert_eq!(
+        fired,
+        [vec![], vec![], vec![AlertKind::Offline], vec![], vec![]]
+    );
+}
+
+#[test]
+fn startup_outage_is_announced_over_only_when_it_was_alerted() {
+    let config = config(json!({ "alert_channel_id": 2 }));
+
+    let fired = alerts(&config, &[false, false, true]);
+    assert!(fired.iter().all(Vec::is_empty), "{fired:?}");
+
+    let fired = alerts(&config, &[false, false, false, true]);
+    assert_eq!(fired[2], [AlertKind::Offline]);
+    assert_eq!(fired[3], [AlertKind::Online]);
+}
+
+#[test]
+fn startup_outage_can_be_left_unalerted() {
+    let config = config(json!({
+        "alert_channel_id": 2,
+        "alert_startup_offline_polls": 0,
+    }));
+
+    let fired = alerts(&config, &[false, false, false, false, true]);
+
+    assert!(fired.iter().all(Vec::is_empty), "{fired:?}");
+}
+
+#[test]
+fn server_seen_online_first_is_alerted_right_away() {
+    let fired = alerts(
+        &config(json!({ "alert_channel_id": 2 })),
+        &[true, false, true],
+    );
+
+    assert_eq!(
+        fired,
+        [vec![], vec![AlertKind::Offline], vec![AlertKind::Online]]
+    );
+}
+
+#[test]
+fn unknown_is_neither_online_nor_offline() {
+    let status = StatusFile::new(MonitorState::Unknown, None, Diagnostics::default());
+    assert_eq!(status.online, None);
+    assert_eq!(status.field("state").unwrap(), "unknown");
+    assert_eq!(status.field("online").unwrap(), "null");
+
+    let status = StatusFile::new(MonitorState::Offline, None, Diagnostics::default());
+    assert_eq!(status.online, Some(false));
+    let status = StatusFile::new(
+        MonitorState::Degraded,
+        Some(&info(12)),
+        Diagnostics::default(),
+    );
+    assert_eq!(status.online, Some(true));
+}
+
+#[tokio::test]
+async fn waiting_for_the_status_skips_the_unknown_state() {
+    let path = std::env::temp_dir().join(format!(
+        "dayz-monitor-initial-status-{}.json",
+        std::process::id()
+    ));
+    let unknown = StatusFile::new(MonitorState::Unknown, None, Diagnostics::default());
+    write_status_file(&path, &unknown).unwrap();
+
+    let waited = wait_for_status(&path, Duration::from_secs(60), Duration::from_secs(1)).await;
+    assert!(waited.is_err());
+
+    let online = StatusFile::new(
+        MonitorState::Online,
+        Some(&info(12)),
+        Diagnostics::default(),
+    );
+    write_status_file(&path, &online).unwrap();
+    let waited = wait_for_status(&path, Duration::from_secs(60), Duration::from_secs(1)).await;
+    assert_eq!(waited.unwrap().players, Some(12));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn unknown_title_is_grey_and_checking() {
+    let config = config(json!({}));
+    let state = TitleState::of(&Observation::unknown());
+
+    assert_eq!(state, TitleState::Unknown);
+    assert_eq!(
+        render_title(&config, state, None, Language::En),
+        "⚪ Chernarus 1 — Checking…"
+    );
+    assert_eq!(state.colour(), 0x95A5A6);
+}
+
+#[test]
+fn first_transition_is_from_unknown() {
+    let entry = AuditEntry {
+        at_unix: NOW,
+        event: AuditEvent::Transition {
+            from: MonitorState::Unknown,
+            to: MonitorState::Online,
+            error: None,
+        },
+    };
+    assert_eq!(entry.event.describe(), "state unknown → online");
+
+    let line = serde_json::to_string(&entry).unwrap();
+    assert!(line.contains(r#""from":"unknown""#), "{line}");
+    assert_eq!(serde_json::from_str::<AuditEntry>(&line).unwrap(), entry);
+}
+
+#[test]
+fn audit_entries_of_earlier_versions_still_read() {
+    let line = r#"{"at_unix":1760000000,"event":"transition","from":null,"to":"offline","error":"timed out"}"#;
+
+    let entry: AuditEntry = serde_json::from_str(line).unwrap();
+
+    assert_eq!(
+        entry.event,
+        AuditEvent::Transition {
+            from: MonitorState::Unknown,
+            to: MonitorState::Offline,
+            error: Some("timed out".to_string()),
+        }
+    );
+}
+
+#[test]
+fn unknown_is_not_a_poll_in_the_daily_stats() {
+    let mut daily = DailyStats::default();
+
+    daily.record(&Observation::unknown(), MonitorState::Unknown, NOW, UTC);
+    assert!(daily.days.is_empty());
+
+    let mut offline = Observation::unknown();
+    offline.state = MonitorState::Offline;
+    daily.record(&offline, MonitorState::Unknown, NOW, UTC);
+    let mut online = Observation::unknown();
+    online.state = MonitorState::Online;
+    online.info = Some(info(12));
+    daily.record(&online, MonitorState::Offline, NOW + 60, UTC);
+
+    let day = daily.days.values().next().unwrap();
+    assert_eq!(day.polls, 2);
+    assert_eq!(day.online_polls, 1);
+    assert_eq!(day.restarts, 1);
+}
@@ -0,0 +1,275 @@
+//! Probing the game port alongside the query port: what counts as unreachable, how long it must
+//! last before it is flagged, and the alert that follows.
+
+mod common;
+
+use std::{io, net::SocketAddr, time::Duration};
+
+use dayz_monitor::{
+    alerts::{alert_rules, AlertKind, AlertTracker},
+    gameport::{self, GamePortProbe, JoinPortCheck, PortProbe},
+    DayzMonitorConfig, ServerInfo,
+};
+use serde_json::json;
+use tokio::net::UdpSocket;
+use GamePortProbe::{Icmp, Strict};
+
+use common::NOW;
+
+fn config(probe: &str) -> DayzMonitorConfig {
+    common::config(json!({
+        "alert_channel_id": 2,
+        "game_port_probe": probe,
+    }))
+}
+
+fn info() -> ServerInfo {
+    ServerInfo {
+        game_port: Some(2302),
+        ..common::info(12)
+    }
+}
+
+fn error(kind: io::ErrorKind) -> Option<io::Result<usize>> {
+    Some(Err(io::Error::from(kind)))
+}
+
+/// Feeds `probes` into a fresh check, returning whether each poll flagged the port.
+fn flags(mode: GamePortProbe, polls: u32, probes: &[Option<PortProbe>]) -> Vec<bool> {
+    let mut check = JoinPortCheck::new(mode, polls);
+    probes.iter().map(|p| check.observe(p.as_ref())).collect()
+}
+
+#[test]
+fn socket_results_are_classified() {
+    assert_eq!(PortProbe::classify(None), PortProbe::Silent);
+    assert_eq!(PortProbe::classify(Some(Ok(4))), PortProbe::Answered);
+
+    for kind in [
+        io::ErrorKind::ConnectionRefused,
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::HostUnreachable,
+        io::ErrorKind::NetworkUnreachable,
+    ] {
+        assert_eq!(
+            PortProbe::classify(error(kind)),
+            PortProbe::Unreachable,
+            "{kind:?}"
+        );
+    }
+
+    for kind in [
+        io::ErrorKind::PermissionDenied,
+        io::ErrorKind::AddrNotAvailable,
+        io::ErrorKind::Other,
+    ] {
+        assert!(
+            matches!(PortProbe::classify(error(kind)), PortProbe::Failed(_)),
+            "{kind:?}"
+        );
+    }
+}
+
+#[test]
+fn silence_counts_only_in_strict_mode() {
+    let failed = PortProbe::Failed("permission denied".to_string());
+
+    assert!(PortProbe::Unreachable.is_unreachable(Icmp));
+    assert!(PortProbe::Unreachable.is_unreachable(Strict));
+    assert!(!PortProbe::Silent.is_unreachable(Icmp));
+    assert!(PortProbe::Silent.is_unreachable(Strict));
+    for mode in [Icmp, Strict] {
+        assert!(!PortProbe::Answered.is_unreachable(mode));
+        assert!(!failed.is_unreachable(mode));
+    }
+}
+
+#[test]
+fn port_is_flagged_after_enough_unreachable_polls() {
+    let unreachable = Some(PortProbe::Unreachable);
+
+    assert_eq!(
+        flags(
+            Icmp,
+            3,
+            &[unreachable.clone(), unreachable.clone(), unreachable]
+        ),
+        [false, false, true]
+    );
+}
+
+#[test]
+fn reachable_poll_ends_the_streak() {
+    let unreachable = Some(PortProbe::Unreachable);
+
+    for reachable in [PortProbe::Answered, PortProbe::Silent] {
+        assert_eq!(
+            flags(
+                Icmp,
+                2,
+                &[
+                    unreachable.clone(),
+                    Some(reachable),
+                    unreachable.clone(),
+                    unreachable.clone(),
+                ]
+            ),
+            [false, false, false, true]
+        );
+    }
+}
+
+#[test]
+fn missing_or_failed_probe_neither_extends_nor_ends_the_streak() {
+    let unreachable = Some(PortProbe::Unreachable);
+    let failed = Some(PortProbe::Failed("permission denied".to_string()));
+
+    assert_eq!(
+        flags(
+            Icmp,
+            2,
+            &[
+                unreachable.clone(),
+                None,
+                failed.clone(),
+                failed,
+                unreachable
+            ]
+        ),
+        [false, false, false, false, true]
+    );
+}
+
+#[test]
+fn strict_mode_flags_a_silent_port() {
+    let silent = Some(PortProbe::Silent);
+
+    assert_eq!(
+        flags(Strict, 2, &[silent.clone(), silent.clone()]),
+        [false, true]
+    );
+    assert_eq!(flags(Icmp, 2, &[silent.clone(), silent]), [false, false]);
+}
+
+#[test]
+fn reset_forgets_the_streak() {
+    let mut check = JoinPortCheck::new(Icmp, 2);
+
+    check.observe(Some(&PortProbe::Unreachable));
+    check.reset();
+
+    assert!(!check.observe(Some(&PortProbe::Unreachable)));
+    assert!(check.observe(Some(&PortProbe::Unreachable)));
+}
+
+#[test]
+fn configured_port_wins_over_the_reported_one() {
+    let query: SocketAddr = "203.0.113.7:27016".parse().unwrap();
+
+    assert_eq!(
+        gameport::target(query, Some(2402), Some(2302)),
+        Some("203.0.113.7:2402".parse().unwrap())
+    );
+    assert_eq!(
+        gameport::target(query, None, Some(2302)),
+        Some("203.0.113.7:2302".parse().unwrap())
+    );
+    assert_eq!(gameport::target(query, None, None), None);
+}
+
+#[tokio::test]
+async fn probe_tells_a_closed_port_from_an_answering_one() {
+    // Bound and dropped, so nothing listens there any more
+    let closed = UdpSocket::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    assert_eq!(
+        gameport::probe(closed, Duration::from_secs(1)).await,
+        PortProbe::Unreachable
+    );
+
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr().unwrap();
+    let echo = tokio::spawn(async move {
+        let mut buf = [0u8; 64];
+        let (len, from) = server.recv_from(&mut buf).await.unwrap();
+        server.send_to(&buf[..len], from).await.unwrap();
+        server
+    });
+    assert_eq!(
+        gameport::probe(addr, Duration::from_secs(1)).await,
+        PortProbe::Answered
+    );
+
+    // Still open, but ignoring the probe like a DayZ server does
+    let server = echo.await.unwrap();
+    assert_eq!(
+        gameport::probe(server.local_addr().unwrap(), Duration::from_millis(200)).await,
+        PortProbe::Silent
+    );
+}
+
+#[test]
+fn alert_rule_needs_the_probe() {
+    let kinds = |config: &DayzMonitorConfig| -> Vec<AlertKind> {
+        alert_rules(config).iter().map(|r| r.kind).collect()
+    };
+
+    assert!(!kinds(&config("off")).contains(&AlertKind::JoinPort));
+    assert!(kinds(&config("icmp")).contains(&AlertKind::JoinPort));
+    assert!(kinds(&config("strict")).contains(&AlertKind::JoinPort));
+}
+
+#[test]
+fn alert_fires_once_per_episode() {
+    let config = config("icmp");
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let info = info();
+    let mut observe = |port| tracker.observe_join_port(&config, &info, port);
+
+    let fired = observe(Some(2302));
+    assert_eq!(fired.len(), 1);
+    assert_eq!(fired[0].kind, AlertKind::JoinPort);
+    assert_eq!(
+        fired[0].message,
+        "⚠️ **Chernarus 1** answers queries, but its game port 2302 is unreachable, so nobody can join."
+    );
+
+    assert!(observe(Some(2302)).is_empty());
+    assert!(observe(None).is_empty());
+    assert_eq!(observe(Some(2302)).len(), 1);
+}
+
+#[test]
+fn server_going_down_rearms_the_alert() {
+    let config = config("icmp");
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let info = info();
+
+    tracker.observe(&config, Some(&info), NOW);
+    assert_eq!(
+        tracker.observe_join_port(&config, &info, Some(2302)).len(),
+        1
+    );
+
+    tracker.observe(&config, None, NOW + 60);
+    tracker.observe(&config, Some(&info), NOW + 120);
+    assert_eq!(
+        tracker.observe_join_port(&config, &info, Some(2302)).len(),
+        1
+    );
+}
+
+#[test]
+fn custom_template_gets_the_port() {
+    let mut config = config("icmp");
+    config.alert_join_port_template = Some("{server_name}: UDP {game_port} blocked".to_string());
+    config.validate().expect("template is valid");
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+
+    let fired = tracker.observe_join_port(&config, &info(), Some(12302));
+
+    assert_eq!(fired[0].message, "Chernarus 1: UDP 12302 blocked");
+}
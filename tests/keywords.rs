@@ -0,0 +1,42 @@
+//! Golden tests for the keywords parser.
+//!
+//! Every `tests/fixtures/keywords/<name>.txt` holds a raw keywords string and is checked against
+//! the parse in `<name>.toml`. `dayz-monitor parse-keywords "<keywords>"` prints a sidecar for a
+//! new sample.
+
+use std::{fs, path::Path};
+
+use dayz_monitor::{parse_keywords, ParsedKeywords};
+
+#[test]
+fn keyword_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keywords");
+    let mut checked = 0;
+
+    for entry in fs::read_dir(&dir).expect("fixture directory is readable") {
+        let path = entry.expect("fixture entry is readable").path();
+        if path.extension().is_none_or(|e| e != "txt") {
+            continue;
+        }
+
+        let raw = fs::read_to_string(&path).expect("fixture is readable");
+        let keywords = raw.strip_suffix('\n').unwrap_or(&raw);
+
+        let sidecar = path.with_extension("toml");
+        let expected: ParsedKeywords = toml::from_str(
+            &fs::read_to_string(&sidecar)
+                .unwrap_or_else(|_| panic!("{} has no sidecar", path.display())),
+        )
+        .unwrap_or_else(|err| panic!("{} is invalid: {err}", sidecar.display()));
+
+        assert_eq!(
+            parse_keywords(keywords),
+            expected,
+            "{} parsed differently than expected",
+            path.display()
+        );
+        checked += 1;
+    }
+
+    assert!(checked > 0, "no fixtures found in {}", dir.display());
+}
@@ -0,0 +1,207 @@
+//! The sustained high latency alert: the rolling p95 it is based on, and the hysteresis that
+//! fires it once and only clears it well below the threshold.
+
+mod common;
+
+use std::time::Duration;
+
+use dayz_monitor::{
+    alerts::{alert_rules, AlertKind, AlertTracker},
+    probe::RollingPercentile,
+    DayzMonitorConfig, LatencyAlertThreshold,
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+const WINDOW: usize = 20;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn alerting(extra: serde_json::Value) -> DayzMonitorConfig {
+    let mut config = config(extra);
+    config.alert_channel_id = Some(2);
+    config
+}
+
+/// Feeds one poll per latency, returning the polls (by index) that fired a latency alert.
+fn fired_at(
+    tracker: &mut AlertTracker,
+    config: &DayzMonitorConfig,
+    latencies: impl IntoIterator<Item = Option<u64>>,
+) -> Vec<usize> {
+    latencies
+        .into_iter()
+        .enumerate()
+        .filter(|(i, latency)| {
+            let mut info = info(12);
+            info.latency = latency.map(ms);
+            tracker
+                .observe(config, Some(&info), NOW + *i as u64 * 60)
+                .iter()
+                .any(|alert| alert.kind == AlertKind::Latency)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn repeat(latency: u64, polls: usize) -> impl Iterator<Item = Option<u64>> {
+    std::iter::repeat_n(Some(latency), polls)
+}
+
+#[test]
+fn percentile_is_nearest_rank_over_the_window() {
+    let mut window = RollingPercentile::new(4);
+    assert_eq!(window.percentile(95.0), None);
+
+    for n in [40, 10, 30] {
+        window.push(ms(n));
+    }
+    assert!(!window.is_full());
+    assert_eq!(window.percentile(50.0), Some(ms(30)));
+    assert_eq!(window.percentile(0.0), Some(ms(10)));
+
+    window.push(ms(20));
+    window.push(ms(5));
+    assert!(window.is_full());
+    // 40 slid out of the window.
+    assert_eq!(window.percentile(100.0), Some(ms(30)));
+    assert_eq!(window.percentile(95.0), Some(ms(30)));
+    assert_eq!(window.percentile(50.0), Some(ms(10)));
+    assert_eq!(window.percentile(250.0), Some(ms(30)));
+}
+
+#[test]
+fn empty_window_holds_one_sample() {
+    let mut window = RollingPercentile::new(0);
+
+    window.push(ms(10));
+    window.push(ms(20));
+
+    assert!(window.is_full());
+    assert_eq!(window.percentile(50.0), Some(ms(20)));
+}
+
+#[test]
+fn clear_threshold_defaults_below_the_alert_one() {
+    let cases = [
+        (json!({}), None),
+        (json!({ "alert_latency_ms": 200 }), Some((ms(200), ms(160)))),
+        (
+            json!({ "alert_latency_ms": 200, "alert_latency_clear_ms": 100 }),
+            Some((ms(200), ms(100))),
+        ),
+        // A clear threshold above the alert one would never let it clear.
+        (
+            json!({ "alert_latency_ms": 200, "alert_latency_clear_ms": 300 }),
+            Some((ms(200), ms(200))),
+        ),
+    ];
+
+    for (extra, expected) in cases {
+        let threshold = config(extra.clone()).latency_alert_threshold();
+        assert_eq!(
+            threshold,
+            expected.map(|(alert, clear)| LatencyAlertThreshold { alert, clear }),
+            "{extra}"
+        );
+    }
+}
+
+#[test]
+fn alert_waits_for_a_full_window_and_fires_once() {
+    let config = alerting(json!({ "alert_latency_ms": 200 }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), WINDOW);
+
+    let fired = fired_at(&mut tracker, &config, repeat(500, WINDOW + 10));
+
+    assert_eq!(fired, [WINDOW - 1]);
+    assert_eq!(tracker.high_latency(), Some(ms(500)));
+}
+
+#[test]
+fn isolated_spikes_do_not_fire() {
+    let config = alerting(json!({ "alert_latency_ms": 200 }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), WINDOW);
+
+    // One spike per window is 5% of it and never reaches the p95.
+    let polls = (0..3 * WINDOW).map(|i| Some(if i % WINDOW == 7 { 900 } else { 40 }));
+
+    assert_eq!(fired_at(&mut tracker, &config, polls), Vec::<usize>::new());
+    assert_eq!(tracker.high_latency(), None);
+}
+
+#[test]
+fn alert_clears_only_below_the_clear_threshold() {
+    let config = alerting(json!({
+        "alert_latency_ms": 200,
+        "alert_latency_clear_ms": 100,
+    }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), WINDOW);
+
+    assert_eq!(
+        fired_at(&mut tracker, &config, repeat(300, WINDOW)).len(),
+        1
+    );
+
+    // Between the two thresholds it stays high, tracking the current p95, without firing.
+    assert_eq!(
+        fired_at(&mut tracker, &config, repeat(150, WINDOW)),
+        Vec::<usize>::new()
+    );
+    assert_eq!(tracker.high_latency(), Some(ms(150)));
+
+    assert_eq!(
+        fired_at(&mut tracker, &config, repeat(50, WINDOW)),
+        Vec::<usize>::new()
+    );
+    assert_eq!(tracker.high_latency(), None);
+
+    // Once cleared, it can fire again.
+    assert_eq!(
+        fired_at(&mut tracker, &config, repeat(300, WINDOW)).len(),
+        1
+    );
+}
+
+#[test]
+fn samples_without_a_latency_are_skipped() {
+    let config = alerting(json!({ "alert_latency_ms": 200 }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), WINDOW);
+
+    let polls = (0..2 * WINDOW).map(|i| (i % 2 == 0).then_some(500));
+
+    assert_eq!(fired_at(&mut tracker, &config, polls), [2 * WINDOW - 2]);
+}
+
+#[test]
+fn latency_alert_renders_its_values() {
+    let config = alerting(json!({
+        "alert_latency_ms": 1_500,
+        "number_locale": "de",
+    }));
+    let mut tracker = AlertTracker::new(alert_rules(&config), 1);
+    let mut info = info(12);
+    info.latency = Some(ms(2_048));
+
+    let alerts = tracker.observe(&config, Some(&info), NOW);
+
+    assert_eq!(
+        alerts[0].message,
+        "🐢 **Chernarus 1** is answering slowly (p95 2.048 ms, threshold 1.500 ms)."
+    );
+}
+
+#[test]
+fn without_a_threshold_latency_is_not_tracked() {
+    let config = alerting(json!({}));
+    let mut tracker = AlertTracker::new(alert_rules(&config), 1);
+
+    assert_eq!(
+        fired_at(&mut tracker, &config, repeat(5_000, 5)),
+        Vec::<usize>::new()
+    );
+    assert_eq!(tracker.high_latency(), None);
+}
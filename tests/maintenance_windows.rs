@@ -0,0 +1,253 @@
+//! Recurring maintenance windows: parsing, which dates they recur on, and whether one is
+//! running at a given moment.
+
+mod common;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::{Europe::Berlin, UTC};
+use dayz_monitor::{
+    monitor::Monitor,
+    schedule::{active_until, Nth, Recurrence, Window},
+    DayzMonitorError, MonitorState,
+};
+use serde_json::json;
+
+use common::config;
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn window(spec: &str) -> Window {
+    Window::parse(spec, UTC).unwrap()
+}
+
+#[test]
+fn windows_parse() {
+    let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let cases = [
+        (
+            "daily 04:00-04:20",
+            Recurrence::Daily,
+            hm(4, 0),
+            hm(4, 20),
+            UTC,
+        ),
+        (
+            "weekly Tue 23:30-00:30 Europe/Berlin",
+            Recurrence::Weekly(Weekday::Tue),
+            hm(23, 30),
+            hm(0, 30),
+            Berlin,
+        ),
+        (
+            "Monthly 15 06:00–07:00",
+            Recurrence::MonthDay(15),
+            hm(6, 0),
+            hm(7, 0),
+            UTC,
+        ),
+        (
+            "monthly last sunday 03:00-05:00",
+            Recurrence::NthWeekday(Nth::Last, Weekday::Sun),
+            hm(3, 0),
+            hm(5, 0),
+            UTC,
+        ),
+        (
+            "monthly first monday 03:00-05:00",
+            Recurrence::NthWeekday(Nth::Ordinal(1), Weekday::Mon),
+            hm(3, 0),
+            hm(5, 0),
+            UTC,
+        ),
+    ];
+
+    for (spec, recurrence, start, end, tz) in cases {
+        assert_eq!(
+            Window::parse(spec, UTC).unwrap(),
+            Window {
+                recurrence,
+                start,
+                end,
+                tz
+            },
+            "{spec}"
+        );
+    }
+}
+
+#[test]
+fn malformed_windows_say_why() {
+    let cases = [
+        ("daily", "missing HH:MM-HH:MM time range"),
+        (
+            "hourly 04:00-05:00",
+            "expected 'daily', 'weekly <day>' or 'monthly ...'",
+        ),
+        ("weekly Funday 04:00-05:00", "'funday' is not a weekday"),
+        ("monthly 32 04:00-05:00", "'32' is not a day of the month"),
+        (
+            "monthly fifth monday 04:00-05:00",
+            "'fifth' is not first/second/third/fourth/last",
+        ),
+        ("daily 04:00", "'04:00' is not a HH:MM-HH:MM range"),
+        ("daily 25:00-26:00", "'25:00' is not a HH:MM time"),
+        ("daily 04:00-04:00", "window is empty"),
+        (
+            "daily 04:00-05:00 Mars/Olympus",
+            "unknown timezone 'Mars/Olympus'",
+        ),
+        (
+            "daily 04:00-05:00 UTC extra",
+            "unexpected text after the timezone",
+        ),
+    ];
+
+    for (spec, expected) in cases {
+        match Window::parse(spec, UTC) {
+            Err(DayzMonitorError::InvalidSchedule { spec: s, reason }) => {
+                assert_eq!((s.as_str(), reason.as_str()), (spec, expected));
+            }
+            other => panic!("{spec}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn recurrences_match_their_dates() {
+    // October 2026 starts on a Thursday.
+    let cases = [
+        (Recurrence::Daily, date(2026, 10, 13), true),
+        (Recurrence::Weekly(Weekday::Tue), date(2026, 10, 13), true),
+        (Recurrence::Weekly(Weekday::Tue), date(2026, 10, 14), false),
+        (Recurrence::MonthDay(31), date(2026, 10, 31), true),
+        (Recurrence::MonthDay(31), date(2026, 11, 30), false),
+        (
+            Recurrence::NthWeekday(Nth::Ordinal(1), Weekday::Thu),
+            date(2026, 10, 1),
+            true,
+        ),
+        (
+            Recurrence::NthWeekday(Nth::Ordinal(1), Weekday::Mon),
+            date(2026, 10, 5),
+            true,
+        ),
+        (
+            Recurrence::NthWeekday(Nth::Ordinal(2), Weekday::Mon),
+            date(2026, 10, 5),
+            false,
+        ),
+        (
+            Recurrence::NthWeekday(Nth::Ordinal(4), Weekday::Thu),
+            date(2026, 10, 22),
+            true,
+        ),
+        (
+            Recurrence::NthWeekday(Nth::Last, Weekday::Thu),
+            date(2026, 10, 29),
+            true,
+        ),
+        (
+            Recurrence::NthWeekday(Nth::Last, Weekday::Thu),
+            date(2026, 10, 22),
+            false,
+        ),
+    ];
+
+    for (recurrence, date, expected) in cases {
+        assert_eq!(recurrence.matches(date), expected, "{recurrence:?} {date}");
+    }
+}
+
+#[test]
+fn window_runs_from_its_start_until_just_before_its_end() {
+    let daily = window("daily 04:00-04:20");
+
+    assert_eq!(daily.active_until(utc(2026, 10, 13, 3, 59)), None);
+    assert_eq!(
+        daily.active_until(utc(2026, 10, 13, 4, 0)),
+        Some(utc(2026, 10, 13, 4, 20))
+    );
+    assert_eq!(
+        daily.active_until(utc(2026, 10, 13, 4, 19)),
+        Some(utc(2026, 10, 13, 4, 20))
+    );
+    assert_eq!(daily.active_until(utc(2026, 10, 13, 4, 20)), None);
+}
+
+#[test]
+fn window_past_midnight_belongs_to_the_day_it_starts() {
+    // 2026-10-13 is a Tuesday.
+    let tuesday_night = window("weekly tue 23:30-00:30");
+
+    assert_eq!(
+        tuesday_night.active_until(utc(2026, 10, 13, 23, 45)),
+        Some(utc(2026, 10, 14, 0, 30))
+    );
+    assert_eq!(
+        tuesday_night.active_until(utc(2026, 10, 14, 0, 15)),
+        Some(utc(2026, 10, 14, 0, 30))
+    );
+    // Tuesday just after midnight is Monday night's, which isn't a window.
+    assert_eq!(tuesday_night.active_until(utc(2026, 10, 13, 0, 15)), None);
+}
+
+#[test]
+fn window_is_in_its_own_timezone() {
+    // 04:00 in Berlin is 02:00 UTC in summer.
+    let berlin = window("daily 04:00-04:20 Europe/Berlin");
+
+    assert_eq!(
+        berlin.active_until(utc(2026, 7, 1, 2, 10)),
+        Some(utc(2026, 7, 1, 2, 20))
+    );
+    assert_eq!(berlin.active_until(utc(2026, 7, 1, 4, 10)), None);
+}
+
+#[test]
+fn overlapping_windows_end_with_the_latest() {
+    let windows = [
+        window("daily 04:00-04:20"),
+        window("weekly tue 04:10-05:00"),
+    ];
+
+    assert_eq!(
+        active_until(&windows, utc(2026, 10, 13, 4, 15)),
+        Some(utc(2026, 10, 13, 5, 0))
+    );
+    assert_eq!(
+        active_until(&windows, utc(2026, 10, 14, 4, 15)),
+        Some(utc(2026, 10, 14, 4, 20))
+    );
+    assert_eq!(active_until(&[], utc(2026, 10, 14, 4, 15)), None);
+}
+
+#[test]
+fn config_windows_default_to_the_maintenance_timezone() {
+    let berlin = config(json!({
+        "maintenance_windows": ["daily 04:00-04:20", "daily 05:00-05:20 UTC"],
+        "maintenance_timezone": "Europe/Berlin",
+    }));
+
+    let windows = berlin.maintenance_schedule().unwrap();
+
+    assert_eq!(windows[0].tz, Berlin);
+    assert_eq!(windows[1].tz, UTC);
+
+    let invalid = config(json!({ "maintenance_windows": ["daily 04:00"] }));
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn maintenance_observation_carries_the_end() {
+    let observation = Monitor::default().maintenance(1_760_000_000);
+
+    assert_eq!(observation.state, MonitorState::Maintenance);
+    assert_eq!(observation.maintenance_until, Some(1_760_000_000));
+    assert!(observation.info.is_none());
+}
@@ -0,0 +1,213 @@
+//! Map rotations: when a new map counts as rotated in, the per-map player and uptime totals,
+//! and the maps section of the `stats` report.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use dayz_monitor::{
+    i18n::Language,
+    maps::{display_name, summary, track, MapRotation, MapStats, MAP_CHANGE_POLLS},
+    monitor::Observation,
+    numbers::NumberLocale,
+    MonitorState,
+};
+
+use common::{info, NOW};
+
+fn observation(state: MonitorState, map: Option<&str>, players: u32) -> Observation {
+    let mut observation = Observation::unknown();
+    observation.state = state;
+    observation.info = map.map(|map| {
+        let mut info = info(players);
+        info.map = map.to_string();
+        info
+    });
+    observation
+}
+
+#[test]
+fn first_map_starts_a_rotation_at_once() {
+    let mut rotation = MapRotation::default();
+
+    assert!(rotation.observe("chernarusplus", NOW));
+    assert_eq!(rotation.current.as_deref(), Some("chernarusplus"));
+    assert_eq!(rotation.started_unix, Some(NOW));
+
+    assert!(!rotation.observe("chernarusplus", NOW + 60));
+    assert_eq!(rotation.started_unix, Some(NOW));
+}
+
+#[test]
+fn new_map_must_be_seen_several_polls_in_a_row() {
+    let mut rotation = MapRotation::default();
+    rotation.observe("chernarusplus", NOW);
+
+    let mut rotated_at = None;
+    for poll in 1..=MAP_CHANGE_POLLS as u64 {
+        if rotation.observe("enoch", NOW + poll * 60) {
+            rotated_at = Some(poll);
+        }
+    }
+
+    assert_eq!(rotated_at, Some(MAP_CHANGE_POLLS as u64));
+    assert_eq!(rotation.current.as_deref(), Some("enoch"));
+    assert_eq!(
+        rotation.started_unix,
+        Some(NOW + MAP_CHANGE_POLLS as u64 * 60)
+    );
+}
+
+#[test]
+fn interrupted_or_empty_map_does_not_rotate() {
+    let mut rotation = MapRotation::default();
+    rotation.observe("chernarusplus", NOW);
+
+    // The old map in between starts the count over.
+    let maps = [
+        "enoch",
+        "enoch",
+        "chernarusplus",
+        "enoch",
+        "enoch",
+        "",
+        "enoch",
+    ];
+    for (i, map) in maps.into_iter().enumerate() {
+        assert!(
+            !rotation.observe(map, NOW + i as u64 * 60),
+            "poll {i}: {map:?}"
+        );
+    }
+
+    assert_eq!(rotation.current.as_deref(), Some("chernarusplus"));
+    assert_eq!(rotation.started_unix, Some(NOW));
+}
+
+#[test]
+fn stats_average_over_online_polls_only() {
+    let mut stats = MapStats::default();
+    assert_eq!(stats.average_players(), 0.0);
+    assert_eq!(stats.uptime_percent(), 0.0);
+
+    for players in [Some(10), Some(20), None, Some(36)] {
+        stats.record(players);
+    }
+
+    assert_eq!(
+        stats,
+        MapStats {
+            cycles: 4,
+            online_cycles: 3,
+            player_sum: 66,
+            peak_players: 36,
+        }
+    );
+    assert_eq!(stats.average_players(), 22.0);
+    assert_eq!(stats.uptime_percent(), 75.0);
+}
+
+#[test]
+fn tracking_counts_online_and_offline_polls_for_the_current_map() {
+    let mut rotation = MapRotation::default();
+    let mut stats = BTreeMap::new();
+    let mut poll = |mut observation: Observation, now: u64| {
+        track(&mut rotation, &mut stats, &mut observation, now);
+        observation
+    };
+
+    let first = poll(
+        observation(MonitorState::Online, Some("chernarusplus"), 12),
+        NOW,
+    );
+    poll(observation(MonitorState::Offline, None, 0), NOW + 60);
+    poll(
+        observation(MonitorState::Starting, Some("chernarusplus"), 0),
+        NOW + 120,
+    );
+    // Neither of these says anything about the map's uptime.
+    poll(
+        observation(MonitorState::Degraded, Some("chernarusplus"), 99),
+        NOW + 180,
+    );
+    poll(observation(MonitorState::Maintenance, None, 0), NOW + 240);
+
+    assert_eq!(first.map_since_unix, Some(NOW));
+    assert_eq!(
+        stats,
+        BTreeMap::from([(
+            "chernarusplus".to_string(),
+            MapStats {
+                cycles: 3,
+                online_cycles: 1,
+                player_sum: 12,
+                peak_players: 12,
+            }
+        )])
+    );
+}
+
+#[test]
+fn unconfirmed_map_is_counted_for_the_old_one_without_a_start_time() {
+    let mut rotation = MapRotation::default();
+    let mut stats = BTreeMap::new();
+    let mut chernarus = observation(MonitorState::Online, Some("chernarusplus"), 12);
+    track(&mut rotation, &mut stats, &mut chernarus, NOW);
+
+    let mut livonia = observation(MonitorState::Online, Some("enoch"), 30);
+    track(&mut rotation, &mut stats, &mut livonia, NOW + 60);
+
+    assert_eq!(livonia.map_since_unix, None);
+    assert_eq!(stats["chernarusplus"].cycles, 2);
+    assert!(!stats.contains_key("enoch"));
+}
+
+#[test]
+fn official_maps_have_readable_names() {
+    assert_eq!(display_name("chernarusplus"), "Chernarus");
+    assert_eq!(display_name("ENOCH"), "Livonia");
+    assert_eq!(display_name("sakhal"), "Sakhal");
+    assert_eq!(display_name("deerisle"), "deerisle");
+}
+
+#[test]
+fn report_lists_every_map_and_marks_the_current_one() {
+    let mut rotation = MapRotation::default();
+    rotation.observe("enoch", NOW);
+    let stats = BTreeMap::from([
+        (
+            "chernarusplus".to_string(),
+            MapStats {
+                cycles: 8,
+                online_cycles: 8,
+                player_sum: 8_100,
+                peak_players: 1_200,
+            },
+        ),
+        (
+            "enoch".to_string(),
+            MapStats {
+                cycles: 3,
+                online_cycles: 2,
+                player_sum: 25,
+                peak_players: 15,
+            },
+        ),
+    ]);
+
+    assert_eq!(
+        summary(&stats, &rotation, NumberLocale::En, Language::En),
+        "Maps:\n\
+         \x20 Chernarus       avg players 1,012.5, peak 1,200, uptime 100.0%\n\
+         \x20 Livonia         avg players 12.5, peak 15, uptime 66.7% (current)\n"
+    );
+    assert_eq!(
+        summary(
+            &BTreeMap::new(),
+            &MapRotation::default(),
+            NumberLocale::En,
+            Language::En
+        ),
+        "Maps:\n  (none)\n"
+    );
+}
@@ -0,0 +1,106 @@
+//! Message priority: which posts go out without a push notification, and the priority
+//! reaching the transport with each post.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use dayz_monitor::{
+    monitor::Monitor,
+    output::{render_output, Encoders, Output, Outputs, Rendered},
+    render::EmbedRepresentation,
+    transport::{Priority, SendRequest, StatusMessage, StatusTransport},
+};
+use serde_json::json;
+
+use common::{config, info, Call, FakeDiscord, NOW};
+
+type Recorder = FakeDiscord<Rendered>;
+
+const ENCODERS: Encoders<Recorder> = Encoders {
+    post: Rendered::clone,
+    edit: Rendered::clone,
+};
+
+fn priorities(fake: &Recorder) -> Vec<Priority> {
+    fake.calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            Call::Send { priority, .. } => Some(priority),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn silent_settings_map_to_low_priority() {
+    assert_eq!(Priority::silent_if(true), Priority::Low);
+    assert_eq!(Priority::silent_if(false), Priority::Normal);
+    assert_eq!(Priority::default(), Priority::Normal);
+}
+
+#[test]
+fn only_alerts_notify_by_default() {
+    let defaults = config(json!({}));
+    assert!(!defaults.silent_alerts);
+    assert!(defaults.silent_status_message);
+    assert!(defaults.silent_mod_list);
+    assert!(defaults.silent_log_channel);
+    assert!(!defaults.silent_wipe_announcement);
+
+    let overridden = config(json!({
+        "silent_alerts": true,
+        "silent_status_message": false,
+        "silent_mod_list": false,
+        "silent_log_channel": false,
+        "silent_wipe_announcement": true,
+    }));
+    assert!(overridden.silent_alerts);
+    assert!(!overridden.silent_status_message);
+    assert!(!overridden.silent_mod_list);
+    assert!(!overridden.silent_log_channel);
+    assert!(overridden.silent_wipe_announcement);
+}
+
+#[tokio::test]
+async fn transport_receives_the_priority_of_each_post() {
+    let fake = Recorder::default();
+
+    for priority in [Priority::Low, Priority::Normal] {
+        let request = SendRequest {
+            channel_id: 2,
+            message: Rendered::Text("🔴 offline".to_string()),
+            priority,
+        };
+        fake.send(request).await.unwrap();
+    }
+
+    assert_eq!(priorities(&fake), [Priority::Low, Priority::Normal]);
+}
+
+#[tokio::test]
+async fn outputs_post_with_the_given_priority_and_edit_the_rest() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let specs = vec![
+        Output::parse("111 compact").unwrap(),
+        Output::parse("222 compact").unwrap(),
+    ];
+    let mut outputs = Outputs::new(&base, specs, &BTreeMap::new());
+    let mut monitor = Monitor::new(base.recovery_criterion());
+
+    for (seq, players) in [(1, 12), (2, 13)] {
+        let observation = monitor.observe(Ok(info(players)), true);
+        outputs
+            .publish(&status, seq, Priority::Low, &ENCODERS, |output, config| {
+                render_output(config, output.layout, &observation, NOW, |_| {
+                    EmbedRepresentation::new()
+                })
+            })
+            .await;
+    }
+
+    // Two posts on the first cycle, edits only on the second.
+    assert_eq!(priorities(status.transport()), [Priority::Low; 2]);
+    assert_eq!(status.transport().edits().len(), 2);
+}
@@ -0,0 +1,159 @@
+//! The server mod list: decoding it from the rules reply, its stable order and hash, and
+//! paging it into companion messages.
+
+use dayz_monitor::mods::{
+    chunk_lines, decode_mods, mod_lines, mod_list_hash, plan_companion_sync, sort_mods,
+    CompanionPlan, Mod,
+};
+
+fn mod_(name: &str, workshop_id: u64) -> Mod {
+    Mod {
+        name: name.to_string(),
+        workshop_id,
+    }
+}
+
+/// The escaped blob of one mod, `CF` with workshop id 1559212036.
+fn cf_blob() -> Vec<u8> {
+    let mut raw = vec![
+        1, // protocol version
+        0, // overflow flags
+        0, 0, // no DLC
+        1, // one mod
+        0xAA, 0xBB, 0xCC, 0xDD, // hash
+        4,    // id length
+    ];
+    raw.extend(1_559_212_036u32.to_le_bytes());
+    raw.push(2);
+    raw.extend(b"CF");
+
+    let mut escaped = Vec::new();
+    for b in raw {
+        match b {
+            0x00 => escaped.extend([0x01, 0x02]),
+            0x01 => escaped.extend([0x01, 0x01]),
+            0xFF => escaped.extend([0x01, 0x03]),
+            b => escaped.push(b),
+        }
+    }
+    escaped
+}
+
+#[test]
+fn fragments_are_joined_in_order_and_unescaped() {
+    let blob = cf_blob();
+    let (first, second) = blob.split_at(7);
+    let rules = vec![
+        (vec![2, 2], second.to_vec()),
+        (b"allowedBuild".to_vec(), b"0".to_vec()),
+        (vec![1, 2], first.to_vec()),
+    ];
+
+    assert_eq!(decode_mods(&rules), Some(vec![mod_("CF", 1_559_212_036)]));
+}
+
+#[test]
+fn rules_without_a_mod_list_decode_to_nothing() {
+    let rules = vec![(b"dedicated".to_vec(), b"1".to_vec())];
+
+    assert_eq!(decode_mods(&rules), None);
+}
+
+#[test]
+fn mods_sort_by_name_ignoring_case() {
+    let mut mods = vec![
+        mod_("zombies", 3),
+        mod_("BaseBuilding", 2),
+        mod_("CF", 1),
+        mod_("basebuilding", 1),
+    ];
+
+    sort_mods(&mut mods);
+
+    assert_eq!(
+        mods,
+        [
+            mod_("basebuilding", 1),
+            mod_("BaseBuilding", 2),
+            mod_("CF", 1),
+            mod_("zombies", 3)
+        ]
+    );
+}
+
+#[test]
+fn hash_changes_with_the_list_only() {
+    let list = [mod_("CF", 1), mod_("Trader", 2)];
+
+    assert_eq!(mod_list_hash(&list), mod_list_hash(&list.clone()));
+    assert_ne!(mod_list_hash(&list), mod_list_hash(&list[..1]));
+    assert_ne!(
+        mod_list_hash(&list),
+        mod_list_hash(&[mod_("CF", 1), mod_("Trader", 3)])
+    );
+    // Names and ids are delimited, so shifting bytes between mods changes the hash.
+    assert_ne!(
+        mod_list_hash(&[mod_("AB", 1), mod_("C", 1)]),
+        mod_list_hash(&[mod_("A", 1), mod_("BC", 1)])
+    );
+    // Persisted across runs, so it must not depend on the build.
+    assert_eq!(mod_list_hash(&[]), 0xcbf29ce484222325);
+}
+
+#[test]
+fn lines_link_to_the_workshop_with_safe_names() {
+    let lines = mod_lines(&[mod_("[Best] *Mod*", 42)]);
+
+    assert_eq!(
+        lines,
+        ["• [Best \\*Mod\\*](https://steamcommunity.com/sharedfiles/filedetails/?id=42)"]
+    );
+}
+
+#[test]
+fn lines_are_packed_into_chunks_below_the_limit() {
+    let lines: Vec<String> = ["aaaa", "bbbb", "cccc", "dd"].map(String::from).to_vec();
+
+    assert_eq!(chunk_lines(&lines, 9), ["aaaa\nbbbb", "cccc\ndd"]);
+    assert_eq!(chunk_lines(&lines, 100), ["aaaa\nbbbb\ncccc\ndd"]);
+    assert!(chunk_lines(&[], 9).is_empty());
+}
+
+#[test]
+fn overlong_line_is_truncated_on_a_character_boundary() {
+    let lines = vec!["ok".to_string(), "ééééé".to_string()];
+
+    let chunks = chunk_lines(&lines, 5);
+
+    assert_eq!(chunks, ["ok", "éé"]);
+}
+
+#[test]
+fn companion_messages_are_reused_in_order() {
+    let chunks = |n: usize| (1..=n).map(|i| format!("page {i}")).collect::<Vec<_>>();
+
+    assert_eq!(
+        plan_companion_sync(&[10, 11], chunks(3)),
+        CompanionPlan {
+            edit: vec![(10, "page 1".into()), (11, "page 2".into())],
+            send: vec!["page 3".into()],
+            delete: vec![],
+        }
+    );
+    assert_eq!(
+        plan_companion_sync(&[10, 11, 12], chunks(1)),
+        CompanionPlan {
+            edit: vec![(10, "page 1".into())],
+            send: vec![],
+            delete: vec![11, 12],
+        }
+    );
+    assert_eq!(
+        plan_companion_sync(&[], chunks(2)),
+        CompanionPlan {
+            edit: vec![],
+            send: chunks(2),
+            delete: vec![],
+        }
+    );
+}
@@ -0,0 +1,151 @@
+//! Time the bot itself was down: detected from the heartbeat on start, merged when gaps
+//! overlap, and left out of the stats.
+
+use std::path::Path;
+
+use dayz_monitor::{
+    daily,
+    gaps::{self, MonitoringGap, GAPS_KEPT_SECS},
+    i18n::Language,
+    playtime::PlayerHours,
+    state::{load_state, PersistentState},
+};
+
+/// 2025-10-10 12:00 UTC
+const NOW: u64 = 1_760_097_600;
+const HOUR: u64 = 3600;
+
+fn fixture(name: &str) -> PersistentState {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/state")
+        .join(name);
+
+    load_state(&path).expect("state fixture loads")
+}
+
+fn gap(from_unix: u64, to_unix: u64) -> MonitoringGap {
+    MonitoringGap { from_unix, to_unix }
+}
+
+#[test]
+fn gap_is_detected_past_the_threshold() {
+    assert_eq!(gaps::detect(None, NOW, 180), None);
+    assert_eq!(gaps::detect(Some(NOW - 180), NOW, 180), None);
+    assert_eq!(
+        gaps::detect(Some(NOW - 2 * HOUR), NOW, 180),
+        Some(gap(NOW - 2 * HOUR, NOW))
+    );
+    // A heartbeat from the future, after the clock was stepped back, is no gap
+    assert_eq!(gaps::detect(Some(NOW + HOUR), NOW, 180), None);
+}
+
+#[test]
+fn overlapping_gaps_count_once() {
+    let state = fixture("gaps.json");
+
+    // 1h of the gap across the start of the week, and 10:00–13:00 on 2025-10-08 from two
+    // gaps overlapping by an hour. The gap on 2025-10-01 is before the week.
+    let week_start = 1_759_536_000;
+    assert_eq!(
+        gaps::unmonitored_secs(&state.monitoring_gaps, week_start, NOW),
+        4 * HOUR
+    );
+}
+
+#[test]
+fn record_merges_overlapping_and_touching_gaps() {
+    let mut recorded = vec![gap(NOW - 10 * HOUR, NOW - 8 * HOUR)];
+
+    gaps::record(&mut recorded, gap(NOW - 9 * HOUR, NOW - 6 * HOUR), NOW);
+    gaps::record(&mut recorded, gap(NOW - 6 * HOUR, NOW - 5 * HOUR), NOW);
+    gaps::record(&mut recorded, gap(NOW - 2 * HOUR, NOW - HOUR), NOW);
+
+    assert_eq!(
+        recorded,
+        vec![
+            gap(NOW - 10 * HOUR, NOW - 5 * HOUR),
+            gap(NOW - 2 * HOUR, NOW - HOUR)
+        ]
+    );
+}
+
+#[test]
+fn record_drops_old_gaps() {
+    let old = NOW - GAPS_KEPT_SECS - HOUR;
+    let mut recorded = vec![gap(old - HOUR, old)];
+
+    gaps::record(&mut recorded, gap(NOW - HOUR, NOW), NOW);
+
+    assert_eq!(recorded, vec![gap(NOW - HOUR, NOW)]);
+}
+
+#[test]
+fn monitored_share_of_a_span() {
+    let recorded = [
+        gap(NOW - 6 * HOUR, NOW - 3 * HOUR),
+        gap(NOW - 4 * HOUR, NOW - 2 * HOUR),
+    ];
+
+    assert_eq!(
+        gaps::monitored_percent(&recorded, NOW - 8 * HOUR, NOW),
+        Some(50.0)
+    );
+    assert_eq!(gaps::monitored_percent(&recorded, NOW, NOW), None);
+}
+
+#[test]
+fn report_notes_the_gaps_and_keeps_uptime_from_polls() {
+    let state = fixture("gaps.json");
+
+    let report = daily::report(
+        &state.daily,
+        &state.player_hours,
+        &state.monitoring_gaps,
+        NOW,
+        chrono_tz::UTC,
+    );
+
+    assert_eq!(report.unmonitored_week_secs, Some(4 * HOUR));
+    // Only the polls made count, so the time the bot was gone is neither up nor down.
+    assert_eq!(report.uptime_week, Some(1740.0 * 100.0 / 1800.0));
+    assert_eq!(report.uptime_today, Some(100.0));
+}
+
+#[test]
+fn report_without_gaps_has_no_note() {
+    let state = fixture("gaps.json");
+
+    let report = daily::report(&state.daily, &state.player_hours, &[], NOW, chrono_tz::UTC);
+
+    assert_eq!(report.unmonitored_week_secs, None);
+}
+
+#[test]
+fn players_are_not_credited_across_a_gap() {
+    let mut hours = PlayerHours::default();
+
+    hours.record(NOW - 2 * HOUR, Some(40), 180, chrono_tz::UTC);
+    hours.record(NOW, Some(40), 180, chrono_tz::UTC);
+
+    assert_eq!(hours.today(NOW, chrono_tz::UTC), 0);
+}
+
+#[test]
+fn state_from_before_gaps_loads_without_any() {
+    let state = fixture("legacy.json");
+
+    assert_eq!(state.heartbeat_unix, None);
+    assert!(state.monitoring_gaps.is_empty());
+}
+
+#[test]
+fn summary_lists_gaps_in_utc() {
+    assert_eq!(
+        gaps::summary(&[gap(1_759_917_600, 1_759_928_400)], Language::En),
+        "Monitoring gaps:\n  2025-10-08 10:00 – 2025-10-08 13:00  3h 0m\n"
+    );
+    assert_eq!(
+        gaps::summary(&[], Language::En),
+        "Monitoring gaps:\n  (none)\n"
+    );
+}
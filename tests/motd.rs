@@ -0,0 +1,80 @@
+//! Which MOTD the status embed shows, and what `/motd edit` accepts.
+
+use dayz_monitor::motd::{
+    effective_motd, sanitize_motd, MotdRejection, MOTD_LIMIT, MOTD_MAX_LINES,
+};
+
+#[test]
+fn configured_motd_is_shown_until_edited() {
+    assert_eq!(
+        effective_motd(Some("No KOS\\nBe nice"), None),
+        Some("No KOS\nBe nice".to_string())
+    );
+    assert_eq!(effective_motd(None, None), None);
+}
+
+#[test]
+fn edited_motd_takes_precedence() {
+    assert_eq!(
+        effective_motd(Some("configured"), Some("edited")),
+        Some("edited".to_string())
+    );
+    assert_eq!(
+        effective_motd(None, Some("edited")),
+        Some("edited".to_string())
+    );
+}
+
+#[test]
+fn empty_edit_hides_configured_motd() {
+    assert_eq!(effective_motd(Some("configured"), Some("")), None);
+}
+
+#[test]
+fn edits_are_not_unescaped() {
+    assert_eq!(
+        effective_motd(None, Some("C:\\new")),
+        Some("C:\\new".to_string())
+    );
+}
+
+#[test]
+fn submission_is_cleaned_up() {
+    assert_eq!(
+        sanitize_motd("  Rules:\r\n1. No KOS  \r\n2. Have\u{7} fun\n\n").as_deref(),
+        Ok("Rules:\n1. No KOS\n2. Have fun")
+    );
+    assert_eq!(sanitize_motd(" \n ").as_deref(), Ok(""));
+}
+
+#[test]
+fn overlong_submission_is_rejected() {
+    let text = "x".repeat(MOTD_LIMIT + 1);
+
+    assert_eq!(
+        sanitize_motd(&text),
+        Err(MotdRejection::TooLong {
+            chars: MOTD_LIMIT + 1
+        })
+    );
+    assert!(sanitize_motd(&"x".repeat(MOTD_LIMIT)).is_ok());
+}
+
+#[test]
+fn submission_with_too_many_lines_is_rejected() {
+    let text = vec!["line"; MOTD_MAX_LINES + 1].join("\n");
+
+    assert_eq!(
+        sanitize_motd(&text),
+        Err(MotdRejection::TooManyLines {
+            lines: MOTD_MAX_LINES + 1
+        })
+    );
+}
+
+#[test]
+fn rejection_reports_count_and_limit() {
+    let rejection = MotdRejection::TooLong { chars: 1200 };
+
+    assert_eq!(rejection.counts(), (1200, MOTD_LIMIT));
+}
@@ -0,0 +1,320 @@
+//! Telegram and ntfy alerts: the message conversions, which settings set up a backend, and
+//! what actually goes over the wire, checked against a local mock HTTP server.
+
+mod common;
+
+use dayz_monitor::{
+    notify::{backends, to_markdown_v2, to_plain_text},
+    DayzMonitorError,
+};
+use serde_json::json;
+
+use common::config;
+
+#[test]
+fn messages_are_converted_for_each_backend() {
+    let cases = [
+        (
+            "🔴 **Chernarus 1** is offline.",
+            "🔴 Chernarus 1 is offline.",
+            "🔴 *Chernarus 1* is offline\\.",
+        ),
+        (
+            "⏳ Queue on **DE\\_1** is __12__ (threshold 10).",
+            "⏳ Queue on DE_1 is 12 (threshold 10).",
+            "⏳ Queue on *DE\\_1* is 12 \\(threshold 10\\)\\.",
+        ),
+        (
+            "@\u{200B}everyone `code` ~~gone~~ ||spoiler|| 1+1=2!",
+            "@everyone code gone spoiler 1+1=2!",
+            "@everyone code gone spoiler 1\\+1\\=2\\!",
+        ),
+    ];
+
+    for (discord, plain, markdown) in cases {
+        assert_eq!(to_plain_text(discord), plain, "{discord}");
+        assert_eq!(to_markdown_v2(discord), markdown, "{discord}");
+    }
+}
+
+#[test]
+fn half_configured_backends_are_rejected() {
+    let cases = [
+        json!({ "telegram_bot_token": "123:abc" }),
+        json!({ "telegram_chat_id": "-100" }),
+        json!({ "ntfy_token": "tk_secret" }),
+    ];
+
+    for extra in cases {
+        assert!(
+            matches!(
+                backends(&config(extra.clone())),
+                Err(DayzMonitorError::IncompleteBackend(_))
+            ),
+            "{extra}"
+        );
+    }
+    assert!(backends(&config(json!({}))).unwrap().is_empty());
+}
+
+#[cfg(feature = "ntfy")]
+#[test]
+fn backends_route_their_configured_rules() {
+    use dayz_monitor::alerts::AlertKind;
+
+    let backends = backends(&config(json!({
+        "ntfy_url": "https://ntfy.sh/dayz",
+        "ntfy_rules": ["offline", "online"],
+    })))
+    .unwrap();
+
+    assert_eq!(backends.len(), 1);
+    assert_eq!(backends[0].name(), "ntfy");
+    let routed: Vec<AlertKind> = AlertKind::ALL
+        .into_iter()
+        .filter(|&kind| backends[0].routes(kind))
+        .collect();
+    assert_eq!(routed, [AlertKind::Offline, AlertKind::Online]);
+}
+
+#[cfg(any(feature = "telegram", feature = "ntfy"))]
+mod wire {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use dayz_monitor::{
+        alerts::{Alert, AlertKind},
+        notify::{Backend, BackendKind},
+        retry::RetryPolicy,
+        DayzMonitorError,
+    };
+    use reqwest::Client;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    /// A request as the mock server received it; header names are lowercased.
+    #[derive(Debug, Clone)]
+    struct Request {
+        method: String,
+        path: String,
+        headers: BTreeMap<String, String>,
+        body: String,
+    }
+
+    /// Answers each request with the next scripted status, then with 200, and records it.
+    struct MockServer {
+        url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl MockServer {
+        async fn start(statuses: &[u16]) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let statuses = Arc::new(Mutex::new(VecDeque::from(statuses.to_vec())));
+
+            let recorded = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut reader = BufReader::new(reader);
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).await.unwrap();
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut headers = BTreeMap::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).await.unwrap();
+                        let Some((name, value)) = line.trim_end().split_once(": ") else {
+                            break;
+                        };
+                        headers.insert(name.to_lowercase(), value.to_string());
+                    }
+
+                    let length = headers
+                        .get("content-length")
+                        .map_or(0, |n| n.parse().unwrap());
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).await.unwrap();
+
+                    recorded.lock().unwrap().push(Request {
+                        method,
+                        path,
+                        headers,
+                        body: String::from_utf8(body).unwrap(),
+                    });
+
+                    let status = statuses.lock().unwrap().pop_front().unwrap_or(200);
+                    let response = format!(
+                        "HTTP/1.1 {status} Mock\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{{}}"
+                    );
+                    writer.write_all(response.as_bytes()).await.unwrap();
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        fn requests(&self) -> Vec<Request> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn alert(kind: AlertKind, message: &str) -> Alert {
+        Alert {
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    fn backend(kind: BackendKind) -> Backend {
+        Backend {
+            kind,
+            rules: None,
+            retry: RetryPolicy::retries(2, Duration::from_millis(1)),
+        }
+    }
+
+    #[cfg(feature = "telegram")]
+    fn telegram(server: &MockServer) -> Backend {
+        use dayz_monitor::notify::Telegram;
+
+        backend(BackendKind::Telegram(Telegram {
+            api_url: server.url.clone(),
+            bot_token: "123:abc".to_string(),
+            chat_id: "-100200".to_string(),
+        }))
+    }
+
+    #[cfg(feature = "ntfy")]
+    fn ntfy(server: &MockServer, token: Option<&str>) -> Backend {
+        use dayz_monitor::notify::Ntfy;
+
+        backend(BackendKind::Ntfy(Ntfy {
+            url: format!("{}/dayz", server.url),
+            token: token.map(str::to_string),
+            title: "Chernarus 1".to_string(),
+        }))
+    }
+
+    #[cfg(feature = "telegram")]
+    #[tokio::test]
+    async fn telegram_posts_markdown_v2_to_the_chat() {
+        let server = MockServer::start(&[]).await;
+
+        telegram(&server)
+            .send(
+                &Client::new(),
+                &alert(AlertKind::Offline, "🔴 **Chernarus 1** is offline."),
+            )
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/bot123:abc/sendMessage");
+        assert_eq!(requests[0].headers["content-type"], "application/json");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&requests[0].body).unwrap(),
+            serde_json::json!({
+                "chat_id": "-100200",
+                "text": "🔴 *Chernarus 1* is offline\\.",
+                "parse_mode": "MarkdownV2",
+            })
+        );
+    }
+
+    #[cfg(feature = "ntfy")]
+    #[tokio::test]
+    async fn ntfy_publishes_plain_text_with_the_alert_severity() {
+        let cases = [
+            (AlertKind::Offline, Some("tk_secret"), "4"),
+            (AlertKind::Latency, None, "3"),
+            (AlertKind::Queue, None, "2"),
+        ];
+
+        for (kind, token, priority) in cases {
+            let server = MockServer::start(&[]).await;
+
+            ntfy(&server, token)
+                .send(&Client::new(), &alert(kind, "**Chernarus 1**: 1\\_2"))
+                .await
+                .unwrap();
+
+            let requests = server.requests();
+            assert_eq!(requests.len(), 1, "{kind:?}");
+            let request = &requests[0];
+            assert_eq!(
+                (request.method.as_str(), request.path.as_str()),
+                ("POST", "/dayz")
+            );
+            assert_eq!(request.body, "Chernarus 1: 1_2");
+            assert_eq!(request.headers["title"], "Chernarus 1");
+            assert_eq!(request.headers["priority"], priority, "{kind:?}");
+            assert_eq!(request.headers["tags"], kind.name());
+            assert_eq!(
+                request.headers.get("authorization").map(String::as_str),
+                token.map(|t| format!("Bearer {t}")).as_deref()
+            );
+        }
+    }
+
+    /// Sends one alert through whichever backend is built, returning how often it was tried.
+    async fn attempts(server: &MockServer) -> (usize, Result<(), DayzMonitorError>) {
+        #[cfg(feature = "telegram")]
+        let backend = telegram(server);
+        #[cfg(not(feature = "telegram"))]
+        let backend = ntfy(server, None);
+
+        let result = backend
+            .send(&Client::new(), &alert(AlertKind::Online, "🟢 back"))
+            .await;
+        (server.requests().len(), result)
+    }
+
+    #[tokio::test]
+    async fn server_errors_and_rate_limits_are_retried() {
+        let cases: [(&[u16], usize, bool); 4] = [
+            (&[503], 2, true),
+            (&[429, 502], 3, true),
+            // Out of retries: the first attempt and two more.
+            (&[500, 500, 500], 3, false),
+            // Rejected requests stay rejected.
+            (&[400], 1, false),
+        ];
+
+        for (statuses, expected_attempts, delivered) in cases {
+            let server = MockServer::start(statuses).await;
+
+            let (attempts, result) = attempts(&server).await;
+
+            assert_eq!(attempts, expected_attempts, "{statuses:?}");
+            assert_eq!(result.is_ok(), delivered, "{statuses:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_name_the_backend_without_the_url() {
+        let server = MockServer::start(&[403]).await;
+
+        let (_, result) = attempts(&server).await;
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.starts_with("Sending to "), "{message}");
+        assert!(message.contains("403"), "{message}");
+        // The Telegram URL carries the bot token.
+        assert!(!message.contains("123:abc"), "{message}");
+        assert!(!message.contains(&server.url), "{message}");
+    }
+}
@@ -0,0 +1,90 @@
+//! `NUMBER_LOCALE`: which locale numbers follow, and numeric template placeholders formatted
+//! for it unless written as `{name:raw}`.
+
+mod common;
+
+use dayz_monitor::{
+    alerts::{alert_rules, AlertTracker},
+    numbers::NumberLocale,
+    template::{render, TemplateValues},
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+#[test]
+fn locale_follows_the_bot_language_unless_set() {
+    let cases = [
+        (json!({}), NumberLocale::En),
+        (json!({ "bot_language": "de" }), NumberLocale::De),
+        (json!({ "bot_language": "ru" }), NumberLocale::Ru),
+        (
+            json!({ "bot_language": "de", "number_locale": "en" }),
+            NumberLocale::En,
+        ),
+        (json!({ "number_locale": "fr" }), NumberLocale::Fr),
+    ];
+
+    for (extra, expected) in cases {
+        assert_eq!(config(extra.clone()).number_locale(), expected, "{extra}");
+    }
+}
+
+#[test]
+fn server_numbers_are_formatted_and_raw_on_request() {
+    let config = config(json!({ "number_locale": "de" }));
+    let mut info = info(1_200);
+    info.max_players = 2_000;
+    info.players_in_queue = Some(1_500);
+
+    let values = TemplateValues::from_server_info(&config, &info);
+
+    assert_eq!(
+        render("{players}/{max_players} +{queue}", &values),
+        "1.200/2.000 +1.500"
+    );
+    assert_eq!(
+        render("{players:raw}/{max_players:raw} +{queue:raw}", &values),
+        "1200/2000 +1500"
+    );
+}
+
+#[test]
+fn raw_suffix_on_text_falls_back_to_the_value() {
+    let config = config(json!({ "number_locale": "de" }));
+    let values = TemplateValues::from_server_info(&config, &info(12));
+
+    assert_eq!(
+        render("{server_name:raw} {server_time:raw}", &values),
+        "Chernarus 1 14:32"
+    );
+    assert_eq!(render("{nope:raw}", &values), "{nope:raw}");
+}
+
+#[test]
+fn set_number_uses_the_config_locale() {
+    for (locale, expected) in [("en", "12,345"), ("fr", "12\u{202F}345")] {
+        let mut values = TemplateValues::from_config(&config(json!({ "number_locale": locale })));
+        values.set_number("threshold", 12_345);
+
+        assert_eq!(render("{threshold}", &values), expected);
+        assert_eq!(render("{threshold:raw}", &values), "12345");
+    }
+}
+
+#[test]
+fn alert_numbers_follow_the_locale() {
+    let mut config = config(json!({
+        "bot_language": "de",
+        "alert_queue_threshold": 1_000,
+        "alert_queue_template": "{queue} > {threshold} ({queue:raw})",
+    }));
+    config.alert_channel_id = Some(2);
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let mut busy = info(60);
+    busy.players_in_queue = Some(1_024);
+
+    let alerts = tracker.observe(&config, Some(&busy), NOW);
+
+    assert_eq!(alerts[0].message, "1.024 > 1.000 (1024)");
+}
@@ -0,0 +1,100 @@
+//! The log channel: what a state transition records, and how one poll's events are batched
+//! into messages.
+
+mod common;
+
+use dayz_monitor::{
+    monitor::{Monitor, Observation},
+    oplog::{OpEvent, OpLogBatch, EMBEDS_PER_MESSAGE},
+    MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+fn posted(message_id: u64) -> OpEvent {
+    OpEvent::StatusMessagePosted {
+        message_id,
+        replaced: None,
+    }
+}
+
+#[test]
+fn transition_records_the_sample_and_the_cause() {
+    let mut monitor = Monitor::new(config(json!({})).recovery_criterion());
+    let online = monitor.observe(Ok(info(12)), true);
+
+    assert_eq!(
+        OpEvent::transition(MonitorState::Unknown, &online),
+        OpEvent::Transition {
+            from: MonitorState::Unknown,
+            to: MonitorState::Online,
+            cause: None,
+            players: Some((12, 60)),
+            maintenance_until: None,
+        }
+    );
+
+    let mut offline = Observation::unknown();
+    offline.state = MonitorState::Offline;
+    offline.error = Some("timed out".to_string());
+    assert_eq!(
+        OpEvent::transition(MonitorState::Online, &offline),
+        OpEvent::Transition {
+            from: MonitorState::Online,
+            to: MonitorState::Offline,
+            cause: Some("timed out".to_string()),
+            players: None,
+            maintenance_until: None,
+        }
+    );
+
+    let mut maintenance = Observation::unknown();
+    maintenance.state = MonitorState::Maintenance;
+    maintenance.maintenance_until = Some(NOW + 1200);
+    assert!(matches!(
+        OpEvent::transition(MonitorState::Online, &maintenance),
+        OpEvent::Transition {
+            to: MonitorState::Maintenance,
+            maintenance_until: Some(until),
+            ..
+        } if until == NOW + 1200
+    ));
+}
+
+#[test]
+fn events_are_batched_into_as_few_messages_as_allowed() {
+    let cases = [
+        (0, vec![]),
+        (1, vec![1]),
+        (EMBEDS_PER_MESSAGE, vec![EMBEDS_PER_MESSAGE]),
+        (EMBEDS_PER_MESSAGE + 1, vec![EMBEDS_PER_MESSAGE, 1]),
+        (
+            2 * EMBEDS_PER_MESSAGE + 3,
+            vec![EMBEDS_PER_MESSAGE, EMBEDS_PER_MESSAGE, 3],
+        ),
+    ];
+
+    for (events, expected) in cases {
+        let mut batch = OpLogBatch::new(NOW);
+        for id in 0..events as u64 {
+            batch.push(posted(id));
+        }
+
+        assert_eq!(batch.is_empty(), events == 0);
+        let sizes: Vec<usize> = batch.messages().map(<[OpEvent]>::len).collect();
+        assert_eq!(sizes, expected, "{events} events");
+    }
+}
+
+#[test]
+fn batch_keeps_the_order_events_happened_in() {
+    let mut batch = OpLogBatch::new(NOW);
+    for id in 0..12 {
+        batch.push(posted(id));
+    }
+
+    let order: Vec<OpEvent> = batch.messages().flatten().cloned().collect();
+    assert_eq!(order, (0..12).map(posted).collect::<Vec<_>>());
+    assert_eq!(batch.at_unix, NOW);
+}
@@ -0,0 +1,217 @@
+//! Several status messages fed by one poll: each output gets its own render, and keeps its own
+//! message.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use dayz_monitor::{
+    cleanup::STATUS_MARKER,
+    i18n::Language,
+    monitor::{Monitor, Observation},
+    output::{render_output, Encoders, Layout, Output, Outputs, Rendered},
+    render::EmbedRepresentation,
+    title::{render_title, TitleState},
+    transport::{Priority, StatusMessage},
+    DayzMonitorConfig,
+};
+use serde_json::json;
+
+use common::{config, info, FakeDiscord, NOW};
+
+const PUBLIC: u64 = 111;
+const STAFF: u64 = 222;
+
+type Recorder = FakeDiscord<Rendered>;
+
+const ENCODERS: Encoders<Recorder> = Encoders {
+    post: Rendered::clone,
+    edit: Rendered::clone,
+};
+
+fn observe(players: u32) -> Observation {
+    Monitor::new(config(json!({})).recovery_criterion()).observe(Ok(info(players)), true)
+}
+
+/// Stands in for the frontend's detailed embed, showing which config it was drawn under.
+fn detailed(config: &DayzMonitorConfig, observation: &Observation) -> EmbedRepresentation {
+    let state = TitleState::of(observation);
+    let mut embed = EmbedRepresentation::new().title(render_title(
+        config,
+        state,
+        observation.info.as_ref(),
+        config.bot_language,
+    ));
+    if config.player_hours_field {
+        embed = embed.field("Player-hours", "0", true);
+    }
+    embed
+}
+
+async fn cycle(
+    outputs: &mut Outputs,
+    status: &StatusMessage<Recorder>,
+    seq: u64,
+    observation: &Observation,
+) -> Vec<u64> {
+    outputs
+        .publish(status, seq, Priority::Low, &ENCODERS, |output, config| {
+            render_output(config, output.layout, observation, NOW, |config| {
+                detailed(config, observation)
+            })
+        })
+        .await
+}
+
+fn two_outputs() -> Vec<Output> {
+    vec![
+        Output::parse("111 compact").unwrap(),
+        Output::parse("222 detailed fr +player_hours_field").unwrap(),
+    ]
+}
+
+#[test]
+fn spec_is_parsed() {
+    assert_eq!(
+        Output::parse("222/333 detailed de +player_hours_field -change_annotations").unwrap(),
+        Output {
+            channel_id: 222,
+            message_id: Some(333),
+            layout: Layout::Detailed,
+            language: Some(Language::De),
+            features: vec![("player_hours_field", true), ("change_annotations", false)],
+        }
+    );
+    assert_eq!(Output::parse("111 TEXT").unwrap().layout, Layout::Text);
+}
+
+#[test]
+fn invalid_specs_are_rejected() {
+    for spec in [
+        "",
+        "general compact",
+        "111",
+        "111 wide",
+        "111 compact xx",
+        "111 compact +nope",
+        "111 compact +change_annotations de",
+    ] {
+        assert!(Output::parse(spec).is_err(), "'{spec}' was accepted");
+    }
+}
+
+#[tokio::test]
+async fn one_cycle_renders_each_output_its_own_way() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let mut outputs = Outputs::new(&base, two_outputs(), &BTreeMap::new());
+
+    let posted = cycle(&mut outputs, &status, 1, &observe(44)).await;
+
+    assert_eq!(posted, vec![1001, 1002]);
+    let sent = status.transport().sent();
+    assert_eq!(
+        sent[0],
+        (
+            PUBLIC,
+            Rendered::Embed(
+                EmbedRepresentation::new()
+                    .description("🟢 44/60 • queue 0 • updated 08:53 UTC")
+                    .colour(TitleState::Online.colour())
+                    .footer(STATUS_MARKER)
+            )
+        )
+    );
+    assert_eq!(
+        sent[1],
+        (
+            STAFF,
+            Rendered::Embed(
+                EmbedRepresentation::new()
+                    .title("🟢 Chernarus 1 — En ligne")
+                    .field("Player-hours", "0", true)
+                    .footer(STATUS_MARKER)
+            )
+        )
+    );
+}
+
+#[tokio::test]
+async fn text_layout_posts_plain_line() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let outputs = vec![Output::parse("111 text").unwrap()];
+    let mut outputs = Outputs::new(&base, outputs, &BTreeMap::new());
+
+    cycle(&mut outputs, &status, 1, &observe(44)).await;
+
+    assert_eq!(
+        status.transport().sent()[0],
+        (
+            PUBLIC,
+            Rendered::Text("🟢 44/60 • queue 0 • updated 08:53 UTC".to_string())
+        )
+    );
+}
+
+#[tokio::test]
+async fn later_cycles_edit_only_changed_outputs() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let mut outputs = Outputs::new(&base, two_outputs(), &BTreeMap::new());
+
+    cycle(&mut outputs, &status, 1, &observe(44)).await;
+    cycle(&mut outputs, &status, 2, &observe(44)).await;
+    assert!(status.transport().edits().is_empty());
+
+    // The detailed stand-in shows no counts, so only the compact output changes.
+    let posted = cycle(&mut outputs, &status, 3, &observe(51)).await;
+
+    assert!(posted.is_empty());
+    let edits = status.transport().edits();
+    assert_eq!(edits.len(), 1);
+    assert_eq!((edits[0].0, edits[0].1), (PUBLIC, 1001));
+}
+
+#[tokio::test]
+async fn edits_share_the_render_sequence() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let outputs = vec![Output::parse("111/500 compact").unwrap()];
+    let mut outputs = Outputs::new(&base, outputs, &BTreeMap::new());
+
+    cycle(&mut outputs, &status, 5, &observe(44)).await;
+    // A slower render from before cycle 5 must not overwrite it.
+    cycle(&mut outputs, &status, 4, &observe(40)).await;
+
+    assert_eq!(status.transport().edits().len(), 1);
+}
+
+#[tokio::test]
+async fn message_ids_survive_a_restart() {
+    let base = config(json!({}));
+    let status = StatusMessage::new(Recorder::default());
+    let mut outputs = Outputs::new(&base, two_outputs(), &BTreeMap::new());
+    cycle(&mut outputs, &status, 1, &observe(44)).await;
+
+    let saved = outputs.message_ids();
+    assert_eq!(
+        saved,
+        BTreeMap::from([
+            ("111:compact".to_string(), 1001),
+            ("222:detailed:fr".to_string(), 1002),
+        ])
+    );
+
+    // Reordered, which must not swap their messages.
+    let mut reordered = two_outputs();
+    reordered.reverse();
+    let restarted = StatusMessage::new(Recorder::default());
+    let mut outputs = Outputs::new(&base, reordered, &saved);
+    let posted = cycle(&mut outputs, &restarted, 1, &observe(51)).await;
+
+    assert!(posted.is_empty());
+    let edits = restarted.transport().edits();
+    let targets: Vec<(u64, u64)> = edits.iter().map(|(c, m, _)| (*c, *m)).collect();
+    assert_eq!(targets, vec![(STAFF, 1002), (PUBLIC, 1001)]);
+}
@@ -0,0 +1,197 @@
+//! What the fuzz targets under `fuzz/` check, run over generated and hostile inputs so
+//! regressions show up without the fuzzer.
+
+use std::{fs, path::Path};
+
+use dayz_monitor::{
+    diagnostics::IgnoreReason,
+    mods::{decode_mods, Mod},
+    parse_keywords, parse_server_time,
+    query::parse_rules,
+    MAX_IGNORED_TOKENS, MAX_IGNORED_TOKEN_CHARS,
+};
+
+/// Deterministic xorshift, so a failure reproduces.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Pieces keywords strings are made of, plus some they shouldn't contain.
+const PIECES: &[&str] = &[
+    "battleye", "lqs", "etm", "4.000000", ",", ",", ":", ":", "0", "1", "2", "9", "12", "23", "24",
+    "59", "60", "99", "-", "+", " ", "a", "é", "\u{FFFD}", "\0", "lqslqs",
+];
+
+fn keywords(rng: &mut Rng) -> String {
+    (0..rng.below(40))
+        .map(|_| PIECES[rng.below(PIECES.len())])
+        .collect()
+}
+
+fn assert_keyword_invariants(keywords: &str) {
+    let parsed = parse_keywords(keywords);
+
+    if let Some(time) = &parsed.server_time {
+        assert!(
+            parse_server_time(time).is_some(),
+            "{keywords:?} gave time {time:?}"
+        );
+    }
+    assert!(parsed.ignored_tokens.len() <= MAX_IGNORED_TOKENS);
+    for ignored in &parsed.ignored_tokens {
+        assert!(ignored.token.chars().count() <= MAX_IGNORED_TOKEN_CHARS);
+    }
+}
+
+fn rules_fixture() -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rules/two-mods.bin");
+
+    fs::read(path).expect("rules fixture is readable")
+}
+
+fn assert_rules_invariants(data: &[u8]) {
+    if let Some(rules) = parse_rules(data) {
+        assert!(rules.len() <= data.len() / 2);
+        if let Some(mods) = decode_mods(&rules) {
+            assert!(mods.len() <= 255);
+        }
+    }
+    let _ = decode_mods(&[(vec![1, 1], data.to_vec())]);
+}
+
+#[test]
+fn generated_keywords_keep_the_invariants() {
+    let mut rng = Rng(0x5EED_1234_ABCD_0001);
+
+    for _ in 0..20_000 {
+        assert_keyword_invariants(&keywords(&mut rng));
+    }
+}
+
+#[test]
+fn hostile_keywords_stay_bounded() {
+    let many = "lqsx,".repeat(100_000);
+    assert_keyword_invariants(&many);
+    assert_eq!(
+        parse_keywords(&many).ignored_tokens.len(),
+        MAX_IGNORED_TOKENS
+    );
+
+    let long = format!("mod:{}", "é".repeat(100_000));
+    let parsed = parse_keywords(&long);
+    assert_eq!(
+        parsed.ignored_tokens[0].token.chars().count(),
+        MAX_IGNORED_TOKEN_CHARS
+    );
+
+    let bytes: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    assert_keyword_invariants(&String::from_utf8_lossy(&bytes));
+}
+
+#[test]
+fn only_real_times_are_read() {
+    for (token, expected) in [
+        ("12:04", Some((12, 4, 0))),
+        ("21:15:33", Some((21, 15, 33))),
+        ("7:30", Some((7, 30, 0))),
+        ("00:00", Some((0, 0, 0))),
+        ("23:59:59", Some((23, 59, 59))),
+        ("24:00", None),
+        ("99:99", None),
+        ("12:60", None),
+        ("12:3", None),
+        ("a:b", None),
+        ("+1:00", None),
+        ("12:00:", None),
+        ("1:2:3:4", None),
+        (":", None),
+        ("", None),
+        ("１２:００", None),
+    ] {
+        assert_eq!(parse_server_time(token), expected, "{token:?}");
+    }
+}
+
+#[test]
+fn bogus_time_tokens_are_ignored() {
+    let parsed = parse_keywords("battleye,lqs2,99:99,14:20");
+
+    assert_eq!(parsed.server_time.as_deref(), Some("14:20"));
+    assert_eq!(parsed.ignored_tokens[0].reason, IgnoreReason::MalformedTime);
+}
+
+#[test]
+fn queue_prefix_is_only_stripped_once() {
+    let parsed = parse_keywords("lqslqs5");
+
+    assert_eq!(parsed.queue, None);
+    assert_eq!(
+        parsed.ignored_tokens[0].reason,
+        IgnoreReason::MalformedQueue
+    );
+}
+
+#[test]
+fn rules_fixture_decodes() {
+    let rules = parse_rules(&rules_fixture()).expect("fixture parses");
+
+    assert_eq!(
+        decode_mods(&rules),
+        Some(vec![
+            Mod {
+                name: "CF".to_string(),
+                workshop_id: 1_559_212_036,
+            },
+            Mod {
+                name: "Community-Online-Tools".to_string(),
+                workshop_id: 1_564_026_768,
+            },
+        ])
+    );
+}
+
+#[test]
+fn truncated_and_corrupted_rules_never_panic() {
+    let fixture = rules_fixture();
+
+    for len in 0..=fixture.len() {
+        assert_rules_invariants(&fixture[..len]);
+    }
+
+    let mut rng = Rng(0x5EED_1234_ABCD_0002);
+    for _ in 0..20_000 {
+        let mut data = fixture.clone();
+        for _ in 0..=rng.below(4) {
+            let at = rng.below(data.len());
+            data[at] = rng.next() as u8;
+        }
+        assert_rules_invariants(&data);
+    }
+}
+
+#[test]
+fn random_rules_never_panic() {
+    let mut rng = Rng(0x5EED_1234_ABCD_0003);
+
+    for _ in 0..20_000 {
+        let mut data: Vec<u8> = (0..rng.below(300)).map(|_| rng.next() as u8).collect();
+        if let Some(first) = data.first_mut() {
+            // Mostly real rules responses, so the decoder is reached.
+            if rng.below(4) > 0 {
+                *first = 0x45;
+            }
+        }
+        assert_rules_invariants(&data);
+    }
+}
@@ -0,0 +1,104 @@
+//! `/ping`: timing each hop, and grading the latencies against the configured thresholds.
+
+mod common;
+
+use std::{net::UdpSocket, time::Duration};
+
+use dayz_monitor::probe::{measure, probe_a2s, LatencyGrade, LatencyThresholds};
+use serde_json::json;
+
+use common::config;
+
+fn ms(millis: u64) -> Option<Duration> {
+    Some(Duration::from_millis(millis))
+}
+
+#[test]
+fn latencies_are_graded_against_the_thresholds() {
+    let thresholds = config(json!({})).latency_thresholds();
+    assert_eq!(
+        thresholds,
+        LatencyThresholds {
+            warn: Duration::from_millis(150),
+            bad: Duration::from_millis(400),
+        }
+    );
+
+    let cases = [
+        (ms(0), LatencyGrade::Good),
+        (ms(149), LatencyGrade::Good),
+        (ms(150), LatencyGrade::Warn),
+        (ms(399), LatencyGrade::Warn),
+        (ms(400), LatencyGrade::Bad),
+        (ms(5000), LatencyGrade::Bad),
+        (None, LatencyGrade::Unavailable),
+    ];
+    for (latency, grade) in cases {
+        assert_eq!(thresholds.grade(latency), grade, "{latency:?}");
+    }
+}
+
+#[test]
+fn thresholds_come_from_the_config() {
+    let thresholds = config(json!({ "ping_warn_ms": 50, "ping_bad_ms": 100 })).latency_thresholds();
+
+    assert_eq!(thresholds.grade(ms(60)), LatencyGrade::Warn);
+    assert_eq!(thresholds.grade(ms(100)), LatencyGrade::Bad);
+}
+
+#[test]
+fn unavailable_is_the_worst_grade() {
+    let mut grades = [
+        LatencyGrade::Unavailable,
+        LatencyGrade::Good,
+        LatencyGrade::Bad,
+        LatencyGrade::Warn,
+    ];
+    grades.sort();
+
+    assert_eq!(
+        grades,
+        [
+            LatencyGrade::Good,
+            LatencyGrade::Warn,
+            LatencyGrade::Bad,
+            LatencyGrade::Unavailable
+        ]
+    );
+}
+
+#[tokio::test]
+async fn measure_times_a_successful_call() {
+    let call = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok::<_, ()>(())
+    };
+
+    let latency = measure(call, Duration::from_secs(3)).await.unwrap();
+
+    assert!(latency >= Duration::from_millis(50), "{latency:?}");
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_or_slow_call_is_unavailable() {
+    assert_eq!(
+        measure(async { Err::<(), _>("403") }, Duration::from_secs(3)).await,
+        None
+    );
+
+    let slow = async {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok::<_, ()>(())
+    };
+    assert_eq!(measure(slow, Duration::from_secs(3)).await, None);
+}
+
+#[tokio::test]
+async fn silent_game_server_is_unavailable() {
+    // Bound but never answering.
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let latency = probe_a2s(server.local_addr().unwrap(), Duration::from_millis(200)).await;
+
+    assert_eq!(latency, None);
+}
@@ -0,0 +1,116 @@
+//! Checking the A2S_INFO player count against the A2S_PLAYER list: what the list backs up,
+//! and how long a contradiction must last before it is flagged.
+
+mod common;
+
+use a2s::players::Player;
+use dayz_monitor::headcount::{CountCheck, CountMismatch, Headcount};
+use serde_json::json;
+
+use common::config;
+
+fn player(name: &str) -> Player {
+    Player {
+        index: 0,
+        name: name.to_string(),
+        score: 0,
+        duration: 60.0,
+        the_ship: None,
+    }
+}
+
+fn headcount(named: u32, connecting: u32) -> Option<Headcount> {
+    Some(Headcount { named, connecting })
+}
+
+#[test]
+fn unnamed_entries_count_as_connecting() {
+    let players = [player("Survivor"), player(""), player("Bandit"), player("")];
+
+    assert_eq!(Some(Headcount::of(&players)), headcount(2, 2));
+    assert_eq!(Headcount::of(&players).listed(), 4);
+    assert_eq!(Some(Headcount::of(&[])), headcount(0, 0));
+}
+
+#[test]
+fn count_agrees_from_the_named_entries_to_the_whole_list() {
+    let list = Headcount {
+        named: 10,
+        connecting: 2,
+    };
+    let cases = [
+        // (reported, tolerance, agrees)
+        (10, 0, true),
+        (12, 0, true),
+        (9, 0, false),
+        (13, 0, false),
+        (7, 3, true),
+        (6, 3, false),
+        (15, 3, true),
+        (16, 3, false),
+        // Servers that report a zeroed count.
+        (0, 3, false),
+        (u32::MAX, u32::MAX, true),
+    ];
+
+    for (reported, tolerance, agrees) in cases {
+        assert_eq!(
+            list.agrees_with(reported, tolerance),
+            agrees,
+            "{reported} ± {tolerance}"
+        );
+    }
+}
+
+#[test]
+fn mismatch_is_flagged_after_several_polls_in_a_row() {
+    let mut check = CountCheck::new(0, 3);
+    let mismatch = Some(CountMismatch {
+        reported: 40,
+        verified: 12,
+    });
+
+    let flagged: Vec<Option<CountMismatch>> = [
+        headcount(10, 2),
+        headcount(10, 2),
+        // Without a list, the streak is kept as it is.
+        None,
+        headcount(10, 2),
+        headcount(10, 2),
+    ]
+    .into_iter()
+    .map(|list| check.observe(40, list))
+    .collect();
+
+    assert_eq!(flagged, [None, None, None, mismatch, mismatch]);
+
+    // One agreeing poll ends it.
+    assert_eq!(check.observe(12, headcount(10, 2)), None);
+    assert_eq!(check.observe(40, headcount(10, 2)), None);
+}
+
+#[test]
+fn reset_starts_the_streak_over() {
+    let mut check = CountCheck::new(0, 2);
+
+    assert_eq!(check.observe(40, headcount(10, 0)), None);
+    check.reset();
+    assert_eq!(check.observe(40, headcount(10, 0)), None);
+    assert!(check.observe(40, headcount(10, 0)).is_some());
+}
+
+#[test]
+fn zero_polls_still_needs_one() {
+    let mut check = CountCheck::new(0, 0);
+
+    assert!(check.observe(40, headcount(10, 0)).is_some());
+}
+
+#[test]
+fn check_is_off_by_default() {
+    let defaults = config(json!({}));
+    assert!(!defaults.verify_player_count);
+    assert!(!defaults.player_count_annotation);
+    assert_eq!(defaults.player_count_tolerance, 3);
+    assert_eq!(defaults.player_count_mismatch_polls, 3);
+}
@@ -0,0 +1,160 @@
+//! Showing, hiding or bucketing the player count, and what turns off along with it.
+
+mod common;
+
+use dayz_monitor::{
+    alerts::{alert_rules, AlertKind},
+    features::{self, Activation},
+    monitor::{Monitor, Observation},
+    numbers::NumberLocale,
+    player_count::{approximate_range, PlayerCountMode},
+    template::{self, TemplateValues},
+    topic::render_topic,
+    DayzMonitorConfig,
+};
+use serde_json::json;
+
+use common::{info, NOW};
+
+fn config(mode: &str) -> DayzMonitorConfig {
+    common::config(json!({
+        "alert_channel_id": 2,
+        "alert_queue_threshold": 5,
+        "alert_chart_rules": ["offline"],
+        "full_forecast_occupancy": 70,
+        "player_count_mode": mode,
+    }))
+}
+
+fn observe(config: &DayzMonitorConfig, players: u32) -> Observation {
+    Monitor::new(config.recovery_criterion()).observe(Ok(info(players)), true)
+}
+
+/// The alert chart's activation in a build with charts, or that it's left out.
+fn chart(activation: Activation) -> Activation {
+    if cfg!(feature = "charts") {
+        activation
+    } else {
+        Activation::NotBuilt("charts")
+    }
+}
+
+fn activation(config: &DayzMonitorConfig, name: &str) -> Activation {
+    features::registry(config)
+        .into_iter()
+        .find(|f| f.name == name)
+        .map(|f| f.activation)
+        .unwrap()
+}
+
+#[test]
+fn ranges_are_steps_of_ten_within_the_slots() {
+    assert_eq!(approximate_range(0, 60), (0, 10));
+    assert_eq!(approximate_range(23, 60), (20, 30));
+    assert_eq!(approximate_range(30, 60), (30, 40));
+    assert_eq!(approximate_range(60, 60), (50, 60));
+    assert_eq!(approximate_range(64, 65), (60, 65));
+    // More players than slots, as some servers report
+    assert_eq!(approximate_range(80, 60), (50, 60));
+}
+
+#[test]
+fn display_per_mode() {
+    let locale = NumberLocale::En;
+
+    assert_eq!(
+        PlayerCountMode::Normal
+            .display(1234, 2000, locale)
+            .as_deref(),
+        Some("1,234")
+    );
+    assert_eq!(PlayerCountMode::Hidden.display(47, 60, locale), None);
+    assert_eq!(
+        PlayerCountMode::Approximate
+            .display(47, 60, locale)
+            .as_deref(),
+        Some("40–50")
+    );
+}
+
+#[test]
+fn topic_per_mode() {
+    for (mode, expected) in [
+        ("normal", "🟢 44/60 • queue 0 • updated 08:53 UTC"),
+        ("hidden", "🟢 ?/60 • queue 0 • updated 08:53 UTC"),
+        ("approximate", "🟢 40–50/60 • queue 0 • updated 08:53 UTC"),
+    ] {
+        let config = config(mode);
+
+        assert_eq!(render_topic(&config, &observe(&config, 44), NOW), expected);
+    }
+}
+
+#[test]
+fn raw_placeholder_does_not_reveal_the_count() {
+    for (mode, expected) in [
+        ("normal", "44 of 60"),
+        ("hidden", "? of 60"),
+        ("approximate", "40–50 of 60"),
+    ] {
+        let values = TemplateValues::from_server_info(&config(mode), &info(44));
+
+        assert_eq!(
+            template::render("{players:raw} of {max_players:raw}", &values),
+            expected
+        );
+    }
+}
+
+#[test]
+fn hidden_mode_turns_off_count_based_features() {
+    let config = config("hidden");
+
+    let rules: Vec<AlertKind> = alert_rules(&config).iter().map(|r| r.kind).collect();
+    assert!(!rules.contains(&AlertKind::Queue));
+    assert!(rules.contains(&AlertKind::Offline));
+
+    let inactive = Activation::Inactive("PLAYER_COUNT_MODE is hidden");
+    assert_eq!(activation(&config, "queue_alert"), inactive);
+    assert_eq!(activation(&config, "full_forecast"), inactive);
+    assert_eq!(
+        activation(&config, "alert_chart"),
+        chart(Activation::Inactive("PLAYER_COUNT_MODE is not normal"))
+    );
+}
+
+#[test]
+fn approximate_mode_keeps_alerts_but_not_charts() {
+    let config = config("approximate");
+
+    let rules: Vec<AlertKind> = alert_rules(&config).iter().map(|r| r.kind).collect();
+    assert!(rules.contains(&AlertKind::Queue));
+
+    assert_eq!(activation(&config, "queue_alert"), Activation::On);
+    assert_eq!(activation(&config, "full_forecast"), Activation::On);
+    assert_eq!(
+        activation(&config, "alert_chart"),
+        chart(Activation::Inactive("PLAYER_COUNT_MODE is not normal"))
+    );
+}
+
+#[test]
+fn normal_mode_changes_nothing() {
+    let config = config("normal");
+
+    assert_eq!(activation(&config, "queue_alert"), Activation::On);
+    assert_eq!(activation(&config, "full_forecast"), Activation::On);
+    assert_eq!(activation(&config, "alert_chart"), chart(Activation::On));
+}
+
+#[test]
+fn unknown_mode_is_rejected() {
+    let result: Result<DayzMonitorConfig, _> = serde_json::from_value(json!({
+        "discord_token": "token",
+        "server_address": "127.0.0.1:2303",
+        "text_channel_id": 1,
+        "player_count_mode": "fuzzy",
+    }));
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,250 @@
+//! Player-hours per day: integrating the sampled player count over irregularly spaced polls,
+//! splitting intervals at local midnights (including DST days), and what is left uncredited.
+
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::{Europe::Berlin, Tz, UTC};
+use dayz_monitor::{
+    i18n::Language,
+    numbers::NumberLocale,
+    playtime::{hours, summary, PlayerHours, DAYS_KEPT},
+};
+
+/// Large enough that no test interval counts as the bot having been down.
+const NO_GAP: u64 = 7 * 86_400;
+
+fn at(tz: Tz, y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> u64 {
+    tz.with_ymd_and_hms(y, m, d, h, min, s)
+        .earliest()
+        .unwrap()
+        .timestamp() as u64
+}
+
+/// Records `(unix, players)` samples in order and returns the per-day totals.
+fn credited(samples: &[(u64, Option<u32>)], max_gap_secs: u64, tz: Tz) -> Vec<(String, u64)> {
+    let mut hours = PlayerHours::default();
+    for &(unix, players) in samples {
+        hours.record(unix, players, max_gap_secs, tz);
+    }
+    hours.days.into_iter().collect()
+}
+
+fn day(date: &str, player_secs: u64) -> (String, u64) {
+    (date.to_string(), player_secs)
+}
+
+#[test]
+fn each_interval_is_credited_with_the_count_at_its_start() {
+    let t = at(UTC, 2025, 10, 9, 12, 0, 0);
+
+    // 5 players for 30 s, 10 for 90 s, nobody for 5 s; the last count has no interval yet.
+    let samples = [
+        (t, Some(5)),
+        (t + 30, Some(10)),
+        (t + 120, Some(0)),
+        (t + 125, Some(40)),
+    ];
+
+    assert_eq!(
+        credited(&samples, 300, UTC),
+        [day("2025-10-09", 5 * 30 + 10 * 90)]
+    );
+}
+
+#[test]
+fn irregular_spacing_sums_to_the_same_total() {
+    let t = at(UTC, 2025, 10, 9, 12, 0, 0);
+    let regular: Vec<_> = (0..=60).map(|i| (t + i * 60, Some(12))).collect();
+    let mut irregular = vec![(t, Some(12))];
+    let mut offset = 0;
+    for step in [1, 59, 7, 173, 60, 2, 298, 200, 3, 97] {
+        offset += step;
+        irregular.push((t + offset, Some(12)));
+    }
+    while offset < 3600 {
+        offset = (offset + 250).min(3600);
+        irregular.push((t + offset, Some(12)));
+    }
+
+    assert_eq!(credited(&regular, 300, UTC), [day("2025-10-09", 12 * 3600)]);
+    assert_eq!(
+        credited(&irregular, 300, UTC),
+        [day("2025-10-09", 12 * 3600)]
+    );
+}
+
+#[test]
+fn gaps_longer_than_the_limit_are_not_credited() {
+    let t = at(UTC, 2025, 10, 9, 12, 0, 0);
+    let cases = [
+        // Exactly at the limit still counts as continuous.
+        (vec![(t, Some(10)), (t + 180, Some(10))], 1_800),
+        (vec![(t, Some(10)), (t + 181, Some(10))], 0),
+        // A long gap only loses its own interval.
+        (
+            vec![(t, Some(10)), (t + 600, Some(10)), (t + 660, Some(10))],
+            600,
+        ),
+    ];
+
+    for (samples, expected) in cases {
+        let total: u64 = credited(&samples, 180, UTC).iter().map(|(_, s)| s).sum();
+        assert_eq!(total, expected, "{samples:?}");
+    }
+}
+
+#[test]
+fn outages_end_the_streak() {
+    let t = at(UTC, 2025, 10, 9, 12, 0, 0);
+
+    let samples = [
+        (t, Some(10)),
+        (t + 60, None),
+        (t + 120, None),
+        (t + 180, Some(8)),
+        (t + 240, Some(8)),
+    ];
+
+    // The minute up to the outage, then only the minute after it ended.
+    assert_eq!(
+        credited(&samples, 300, UTC),
+        [day("2025-10-09", 10 * 60 + 8 * 60)]
+    );
+}
+
+#[test]
+fn repeated_or_backwards_samples_credit_nothing() {
+    let t = at(UTC, 2025, 10, 9, 12, 0, 0);
+
+    let samples = [(t, Some(10)), (t, Some(10)), (t - 30, Some(10))];
+
+    assert_eq!(credited(&samples, 300, UTC), []);
+}
+
+#[test]
+fn intervals_are_split_at_midnight() {
+    let cases = [
+        // UTC: one minute either side.
+        (
+            UTC,
+            at(UTC, 2025, 10, 9, 23, 59, 0),
+            120,
+            vec![day("2025-10-09", 600), day("2025-10-10", 600)],
+        ),
+        // Ending exactly at midnight credits nothing to the next day.
+        (
+            UTC,
+            at(UTC, 2025, 10, 9, 23, 59, 0),
+            60,
+            vec![day("2025-10-09", 600)],
+        ),
+        // Starting exactly at midnight belongs to the new day.
+        (
+            UTC,
+            at(UTC, 2025, 10, 10, 0, 0, 0),
+            60,
+            vec![day("2025-10-10", 600)],
+        ),
+        // Berlin's midnight is 22:00 UTC in summer.
+        (
+            Berlin,
+            at(UTC, 2025, 7, 1, 21, 59, 0),
+            120,
+            vec![day("2025-07-01", 600), day("2025-07-02", 600)],
+        ),
+        // One interval spanning two midnights touches three days.
+        (
+            UTC,
+            at(UTC, 2025, 10, 9, 23, 0, 0),
+            26 * 3600,
+            vec![
+                day("2025-10-09", 10 * 3600),
+                day("2025-10-10", 10 * 86_400),
+                day("2025-10-11", 10 * 3600),
+            ],
+        ),
+    ];
+
+    for (tz, start, length, expected) in cases {
+        let samples = [(start, Some(10)), (start + length, Some(10))];
+        assert_eq!(
+            credited(&samples, NO_GAP, tz),
+            expected,
+            "{tz} from {start} for {length}s"
+        );
+    }
+}
+
+#[test]
+fn dst_days_have_their_real_length() {
+    let cases = [
+        // Spring forward: 23 hours.
+        (3, 30, 23),
+        // Fall back: 25 hours.
+        (10, 26, 25),
+        (7, 1, 24),
+    ];
+
+    for (month, day, expected_hours) in cases {
+        let date = NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let start = at(Berlin, 2025, month, day, 0, 0, 0);
+        // Polled every 10 minutes from local midnight into the next day.
+        let samples: Vec<_> = (0..=27 * 6).map(|i| (start + i * 600, Some(1))).collect();
+
+        let mut hours = PlayerHours::default();
+        for &(unix, players) in &samples {
+            hours.record(unix, players, 900, Berlin);
+        }
+
+        assert_eq!(
+            hours.days[&date.to_string()],
+            expected_hours * 3600,
+            "{date}"
+        );
+    }
+}
+
+#[test]
+fn today_follows_the_stats_timezone() {
+    let start = at(UTC, 2025, 7, 1, 21, 0, 0);
+    let mut hours = PlayerHours::default();
+    hours.record(start, Some(10), NO_GAP, Berlin);
+    hours.record(start + 7200, Some(10), NO_GAP, Berlin);
+
+    // 21:00–22:00 UTC was July 1st in Berlin, 22:00–23:00 UTC July 2nd.
+    assert_eq!(hours.today(start + 7200, Berlin), 36_000);
+    assert_eq!(hours.today(start, Berlin), 36_000);
+    assert_eq!(hours.today(start + 7200 + 86_400, Berlin), 0);
+}
+
+#[test]
+fn only_the_last_days_are_kept() {
+    let start = at(UTC, 2025, 10, 1, 12, 0, 0);
+    let mut hours = PlayerHours::default();
+    for i in 0..=10 * 24 {
+        hours.record(start + i * 3600, Some(1), NO_GAP, UTC);
+    }
+
+    assert_eq!(hours.days.len(), DAYS_KEPT);
+    assert_eq!(hours.days.keys().next().unwrap(), "2025-10-04");
+    assert_eq!(hours.days.keys().last().unwrap(), "2025-10-11");
+    assert_eq!(hours.days["2025-10-10"], 86_400);
+}
+
+#[test]
+fn report_lists_hours_per_day() {
+    let mut hours_per_day = PlayerHours::default();
+    hours_per_day.days.insert("2025-10-08".to_string(), 45_000);
+    hours_per_day
+        .days
+        .insert("2025-10-09".to_string(), 3_600_000);
+
+    assert_eq!(hours(5_400), 1.5);
+    assert_eq!(
+        summary(&hours_per_day, NumberLocale::De, Language::De),
+        "Spielerstunden:\n  2025-10-08      12,5\n  2025-10-09      1.000,0\n"
+    );
+    assert_eq!(
+        summary(&PlayerHours::default(), NumberLocale::En, Language::En),
+        "Player-hours:\n  (none)\n"
+    );
+}
@@ -0,0 +1,173 @@
+//! Today's players from the A2S_PLAYER list: distinct names up to a cap, session lengths for a
+//! bounded number of players, and what `/stats` and the usage summary make of them.
+
+mod common;
+
+use a2s::players::Player;
+use chrono::TimeZone;
+use chrono_tz::{Europe::Berlin, UTC};
+use dayz_monitor::{
+    daily::{report, DailyStats},
+    i18n::Language,
+    numbers::NumberLocale,
+    playtime::PlayerHours,
+    roster::{listed_names, DayRoster, Sessions},
+    state::PersistentState,
+    usage::UsageCounters,
+};
+
+use common::NOW;
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn players_still_connecting_have_no_name_to_list() {
+    let player = |name: &str| Player {
+        index: 0,
+        name: name.to_string(),
+        score: 0,
+        duration: 60.0,
+        the_ship: None,
+    };
+
+    let listed = listed_names(&[player("Survivor"), player(""), player("Bandit")]);
+
+    assert_eq!(listed, ["Survivor", "Bandit"]);
+}
+
+#[test]
+fn roster_counts_distinct_players_and_keeps_names_up_to_the_cap() {
+    let mut roster = DayRoster::default();
+
+    let dropped = [
+        roster.record(&names(&["Survivor", "Bandit"]), NOW, UTC, 3),
+        roster.record(&names(&["Survivor", "Medic", "Hunter"]), NOW + 60, UTC, 3),
+        roster.record(&names(&["Hunter", "Trader"]), NOW + 120, UTC, 3),
+    ];
+
+    assert_eq!(dropped, [0, 1, 1]);
+    assert_eq!(roster.seen.len(), 5);
+    assert_eq!(
+        roster.seen.names().collect::<Vec<_>>(),
+        ["Bandit", "Medic", "Survivor"]
+    );
+}
+
+#[test]
+fn roster_starts_over_at_local_midnight() {
+    let before = Berlin
+        .with_ymd_and_hms(2025, 10, 9, 23, 59, 0)
+        .unwrap()
+        .timestamp() as u64;
+    let mut roster = DayRoster::default();
+
+    roster.record(&names(&["Survivor", "Bandit"]), before, Berlin, 10);
+    assert_eq!(roster.day, "2025-10-09");
+    roster.record(&names(&["Survivor"]), before + 60, Berlin, 10);
+
+    assert_eq!(roster.day, "2025-10-10");
+    assert_eq!(roster.seen.len(), 1);
+}
+
+#[test]
+fn sessions_last_while_a_player_stays_listed() {
+    let mut sessions = Sessions::new(10);
+
+    assert_eq!(sessions.observe(&names(&["Survivor"]), NOW), Some(0));
+    assert_eq!(
+        sessions.observe(&names(&["Survivor", "Bandit"]), NOW + 600),
+        Some(600)
+    );
+    // Survivor left; coming back starts a new session.
+    assert_eq!(sessions.observe(&names(&["Bandit"]), NOW + 1200), Some(600));
+    assert_eq!(
+        sessions.observe(&names(&["Bandit", "Survivor"]), NOW + 1800),
+        Some(1200)
+    );
+
+    sessions.clear();
+    assert!(sessions.is_empty());
+    assert_eq!(sessions.observe(&[], NOW + 2400), None);
+}
+
+#[test]
+fn sessions_past_the_cap_forget_the_least_recently_listed() {
+    let mut sessions = Sessions::new(2);
+
+    sessions.observe(&names(&["Survivor", "Bandit"]), NOW);
+    // A third player only fits by dropping one of the others.
+    sessions.observe(&names(&["Survivor", "Bandit", "Medic"]), NOW + 60);
+
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions.evictions(), 1);
+}
+
+#[test]
+fn sessions_follow_a_clock_step() {
+    let mut sessions = Sessions::new(10);
+    sessions.observe(&names(&["Survivor"]), NOW);
+
+    sessions.shift_clock(3600);
+
+    assert_eq!(
+        sessions.observe(&names(&["Survivor"]), NOW + 3660),
+        Some(60)
+    );
+}
+
+#[test]
+fn stats_show_todays_players_and_longest_session() {
+    let mut daily = DailyStats::default();
+    daily.record_roster(40, Some(1800), NOW, UTC);
+    // A shorter session later doesn't lower the longest.
+    daily.record_roster(42, Some(600), NOW + 60, UTC);
+
+    let today = report(&daily, &PlayerHours::default(), &[], NOW + 60, UTC);
+
+    assert_eq!(today.unique_players_today, Some(42));
+    assert_eq!(today.longest_session_today_secs, Some(1800));
+
+    // Without a player list there is nothing to show.
+    let empty = report(
+        &DailyStats::default(),
+        &PlayerHours::default(),
+        &[],
+        NOW,
+        UTC,
+    );
+    assert_eq!(empty.unique_players_today, None);
+    assert_eq!(empty.longest_session_today_secs, None);
+}
+
+#[test]
+fn usage_summary_mentions_caps_only_once_hit() {
+    let mut usage = UsageCounters::default();
+    let summary = usage.summary(NOW, NumberLocale::En, Language::En);
+    assert!(!summary.contains("Names not kept"), "{summary}");
+
+    usage.record_caps(25, 0);
+    usage.record_caps(5, 3);
+    let summary = usage.summary(NOW, NumberLocale::En, Language::En);
+
+    assert!(summary.contains("Names not kept:   30\n"), "{summary}");
+    assert!(summary.contains("Sessions dropped: 3\n"), "{summary}");
+}
+
+#[test]
+fn roster_is_kept_in_the_state_file() {
+    let mut state = PersistentState::default();
+    state
+        .roster
+        .record(&names(&["Survivor", "Bandit", "Medic"]), NOW, UTC, 2);
+
+    let json = serde_json::to_string(&state).unwrap();
+    let loaded: PersistentState = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.roster, state.roster);
+    assert_eq!(loaded.roster.seen.len(), 3);
+    // State files from before the roster load with an empty one.
+    let legacy: PersistentState = serde_json::from_str(r#"{"version": 1}"#).unwrap();
+    assert!(legacy.roster.seen.is_empty());
+}
@@ -0,0 +1,281 @@
+//! Checking the message ids saved in the state file against what is actually in the channels
+//! at startup, and repairing them under each policy.
+
+mod common;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use dayz_monitor::{
+    reconcile::{self, Check, PersistedMessage, Summary, Use},
+    state::PersistentState,
+    DayzMonitorConfig, DayzMonitorError,
+};
+use serde_json::json;
+
+use common::BOT;
+
+const STATUS_CHANNEL: u64 = 10;
+
+type FakeDiscord = common::FakeDiscord<()>;
+
+fn not_found() -> impl Fn(&String) -> bool {
+    |err| err.starts_with("404")
+}
+
+fn config(policy: &str) -> DayzMonitorConfig {
+    common::config(json!({
+        "text_channel_id": STATUS_CHANNEL,
+        "outputs": ["111 compact", "222 detailed"],
+        "reconcile_policy": policy,
+    }))
+}
+
+/// Two outputs, one since removed, and three mod list pages.
+fn saved() -> PersistentState {
+    PersistentState {
+        output_message_ids: BTreeMap::from([
+            ("111:compact".to_string(), 11),
+            ("222:detailed".to_string(), 22),
+            ("333:text".to_string(), 33),
+        ]),
+        mod_list_message_ids: vec![41, 42, 43],
+        mod_list_hash: Some(7),
+        ..Default::default()
+    }
+}
+
+/// Everything in `saved` where it belongs, posted by the bot.
+fn intact() -> FakeDiscord {
+    FakeDiscord::default()
+        .with(111, 11, BOT)
+        .with(222, 22, BOT)
+        .with(333, 33, BOT)
+        .with(STATUS_CHANNEL, 41, BOT)
+        .with(STATUS_CHANNEL, 42, BOT)
+        .with(STATUS_CHANNEL, 43, BOT)
+}
+
+async fn run(
+    discord: &FakeDiscord,
+    policy: &str,
+    state: &mut PersistentState,
+) -> Result<Summary, DayzMonitorError> {
+    reconcile::reconcile(discord, &config(policy), state, BOT, not_found()).await
+}
+
+#[test]
+fn saved_ids_are_listed_with_their_channel() {
+    let configured = BTreeSet::from(["111:compact".to_string(), "222:detailed".to_string()]);
+
+    let messages = reconcile::persisted(&saved(), &configured, STATUS_CHANNEL);
+
+    let message = |used_as, channel_id, message_id| PersistedMessage {
+        used_as,
+        channel_id,
+        message_id,
+    };
+    assert_eq!(
+        messages,
+        [
+            message(Use::Output("111:compact".to_string()), 111, 11),
+            message(Use::Output("222:detailed".to_string()), 222, 22),
+            message(Use::Orphan("333:text".to_string()), 333, 33),
+            message(Use::ModListPage(1), STATUS_CHANNEL, 41),
+            message(Use::ModListPage(2), STATUS_CHANNEL, 42),
+            message(Use::ModListPage(3), STATUS_CHANNEL, 43),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn intact_messages_are_kept_and_the_orphan_deleted() {
+    let discord = intact();
+    let mut state = saved();
+
+    let summary = run(&discord, "adopt", &mut state).await.unwrap();
+
+    assert_eq!(summary.to_string(), "5 ok, 1 orphan deleted");
+    assert!(summary.changed());
+    assert_eq!(discord.deleted(), [(333, 33)]);
+    assert_eq!(
+        state.output_message_ids,
+        BTreeMap::from([
+            ("111:compact".to_string(), 11),
+            ("222:detailed".to_string(), 22)
+        ])
+    );
+    assert_eq!(state.mod_list_message_ids, [41, 42, 43]);
+    assert_eq!(state.mod_list_hash, Some(7));
+}
+
+#[tokio::test]
+async fn nothing_to_repair_changes_nothing() {
+    let discord = intact();
+    let mut state = PersistentState {
+        output_message_ids: BTreeMap::from([("111:compact".to_string(), 11)]),
+        ..saved()
+    };
+
+    let summary = run(&discord, "adopt", &mut state).await.unwrap();
+
+    assert_eq!(summary.to_string(), "4 ok");
+    assert!(!summary.changed());
+}
+
+#[tokio::test]
+async fn missing_moved_and_foreign_messages_are_recreated() {
+    let discord = FakeDiscord::default()
+        .with(111, 11, BOT)
+        // Output 222 now posts somewhere else than where its message is
+        .with(999, 22, BOT)
+        .with(333, 33, BOT)
+        .with(STATUS_CHANNEL, 41, BOT)
+        .with(STATUS_CHANNEL, 43, 500);
+    let mut state = saved();
+
+    let summary = run(&discord, "adopt", &mut state).await.unwrap();
+
+    assert_eq!(summary.to_string(), "2 ok, 3 recreated, 1 orphan deleted");
+    assert_eq!(
+        state.output_message_ids,
+        BTreeMap::from([("111:compact".to_string(), 11)])
+    );
+    // The list syncs again, editing the page left and posting the others
+    assert_eq!(state.mod_list_message_ids, [41]);
+    assert_eq!(state.mod_list_hash, None);
+}
+
+#[tokio::test]
+async fn failed_lookups_are_kept_by_adopt_and_recreated_by_recreate() {
+    let discord = || FakeDiscord {
+        unavailable: BTreeSet::from([22, 42]),
+        ..intact()
+    };
+
+    let mut adopted = saved();
+    let summary = run(&discord(), "adopt", &mut adopted).await.unwrap();
+    assert_eq!(summary.to_string(), "3 ok, 2 unverified, 1 orphan deleted");
+    assert_eq!(adopted.output_message_ids.get("222:detailed"), Some(&22));
+    assert_eq!(adopted.mod_list_message_ids, [41, 42, 43]);
+
+    let mut recreated = saved();
+    let summary = run(&discord(), "recreate", &mut recreated).await.unwrap();
+    assert_eq!(summary.to_string(), "3 ok, 2 recreated, 1 orphan deleted");
+    assert_eq!(recreated.output_message_ids.get("222:detailed"), None);
+    assert_eq!(recreated.mod_list_message_ids, [41, 43]);
+}
+
+#[tokio::test]
+async fn orphans_that_cant_be_deleted_are_forgotten() {
+    let discord = FakeDiscord {
+        undeletable: BTreeSet::from([33]),
+        ..intact()
+    }
+    .with(444, 44, 500);
+    let mut state = saved();
+    state
+        .output_message_ids
+        .insert("444:compact:de".to_string(), 44);
+    state.output_message_ids.insert("555:text".to_string(), 55);
+
+    let summary = run(&discord, "adopt", &mut state).await.unwrap();
+
+    // Failed to delete, someone else's, and already gone
+    assert_eq!(summary.to_string(), "5 ok, 3 orphans forgotten");
+    assert!(discord.deleted().is_empty());
+    assert_eq!(
+        state.output_message_ids.keys().collect::<Vec<_>>(),
+        ["111:compact", "222:detailed"]
+    );
+}
+
+#[tokio::test]
+async fn strict_mode_fails_and_leaves_the_state_alone() {
+    let discord = FakeDiscord {
+        unavailable: BTreeSet::from([43]),
+        ..FakeDiscord::default()
+            .with(111, 11, BOT)
+            .with(STATUS_CHANNEL, 41, BOT)
+            .with(STATUS_CHANNEL, 42, 500)
+    };
+    let mut state = saved();
+
+    let err = run(&discord, "strict", &mut state).await.unwrap_err();
+
+    let DayzMonitorError::MessageMismatch(mismatches) = &err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(
+        mismatches,
+        "output 222:detailed: message 22 is not in 222; \
+         mod list page 2: message 42 was posted by someone; \
+         mod list page 3: message 43 could not be looked up (503 Service Unavailable)"
+    );
+    assert_eq!(state, saved());
+    assert!(discord.deleted().is_empty());
+}
+
+#[tokio::test]
+async fn strict_mode_passes_when_everything_checks_out() {
+    let discord = intact();
+    let mut state = saved();
+
+    let summary = run(&discord, "strict", &mut state).await.unwrap();
+
+    assert_eq!(summary.to_string(), "5 ok, 1 orphan deleted");
+}
+
+#[tokio::test(start_paused = true)]
+async fn lookups_are_bounded_and_keep_their_order() {
+    let mut discord = FakeDiscord {
+        delay: Duration::from_millis(100),
+        ..FakeDiscord::default()
+    };
+    let messages: Vec<PersistedMessage> = (1..=10)
+        .map(|id| PersistedMessage {
+            used_as: Use::ModListPage(id as usize),
+            channel_id: STATUS_CHANNEL,
+            message_id: id,
+        })
+        .collect();
+    for id in (1..=10).filter(|id| id % 3 != 0) {
+        discord = discord.with(STATUS_CHANNEL, id, BOT);
+    }
+
+    let checks = reconcile::check(&discord, &messages, BOT, 3, not_found()).await;
+
+    assert_eq!(discord.max_in_flight.load(Ordering::SeqCst), 3);
+    let missing: Vec<u64> = messages
+        .iter()
+        .zip(&checks)
+        .filter(|(_, check)| **check == Check::Missing)
+        .map(|(message, _)| message.message_id)
+        .collect();
+    assert_eq!(missing, [3, 6, 9]);
+    assert_eq!(checks.len(), 10);
+}
+
+#[test]
+fn summary_names_only_what_happened() {
+    let summary = Summary {
+        ok: 7,
+        recreated: 2,
+        orphans_deleted: 1,
+        ..Default::default()
+    };
+    assert_eq!(summary.to_string(), "7 ok, 2 recreated, 1 orphan deleted");
+
+    let summary = Summary {
+        orphans_deleted: 2,
+        orphans_forgotten: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        summary.to_string(),
+        "0 ok, 2 orphans deleted, 1 orphan forgotten"
+    );
+}
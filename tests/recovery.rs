@@ -0,0 +1,193 @@
+//! The recovery criterion: how many good polls, and what evidence, a server needs after an
+//! outage before it counts as online again, and the starting state shown meanwhile.
+
+mod common;
+
+use dayz_monitor::{
+    i18n::Language,
+    monitor::{Monitor, RecoveryCheck, RecoveryCriterion},
+    title::{render_title, TitleState},
+    DayzMonitorError, MonitorState, ServerInfo,
+};
+use serde_json::json;
+
+use common::{config, info};
+
+/// One poll: a sample with this many players (and whether a rules query answered), a
+/// rejected sample, or no answer at all.
+#[derive(Clone, Copy)]
+enum Poll {
+    Up(u32, bool),
+    Rejected,
+    Down,
+}
+
+use Poll::{Down, Rejected, Up};
+
+fn states(criterion: RecoveryCriterion, polls: &[Poll]) -> Vec<MonitorState> {
+    let mut monitor = Monitor::new(criterion);
+    polls
+        .iter()
+        .map(|poll| {
+            let (result, rules_answered) = match *poll {
+                Up(players, rules) => (Ok(info(players)), rules),
+                Rejected => (
+                    Err(DayzMonitorError::ImplausibleResponse(
+                        "players 65535 above 200".to_string(),
+                    )),
+                    false,
+                ),
+                Down => (down(), false),
+            };
+            monitor.observe(result, rules_answered).state
+        })
+        .collect()
+}
+
+fn down() -> Result<ServerInfo, DayzMonitorError> {
+    Err(DayzMonitorError::A2SError(
+        a2s::errors::Error::InvalidResponse,
+    ))
+}
+
+fn criterion(polls: u32, check: RecoveryCheck) -> RecoveryCriterion {
+    RecoveryCriterion { polls, check }
+}
+
+#[test]
+fn default_criterion_recovers_on_the_first_answer() {
+    use MonitorState::{Offline, Online};
+
+    assert_eq!(
+        states(
+            RecoveryCriterion::default(),
+            &[Up(12, false), Down, Up(0, false)]
+        ),
+        [Online, Offline, Online]
+    );
+}
+
+#[test]
+fn recovery_needs_consecutive_polls_and_evidence() {
+    use MonitorState::{Degraded, Offline, Online, Starting};
+
+    let cases = [
+        // Answering three times in a row is enough without a check.
+        (
+            criterion(3, RecoveryCheck::Answer),
+            vec![Down, Up(0, false), Up(0, false), Up(0, false)],
+            vec![Offline, Starting, Starting, Online],
+        ),
+        // Going down again starts the count over.
+        (
+            criterion(2, RecoveryCheck::Answer),
+            vec![Down, Up(0, false), Down, Up(0, false), Up(0, false)],
+            vec![Offline, Starting, Offline, Starting, Online],
+        ),
+        // A rejected sample neither counts nor resets the count.
+        (
+            criterion(2, RecoveryCheck::Answer),
+            vec![Down, Up(0, false), Rejected, Up(0, false)],
+            vec![Offline, Starting, Starting, Online],
+        ),
+        // Joinable: the same player count and no rules answer keeps it starting.
+        (
+            criterion(1, RecoveryCheck::Joinable),
+            vec![Down, Up(0, false), Up(0, false), Up(0, false)],
+            vec![Offline, Starting, Starting, Starting],
+        ),
+        // Joinable: somebody got in.
+        (
+            criterion(1, RecoveryCheck::Joinable),
+            vec![Down, Up(0, false), Up(1, false)],
+            vec![Offline, Starting, Online],
+        ),
+        // Joinable: the rules query answered.
+        (
+            criterion(1, RecoveryCheck::Joinable),
+            vec![Down, Up(0, true)],
+            vec![Offline, Online],
+        ),
+        // Evidence early on still waits for the poll count.
+        (
+            criterion(3, RecoveryCheck::Joinable),
+            vec![Down, Up(0, true), Up(0, false), Up(0, false)],
+            vec![Offline, Starting, Starting, Online],
+        ),
+        // The bot starting up is not a recovery, and a rejected sample while online is
+        // degraded, not starting.
+        (
+            criterion(3, RecoveryCheck::Joinable),
+            vec![Up(12, false), Rejected, Up(12, false)],
+            vec![Online, Degraded, Online],
+        ),
+    ];
+
+    for (i, (criterion, polls, expected)) in cases.into_iter().enumerate() {
+        assert_eq!(states(criterion, &polls), expected, "case {i}");
+    }
+}
+
+#[test]
+fn rules_are_probed_only_while_they_can_complete_a_recovery() {
+    let mut monitor = Monitor::new(criterion(2, RecoveryCheck::Joinable));
+    assert!(!monitor.needs_rules_probe());
+
+    monitor.observe(down(), false);
+    assert!(monitor.needs_rules_probe());
+
+    monitor.observe(Ok(info(0)), true);
+    assert!(!monitor.needs_rules_probe());
+
+    monitor.observe(Ok(info(0)), false);
+    assert!(!monitor.needs_rules_probe());
+
+    let mut answer_only = Monitor::new(criterion(2, RecoveryCheck::Answer));
+    answer_only.observe(down(), false);
+    assert!(!answer_only.needs_rules_probe());
+}
+
+#[test]
+fn criterion_comes_from_the_config() {
+    assert_eq!(
+        config(json!({})).recovery_criterion(),
+        RecoveryCriterion::default()
+    );
+    assert_eq!(
+        config(json!({ "recovery_polls": 3, "recovery_check": "joinable" })).recovery_criterion(),
+        criterion(3, RecoveryCheck::Joinable)
+    );
+}
+
+#[test]
+fn strict_recovery_is_listed_as_a_feature() {
+    let enabled = |extra| {
+        config(extra)
+            .enabled_features()
+            .contains(&"strict_recovery")
+    };
+
+    assert!(!enabled(json!({})));
+    assert!(enabled(json!({ "recovery_polls": 2 })));
+    assert!(enabled(json!({ "recovery_check": "joinable" })));
+}
+
+#[test]
+fn starting_server_keeps_its_data_under_a_starting_title() {
+    let config = config(json!({}));
+    let mut monitor = Monitor::new(criterion(2, RecoveryCheck::Answer));
+    monitor.observe(down(), false);
+
+    let observation = monitor.observe(Ok(info(4)), false);
+
+    assert_eq!(TitleState::of(&observation), TitleState::Starting);
+    assert_eq!(observation.info.as_ref().unwrap().players, 4);
+    assert_ne!(TitleState::Starting.colour(), TitleState::Online.colour());
+    let title = render_title(
+        &config,
+        TitleState::Starting,
+        observation.info.as_ref(),
+        Language::En,
+    );
+    assert_eq!(title, "🔵 Chernarus 1 — Starting up…");
+}
@@ -0,0 +1,163 @@
+//! Ping sharing between bots in different regions: two instances writing to one shared
+//! directory, and which of the other regions' pings each one shows.
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use dayz_monitor::{
+    i18n::Language,
+    numbers::NumberLocale,
+    regions::{other_regions, ping_line, DirStore, PingStore, RegionPing},
+};
+
+use common::NOW;
+
+const INTERVAL: u64 = 60;
+
+fn shared_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("dayz-monitor-region-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn ping(region: &str, latency_ms: u32, measured_unix: u64) -> RegionPing {
+    RegionPing {
+        region: region.to_string(),
+        latency_ms,
+        measured_unix,
+    }
+}
+
+#[test]
+fn two_instances_see_each_other() {
+    let dir = shared_dir("two");
+    let eu = DirStore { dir: dir.clone() };
+    let na = DirStore { dir: dir.clone() };
+
+    eu.publish(&ping("EU", 18, NOW)).unwrap();
+    na.publish(&ping("NA", 95, NOW + 5)).unwrap();
+
+    let seen_by_eu = other_regions(eu.read_all().unwrap(), "EU", NOW + 10, INTERVAL);
+    assert_eq!(seen_by_eu, [ping("NA", 95, NOW + 5)]);
+    let seen_by_na = other_regions(na.read_all().unwrap(), "NA", NOW + 10, INTERVAL);
+    assert_eq!(seen_by_na, [ping("EU", 18, NOW)]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn publishing_again_replaces_the_instance_ping() {
+    let dir = shared_dir("replace");
+    let eu = DirStore { dir: dir.clone() };
+    let na = DirStore { dir: dir.clone() };
+
+    na.publish(&ping("NA", 95, NOW)).unwrap();
+    na.publish(&ping("NA", 110, NOW + INTERVAL)).unwrap();
+
+    let pings = eu.read_all().unwrap();
+    assert_eq!(pings, [ping("NA", 110, NOW + INTERVAL)]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn unreadable_files_are_skipped() {
+    let dir = shared_dir("skip");
+    let store = DirStore { dir: dir.clone() };
+    store.publish(&ping("NA", 95, NOW)).unwrap();
+    fs::write(dir.join("AS.json"), "{\"region\":").unwrap();
+    fs::write(dir.join("AS.json.tmp"), "{}").unwrap();
+    fs::write(dir.join("notes.txt"), "hello").unwrap();
+
+    assert_eq!(store.read_all().unwrap(), [ping("NA", 95, NOW)]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn missing_directory_is_an_error() {
+    let store = DirStore {
+        dir: shared_dir("missing").join("nope"),
+    };
+
+    assert!(store.read_all().is_err());
+    assert!(store.publish(&ping("EU", 18, NOW)).is_err());
+}
+
+#[test]
+fn region_names_stay_inside_the_directory() {
+    let dir = shared_dir("names");
+    let store = DirStore { dir: dir.clone() };
+
+    store.publish(&ping("../US East", 40, NOW)).unwrap();
+
+    let names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["___US_East.json"]);
+    assert_eq!(store.read_all().unwrap()[0].region, "../US East");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn stale_and_own_pings_are_left_out() {
+    let pings = vec![
+        ping("EU", 18, NOW),
+        ping("NA", 95, NOW - 3 * INTERVAL),
+        ping("AS", 210, NOW - 3 * INTERVAL - 1),
+    ];
+
+    let shown = other_regions(pings, "EU", NOW, INTERVAL);
+
+    assert_eq!(shown, [ping("NA", 95, NOW - 3 * INTERVAL)]);
+}
+
+#[test]
+fn newest_ping_per_region_is_shown_sorted_by_region() {
+    let pings = vec![
+        ping("SA", 150, NOW),
+        ping("NA", 95, NOW - 30),
+        ping("NA", 99, NOW - 10),
+        ping("AS", 210, NOW),
+    ];
+
+    let shown = other_regions(pings, "EU", NOW, INTERVAL);
+
+    assert_eq!(
+        shown,
+        [
+            ping("AS", 210, NOW),
+            ping("NA", 99, NOW - 10),
+            ping("SA", 150, NOW)
+        ]
+    );
+}
+
+#[test]
+fn ping_line_lists_the_own_region_first() {
+    let own = ping("EU", 18, NOW);
+    let others = [ping("AS", 1_210, NOW), ping("NA", 95, NOW)];
+
+    assert_eq!(
+        ping_line(&own, &[], Language::En, NumberLocale::En),
+        "Ping from EU: **18 ms**"
+    );
+    assert_eq!(
+        ping_line(&own, &others, Language::Fr, NumberLocale::Fr),
+        "Ping depuis EU: **18 ms** • AS: **1\u{202f}210 ms** • NA: **95 ms**"
+    );
+}
+
+#[test]
+fn region_names_are_sanitized() {
+    let own = ping("EU *bold*", 18, NOW);
+
+    let line = ping_line(&own, &[], Language::En, NumberLocale::En);
+
+    assert_eq!(line, "Ping from EU \\*bold\\*: **18 ms**");
+}
@@ -0,0 +1,128 @@
+//! Status renders applied out of order: edits to one message are serialized, and a render
+//! older than the one already applied is dropped, so the message always ends on the newest.
+
+mod common;
+
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+use dayz_monitor::transport::{EditOutcome, RenderSequence, StatusMessage, StatusUpdate};
+
+use common::FakeDiscord;
+
+const CHANNEL: u64 = 1;
+const MESSAGE: u64 = 500;
+
+type Status = StatusMessage<FakeDiscord<u64>>;
+
+fn update(seq: u64) -> StatusUpdate<u64> {
+    StatusUpdate { seq, payload: seq }
+}
+
+/// Applies every update in its own task, starting them `stagger` apart in the given order.
+async fn apply_concurrently(
+    status: &Arc<Status>,
+    seqs: &[u64],
+    stagger: Duration,
+) -> Vec<EditOutcome> {
+    let mut tasks = Vec::new();
+    for &seq in seqs {
+        let status = status.clone();
+        tasks.push(tokio::spawn(async move {
+            status.apply(CHANNEL, MESSAGE, update(seq)).await.unwrap()
+        }));
+        tokio::time::sleep(stagger).await;
+    }
+
+    let mut outcomes = Vec::new();
+    for task in tasks {
+        outcomes.push(task.await.unwrap());
+    }
+    outcomes
+}
+
+#[test]
+fn sequence_numbers_increase() {
+    let sequence = RenderSequence::default();
+
+    assert_eq!(
+        [sequence.next(), sequence.next(), sequence.next()],
+        [1, 2, 3]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn edits_finishing_in_reverse_order_end_on_the_last_render() {
+    // Each render's edit is slower than the next one's, so unserialized edits would complete
+    // newest first and leave the oldest render on the message.
+    let status = Arc::new(StatusMessage::new(FakeDiscord {
+        edit_delay: Some(|seq: &u64| Duration::from_millis(400 - seq * 100)),
+        ..Default::default()
+    }));
+
+    let outcomes = apply_concurrently(&status, &[1, 2, 3], Duration::from_millis(10)).await;
+
+    assert_eq!(outcomes, [EditOutcome::Applied; 3]);
+    let fake = status.transport();
+    assert_eq!(fake.payloads(), [1, 2, 3]);
+    assert_eq!(fake.max_in_flight.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn renders_arriving_in_reverse_order_keep_the_newest() {
+    let status = Arc::new(StatusMessage::new(FakeDiscord {
+        delay: Duration::from_millis(100),
+        ..Default::default()
+    }));
+
+    let outcomes = apply_concurrently(&status, &[3, 2, 1], Duration::from_millis(10)).await;
+
+    assert_eq!(
+        outcomes,
+        [EditOutcome::Applied, EditOutcome::Stale, EditOutcome::Stale]
+    );
+    assert_eq!(status.transport().payloads(), [3]);
+}
+
+#[tokio::test]
+async fn failed_edit_does_not_count_as_applied() {
+    let status = StatusMessage::new(FakeDiscord::<u64>::default());
+
+    status.transport().fail_edits(true);
+    assert!(status.apply(CHANNEL, MESSAGE, update(5)).await.is_err());
+    status.transport().fail_edits(false);
+
+    // Render 4 was older than the failed one, but nothing newer reached the message.
+    let outcome = status.apply(CHANNEL, MESSAGE, update(4)).await.unwrap();
+    assert_eq!(outcome, EditOutcome::Applied);
+    assert_eq!(status.transport().payloads(), [4]);
+}
+
+#[tokio::test]
+async fn messages_are_sequenced_independently() {
+    let status = StatusMessage::new(FakeDiscord::<u64>::default());
+
+    status.apply(CHANNEL, MESSAGE, update(5)).await.unwrap();
+    let outcome = status.apply(CHANNEL, MESSAGE + 1, update(2)).await.unwrap();
+
+    assert_eq!(outcome, EditOutcome::Applied);
+}
+
+#[tokio::test]
+async fn forgotten_message_accepts_any_render() {
+    let status = StatusMessage::new(FakeDiscord::<u64>::default());
+    status.apply(CHANNEL, MESSAGE, update(5)).await.unwrap();
+    assert_eq!(
+        status.apply(CHANNEL, MESSAGE, update(4)).await.unwrap(),
+        EditOutcome::Stale
+    );
+
+    status.forget(MESSAGE);
+
+    assert_eq!(
+        status.apply(CHANNEL, MESSAGE, update(4)).await.unwrap(),
+        EditOutcome::Applied
+    );
+}
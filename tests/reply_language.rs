@@ -0,0 +1,72 @@
+//! Which language a slash command reply is in: the invoking user's Discord locale when it is
+//! supported, the configured language otherwise or when forced.
+
+use dayz_monitor::i18n::{resolve_language, Language, Text};
+
+#[test]
+fn locales_map_to_their_primary_language() {
+    let cases = [
+        ("en-US", Some(Language::En)),
+        ("en-GB", Some(Language::En)),
+        ("de", Some(Language::De)),
+        ("fr", Some(Language::Fr)),
+        ("ru_RU.UTF-8", Some(Language::Ru)),
+        (" DE-at ", Some(Language::De)),
+        ("pt-BR", None),
+        ("zh-CN", None),
+        ("", None),
+        ("-", None),
+    ];
+
+    for (locale, expected) in cases {
+        assert_eq!(Language::from_locale(locale), expected, "{locale:?}");
+    }
+}
+
+#[test]
+fn reply_follows_the_user_locale() {
+    assert_eq!(
+        resolve_language(Some("de"), Language::En, false),
+        Language::De
+    );
+    assert_eq!(
+        resolve_language(Some("ru"), Language::Fr, false),
+        Language::Ru
+    );
+}
+
+#[test]
+fn unsupported_or_missing_locale_falls_back_to_the_configured_language() {
+    assert_eq!(
+        resolve_language(Some("pt-BR"), Language::Fr, false),
+        Language::Fr
+    );
+    assert_eq!(resolve_language(None, Language::De, false), Language::De);
+}
+
+#[test]
+fn forced_language_ignores_the_user_locale() {
+    assert_eq!(
+        resolve_language(Some("de"), Language::Fr, true),
+        Language::Fr
+    );
+}
+
+#[test]
+fn reply_texts_are_translated() {
+    let texts = [
+        Text::Online,
+        Text::Offline,
+        Text::Players,
+        Text::Queue,
+        Text::NoPlayers,
+        Text::OnlinePlayers,
+        Text::NoStatusYet,
+    ];
+
+    for text in texts {
+        let translations: Vec<&str> = Language::ALL.iter().map(|l| l.tr(text)).collect();
+        assert!(translations.iter().all(|t| !t.is_empty()), "{text:?}");
+        assert_ne!(Language::En.tr(text), Language::Ru.tr(text), "{text:?}");
+    }
+}
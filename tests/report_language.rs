@@ -0,0 +1,199 @@
+//! The `stats` reports and one-line outputs in every supported language, with per-language
+//! plural forms.
+
+mod common;
+
+use std::path::Path;
+
+use dayz_monitor::{
+    i18n::{Language, Noun, Plural, REPORT_NOUNS, REPORT_TEXTS},
+    maps,
+    monitor::Monitor,
+    numbers::NumberLocale,
+    output::{render_output, Layout, Output, Rendered},
+    state::{load_state, PersistentState},
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+fn legacy_state() -> PersistentState {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/state/legacy.json");
+
+    load_state(&path).expect("state fixture loads")
+}
+
+fn usage(lang: Language) -> String {
+    legacy_state()
+        .usage
+        .summary(NOW, NumberLocale::from_language(lang), lang)
+}
+
+#[test]
+fn plural_rules_per_language() {
+    use Plural::*;
+
+    let cases: [(Language, &[(u64, Plural)]); 4] = [
+        (
+            Language::En,
+            &[(0, Other), (1, One), (2, Other), (21, Other)],
+        ),
+        (
+            Language::De,
+            &[(0, Other), (1, One), (2, Other), (21, Other)],
+        ),
+        (Language::Fr, &[(0, One), (1, One), (2, Other), (21, Other)]),
+        (
+            Language::Ru,
+            &[
+                (0, Other),
+                (1, One),
+                (2, Few),
+                (4, Few),
+                (5, Other),
+                (11, Other),
+                (12, Other),
+                (14, Other),
+                (21, One),
+                (22, Few),
+                (25, Other),
+                (101, One),
+                (111, Other),
+                (112, Other),
+                (1_004, Few),
+            ],
+        ),
+    ];
+
+    for (lang, expected) in cases {
+        for &(n, plural) in expected {
+            assert_eq!(lang.plural(n), plural, "{lang:?} {n}");
+        }
+    }
+}
+
+#[test]
+fn russian_nouns_follow_the_number() {
+    let days: Vec<String> = [1, 3, 5, 11, 21, 24, 115]
+        .into_iter()
+        .map(|n| format!("{n} {}", Language::Ru.noun(Noun::Day, n)))
+        .collect();
+
+    assert_eq!(
+        days,
+        [
+            "1 день",
+            "3 дня",
+            "5 дней",
+            "11 дней",
+            "21 день",
+            "24 дня",
+            "115 дней"
+        ]
+    );
+}
+
+#[test]
+fn report_strings_exist_in_every_language() {
+    // The string table is an exhaustive match, so a missing translation doesn't compile; this
+    // guards against placeholders left empty.
+    for lang in Language::ALL {
+        for &text in REPORT_TEXTS {
+            assert!(!lang.tr(text).trim().is_empty(), "{lang:?} {text:?}");
+        }
+        for &noun in REPORT_NOUNS {
+            for n in [0, 1, 2, 5, 21] {
+                assert!(!lang.noun(noun, n).is_empty(), "{lang:?} {noun:?} {n}");
+            }
+        }
+    }
+}
+
+#[test]
+fn every_language_translates_the_report() {
+    let english = usage(Language::En);
+
+    for lang in Language::ALL.into_iter().filter(|l| *l != Language::En) {
+        let report = usage(lang);
+
+        assert_eq!(report.lines().count(), english.lines().count(), "{lang:?}");
+        assert_ne!(
+            report.lines().next(),
+            english.lines().next(),
+            "{lang:?} starts like the English report"
+        );
+    }
+}
+
+#[test]
+fn usage_report_in_english() {
+    assert_eq!(
+        usage(Language::En),
+        "Running for:      115 days (12 starts)\n\
+         Update cycles:    86,400\n\
+         Offline:          2.8% of cycles\n\
+         \n\
+         Alerts fired:\n  \
+         offline         3\n\
+         \n\
+         Features enabled (starts):\n  \
+         mod_list        12\n"
+    );
+}
+
+#[test]
+fn usage_report_in_russian() {
+    let report = usage(Language::Ru);
+    let mut lines = report.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("Работает:         115 дней (12 запусков)")
+    );
+    assert_eq!(lines.next(), Some("Циклов обновления: 86\u{a0}400"));
+}
+
+#[test]
+fn maps_report_in_german() {
+    let state = legacy_state();
+
+    assert_eq!(
+        maps::summary(
+            &state.map_stats,
+            &state.map_rotation,
+            NumberLocale::De,
+            Language::De
+        ),
+        "Karten:\n  Chernarus       Ø Spieler 38,1, Spitze 60, Verfügbarkeit 97,2% (aktuell)\n"
+    );
+}
+
+#[test]
+fn one_line_outputs_follow_their_language() {
+    let base = config(json!({}));
+    let observation = Monitor::new(base.recovery_criterion()).observe(Ok(info(44)), true);
+
+    let line = |spec: &str| {
+        let output = Output::parse(spec).unwrap();
+        match render_output(
+            &output.config(&base),
+            Layout::Text,
+            &observation,
+            NOW,
+            |_| unreachable!(),
+        ) {
+            Rendered::Text(text) => text,
+            Rendered::Embed(_) => unreachable!(),
+        }
+    };
+
+    assert_eq!(
+        line("111 text de"),
+        "🟢 44/60 • Warteschlange 0 • aktualisiert 08:53 UTC"
+    );
+    assert_eq!(line("222 text"), "🟢 44/60 • queue 0 • updated 08:53 UTC");
+    assert_eq!(
+        line("333 text ru"),
+        "🟢 44/60 • очередь 0 • обновлено 08:53 UTC"
+    );
+}
@@ -0,0 +1,207 @@
+//! The retry policy every network operation shares: how many attempts, how long in between,
+//! which errors are worth another try, and when the time budget runs out.
+
+use std::time::Duration;
+
+use dayz_monitor::{retry::RetryPolicy, DayzMonitorConfig, DayzMonitorError};
+use serde_json::json;
+use tokio::time::Instant;
+
+const SECOND: Duration = Duration::from_secs(1);
+
+/// Doubling delays from one second, without jitter so the timing is exact.
+fn doubling(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay: SECOND,
+        multiplier: 2.0,
+        jitter: 0.0,
+        budget: None,
+    }
+}
+
+/// Runs `policy` on an operation that fails `failures` times before succeeding, returning its
+/// result, how many attempts were made and how long it all took.
+async fn run(
+    policy: RetryPolicy,
+    failures: u32,
+    retryable: impl Fn(&u32) -> bool,
+) -> (Result<u32, u32>, u32, Duration) {
+    let started = Instant::now();
+    let mut attempts = 0;
+
+    let result = policy
+        .run_if(
+            || {
+                attempts += 1;
+                let attempt = attempts;
+                async move {
+                    if attempt <= failures {
+                        Err(attempt)
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+            retryable,
+        )
+        .await;
+
+    (result, attempts, started.elapsed())
+}
+
+#[test]
+fn delays_grow_by_the_multiplier() {
+    let policy = doubling(5);
+
+    let delays: Vec<Duration> = (1..=4).map(|retry| policy.delay(retry, 0.5)).collect();
+
+    assert_eq!(delays, [1, 2, 4, 8].map(Duration::from_secs));
+}
+
+#[test]
+fn jitter_stays_within_its_fraction() {
+    let policy = RetryPolicy::retries(5, SECOND);
+
+    assert_eq!(policy.delay(1, 0.0), Duration::from_millis(800));
+    assert_eq!(policy.delay(1, 0.5), SECOND);
+    assert_eq!(policy.delay(3, 0.0), Duration::from_millis(3200));
+
+    for retry in 1..=5 {
+        let nominal = SECOND * 2u32.pow(retry - 1);
+        for sample in [0.0, 0.1, 0.25, 0.5, 0.75, 0.999, 1.0] {
+            let delay = policy.delay(retry, sample);
+
+            assert!(
+                delay >= nominal.mul_f64(0.8) && delay <= nominal.mul_f64(1.2),
+                "retry {retry} with sample {sample} waited {delay:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn out_of_range_settings_are_clamped() {
+    let policy = RetryPolicy {
+        jitter: 3.0,
+        ..doubling(3)
+    };
+
+    assert_eq!(policy.delay(1, 0.0), Duration::ZERO);
+    assert_eq!(policy.delay(1, 1.0), 2 * SECOND);
+    assert_eq!(policy.delay(1, -5.0), Duration::ZERO);
+    assert_eq!(doubling(3).delay(u32::MAX, 0.5), Duration::MAX);
+}
+
+#[tokio::test(start_paused = true)]
+async fn jittered_run_waits_within_bounds() {
+    let (result, attempts, elapsed) = run(RetryPolicy::retries(3, SECOND), 3, |_| true).await;
+
+    assert_eq!(result, Ok(4));
+    assert_eq!(attempts, 4);
+    // 1s + 2s + 4s, each up to 20% shorter or longer
+    assert!(
+        elapsed >= Duration::from_millis(5600) && elapsed <= Duration::from_millis(8400),
+        "took {elapsed:?}"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn success_after_failures_is_returned() {
+    let (result, attempts, elapsed) = run(doubling(5), 2, |_| true).await;
+
+    assert_eq!(result, Ok(3));
+    assert_eq!(attempts, 3);
+    assert_eq!(elapsed, 3 * SECOND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn attempts_run_out_with_the_last_error() {
+    let (result, attempts, elapsed) = run(doubling(3), 10, |_| true).await;
+
+    assert_eq!(result, Err(3));
+    assert_eq!(attempts, 3);
+    assert_eq!(elapsed, 3 * SECOND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn non_retryable_error_short_circuits() {
+    // Only odd attempts fail in a way worth retrying
+    let (result, attempts, elapsed) = run(doubling(5), 10, |attempt| attempt % 2 == 1).await;
+
+    assert_eq!(result, Err(2));
+    assert_eq!(attempts, 2);
+    assert_eq!(elapsed, SECOND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn budget_stops_retries_that_would_wait_past_it() {
+    let policy = doubling(10).with_budget(Duration::from_secs(5));
+
+    // Waits 1s and 2s; the next 4s would end at 7s, past the budget.
+    let (result, attempts, elapsed) = run(policy, 10, |_| true).await;
+
+    assert_eq!(result, Err(3));
+    assert_eq!(attempts, 3);
+    assert_eq!(elapsed, 3 * SECOND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fixed_interval_polls_until_the_budget_is_spent() {
+    let policy = RetryPolicy::every(Duration::from_millis(500), Duration::from_secs(2));
+
+    let (result, attempts, _) = run(policy, 100, |_| true).await;
+    assert_eq!(result, Err(5));
+    assert_eq!(attempts, 5);
+
+    let (result, attempts, elapsed) = run(policy, 2, |_| true).await;
+    assert_eq!(result, Ok(3));
+    assert_eq!(attempts, 3);
+    assert_eq!(elapsed, SECOND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn none_attempts_once() {
+    let (result, attempts, elapsed) = run(RetryPolicy::NONE, 1, |_| true).await;
+
+    assert_eq!(result, Err(1));
+    assert_eq!(attempts, 1);
+    assert_eq!(elapsed, Duration::ZERO);
+}
+
+#[test]
+fn config_policies_follow_the_naming_scheme() {
+    let config: DayzMonitorConfig = serde_json::from_value(json!({
+        "discord_token": "token",
+        "server_address": "127.0.0.1:2303",
+        "text_channel_id": 1,
+        "update_interval_secs": 30,
+        "a2s_retries": 2,
+        "a2s_retry_delay_ms": 250,
+    }))
+    .expect("config deserializes");
+
+    let a2s = config.a2s_retry();
+    assert_eq!(a2s.max_attempts, 3);
+    assert_eq!(a2s.base_delay, Duration::from_millis(250));
+    assert_eq!(a2s.budget, Some(Duration::from_secs(15)));
+
+    let edit = config.status_edit_retry();
+    assert_eq!(edit.max_attempts, 3);
+    assert_eq!(edit.base_delay, SECOND);
+    assert_eq!(edit.budget, Some(Duration::from_secs(15)));
+}
+
+#[test]
+fn only_network_failures_are_transient() {
+    let timeout = DayzMonitorError::A2SError(a2s::errors::Error::ErrTimeout);
+    let refused =
+        DayzMonitorError::TokioIOError(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+    let garbled = DayzMonitorError::A2SError(a2s::errors::Error::InvalidResponse);
+    let implausible = DayzMonitorError::ImplausibleResponse("timed out".to_string());
+
+    assert!(timeout.is_transient());
+    assert!(refused.is_transient());
+    assert!(!garbled.is_transient());
+    assert!(!implausible.is_transient());
+}
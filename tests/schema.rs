@@ -0,0 +1,116 @@
+//! The status file's JSON Schema is a published contract: the generated schema must match
+//! `schema/status-file.schema.json`, and documents the bot writes must validate against it.
+//!
+//! Run with `cargo test --features schema`. After a deliberate change, regenerate the file with
+//! `cargo run --features schema -- --print-schema > schema/status-file.schema.json`.
+#![cfg(feature = "schema")]
+
+use std::{fs, path::Path, time::Duration};
+
+use dayz_monitor::{
+    diagnostics::{Diagnostics, IgnoreReason, IgnoredToken},
+    status_file::{status_file_schema, StatusFile},
+    MonitorState, ServerInfo,
+};
+use serde_json::Value;
+
+fn published() -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("schema/status-file.schema.json");
+    let raw = fs::read_to_string(&path).expect("published schema is readable");
+
+    serde_json::from_str(&raw).expect("published schema is JSON")
+}
+
+fn sample_info() -> ServerInfo {
+    ServerInfo {
+        server_time: Some("14:32".to_string()),
+        players_in_queue: Some(3),
+        players: 60,
+        max_players: 60,
+        map: "chernarusplus".to_string(),
+        latency: Some(Duration::from_millis(38)),
+        last_updated_unix: 1_760_000_000,
+        mods: None,
+        headcount: None,
+        player_names: None,
+        game_port: None,
+        join_port: None,
+        ignored_tokens: Vec::new(),
+    }
+}
+
+fn assert_valid(status: &StatusFile) {
+    let schema = status_file_schema();
+    let validator = jsonschema::validator_for(&schema).expect("schema compiles");
+    let document = serde_json::to_value(status).expect("status file serializes");
+
+    if let Err(err) = validator.validate(&document) {
+        panic!("{document} does not validate: {err}");
+    }
+}
+
+#[test]
+fn generated_schema_matches_published() {
+    assert_eq!(
+        status_file_schema(),
+        published(),
+        "schema/status-file.schema.json is out of date"
+    );
+}
+
+#[test]
+fn online_document_validates() {
+    assert_valid(&StatusFile::new(
+        MonitorState::Online,
+        Some(&sample_info()),
+        Diagnostics::default(),
+    ));
+}
+
+#[test]
+fn unknown_document_validates() {
+    assert_valid(&StatusFile::new(
+        MonitorState::Unknown,
+        None,
+        Diagnostics::default(),
+    ));
+}
+
+#[test]
+fn offline_document_validates() {
+    assert_valid(&StatusFile::new(
+        MonitorState::Offline,
+        None,
+        Diagnostics::default(),
+    ));
+}
+
+#[test]
+fn document_with_diagnostics_validates() {
+    let diagnostics = Diagnostics {
+        ignored_tokens: vec![IgnoredToken {
+            token: "lqsabc".to_string(),
+            reason: IgnoreReason::MalformedQueue,
+        }],
+        rejected: Some("players 900 above 200".to_string()),
+    };
+
+    assert_valid(&StatusFile::new(
+        MonitorState::Degraded,
+        Some(&sample_info()),
+        diagnostics,
+    ));
+}
+
+#[test]
+fn other_version_does_not_validate() {
+    let mut document = serde_json::to_value(StatusFile::new(
+        MonitorState::Maintenance,
+        None,
+        Diagnostics::default(),
+    ))
+    .expect("status file serializes");
+    document["version"] = 1.into();
+
+    assert!(!jsonschema::is_valid(&status_file_schema(), &document));
+}
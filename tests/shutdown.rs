@@ -0,0 +1,178 @@
+//! Stopping background components on shutdown: one at a time in reverse order of registration,
+//! each within its own timeout, with a summary of how each went.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use dayz_monitor::shutdown::{Outcome, Shutdown, Stopped, Summary};
+use tokio::time::{sleep, Instant};
+
+const MS: Duration = Duration::from_millis(1);
+
+type Events = Arc<Mutex<Vec<String>>>;
+
+/// Registers a component that takes `flush` to stop once told to, logging both to `events`.
+fn component(shutdown: &Shutdown, events: &Events, name: &'static str, flush: Duration) {
+    let mut component = shutdown.register(name, 100 * MS);
+    let events = events.clone();
+
+    tokio::spawn(async move {
+        component.cancelled().await;
+        events.lock().unwrap().push(format!("stop {name}"));
+        sleep(flush).await;
+        events.lock().unwrap().push(format!("done {name}"));
+        component.done();
+    });
+}
+
+fn stopped(name: &'static str, outcome: Outcome) -> Stopped {
+    Stopped { name, outcome }
+}
+
+#[tokio::test(start_paused = true)]
+async fn components_stop_one_at_a_time_last_registered_first() {
+    let shutdown = Shutdown::default();
+    let events = Events::default();
+    component(&shutdown, &events, "history", 30 * MS);
+    component(&shutdown, &events, "status_loop", 10 * MS);
+    component(&shutdown, &events, "http", Duration::ZERO);
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "stop http",
+            "done http",
+            "stop status_loop",
+            "done status_loop",
+            "stop history",
+            "done history"
+        ]
+    );
+    assert_eq!(
+        summary.0,
+        [
+            stopped("http", Outcome::Finished(Duration::ZERO)),
+            stopped("status_loop", Outcome::Finished(10 * MS)),
+            stopped("history", Outcome::Finished(30 * MS)),
+        ]
+    );
+    assert!(summary.is_clean());
+}
+
+#[tokio::test(start_paused = true)]
+async fn component_missing_its_timeout_is_given_up_on() {
+    let shutdown = Shutdown::default();
+    let events = Events::default();
+    component(&shutdown, &events, "history", 5 * MS);
+    component(&shutdown, &events, "mqtt", 500 * MS);
+    let started = Instant::now();
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(
+        summary.0,
+        [
+            stopped("mqtt", Outcome::TimedOut(100 * MS)),
+            stopped("history", Outcome::Finished(5 * MS)),
+        ]
+    );
+    assert!(!summary.is_clean());
+    assert_eq!(started.elapsed(), 105 * MS);
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_handle_counts_as_abandoned() {
+    let shutdown = Shutdown::default();
+    let component = shutdown.register("panicky", 100 * MS);
+    drop(component);
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(summary.0, [stopped("panicky", Outcome::Abandoned)]);
+    assert_eq!(summary.to_string(), "panicky quit without reporting");
+}
+
+#[tokio::test(start_paused = true)]
+async fn task_group_waits_for_running_tasks() {
+    let shutdown = Shutdown::default();
+    let tasks = shutdown.register_tasks("alert_delivery", 100 * MS);
+    let delivered = Events::default();
+    for delay in [20, 60] {
+        let delivered = delivered.clone();
+        tasks.spawn(async move {
+            sleep(delay * MS).await;
+            delivered.lock().unwrap().push(format!("after {delay}ms"));
+        });
+    }
+    assert_eq!(tasks.running(), 2);
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(
+        summary.0,
+        [stopped("alert_delivery", Outcome::Finished(60 * MS))]
+    );
+    assert_eq!(delivered.lock().unwrap().len(), 2);
+    assert_eq!(tasks.running(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn task_group_times_out_on_a_stuck_task() {
+    let shutdown = Shutdown::default();
+    let tasks = shutdown.register_tasks("alert_delivery", 100 * MS);
+    tasks.spawn(sleep(Duration::from_secs(60)));
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(
+        summary.0,
+        [stopped("alert_delivery", Outcome::TimedOut(100 * MS))]
+    );
+    assert_eq!(tasks.running(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn idle_task_group_and_panicking_task_stop_at_once() {
+    let shutdown = Shutdown::default();
+    let tasks = shutdown.register_tasks("alert_delivery", 100 * MS);
+    tasks.spawn(async { panic!("delivery bug") });
+    tokio::task::yield_now().await;
+
+    let summary = shutdown.run().await;
+
+    assert_eq!(
+        summary.0,
+        [stopped("alert_delivery", Outcome::Finished(Duration::ZERO))]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn late_registration_is_cancelled_and_not_waited_for() {
+    let shutdown = Shutdown::default();
+    assert!(!shutdown.is_started());
+    assert_eq!(shutdown.run().await.to_string(), "nothing to stop");
+    assert!(shutdown.is_started());
+
+    let mut late = shutdown.register("status_loop", 100 * MS);
+    assert!(late.is_cancelled());
+    late.cancelled().await;
+
+    assert_eq!(shutdown.run().await, Summary::default());
+}
+
+#[test]
+fn summary_names_every_component() {
+    let summary = Summary(vec![
+        stopped("status_loop", Outcome::Finished(3 * MS)),
+        stopped("alert_delivery", Outcome::TimedOut(Duration::from_secs(10))),
+    ]);
+
+    assert_eq!(
+        summary.to_string(),
+        "status_loop stopped in 3ms, alert_delivery timed out after 10000ms"
+    );
+}
@@ -0,0 +1,384 @@
+//! Bundling the bot's state into a snapshot archive and restoring it: validation of what is in
+//! the archive, archives without an audit log, and refusing to restore under a running bot.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dayz_monitor::{
+    snapshot::{
+        self, running_heartbeat_secs, AuditRestore, Manifest, AUDIT_MEMBER, CONFIG_MEMBER,
+        MANIFEST_MEMBER, RUNNING_HEARTBEAT_SECS, SNAPSHOT_FORMAT, STATE_MEMBER,
+    },
+    state::{load_state, save_state, PersistentState, STATE_VERSION},
+    DayzMonitorError,
+};
+
+/// `heartbeat_unix` in `gaps.json`, the state inside `full.tar`
+const HEARTBEAT: u64 = 1_760_097_480;
+/// Long enough after every fixture's heartbeat that no bot is running
+const NOW: u64 = HEARTBEAT + 86_400;
+
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
+
+fn archive(name: &str) -> Vec<u8> {
+    fs::read(fixture(&format!("snapshot/{name}"))).expect("snapshot fixture reads")
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dayz-monitor-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("scratch dir is creatable");
+    dir
+}
+
+/// An archive of `members`, listed in a manifest with their checksums and `format`.
+fn build(format: u32, members: &[(&str, &[u8])]) -> Vec<u8> {
+    let manifest = Manifest {
+        format,
+        bot_version: "0.1.0".to_string(),
+        created_unix: NOW,
+        state_version: STATE_VERSION,
+        members: members
+            .iter()
+            .map(|(name, bytes)| (name.to_string(), crc32fast::hash(bytes)))
+            .collect(),
+    };
+    let manifest = serde_json::to_vec(&manifest).unwrap();
+
+    let mut all = vec![(MANIFEST_MEMBER, manifest.as_slice())];
+    all.extend_from_slice(members);
+    tar_of(&all)
+}
+
+fn tar_of(members: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut archive = tar::Builder::new(Vec::new());
+    for (name, bytes) in members {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o600);
+        header.set_cksum();
+        archive.append_data(&mut header, name, *bytes).unwrap();
+    }
+    archive.into_inner().unwrap()
+}
+
+fn invalid_reason(archive: &[u8]) -> String {
+    match snapshot::read(archive) {
+        Err(DayzMonitorError::InvalidSnapshot(reason)) => reason,
+        other => panic!("archive was not rejected as invalid: {other:?}"),
+    }
+}
+
+#[test]
+fn full_fixture_holds_state_audit_and_redacted_settings() {
+    let snapshot = snapshot::read(&archive("full.tar")).expect("fixture is valid");
+
+    assert_eq!(snapshot.manifest.format, SNAPSHOT_FORMAT);
+    assert_eq!(
+        snapshot.state,
+        load_state(&fixture("state/gaps.json")).unwrap()
+    );
+    assert_eq!(snapshot.audit.unwrap().split(|b| *b == b'\n').count(), 4);
+
+    let config = snapshot.config.expect("settings are included");
+    assert!(config.contains("SERVER_ADDRESS=127.0.0.1:2303\n"));
+    assert!(config.contains("TELEGRAM_BOT_TOKEN=unset\n"));
+    assert!(!config.contains("secret-token"));
+}
+
+#[test]
+fn created_archive_reads_back() {
+    let dir = scratch_dir("snapshot-create");
+    let state_path = dir.join("state.json");
+    let audit_path = dir.join("audit.jsonl");
+    let state = PersistentState {
+        heartbeat_unix: Some(HEARTBEAT),
+        ..Default::default()
+    };
+    save_state(&state_path, &state).unwrap();
+    fs::write(&audit_path, b"{}\n").unwrap();
+
+    let archive = snapshot::create(
+        &state_path,
+        Some(&audit_path),
+        Some("UPDATE_INTERVAL_SECS=60\n"),
+        NOW,
+    )
+    .unwrap();
+    let snapshot = snapshot::read(&archive).unwrap();
+
+    assert_eq!(snapshot.state, state);
+    assert_eq!(snapshot.audit.as_deref(), Some(b"{}\n".as_slice()));
+    assert_eq!(
+        snapshot.config.as_deref(),
+        Some("UPDATE_INTERVAL_SECS=60\n")
+    );
+    assert_eq!(snapshot.manifest.created_unix, NOW);
+    assert_eq!(
+        snapshot.manifest.members.keys().collect::<Vec<_>>(),
+        [AUDIT_MEMBER, CONFIG_MEMBER, STATE_MEMBER]
+    );
+}
+
+#[test]
+fn missing_audit_log_is_left_out() {
+    let dir = scratch_dir("snapshot-no-audit");
+    let state_path = dir.join("state.json");
+
+    let archive = snapshot::create(&state_path, Some(&dir.join("audit.jsonl")), None, NOW).unwrap();
+    let snapshot = snapshot::read(&archive).unwrap();
+
+    assert_eq!(snapshot.state, PersistentState::default());
+    assert_eq!(snapshot.audit, None);
+    assert_eq!(snapshot.config, None);
+}
+
+#[test]
+fn corrupted_member_is_rejected() {
+    assert_eq!(
+        invalid_reason(&archive("corrupted-state.tar")),
+        "state.json is corrupted (checksum mismatch)"
+    );
+}
+
+#[test]
+fn malformed_archives_are_rejected() {
+    let state = serde_json::to_vec(&PersistentState::default()).unwrap();
+    let mut truncated = archive("full.tar");
+    truncated.truncate(700);
+
+    for (archive, reason) in [
+        (b"not an archive at all".to_vec(), "unreadable member"),
+        (tar_of(&[(STATE_MEMBER, &state)]), "no manifest.json"),
+        (truncated, "unreadable member"),
+        (build(SNAPSHOT_FORMAT, &[]), "no state.json"),
+        (
+            build(SNAPSHOT_FORMAT + 1, &[(STATE_MEMBER, &state)]),
+            "newer than this bot's",
+        ),
+        (
+            build(SNAPSHOT_FORMAT, &[(STATE_MEMBER, b"{\"version\": ")]),
+            "state.json does not parse",
+        ),
+        (
+            build(
+                SNAPSHOT_FORMAT,
+                &[(STATE_MEMBER, br#"{"version": 99}"#.as_slice())],
+            ),
+            "state version 99 is newer",
+        ),
+    ] {
+        let found = invalid_reason(&archive);
+        assert!(found.contains(reason), "expected '{reason}', got '{found}'");
+    }
+}
+
+#[test]
+fn listed_member_missing_from_the_archive_is_rejected() {
+    let state = serde_json::to_vec(&PersistentState::default()).unwrap();
+    let manifest = format!(
+        r#"{{"format": 1, "bot_version": "0.1.0", "created_unix": 0, "state_version": 1,
+            "members": {{"state.json": {}, "audit.jsonl": 1}}}}"#,
+        crc32fast::hash(&state)
+    );
+    let archive = tar_of(&[
+        (MANIFEST_MEMBER, manifest.as_bytes()),
+        (STATE_MEMBER, &state),
+    ]);
+
+    assert_eq!(
+        invalid_reason(&archive),
+        "audit.jsonl is listed but missing"
+    );
+}
+
+#[test]
+fn partial_archive_restores_the_state_only() {
+    let dir = scratch_dir("snapshot-partial");
+    let state_path = dir.join("state.json");
+    let audit_path = dir.join("audit.jsonl");
+    fs::write(&audit_path, b"kept\n").unwrap();
+
+    let snapshot = snapshot::read(&archive("state-only.tar")).expect("fixture is valid");
+    let audit = snapshot::restore(&snapshot, &state_path, Some(&audit_path), NOW, false).unwrap();
+
+    assert_eq!(audit, AuditRestore::NotInSnapshot);
+    assert_eq!(fs::read(&audit_path).unwrap(), b"kept\n");
+    // Written in the current layout, with the same content as the older file
+    assert_eq!(
+        load_state(&state_path).unwrap(),
+        load_state(&fixture("state/legacy.json")).unwrap()
+    );
+}
+
+#[test]
+fn full_restore_replaces_state_and_audit_log() {
+    let dir = scratch_dir("snapshot-restore");
+    let state_path = dir.join("state.json");
+    let audit_path = dir.join("audit.jsonl");
+    save_state(&state_path, &PersistentState::default()).unwrap();
+
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+    let audit = snapshot::restore(&snapshot, &state_path, Some(&audit_path), NOW, false).unwrap();
+
+    assert_eq!(audit, AuditRestore::Restored);
+    assert_eq!(load_state(&state_path).unwrap(), snapshot.state);
+    assert_eq!(fs::read(&audit_path).unwrap(), snapshot.audit.unwrap());
+
+    let names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(names.len(), 2, "temp files left behind: {names:?}");
+}
+
+#[test]
+fn audit_log_without_audit_file_is_skipped() {
+    let dir = scratch_dir("snapshot-no-audit-file");
+    let state_path = dir.join("state.json");
+
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+    let audit = snapshot::restore(&snapshot, &state_path, None, NOW, false).unwrap();
+
+    assert_eq!(audit, AuditRestore::NoAuditFile);
+    assert_eq!(load_state(&state_path).unwrap(), snapshot.state);
+}
+
+#[test]
+fn running_bot_is_refused_unless_forced() {
+    let dir = scratch_dir("snapshot-running");
+    let state_path = dir.join("state.json");
+    let running = PersistentState {
+        heartbeat_unix: Some(NOW - 60),
+        ..Default::default()
+    };
+    save_state(&state_path, &running).unwrap();
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+
+    let refused = snapshot::restore(&snapshot, &state_path, None, NOW, false);
+    assert!(matches!(refused, Err(DayzMonitorError::BotRunning(60))));
+    assert_eq!(load_state(&state_path).unwrap(), running);
+
+    // A heartbeat this old is from a bot that stopped
+    let stopped = snapshot::restore(
+        &snapshot,
+        &state_path,
+        None,
+        NOW - 60 + RUNNING_HEARTBEAT_SECS,
+        false,
+    );
+    assert!(stopped.is_ok());
+
+    save_state(&state_path, &running).unwrap();
+    snapshot::restore(&snapshot, &state_path, None, NOW, true).unwrap();
+    assert_eq!(load_state(&state_path).unwrap(), snapshot.state);
+}
+
+#[test]
+fn running_threshold_follows_the_update_interval() {
+    let cases = [
+        (None, RUNNING_HEARTBEAT_SECS),
+        (Some(30), 120),
+        (Some(60), 180),
+        (Some(600), 1260),
+        (Some(u64::MAX), u64::MAX),
+    ];
+
+    for (interval, expected) in cases {
+        assert_eq!(running_heartbeat_secs(interval), expected, "{interval:?}");
+    }
+}
+
+#[test]
+fn slowly_polling_bot_is_still_running() {
+    let dir = scratch_dir("snapshot-slow-poll");
+    let state_path = dir.join("state.json");
+    // Polling every 10 minutes, last seen 8 minutes ago.
+    let running = PersistentState {
+        heartbeat_unix: Some(NOW - 480),
+        heartbeat_interval_secs: Some(600),
+        ..Default::default()
+    };
+    save_state(&state_path, &running).unwrap();
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+
+    let refused = snapshot::restore(&snapshot, &state_path, None, NOW, false);
+    assert!(matches!(refused, Err(DayzMonitorError::BotRunning(480))));
+
+    // Two missed cycles later it has stopped.
+    let stopped = snapshot::restore(&snapshot, &state_path, None, NOW + 780, false);
+    assert!(stopped.is_ok());
+}
+
+#[test]
+fn cleanly_stopped_bot_can_be_restored_right_away() {
+    let dir = scratch_dir("snapshot-stopped");
+    let state_path = dir.join("state.json");
+    let stopped = PersistentState {
+        heartbeat_unix: Some(NOW - 5),
+        heartbeat_interval_secs: Some(60),
+        stopped: true,
+        ..Default::default()
+    };
+    save_state(&state_path, &stopped).unwrap();
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+
+    snapshot::restore(&snapshot, &state_path, None, NOW, false).unwrap();
+
+    assert_eq!(load_state(&state_path).unwrap(), snapshot.state);
+}
+
+#[test]
+fn state_file_from_a_newer_bot_is_not_loaded() {
+    let dir = scratch_dir("snapshot-newer-state");
+    let state_path = dir.join("state.json");
+    let newer = format!("{{\"version\": {}}}", STATE_VERSION + 1);
+    fs::write(&state_path, &newer).unwrap();
+
+    assert!(matches!(
+        load_state(&state_path),
+        Err(DayzMonitorError::NewerState(v)) if v == STATE_VERSION + 1
+    ));
+
+    // Nor replaced by a restore, short of forcing it.
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+    assert!(snapshot::restore(&snapshot, &state_path, None, NOW, false).is_err());
+    assert_eq!(fs::read_to_string(&state_path).unwrap(), newer);
+}
+
+#[test]
+fn state_that_does_not_parse_is_no_running_bot() {
+    let dir = scratch_dir("snapshot-corrupt-state");
+    let state_path = dir.join("state.json");
+    fs::write(&state_path, b"{\"heartbeat_unix\": 17600").unwrap();
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+
+    snapshot::restore(&snapshot, &state_path, None, NOW, false).unwrap();
+
+    assert_eq!(load_state(&state_path).unwrap(), snapshot.state);
+}
+
+#[test]
+fn failed_audit_write_leaves_the_state_alone() {
+    let dir = scratch_dir("snapshot-unwritable-audit");
+    let state_path = dir.join("state.json");
+    let audit_path = dir.join("missing").join("audit.jsonl");
+    save_state(&state_path, &PersistentState::default()).unwrap();
+    let snapshot = snapshot::read(&archive("full.tar")).unwrap();
+
+    let failed = snapshot::restore(&snapshot, &state_path, Some(&audit_path), NOW, false);
+
+    assert!(failed.is_err());
+    assert_eq!(load_state(&state_path).unwrap(), PersistentState::default());
+    let names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(names, ["state.json"]);
+}
@@ -0,0 +1,147 @@
+//! `/snooze`: the durations it accepts, whether alerts are held back at a given moment, and
+//! the snooze surviving a restart.
+
+mod common;
+
+use std::fs;
+
+use dayz_monitor::{
+    alerts::{
+        alert_rules, format_duration, parse_duration, suppression, AlertKind, AlertTracker,
+        Suppression, MAX_SNOOZE_SECS,
+    },
+    state::{load_state, save_state, PersistentState},
+};
+use serde_json::json;
+
+use common::{config, info, NOW};
+
+#[test]
+fn durations_parse_to_seconds() {
+    let cases = [
+        ("45s", Some(45)),
+        ("30m", Some(1_800)),
+        ("2h", Some(7_200)),
+        ("1d", Some(86_400)),
+        ("1h30m", Some(5_400)),
+        ("1d2h3m4s", Some(93_784)),
+        (" 2H ", Some(7_200)),
+        ("90", None),
+        ("1h30", None),
+        ("", None),
+        ("0m", None),
+        ("5x", None),
+        ("h", None),
+        ("-5m", None),
+        ("99999999999999999999d", None),
+        ("999999999999999d", None),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(parse_duration(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn snooze_is_limited_to_a_week() {
+    let accepted = |arg: &str| parse_duration(arg).filter(|secs| *secs <= MAX_SNOOZE_SECS);
+
+    assert_eq!(accepted("7d"), Some(MAX_SNOOZE_SECS));
+    assert_eq!(accepted("6d23h59m"), Some(MAX_SNOOZE_SECS - 60));
+    assert_eq!(accepted("7d1s"), None);
+    assert_eq!(accepted("8d"), None);
+}
+
+#[test]
+fn durations_format_compactly() {
+    let cases = [
+        (0, "0s"),
+        (45, "45s"),
+        (60, "1m"),
+        (3_599, "59m"),
+        (3_600, "1h 0m"),
+        (11_100, "3h 5m"),
+        (90_000, "25h 0m"),
+    ];
+
+    for (secs, expected) in cases {
+        assert_eq!(format_duration(secs), expected, "{secs}");
+    }
+}
+
+#[test]
+fn suppression_decision() {
+    let snoozed = Some(Suppression::Snoozed {
+        until_unix: NOW + 60,
+    });
+    let cases = [
+        (false, None, None),
+        (false, Some(NOW + 60), snoozed),
+        (false, Some(NOW), None),
+        (false, Some(NOW - 60), None),
+        (true, None, Some(Suppression::Maintenance)),
+        (true, Some(NOW + 60), Some(Suppression::Maintenance)),
+        (true, Some(NOW - 60), Some(Suppression::Maintenance)),
+    ];
+
+    for (in_maintenance, snoozed_until, expected) in cases {
+        assert_eq!(
+            suppression(NOW, in_maintenance, snoozed_until),
+            expected,
+            "maintenance: {in_maintenance}, snoozed until: {snoozed_until:?}"
+        );
+    }
+}
+
+#[test]
+fn suppression_reasons_print_for_the_log() {
+    assert_eq!(Suppression::Maintenance.to_string(), "maintenance");
+    assert_eq!(
+        Suppression::Snoozed { until_unix: NOW }.to_string(),
+        format!("snoozed until {NOW}")
+    );
+}
+
+#[test]
+fn tracking_continues_while_snoozed() {
+    let mut config = config(json!({}));
+    config.alert_channel_id = Some(2);
+    let mut tracker = AlertTracker::new(alert_rules(&config), 20);
+    let snoozed_until = Some(NOW + 600);
+    let mut sent = Vec::new();
+    let mut poll = |players: Option<u32>, now: u64| {
+        let info = players.map(info);
+        let alerts = tracker.observe(&config, info.as_ref(), now);
+        if suppression(now, false, snoozed_until).is_none() {
+            sent.extend(alerts.into_iter().map(|alert| alert.kind));
+        }
+    };
+
+    poll(Some(12), NOW);
+    poll(None, NOW + 300);
+    poll(Some(12), NOW + 900);
+
+    // The outage started during the snooze, so only the recovery after it is sent.
+    assert_eq!(sent, [AlertKind::Online]);
+}
+
+#[test]
+fn snooze_survives_a_restart() {
+    let dir = std::env::temp_dir().join(format!("dayz-monitor-snooze-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("state.json");
+
+    let state = PersistentState {
+        snoozed_until_unix: Some(NOW + 3_600),
+        ..Default::default()
+    };
+    save_state(&path, &state).unwrap();
+
+    assert_eq!(
+        load_state(&path).unwrap().snoozed_until_unix,
+        Some(NOW + 3_600)
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
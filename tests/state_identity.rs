@@ -0,0 +1,140 @@
+//! Persisted history belongs to the server identity, not its address: state files from before
+//! identities are claimed as they are, and moves and renames keep the history and are audited.
+
+mod common;
+
+use std::path::Path;
+
+use dayz_monitor::{
+    audit::AuditEvent,
+    state::{claim_history, load_state, save_state, PersistentState, ServerRecord},
+};
+use serde_json::json;
+
+use common::config;
+
+fn fixture(name: &str) -> PersistentState {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/state")
+        .join(name);
+
+    load_state(&path).expect("state fixture loads")
+}
+
+fn claimed(id: &str, address: &str) -> PersistentState {
+    let mut state = fixture("legacy.json");
+    claim_history(&mut state, id, address);
+    state
+}
+
+#[test]
+fn identity_defaults_to_server_name() {
+    assert_eq!(config(json!({})).server_identity(), "Chernarus 1");
+    assert_eq!(
+        config(json!({ "server_id": "cherno" })).server_identity(),
+        "cherno"
+    );
+}
+
+#[test]
+fn fresh_state_is_claimed_silently() {
+    let missing = std::env::temp_dir().join("dayz-monitor-no-such-state.json");
+    let mut state = load_state(&missing).expect("missing state file starts fresh");
+
+    assert!(claim_history(&mut state, "cherno", "203.0.113.7:2303").is_empty());
+    assert_eq!(
+        state.server,
+        Some(ServerRecord {
+            id: "cherno".to_string(),
+            address: "203.0.113.7:2303".to_string(),
+        })
+    );
+}
+
+#[test]
+fn legacy_state_keeps_its_history() {
+    let before = fixture("legacy.json");
+    assert_eq!(before.server, None);
+
+    let mut state = before.clone();
+    assert!(claim_history(&mut state, "cherno", "203.0.113.7:2303").is_empty());
+
+    assert_eq!(state.usage, before.usage);
+    assert_eq!(state.map_stats, before.map_stats);
+    assert_eq!(state.player_hours, before.player_hours);
+    assert_eq!(state.usage.starts, 12);
+    assert_eq!(state.map_stats["chernarusplus"].peak_players, 60);
+}
+
+#[test]
+fn migrated_state_round_trips() {
+    let state = claimed("cherno", "203.0.113.7:2303");
+    let path =
+        std::env::temp_dir().join(format!("dayz-monitor-migrated-{}.json", std::process::id()));
+
+    save_state(&path, &state).expect("state saves");
+    let reloaded = load_state(&path).expect("state reloads");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(reloaded, state);
+}
+
+#[test]
+fn address_change_keeps_history_and_is_audited() {
+    let mut state = claimed("cherno", "203.0.113.7:2303");
+    let before = state.clone();
+
+    let changes = claim_history(&mut state, "cherno", "198.51.100.20:27016");
+
+    assert_eq!(
+        changes,
+        vec![AuditEvent::AddressChanged {
+            server_id: "cherno".to_string(),
+            from: "203.0.113.7:2303".to_string(),
+            to: "198.51.100.20:27016".to_string(),
+        }]
+    );
+    assert_eq!(state.usage, before.usage);
+    assert_eq!(state.map_stats, before.map_stats);
+    assert_eq!(
+        state.server.map(|s| s.address),
+        Some("198.51.100.20:27016".to_string())
+    );
+}
+
+#[test]
+fn rename_keeps_history_and_is_audited() {
+    let mut state = claimed("Chernarus 1", "203.0.113.7:2303");
+
+    let changes = claim_history(&mut state, "cherno", "203.0.113.7:2303");
+
+    assert_eq!(
+        changes,
+        vec![AuditEvent::ServerRenamed {
+            from: "Chernarus 1".to_string(),
+            to: "cherno".to_string(),
+        }]
+    );
+    assert_eq!(state.usage.starts, 12);
+}
+
+#[test]
+fn unchanged_server_records_nothing() {
+    let mut state = claimed("cherno", "203.0.113.7:2303");
+
+    assert!(claim_history(&mut state, "cherno", "203.0.113.7:2303").is_empty());
+}
+
+#[test]
+fn changes_describe_themselves() {
+    let event = AuditEvent::AddressChanged {
+        server_id: "cherno".to_string(),
+        from: "203.0.113.7:2303".to_string(),
+        to: "198.51.100.20:27016".to_string(),
+    };
+
+    assert_eq!(
+        event.describe(),
+        "server cherno moved from 203.0.113.7:2303 to 198.51.100.20:27016"
+    );
+}
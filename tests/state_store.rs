@@ -0,0 +1,202 @@
+//! Saving the state to a store that starts failing, e.g. an SD card gone read-only: a few
+//! failures are retried, more suspend persisting, and a periodic probe resumes it.
+
+use std::{cell::Cell, fs, path::PathBuf};
+
+use dayz_monitor::{
+    state::{
+        load_state, FileStore, GuardedStore, PersistentState, SaveOutcome, StateStore,
+        SUSPEND_AFTER_FAILURES, WRITE_PROBE_INTERVAL_SECS,
+    },
+    DayzMonitorError,
+};
+
+const NOW: u64 = 1_760_000_000;
+
+/// Fails every save and probe while `failing` is set, and counts what was attempted.
+#[derive(Default)]
+struct FakeStore {
+    failing: Cell<bool>,
+    saves: Cell<u32>,
+    probes: Cell<u32>,
+}
+
+impl FakeStore {
+    fn result(&self) -> Result<(), DayzMonitorError> {
+        if self.failing.get() {
+            Err(std::io::Error::other("read-only file system").into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl StateStore for FakeStore {
+    fn save(&self, _: &PersistentState) -> Result<(), DayzMonitorError> {
+        self.saves.set(self.saves.get() + 1);
+        self.result()
+    }
+
+    fn probe(&self) -> Result<(), DayzMonitorError> {
+        self.probes.set(self.probes.get() + 1);
+        self.result()
+    }
+}
+
+/// A store that has just been suspended at `NOW`.
+fn suspended() -> GuardedStore<FakeStore> {
+    let mut store = GuardedStore::new(FakeStore::default());
+    store.store().failing.set(true);
+    for _ in 0..SUSPEND_AFTER_FAILURES {
+        store.save(&PersistentState::default(), NOW);
+    }
+    assert_eq!(store.suspended_since(), Some(NOW));
+
+    store
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dayz-monitor-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("scratch dir is creatable");
+    dir
+}
+
+#[test]
+fn working_store_saves_every_time() {
+    let mut store = GuardedStore::new(FakeStore::default());
+
+    for cycle in 0..5 {
+        assert_eq!(
+            store.save(&PersistentState::default(), NOW + cycle * 60),
+            SaveOutcome::Saved
+        );
+    }
+    assert_eq!(store.store().saves.get(), 5);
+    assert_eq!(store.suspended_since(), None);
+}
+
+#[test]
+fn repeated_failures_suspend_persisting() {
+    let mut store = GuardedStore::new(FakeStore::default());
+    store.store().failing.set(true);
+    let reason = "Tokio IO error: read-only file system".to_string();
+
+    for _ in 1..SUSPEND_AFTER_FAILURES {
+        assert_eq!(
+            store.save(&PersistentState::default(), NOW),
+            SaveOutcome::Failed(reason.clone())
+        );
+    }
+    assert_eq!(
+        store.save(&PersistentState::default(), NOW),
+        SaveOutcome::Suspended(reason)
+    );
+    assert_eq!(store.suspended_since(), Some(NOW));
+}
+
+#[test]
+fn success_in_between_resets_the_count() {
+    let mut store = GuardedStore::new(FakeStore::default());
+
+    for _ in 0..3 {
+        store.store().failing.set(true);
+        for _ in 1..SUSPEND_AFTER_FAILURES {
+            store.save(&PersistentState::default(), NOW);
+        }
+        store.store().failing.set(false);
+        assert_eq!(
+            store.save(&PersistentState::default(), NOW),
+            SaveOutcome::Saved
+        );
+    }
+    assert_eq!(store.suspended_since(), None);
+}
+
+#[test]
+fn suspended_store_is_left_alone_between_probes() {
+    let mut store = suspended();
+    let saves = store.store().saves.get();
+
+    for secs in (60..WRITE_PROBE_INTERVAL_SECS).step_by(60) {
+        assert_eq!(
+            store.save(&PersistentState::default(), NOW + secs),
+            SaveOutcome::Skipped
+        );
+    }
+    assert_eq!(store.store().saves.get(), saves);
+    assert_eq!(store.store().probes.get(), 0);
+}
+
+#[test]
+fn failed_probe_keeps_it_suspended_for_another_interval() {
+    let mut store = suspended();
+    let probe_at = NOW + WRITE_PROBE_INTERVAL_SECS;
+
+    assert_eq!(
+        store.save(&PersistentState::default(), probe_at),
+        SaveOutcome::Skipped
+    );
+    assert_eq!(store.store().probes.get(), 1);
+
+    assert_eq!(
+        store.save(&PersistentState::default(), probe_at + 60),
+        SaveOutcome::Skipped
+    );
+    assert_eq!(store.store().probes.get(), 1);
+    assert_eq!(store.suspended_since(), Some(NOW));
+}
+
+#[test]
+fn working_probe_resumes_persisting() {
+    let mut store = suspended();
+    store.store().failing.set(false);
+    let saves = store.store().saves.get();
+
+    assert_eq!(
+        store.save(&PersistentState::default(), NOW + WRITE_PROBE_INTERVAL_SECS),
+        SaveOutcome::Resumed
+    );
+    assert_eq!(store.store().saves.get(), saves + 1);
+    assert_eq!(store.suspended_since(), None);
+
+    assert_eq!(
+        store.save(
+            &PersistentState::default(),
+            NOW + WRITE_PROBE_INTERVAL_SECS + 60
+        ),
+        SaveOutcome::Saved
+    );
+}
+
+#[test]
+fn file_store_in_a_missing_directory_is_suspended() {
+    let path = scratch_dir("state-store").join("missing-dir/state.json");
+    let mut store = GuardedStore::new(FileStore { path });
+
+    let outcomes: Vec<SaveOutcome> = (0..SUSPEND_AFTER_FAILURES as u64)
+        .map(|cycle| store.save(&PersistentState::default(), NOW + cycle * 60))
+        .collect();
+
+    assert!(matches!(outcomes.last(), Some(SaveOutcome::Suspended(_))));
+}
+
+#[test]
+fn file_store_saves_and_leaves_no_temp_file() {
+    let dir = scratch_dir("state-store-ok");
+    let path = dir.join("state.json");
+    let mut store = GuardedStore::new(FileStore { path: path.clone() });
+    let state = PersistentState {
+        heartbeat_unix: Some(NOW),
+        ..Default::default()
+    };
+
+    assert_eq!(store.save(&state, NOW), SaveOutcome::Saved);
+    assert_eq!(load_state(&path).unwrap(), state);
+
+    let names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec!["state.json"]);
+}
@@ -0,0 +1,163 @@
+//! Whole status cycles against a fake server and a fake Discord: what gets posted and edited,
+//! which alerts are counted and sent, and what is fetched along the way.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use dayz_monitor::{
+    alerts::AlertKind, monitor::Observation, output::Rendered, render::EmbedRepresentation,
+    transport::Priority, MonitorState,
+};
+use serde_json::json;
+
+use common::{config, info, Bot, Call, NOW};
+
+fn embed(state: &str, players: &str) -> Rendered {
+    Rendered::Embed(
+        EmbedRepresentation::new()
+            .title(state)
+            .description(players)
+            .footer(dayz_monitor::cleanup::STATUS_MARKER),
+    )
+}
+
+#[tokio::test]
+async fn first_cycle_posts_the_status_and_later_ones_edit_it() {
+    let config = config(json!({}));
+    let mut bot = Bot::start(&config, vec![Some(info(12)), Some(info(14))]).await;
+
+    bot.cycles(1).await;
+    assert_eq!(bot.fake().sent(), [(1, embed("online", "12/60"))]);
+    assert_eq!(*bot.shared.status_message_id.read().await, Some(1001));
+
+    // The second sample changes the message, the third repeats it.
+    bot.cycles(2).await;
+    assert_eq!(bot.fake().edits(), [(1, 1001, embed("online", "14/60"))]);
+
+    let observation: Observation = bot.shared.last_observation.read().await.clone();
+    assert_eq!(observation.info.map(|i| i.players), Some(14));
+    assert_eq!(
+        *bot.shared.query_addr.read().await,
+        Some("127.0.0.1:2303".parse().unwrap())
+    );
+    let persistent = bot.shared.persistent.read().await;
+    assert_eq!(persistent.heartbeat_unix, Some(NOW + 120));
+    assert_eq!(persistent.usage.cycles_online, 3);
+}
+
+#[tokio::test]
+async fn cycle_reports_its_render_and_edit() {
+    let config = config(json!({}));
+    let mut bot = Bot::start(&config, vec![Some(info(12)), Some(info(14))]).await;
+
+    let first = bot
+        .cycle
+        .run(&bot.status, &bot.host, &bot.shared, &bot.clock)
+        .await;
+    bot.clock.advance(60);
+    let second = bot
+        .cycle
+        .run(&bot.status, &bot.host, &bot.shared, &bot.clock)
+        .await;
+
+    // Posting isn't an edit, and a fake poll neither resolves nor queries.
+    assert!(first.render.is_some());
+    assert_eq!(first.edit, None);
+    assert!(second.edit.is_some());
+    assert!(second.attempts.is_empty());
+}
+
+#[tokio::test]
+async fn alerts_are_counted_even_when_they_cannot_be_sent() {
+    let config = config(json!({ "alert_channel_id": 2 }));
+    let mut bot = Bot::start(&config, vec![Some(info(12)), None]).await;
+
+    bot.cycles(1).await;
+    bot.fake().fail_sends(true);
+    bot.cycles(1).await;
+
+    let persistent = bot.shared.persistent.read().await;
+    assert_eq!(persistent.usage.alerts_fired.get("offline"), Some(&1));
+    assert!(bot.fake().sent().iter().all(|(channel, _)| *channel != 2));
+    assert_eq!(*bot.host.notified.lock().unwrap(), [AlertKind::Offline]);
+}
+
+#[tokio::test]
+async fn alerts_and_log_entries_go_out_with_their_priority() {
+    let config = config(json!({
+        "alert_channel_id": 2,
+        "log_channel_id": 3,
+    }));
+    let mut bot = Bot::start(&config, vec![Some(info(12)), None]).await;
+
+    bot.cycles(2).await;
+
+    let posts: Vec<(u64, Priority)> = bot
+        .fake()
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            Call::Send {
+                channel_id,
+                priority,
+                ..
+            } => Some((channel_id, priority)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        posts,
+        [
+            (1, Priority::Low),
+            (3, Priority::Low),
+            (2, Priority::Normal),
+            (3, Priority::Low),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn offline_cycles_count_as_offline_and_keep_the_message() {
+    let config = config(json!({}));
+    let mut bot = Bot::start(&config, vec![Some(info(12)), None]).await;
+
+    bot.cycles(2).await;
+
+    assert_eq!(bot.fake().edits(), [(1, 1001, embed("offline", "–"))]);
+    let persistent = bot.shared.persistent.read().await;
+    assert_eq!(
+        (
+            persistent.usage.cycles_online,
+            persistent.usage.cycles_offline
+        ),
+        (1, 1)
+    );
+    assert_eq!(
+        bot.shared.last_observation.read().await.state,
+        MonitorState::Offline
+    );
+}
+
+#[tokio::test]
+async fn scheduled_events_are_only_fetched_with_the_gateway() {
+    for (gateway, fetches) in [(true, 1), (false, 0)] {
+        let config = config(json!({ "event_impact": true, "gateway": gateway }));
+        let mut bot = Bot::start(&config, vec![Some(info(12))]).await;
+
+        // Refetched only every half hour.
+        bot.cycles(3).await;
+
+        assert_eq!(
+            bot.host.event_fetches.load(Ordering::SeqCst),
+            fetches,
+            "gateway {gateway}"
+        );
+        let persistent = bot.shared.persistent.read().await;
+        assert_eq!(
+            persistent.hourly_players.hours.is_empty(),
+            !gateway,
+            "gateway {gateway}"
+        );
+    }
+}
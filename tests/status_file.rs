@@ -0,0 +1,175 @@
+//! The status file: written atomically, read back whole, and waited for with
+//! `--wait-for-status`.
+
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use dayz_monitor::{
+    diagnostics::Diagnostics,
+    status_file::{read_status_file, wait_for_status, write_status_file, StatusFile},
+    unix_now, write_atomic, DayzMonitorError, MonitorState,
+};
+
+use common::info;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("dayz-monitor-status-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn online(players: u32) -> StatusFile {
+    StatusFile::new(
+        MonitorState::Online,
+        Some(&info(players)),
+        Diagnostics::default(),
+    )
+}
+
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn atomic_write_replaces_the_whole_file() {
+    let dir = temp_dir("replace");
+    let path = dir.join("status.json");
+
+    write_atomic(&path, b"a much longer first document").unwrap();
+    write_atomic(&path, b"short").unwrap();
+
+    assert_eq!(fs::read(&path).unwrap(), b"short");
+    assert_eq!(names(&dir), ["status.json"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn failed_atomic_write_keeps_the_old_file_and_no_temp_file() {
+    let dir = temp_dir("failed");
+    let path = dir.join("status.json");
+    write_atomic(&path, b"old").unwrap();
+
+    // The temp file can't be renamed over a directory.
+    let blocked = dir.join("blocked");
+    fs::create_dir(&blocked).unwrap();
+    fs::write(blocked.join("inside"), b"").unwrap();
+    assert!(write_atomic(&blocked, b"new").is_err());
+
+    assert_eq!(fs::read(&path).unwrap(), b"old");
+    assert_eq!(names(&dir), ["blocked", "status.json"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn atomic_write_into_a_missing_directory_fails() {
+    let path = temp_dir("missing").join("nope").join("status.json");
+
+    assert!(matches!(
+        write_atomic(&path, b"{}"),
+        Err(DayzMonitorError::TokioIOError(_))
+    ));
+}
+
+#[test]
+fn status_file_reads_back_what_was_written() {
+    let dir = temp_dir("roundtrip");
+    let path = dir.join("status.json");
+    let status = online(12);
+
+    write_status_file(&path, &status).unwrap();
+
+    assert_eq!(read_status_file(&path).unwrap(), status);
+    assert!(matches!(
+        read_status_file(&dir.join("other.json")),
+        Err(DayzMonitorError::TokioIOError(_))
+    ));
+    fs::write(&path, "{\"version\":").unwrap();
+    assert!(matches!(
+        read_status_file(&path),
+        Err(DayzMonitorError::JsonError(_))
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn status_fields_print_for_the_shell() {
+    let status = online(12);
+
+    assert_eq!(status.field("state").unwrap(), "online");
+    assert_eq!(status.field("players").unwrap(), "12");
+    assert_eq!(status.field("online").unwrap(), "true");
+
+    let offline = StatusFile::new(MonitorState::Offline, None, Diagnostics::default());
+    assert_eq!(offline.field("players").unwrap(), "null");
+    assert!(matches!(
+        offline.field("nope"),
+        Err(DayzMonitorError::UnknownStatusField(name)) if name == "nope"
+    ));
+}
+
+#[test]
+fn freshness_is_measured_from_the_write() {
+    let status = online(12);
+    let now = status.written_unix;
+    assert!(status.is_fresh(Duration::from_secs(60), now));
+
+    assert!(!status.is_fresh(Duration::from_secs(60), now + 120));
+    assert!(status.is_fresh(Duration::from_secs(180), now + 120));
+    // A clock stepped back since the write doesn't make it stale.
+    assert!(status.is_fresh(Duration::from_secs(60), now - 600));
+}
+
+#[tokio::test]
+async fn waiting_gives_up_on_a_missing_or_stale_file() {
+    let dir = temp_dir("stale");
+    let path = dir.join("status.json");
+
+    let waited = wait_for_status(&path, Duration::from_secs(60), Duration::from_secs(1)).await;
+    assert!(matches!(
+        waited,
+        Err(DayzMonitorError::StatusFileWaitTimeout(1))
+    ));
+
+    let mut stale = online(12);
+    stale.written_unix = unix_now() - 600;
+    write_status_file(&path, &stale).unwrap();
+    let waited = wait_for_status(&path, Duration::from_secs(60), Duration::from_secs(1)).await;
+    assert!(waited.is_err());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn waiting_returns_once_the_file_is_written() {
+    let dir = temp_dir("wait");
+    let path = dir.join("status.json");
+
+    let writer = {
+        let path = path.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(700)).await;
+            write_status_file(&path, &online(7)).unwrap();
+        })
+    };
+    let waited = wait_for_status(&path, Duration::from_secs(60), Duration::from_secs(5)).await;
+    writer.await.unwrap();
+
+    assert_eq!(waited.unwrap().players, Some(7));
+
+    let _ = fs::remove_dir_all(&dir);
+}
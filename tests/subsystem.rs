@@ -0,0 +1,99 @@
+//! How optional subsystems fail: local resources disable themselves, network services keep
+//! retrying, and neither is ever fatal.
+
+use std::{fs, io, path::PathBuf};
+
+use dayz_monitor::subsystem::{probe_writable, Health, RestartPolicy, Subsystem, Subsystems};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dayz-monitor-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("scratch dir is creatable");
+    dir
+}
+
+fn failure(reason: &str) -> Result<(), io::Error> {
+    Err(io::Error::other(reason.to_string()))
+}
+
+#[test]
+fn unwritable_local_file_is_disabled_at_startup() {
+    let path = scratch_dir("unwritable").join("missing-dir/audit.jsonl");
+    let subsystems = Subsystems::default();
+
+    subsystems.start("audit_log", RestartPolicy::Disable, probe_writable(&path));
+
+    assert!(!subsystems.is_enabled("audit_log"));
+    assert!(matches!(
+        subsystems.snapshot()[0],
+        ("audit_log", Health::Disabled(_))
+    ));
+}
+
+#[test]
+fn disabled_subsystem_stays_disabled() {
+    let mut subsystem = Subsystem::new("state_file", RestartPolicy::Disable);
+    subsystem.start(failure("permission denied"));
+
+    subsystem.record(Ok::<(), io::Error>(()));
+
+    assert_eq!(
+        subsystem.health(),
+        &Health::Disabled("permission denied".to_string())
+    );
+}
+
+#[test]
+fn local_failure_after_startup_degrades_and_recovers() {
+    let mut subsystem = Subsystem::new("status_file", RestartPolicy::Disable);
+    subsystem.start(Ok::<(), io::Error>(()));
+
+    subsystem.record(failure("disk full"));
+    assert_eq!(
+        subsystem.health(),
+        &Health::Degraded("disk full".to_string())
+    );
+    assert!(subsystem.is_enabled());
+
+    subsystem.record(Ok::<(), io::Error>(()));
+    assert_eq!(subsystem.health(), &Health::Up);
+}
+
+#[test]
+fn unreachable_network_service_keeps_retrying() {
+    let subsystems = Subsystems::default();
+    subsystems.start("ntfy", RestartPolicy::Retry, failure("connection refused"));
+
+    assert!(subsystems.is_enabled("ntfy"));
+
+    subsystems.record("ntfy", failure("401 Unauthorized"));
+    assert_eq!(
+        subsystems.snapshot(),
+        vec![("ntfy", Health::Degraded("401 Unauthorized".to_string()))]
+    );
+
+    subsystems.record("ntfy", Ok::<(), io::Error>(()));
+    assert_eq!(subsystems.snapshot(), vec![("ntfy", Health::Up)]);
+}
+
+#[test]
+fn unknown_subsystems_are_enabled() {
+    assert!(Subsystems::default().is_enabled("telegram"));
+}
+
+#[test]
+fn probe_leaves_existing_file_alone() {
+    let dir = scratch_dir("probe");
+    let path = dir.join("state.json");
+    fs::write(&path, "{\"version\":1}").expect("state file is writable");
+
+    probe_writable(&path).expect("state file is probed");
+
+    assert_eq!(
+        fs::read_to_string(&path).expect("state file is readable"),
+        "{\"version\":1}"
+    );
+    assert_eq!(fs::read_dir(&dir).expect("dir is readable").count(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,172 @@
+//! Local usage counters: what each start, cycle and alert adds, and that they survive a
+//! restart through the state file.
+
+mod common;
+
+use std::fs;
+
+use dayz_monitor::{
+    i18n::Language,
+    numbers::NumberLocale,
+    state::{load_state, save_state, PersistentState},
+    usage::UsageCounters,
+    MonitorState::{Degraded, Maintenance, Offline, Online, Starting, Unknown},
+};
+
+use common::NOW;
+
+const DAY: u64 = 86_400;
+
+#[test]
+fn starts_count_features_and_keep_the_first_start() {
+    let mut usage = UsageCounters::default();
+
+    usage.record_start(NOW, &["alerts", "status_file"]);
+    usage.record_start(NOW + DAY, &["alerts"]);
+
+    assert_eq!(usage.first_started_unix, Some(NOW));
+    assert_eq!(usage.starts, 2);
+    assert_eq!(usage.feature_starts["alerts"], 2);
+    assert_eq!(usage.feature_starts["status_file"], 1);
+}
+
+#[test]
+fn cycles_are_split_into_online_and_offline() {
+    let mut usage = UsageCounters::default();
+
+    for state in [Online, Online, Offline, Online, Starting] {
+        usage.record_cycle(state);
+    }
+
+    assert_eq!(
+        (usage.cycles, usage.cycles_online, usage.cycles_offline),
+        (5, 3, 2)
+    );
+}
+
+#[test]
+fn maintenance_and_degraded_cycles_are_neither_online_nor_offline() {
+    let mut usage = UsageCounters::default();
+
+    for state in [Unknown, Online, Maintenance, Maintenance, Degraded, Offline] {
+        usage.record_cycle(state);
+    }
+
+    assert_eq!(
+        (usage.cycles, usage.cycles_online, usage.cycles_offline),
+        (6, 1, 1)
+    );
+}
+
+#[test]
+fn alerts_are_counted_per_rule() {
+    let mut usage = UsageCounters::default();
+
+    usage.record_alert("offline");
+    usage.record_alert("online");
+    usage.record_alert("offline");
+
+    assert_eq!(
+        usage.alerts_fired.into_iter().collect::<Vec<_>>(),
+        [("offline".to_string(), 2), ("online".to_string(), 1)]
+    );
+}
+
+#[test]
+fn counters_saturate_instead_of_wrapping() {
+    let mut usage = UsageCounters {
+        starts: u64::MAX,
+        cycles: u64::MAX,
+        cycles_online: u64::MAX,
+        ..Default::default()
+    };
+
+    usage.record_start(NOW, &[]);
+    usage.record_cycle(Online);
+
+    assert_eq!(usage.starts, u64::MAX);
+    assert_eq!(usage.cycles, u64::MAX);
+    assert_eq!(usage.cycles_online, u64::MAX);
+}
+
+#[test]
+fn summary_of_a_bot_that_never_ran() {
+    let summary = UsageCounters::default().summary(NOW, NumberLocale::En, Language::En);
+
+    assert_eq!(
+        summary,
+        "Running for:      never started\n\
+         Update cycles:    0\n\
+         \n\
+         Alerts fired:\n  \
+         (none)\n\
+         \n\
+         Features enabled (starts):\n  \
+         (none)\n"
+    );
+}
+
+#[test]
+fn summary_counts_days_and_the_offline_share() {
+    let mut usage = UsageCounters::default();
+    usage.record_start(NOW, &["alerts"]);
+    for state in [
+        Online, Online, Online, Offline, Online, Online, Online, Online,
+    ] {
+        usage.record_cycle(state);
+    }
+    usage.record_alert("offline");
+
+    let summary = usage.summary(NOW + 3 * DAY + 10, NumberLocale::En, Language::En);
+
+    assert!(
+        summary.starts_with("Running for:      3 days (1 start)\n"),
+        "{summary}"
+    );
+    assert!(summary.contains("Update cycles:    8\n"), "{summary}");
+    assert!(
+        summary.contains("Offline:          12.5% of cycles\n"),
+        "{summary}"
+    );
+    assert!(summary.contains("  offline         1\n"), "{summary}");
+    assert!(summary.contains("  alerts          1\n"), "{summary}");
+}
+
+#[test]
+fn counters_survive_a_restart() {
+    let path = std::env::temp_dir().join(format!("dayz-monitor-usage-{}.json", std::process::id()));
+    let mut state = PersistentState::default();
+    state.usage.record_start(NOW, &["alerts"]);
+    state.usage.record_cycle(Offline);
+
+    save_state(&path, &state).unwrap();
+    let mut loaded = load_state(&path).unwrap();
+    loaded.usage.record_start(NOW + DAY, &[]);
+
+    assert_eq!(loaded.usage.first_started_unix, Some(NOW));
+    assert_eq!(loaded.usage.starts, 2);
+    assert_eq!(loaded.usage.cycles_offline, 1);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn missing_state_file_starts_from_zero() {
+    let path = std::env::temp_dir().join(format!(
+        "dayz-monitor-usage-missing-{}.json",
+        std::process::id()
+    ));
+
+    let state = load_state(&path).unwrap();
+
+    assert_eq!(state.usage, UsageCounters::default());
+}
+
+#[test]
+fn counters_missing_from_the_file_default_to_zero() {
+    let usage: UsageCounters = serde_json::from_str(r#"{"starts": 4}"#).unwrap();
+
+    assert_eq!(usage.starts, 4);
+    assert_eq!(usage.cycles, 0);
+    assert!(usage.alerts_fired.is_empty());
+}
@@ -0,0 +1,305 @@
+//! Announcing a planned wipe ahead of time, counting down to it and starting over after it,
+//! including when the bot was down across an announcement.
+
+use std::path::Path;
+
+use dayz_monitor::{
+    i18n::Language,
+    maps::MapStats,
+    state::{load_state, PersistentState},
+    wipe::{self, MissedAnnouncements, WipeEvent, WipeField, WipePlan, WipeProgress},
+    DayzMonitorConfig, DayzMonitorError,
+};
+use serde_json::json;
+use MissedAnnouncements::{CatchUp, Skip};
+
+/// 2026-11-05 17:00 UTC, 18:00 in Europe/Berlin
+const WIPE: u64 = 1_793_898_000;
+const HOUR: u64 = 3600;
+const DAY: u64 = 24 * HOUR;
+/// Three polls of the default interval
+const GRACE: u64 = 180;
+
+fn plan() -> WipePlan {
+    WipePlan {
+        at_unix: WIPE,
+        offsets_secs: vec![7 * DAY, DAY, HOUR],
+    }
+}
+
+/// Runs the schedule at each of `times`, collecting what was due.
+fn run(
+    missed: MissedAnnouncements,
+    progress: &mut WipeProgress,
+    times: &[u64],
+) -> Vec<(u64, WipeEvent)> {
+    times
+        .iter()
+        .filter_map(|now| wipe::due(&plan(), missed, progress, *now, GRACE).map(|e| (*now, e)))
+        .collect()
+}
+
+/// One poll a minute from `from` to `to`.
+fn polls(from: u64, to: u64) -> Vec<u64> {
+    (from..=to).step_by(60).collect()
+}
+
+fn fixture(name: &str) -> PersistentState {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/state")
+        .join(name);
+
+    load_state(&path).expect("state fixture loads")
+}
+
+#[test]
+fn config_is_parsed_with_timezone_and_sorted_offsets() {
+    let config: DayzMonitorConfig = serde_json::from_value(json!({
+        "discord_token": "token",
+        "server_address": "127.0.0.1:2303",
+        "text_channel_id": 1,
+        "next_wipe_date": "2026-11-05 18:00 Europe/Berlin",
+        "wipe_announce_offsets": ["1h", "7d", "1d", "1h"],
+        "wipe_missed_announcements": "catch_up",
+    }))
+    .expect("config deserializes");
+
+    assert_eq!(config.wipe_plan().unwrap(), Some(plan()));
+    assert_eq!(config.wipe_missed_announcements, CatchUp);
+}
+
+#[test]
+fn default_offsets_and_utc() {
+    let config: DayzMonitorConfig = serde_json::from_value(json!({
+        "discord_token": "token",
+        "server_address": "127.0.0.1:2303",
+        "text_channel_id": 1,
+        "next_wipe_date": "2026-11-05 17:00",
+    }))
+    .expect("config deserializes");
+
+    assert_eq!(config.wipe_plan().unwrap(), Some(plan()));
+    assert_eq!(config.wipe_missed_announcements, Skip);
+    assert!(!config.wipe_reset_stats);
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    for (date, offset) in [
+        ("2026-11-05", "1h"),
+        ("05.11.2026 18:00", "1h"),
+        ("2026-11-05 18:00 Mars/Olympus", "1h"),
+        ("2026-11-05 18:00", "soon"),
+        ("2026-11-05 18:00", "0h"),
+    ] {
+        let result = WipePlan::parse(date, &[offset.to_string()], chrono_tz::UTC);
+
+        assert!(
+            matches!(result, Err(DayzMonitorError::InvalidWipe { .. })),
+            "'{date}' / '{offset}' was accepted"
+        );
+    }
+}
+
+#[test]
+fn each_offset_is_announced_once_then_the_wipe() {
+    let mut progress = WipeProgress::default();
+    // Seen for the first time ten days ahead
+    let events = run(Skip, &mut progress, &polls(WIPE - 10 * DAY, WIPE + HOUR));
+
+    assert_eq!(
+        events,
+        vec![
+            (
+                WIPE - 7 * DAY,
+                WipeEvent::Countdown {
+                    before_secs: 7 * DAY
+                }
+            ),
+            (WIPE - DAY, WipeEvent::Countdown { before_secs: DAY }),
+            (WIPE - HOUR, WipeEvent::Countdown { before_secs: HOUR }),
+            (WIPE, WipeEvent::Wiped { announce: true }),
+        ]
+    );
+    assert_eq!(progress.last_wipe_unix, Some(WIPE));
+}
+
+#[test]
+fn restart_does_not_repeat_announcements() {
+    let mut progress = WipeProgress::default();
+    run(Skip, &mut progress, &polls(WIPE - 2 * DAY, WIPE - DAY));
+
+    // The progress is persisted, so a restarted bot picks up where it left off.
+    let mut restored: WipeProgress =
+        serde_json::from_value(serde_json::to_value(&progress).unwrap()).unwrap();
+    let events = run(
+        Skip,
+        &mut restored,
+        &polls(WIPE - DAY + 60, WIPE - 2 * HOUR),
+    );
+
+    assert!(events.is_empty());
+}
+
+#[test]
+fn moment_a_few_polls_late_is_still_on_time() {
+    let mut progress = WipeProgress::default();
+    run(Skip, &mut progress, &[WIPE - 2 * DAY]);
+
+    assert_eq!(
+        run(Skip, &mut progress, &[WIPE - DAY + GRACE]),
+        vec![(
+            WIPE - DAY + GRACE,
+            WipeEvent::Countdown { before_secs: DAY }
+        )]
+    );
+}
+
+#[test]
+fn missed_announcements_are_skipped() {
+    let mut progress = WipeProgress::default();
+    run(Skip, &mut progress, &[WIPE - 8 * DAY]);
+
+    // Down from 8 days to 2 hours before the wipe, across the 7d and 1d moments
+    let events = run(Skip, &mut progress, &polls(WIPE - 2 * HOUR, WIPE - 30 * 60));
+
+    assert_eq!(
+        events,
+        vec![(WIPE - HOUR, WipeEvent::Countdown { before_secs: HOUR })]
+    );
+}
+
+#[test]
+fn catch_up_posts_only_the_latest_missed_announcement() {
+    let mut progress = WipeProgress::default();
+    run(CatchUp, &mut progress, &[WIPE - 8 * DAY]);
+
+    let events = run(
+        CatchUp,
+        &mut progress,
+        &polls(WIPE - 2 * HOUR, WIPE - 30 * 60),
+    );
+
+    assert_eq!(
+        events,
+        vec![
+            (WIPE - 2 * HOUR, WipeEvent::Countdown { before_secs: DAY }),
+            (WIPE - HOUR, WipeEvent::Countdown { before_secs: HOUR }),
+        ]
+    );
+}
+
+#[test]
+fn wipe_missed_while_down_still_counts() {
+    for (missed, announce) in [(Skip, false), (CatchUp, true)] {
+        let mut progress = WipeProgress::default();
+        run(missed, &mut progress, &[WIPE - 2 * HOUR]);
+
+        assert_eq!(
+            run(missed, &mut progress, &[WIPE + 3 * HOUR, WIPE + 4 * HOUR]),
+            vec![(WIPE + 3 * HOUR, WipeEvent::Wiped { announce })]
+        );
+        assert_eq!(progress.last_wipe_unix, Some(WIPE));
+    }
+}
+
+#[test]
+fn date_already_past_when_first_seen_is_the_last_wipe() {
+    let mut progress = WipeProgress::default();
+
+    assert!(run(CatchUp, &mut progress, &[WIPE + 3 * DAY]).is_empty());
+    assert_eq!(progress.last_wipe_unix, Some(WIPE));
+    assert_eq!(progress.days_since(WIPE + 3 * DAY + HOUR), Some(3));
+}
+
+#[test]
+fn new_date_starts_the_series_over() {
+    let mut progress = WipeProgress::default();
+    run(Skip, &mut progress, &polls(WIPE - 2 * DAY, WIPE + HOUR));
+
+    let next = WipePlan {
+        at_unix: WIPE + 30 * DAY,
+        offsets_secs: plan().offsets_secs,
+    };
+    let now = next.at_unix - 7 * DAY;
+
+    assert_eq!(
+        wipe::due(&next, Skip, &mut progress, now, GRACE),
+        Some(WipeEvent::Countdown {
+            before_secs: 7 * DAY
+        })
+    );
+    // The last wipe stays until the next one happens
+    assert_eq!(progress.last_wipe_unix, Some(WIPE));
+}
+
+#[test]
+fn field_counts_down_in_the_last_day_then_up() {
+    let plan = plan();
+    let mut progress = WipeProgress::default();
+
+    assert_eq!(wipe::field(Some(&plan), &progress, WIPE - 2 * DAY), None);
+    assert_eq!(
+        wipe::field(Some(&plan), &progress, WIPE - DAY),
+        Some(WipeField::Countdown { at_unix: WIPE })
+    );
+
+    wipe::due(&plan, Skip, &mut progress, WIPE, GRACE);
+
+    assert_eq!(
+        wipe::field(Some(&plan), &progress, WIPE + HOUR),
+        Some(WipeField::DaysSince(0))
+    );
+    assert_eq!(
+        wipe::field(None, &progress, WIPE + 10 * DAY),
+        Some(WipeField::DaysSince(10))
+    );
+}
+
+#[test]
+fn announcements_name_the_server_and_time() {
+    assert_eq!(
+        wipe::announcement(
+            WipeEvent::Countdown { before_secs: DAY },
+            "Chernarus 1",
+            WIPE,
+            Language::En
+        ),
+        "🧹 **Chernarus 1**: Wipe <t:1793898000:R> (<t:1793898000:F>)"
+    );
+    assert_eq!(
+        wipe::announcement(
+            WipeEvent::Wiped { announce: true },
+            "Chernarus 1",
+            WIPE,
+            Language::De
+        ),
+        "🧹 **Chernarus 1**: der Server wurde gewipt."
+    );
+}
+
+#[test]
+fn reset_starts_the_stats_over_and_keeps_the_rest() {
+    let mut state = fixture("gaps.json");
+    state.map_stats.insert(
+        "chernarusplus".to_string(),
+        MapStats {
+            cycles: 100,
+            online_cycles: 90,
+            player_sum: 4000,
+            peak_players: 60,
+        },
+    );
+    state.usage.record_alert("offline");
+    state.wipe.last_wipe_unix = Some(WIPE);
+    let before = state.clone();
+
+    wipe::reset_stats(&mut state);
+
+    assert!(state.map_stats.is_empty());
+    assert_eq!(state.daily, Default::default());
+    assert_eq!(state.player_hours, Default::default());
+    assert_eq!(state.usage, before.usage);
+    assert_eq!(state.monitoring_gaps, before.monitoring_gaps);
+    assert_eq!(state.wipe, before.wipe);
+}